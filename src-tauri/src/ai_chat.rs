@@ -0,0 +1,457 @@
+//! 启动器 AI 问答
+//!
+//! 对话保存在 `ai_conversations` / `ai_messages` 中，追问时带上最近的历史消息。
+//! 用户消息可以附带参考内容（剪切板、文件或备忘录），原文保存在 `context` 列，
+//! 组装请求时再拼入提示词，展示时只显示问题本身。
+
+use crate::db;
+use crate::ollama::ChatMessage;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 组装请求时最多带上的历史消息数
+const MAX_HISTORY_MESSAGES: usize = 20;
+/// 参考内容的最大字符数，超出部分截断
+const MAX_CONTEXT_CHARS: usize = 12_000;
+const TITLE_CHARS: usize = 40;
+
+const SYSTEM_PROMPT: &str = "你是一个桌面启动器中的助手。回答要简洁、准确，优先使用用户提问的语言。\
+如果提供了参考内容，请基于参考内容回答；参考内容不足以回答时请直接说明。";
+
+/// 问题附带的参考内容来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AskContext {
+    /// 当前剪切板文本
+    Clipboard,
+    /// 选中文件的内容
+    File { path: String },
+    Memo { id: String },
+    Text { text: String },
+}
+
+/// 已读取的参考内容
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedContext {
+    /// 展示用的来源说明，如 "剪切板"、文件名
+    pub label: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiConversation {
+    pub id: String,
+    pub title: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub message_count: u32,
+    /// 最后一条消息的开头，用于列表展示
+    pub preview: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiMessage {
+    pub id: i64,
+    pub conversation_id: String,
+    /// user / assistant
+    pub role: String,
+    pub content: String,
+    pub context_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// 请求被取消时保存的不完整回答，及没有得到完整回答的问题；不作为历史发给模型
+    #[serde(default)]
+    pub cancelled: bool,
+    pub created_at: u64,
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn truncate_chars(text: &str, max: usize) -> (&str, bool) {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => (&text[..i], true),
+        None => (text, false),
+    }
+}
+
+/// 去掉启动器查询中的前缀（不区分大小写），没有前缀时返回 None
+pub fn strip_ai_prefix<'a>(query: &'a str, prefix: &str) -> Option<&'a str> {
+    let query = query.trim_start();
+    let prefix = prefix.trim_start();
+    if prefix.trim().is_empty() {
+        return None;
+    }
+    let head = query.get(..prefix.len())?;
+    if head.eq_ignore_ascii_case(prefix) {
+        Some(query[prefix.len()..].trim())
+    } else {
+        None
+    }
+}
+
+fn user_prompt(question: &str, context: Option<&ResolvedContext>) -> String {
+    match context {
+        Some(ctx) if !ctx.content.trim().is_empty() => {
+            let (content, truncated) = truncate_chars(&ctx.content, MAX_CONTEXT_CHARS);
+            format!(
+                "参考内容（{}{}）：\n```\n{}\n```\n\n{}",
+                ctx.label,
+                if truncated { "，已截断" } else { "" },
+                content,
+                question
+            )
+        }
+        _ => question.to_string(),
+    }
+}
+
+/// 组装发送给模型的消息：系统提示 + 最近的历史 + 本次问题
+pub fn build_messages(
+    history: &[AiMessage],
+    question: &str,
+    context: Option<&ResolvedContext>,
+) -> Vec<ChatMessage> {
+    let mut messages = vec![ChatMessage::system(SYSTEM_PROMPT)];
+    let start = history.len().saturating_sub(MAX_HISTORY_MESSAGES);
+    for message in history[start..].iter().filter(|m| !m.cancelled) {
+        let content = match (&message.context, &message.context_label) {
+            (Some(content), label) if message.role == "user" => user_prompt(
+                &message.content,
+                Some(&ResolvedContext {
+                    label: label.clone().unwrap_or_default(),
+                    content: content.clone(),
+                }),
+            ),
+            _ => message.content.clone(),
+        };
+        messages.push(ChatMessage { role: message.role.clone(), content });
+    }
+    messages.push(ChatMessage::user(user_prompt(question, context)));
+    messages
+}
+
+pub fn create_conversation(title: &str, app_data_dir: &Path) -> Result<AiConversation, String> {
+    let now = now_ts();
+    let (title, truncated) = truncate_chars(title.trim(), TITLE_CHARS);
+    let title = if truncated { format!("{}…", title) } else { title.to_string() };
+    let id = format!("ai-{}-{:04}", now, now_nanos() % 10_000);
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute(
+        "INSERT INTO ai_conversations (id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+        params![id, title, now as i64],
+    )
+    .map_err(|e| format!("Failed to create conversation: {}", e))?;
+    Ok(AiConversation {
+        id,
+        title,
+        created_at: now,
+        updated_at: now,
+        message_count: 0,
+        preview: None,
+    })
+}
+
+fn now_nanos() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos()
+}
+
+pub fn conversation_exists(conversation_id: &str, app_data_dir: &Path) -> Result<bool, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    find_conversation(&conn, conversation_id)
+}
+
+fn find_conversation(conn: &rusqlite::Connection, conversation_id: &str) -> Result<bool, String> {
+    let exists: Option<String> = conn
+        .query_row(
+            "SELECT id FROM ai_conversations WHERE id = ?1",
+            params![conversation_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load conversation: {}", e))?;
+    Ok(exists.is_some())
+}
+
+pub fn append_message(
+    conversation_id: &str,
+    role: &str,
+    content: &str,
+    context: Option<&ResolvedContext>,
+    app_data_dir: &Path,
+) -> Result<AiMessage, String> {
+    insert_message(conversation_id, role, content, context, false, app_data_dir)
+}
+
+/// 保存被取消的回答（已生成的部分）
+pub fn append_cancelled_answer(
+    conversation_id: &str,
+    content: &str,
+    app_data_dir: &Path,
+) -> Result<AiMessage, String> {
+    insert_message(conversation_id, "assistant", content, None, true, app_data_dir)
+}
+
+/// 请求失败时标记已保存的问题，否则追问时历史中会出现连续两条用户消息
+pub fn mark_unanswered(message_id: i64, app_data_dir: &Path) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute(
+        "UPDATE ai_messages SET cancelled = 1 WHERE id = ?1",
+        params![message_id],
+    )
+    .map_err(|e| format!("Failed to update message: {}", e))?;
+    Ok(())
+}
+
+fn insert_message(
+    conversation_id: &str,
+    role: &str,
+    content: &str,
+    context: Option<&ResolvedContext>,
+    cancelled: bool,
+    app_data_dir: &Path,
+) -> Result<AiMessage, String> {
+    let now = now_ts();
+    let conn = db::get_connection(app_data_dir)?;
+    if !find_conversation(&conn, conversation_id)? {
+        return Err("对话不存在".to_string());
+    }
+
+    let context_content = context.map(|c| truncate_chars(&c.content, MAX_CONTEXT_CHARS).0.to_string());
+    let context_label = context.map(|c| c.label.clone());
+    conn.execute(
+        "INSERT INTO ai_messages (conversation_id, role, content, context, context_label, cancelled, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![conversation_id, role, content, context_content, context_label, cancelled, now as i64],
+    )
+    .map_err(|e| format!("Failed to save message: {}", e))?;
+    let id = conn.last_insert_rowid();
+    conn.execute(
+        "UPDATE ai_conversations SET updated_at = ?1 WHERE id = ?2",
+        params![now as i64, conversation_id],
+    )
+    .map_err(|e| format!("Failed to update conversation: {}", e))?;
+
+    Ok(AiMessage {
+        id,
+        conversation_id: conversation_id.to_string(),
+        role: role.to_string(),
+        content: content.to_string(),
+        context_label,
+        context: context_content,
+        cancelled,
+        created_at: now,
+    })
+}
+
+pub fn get_messages(conversation_id: &str, app_data_dir: &Path) -> Result<Vec<AiMessage>, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, conversation_id, role, content, context_label, context, cancelled, created_at
+             FROM ai_messages WHERE conversation_id = ?1 ORDER BY id ASC",
+        )
+        .map_err(|e| format!("Failed to prepare messages query: {}", e))?;
+    let rows = stmt
+        .query_map(params![conversation_id], |row| {
+            Ok(AiMessage {
+                id: row.get(0)?,
+                conversation_id: row.get(1)?,
+                role: row.get(2)?,
+                content: row.get(3)?,
+                context_label: row.get(4)?,
+                context: row.get(5)?,
+                cancelled: row.get(6)?,
+                created_at: row.get::<_, i64>(7)?.max(0) as u64,
+            })
+        })
+        .map_err(|e| format!("Failed to query messages: {}", e))?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read message row: {}", e))?);
+    }
+    Ok(items)
+}
+
+fn query_conversations(
+    conn: &rusqlite::Connection,
+    where_clause: &str,
+    args: impl rusqlite::Params,
+) -> Result<Vec<AiConversation>, String> {
+    let sql = format!(
+        "SELECT c.id, c.title, c.created_at, c.updated_at,
+                (SELECT COUNT(*) FROM ai_messages m WHERE m.conversation_id = c.id),
+                (SELECT substr(m.content, 1, 80) FROM ai_messages m
+                  WHERE m.conversation_id = c.id ORDER BY m.id DESC LIMIT 1)
+         FROM ai_conversations c
+         {}
+         ORDER BY c.updated_at DESC
+         LIMIT ?1",
+        where_clause
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Failed to prepare conversations query: {}", e))?;
+    let rows = stmt
+        .query_map(args, |row| {
+            Ok(AiConversation {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                created_at: row.get::<_, i64>(2)?.max(0) as u64,
+                updated_at: row.get::<_, i64>(3)?.max(0) as u64,
+                message_count: row.get::<_, i64>(4)?.max(0) as u32,
+                preview: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query conversations: {}", e))?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read conversation row: {}", e))?);
+    }
+    Ok(items)
+}
+
+/// 最近的对话
+pub fn list_conversations(limit: u32, app_data_dir: &Path) -> Result<Vec<AiConversation>, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    query_conversations(&conn, "", params![limit as i64])
+}
+
+/// 按标题和消息内容搜索对话
+pub fn search_conversations(
+    query: &str,
+    limit: u32,
+    app_data_dir: &Path,
+) -> Result<Vec<AiConversation>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return list_conversations(limit, app_data_dir);
+    }
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let pattern = format!(
+        "%{}%",
+        query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    );
+    query_conversations(
+        &conn,
+        "WHERE c.title LIKE ?2 ESCAPE '\\'
+            OR EXISTS (SELECT 1 FROM ai_messages m
+                       WHERE m.conversation_id = c.id AND m.content LIKE ?2 ESCAPE '\\')",
+        params![limit as i64, pattern],
+    )
+}
+
+pub fn delete_conversation(id: &str, app_data_dir: &Path) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute("DELETE FROM ai_conversations WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete conversation: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn test_strip_ai_prefix() {
+        assert_eq!(strip_ai_prefix("ai what is rust", "ai "), Some("what is rust"));
+        assert_eq!(strip_ai_prefix("  AI  hello ", "ai "), Some("hello"));
+        assert_eq!(strip_ai_prefix("aim high", "ai "), None);
+        assert_eq!(strip_ai_prefix("问 天气", "问 "), Some("天气"));
+        assert_eq!(strip_ai_prefix("a", "ai "), None);
+    }
+
+    #[test]
+    fn test_build_messages_replays_context() {
+        let history = vec![
+            AiMessage {
+                id: 1,
+                conversation_id: "c".into(),
+                role: "user".into(),
+                content: "总结一下".into(),
+                context_label: Some("剪切板".into()),
+                context: Some("long text".into()),
+                cancelled: false,
+                created_at: 0,
+            },
+            AiMessage {
+                id: 2,
+                conversation_id: "c".into(),
+                role: "assistant".into(),
+                content: "摘要".into(),
+                context_label: None,
+                context: None,
+                cancelled: false,
+                created_at: 0,
+            },
+            AiMessage {
+                id: 3,
+                conversation_id: "c".into(),
+                role: "assistant".into(),
+                content: "摘".into(),
+                context_label: None,
+                context: None,
+                cancelled: true,
+                created_at: 0,
+            },
+        ];
+        let messages = build_messages(&history, "再短一点", None);
+        // 被取消的回答不会发给模型
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].role, "system");
+        assert!(messages[1].content.contains("参考内容（剪切板）") && messages[1].content.ends_with("总结一下"));
+        assert_eq!(messages[2].content, "摘要");
+        assert_eq!(messages[3], ChatMessage::user("再短一点"));
+    }
+
+    #[test]
+    fn test_conversation_history_and_search() {
+        let dir = temp_dir("ai-chat-history");
+        let conversation = create_conversation("How do lifetimes work in Rust and why?", &dir).unwrap();
+        assert_eq!(conversation.title, "How do lifetimes work in Rust and why?");
+
+        let ctx = ResolvedContext { label: "main.rs".into(), content: "fn main() {}".into() };
+        append_message(&conversation.id, "user", "explain this", Some(&ctx), &dir).unwrap();
+        append_message(&conversation.id, "assistant", "It is an empty entry point.", None, &dir).unwrap();
+
+        append_cancelled_answer(&conversation.id, "It is", &dir).unwrap();
+
+        let messages = get_messages(&conversation.id, &dir).unwrap();
+        assert_eq!(messages.len(), 3);
+        assert!(messages[2].cancelled && !messages[1].cancelled);
+        assert_eq!(messages[0].context.as_deref(), Some("fn main() {}"));
+
+        let found = search_conversations("entry point", 10, &dir).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].message_count, 3);
+        assert_eq!(found[0].preview.as_deref(), Some("It is"));
+        assert!(search_conversations("100%", 10, &dir).unwrap().is_empty());
+
+        // 请求失败的问题保留展示，但追问时不再作为历史
+        let failed = append_message(&conversation.id, "user", "and why?", None, &dir).unwrap();
+        mark_unanswered(failed.id, &dir).unwrap();
+        let history = get_messages(&conversation.id, &dir).unwrap();
+        assert!(history[3].cancelled);
+        let messages = build_messages(&history, "try again", None);
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[2], ChatMessage::assistant("It is an empty entry point."));
+        assert_eq!(messages[3], ChatMessage::user("try again"));
+
+        delete_conversation(&conversation.id, &dir).unwrap();
+        assert!(list_conversations(10, &dir).unwrap().is_empty());
+        assert!(!conversation_exists(&conversation.id, &dir).unwrap());
+        assert_eq!(append_message(&conversation.id, "user", "x", None, &dir).unwrap_err(), "对话不存在");
+    }
+}
@@ -3668,6 +3668,10 @@ pub mod windows {
     pub fn launch_app(_app: &AppInfo) -> Result<(), String> {
         Err("App launch is only supported on Windows".to_string())
     }
+
+    pub fn load_cache(_app_data_dir: &Path) -> Result<Vec<AppInfo>, String> {
+        Err("App search is only supported on Windows".to_string())
+    }
 }
 
 #[cfg(test)]
@@ -109,7 +109,7 @@ pub fn add_clipboard_item(
 }
 
 /// 限制剪切板历史的最大数量，删除超出部分的记录
-fn enforce_max_items(app_data_dir: &PathBuf) -> Result<(), String> {
+pub(crate) fn enforce_max_items(app_data_dir: &PathBuf) -> Result<(), String> {
     // 获取设置中的最大数量
     let settings = settings::load_settings(app_data_dir)
         .unwrap_or_default();
@@ -523,8 +523,9 @@ pub mod monitor {
                         // 检查文本内容
                         if let Ok(content) = get_clipboard_text() {
                             if !content.is_empty() && content != last_text_content {
-                                if let Err(e) = add_clipboard_item(content.clone(), "text".to_string(), &app_data_dir) {
-                                    eprintln!("[Clipboard Monitor] Failed to add text clipboard item: {}", e);
+                                match add_clipboard_item(content.clone(), "text".to_string(), &app_data_dir) {
+                                    Ok(_) => crate::semantic_search::notify_changed(),
+                                    Err(e) => eprintln!("[Clipboard Monitor] Failed to add text clipboard item: {}", e),
                                 }
                                 last_text_content = content;
                             }
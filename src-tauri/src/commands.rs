@@ -3,16 +3,110 @@ pub use recording::*;
 pub mod color_picker;
 pub mod memos;
 pub mod word_records;
+pub mod snippets;
+pub mod ollama;
+pub mod semantic_search;
+pub mod ai_chat;
+pub mod settings_profiles;
+pub mod web_search;
 
 // 重新导出子模块中的所有命令
 pub use color_picker::{show_color_picker_window, pick_color_from_screen};
-pub use memos::{get_all_memos, add_memo, update_memo, delete_memo, search_memos};
+pub use memos::{
+    get_all_memos,
+    add_memo,
+    update_memo,
+    delete_memo,
+    search_memos,
+    list_memos,
+    update_memo_meta,
+    get_memo_notebooks,
+    add_memo_notebook,
+    update_memo_notebook,
+    delete_memo_notebook,
+    get_memo_tags,
+    update_memo_tag,
+    get_memo_revisions,
+    get_memo_revision,
+    diff_memo_revisions,
+    restore_memo_revision,
+    list_deleted_memos,
+    export_memos,
+    import_memos,
+    set_memo_todo,
+    set_memo_reminder,
+    clear_memo_reminder,
+    get_upcoming_memo_reminders,
+    snooze_memo_reminder,
+    complete_memo_reminder,
+};
 pub use word_records::{
     get_all_word_records,
     add_word_record,
     update_word_record,
     delete_word_record,
     search_word_records,
+    get_due_words,
+    submit_review,
+    get_review_stats,
+    get_word_stats,
+    preview_word_import,
+    import_word_records,
+    export_word_records,
+    import_dictionary,
+    lookup_dictionary,
+    search_dictionary,
+    get_dictionary_info,
+    remove_dictionary,
+    generate_word_explanation,
+    generate_missing_word_explanations,
+};
+pub use ollama::{
+    get_ollama_models,
+    check_ollama_health,
+    ollama_chat_stream,
+    ollama_generate_stream,
+    cancel_ollama_request,
+};
+pub use semantic_search::{
+    search_semantic_index,
+    rebuild_semantic_index,
+    get_semantic_index_status,
+};
+pub use ai_chat::{
+    ask_ai,
+    list_ai_conversations,
+    get_ai_conversation_messages,
+    search_ai_conversations,
+    delete_ai_conversation,
+};
+pub use settings_profiles::{
+    list_settings_profiles,
+    save_settings_profile,
+    switch_settings_profile,
+    delete_settings_profile,
+    export_settings_profile,
+    preview_settings_profile_import,
+    import_settings_profile,
+};
+pub use web_search::{
+    resolve_web_search,
+    get_search_suggestions,
+    import_search_bangs,
+    search_bangs,
+    clear_search_bangs,
+};
+pub use snippets::{
+    get_all_snippets,
+    add_snippet,
+    update_snippet,
+    delete_snippet,
+    get_snippet_folders,
+    search_snippets,
+    get_snippet_inputs,
+    render_snippet,
+    import_clipboard_favorites_as_snippets,
+    paste_snippet,
 };
 
 use crate::app_search;
@@ -20,10 +114,12 @@ use crate::db;
 use crate::everything_search;
 use crate::everything_filters;
 use crate::file_history;
+use crate::hotkey_model::{self, HotkeyOwner, HotkeyTrigger};
 use crate::open_history;
 use crate::plugin_usage;
 
 use crate::settings;
+use crate::settings_service;
 use crate::shortcuts;
 use crate::system_folders_search;
 use crate::window_config;
@@ -3383,6 +3479,81 @@ pub fn get_clipboard_text() -> Result<Option<String>, String> {
     }
 }
 
+/// 将文本写入系统剪贴板（供后端直接粘贴时使用）
+pub fn set_clipboard_text(text: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::System::DataExchange::{
+            CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData,
+        };
+        use windows_sys::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+
+        const CF_UNICODETEXT: u32 = 13;
+
+        unsafe {
+            if OpenClipboard(0) == 0 {
+                return Err("Failed to open clipboard".to_string());
+            }
+
+            let result = (|| -> Result<(), String> {
+                EmptyClipboard();
+                let text_wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+                let h_mem = GlobalAlloc(GMEM_MOVEABLE, text_wide.len() * 2);
+                if h_mem.is_null() {
+                    return Err("Failed to allocate clipboard memory".to_string());
+                }
+                let p_mem = GlobalLock(h_mem) as *mut u16;
+                if p_mem.is_null() {
+                    return Err("Failed to lock clipboard memory".to_string());
+                }
+                std::ptr::copy_nonoverlapping(text_wide.as_ptr(), p_mem, text_wide.len());
+                GlobalUnlock(h_mem);
+                if SetClipboardData(CF_UNICODETEXT, h_mem as isize) == 0 {
+                    return Err("Failed to set clipboard data".to_string());
+                }
+                Ok(())
+            })();
+
+            CloseClipboard();
+            result
+        }
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let mut child = if cfg!(target_os = "macos") {
+            Command::new("pbcopy").stdin(Stdio::piped()).spawn()
+        } else {
+            Command::new("xclip")
+                .arg("-selection")
+                .arg("clipboard")
+                .stdin(Stdio::piped())
+                .spawn()
+        }
+        .map_err(|e| format!("Failed to start clipboard tool: {}", e))?;
+
+        child
+            .stdin
+            .take()
+            .ok_or_else(|| "Failed to get clipboard tool stdin".to_string())?
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to write clipboard text: {}", e))?;
+        child
+            .wait()
+            .map_err(|e| format!("Failed to wait for clipboard tool: {}", e))?;
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = text;
+        Err("Clipboard text writing is not supported on this platform".to_string())
+    }
+}
+
 #[tauri::command]
 pub fn save_clipboard_image(image_data: Vec<u8>, extension: String) -> Result<String, String> {
     use std::fs;
@@ -5083,9 +5254,20 @@ pub fn get_settings(app: tauri::AppHandle) -> Result<settings::Settings, String>
 #[tauri::command]
 pub fn save_settings(app: tauri::AppHandle, settings: settings::Settings) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app)?;
+    settings.validate()?;
     settings::save_settings(&app_data_dir, &settings)
 }
 
+/// 按 JSON Merge Patch 只更新提交的字段，返回更新后的完整设置
+#[tauri::command]
+pub fn update_settings(
+    app: tauri::AppHandle,
+    patch: serde_json::Value,
+) -> Result<settings::Settings, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings::update_settings(&app_data_dir, &patch)
+}
+
 // ===== Everything Filters commands =====
 
 #[tauri::command]
@@ -5103,6 +5285,127 @@ pub fn save_everything_custom_filters(
     everything_filters::save_custom_filters(&app_data_dir, &filters)
 }
 
+/// 校验快捷键并检查与其它快捷键的冲突，返回规范化后的配置
+pub fn validate_hotkey_config(
+    settings: &settings::Settings,
+    config: &settings::HotkeyConfig,
+    owner: &HotkeyOwner,
+) -> Result<settings::HotkeyConfig, String> {
+    let trigger = HotkeyTrigger::from_config(config).map_err(|e| format!("快捷键无效：{}", e))?;
+    // 启动器快捷键走 RegisterHotKey，不经过序列匹配
+    if *owner == HotkeyOwner::Launcher && trigger.single().is_none() {
+        return Err("启动器快捷键不支持组合序列和按住触发".to_string());
+    }
+    if let Some((other, other_hotkey)) = hotkey_model::find_conflict(settings, &trigger, owner) {
+        return Err(if other_hotkey == trigger {
+            other.conflict_message()
+        } else {
+            other.overlap_message(&other_hotkey)
+        });
+    }
+    Ok(trigger.to_config())
+}
+
+/// 解析快捷键文本（如 "Ctrl+Shift+K"、"Ctrl Ctrl"、"Ctrl+K, Ctrl+S"、"Hold Ctrl+Space"），
+/// 返回规范写法、设置格式和各平台编码
+#[tauri::command]
+pub fn parse_hotkey(text: String) -> Result<hotkey_model::HotkeyInfo, String> {
+    let trigger: HotkeyTrigger = text.parse().map_err(|e| format!("快捷键无效：{}", e))?;
+    Ok(hotkey_model::HotkeyInfo::from(&trigger))
+}
+
+#[tauri::command]
+pub fn get_hotkey_timing(app: tauri::AppHandle) -> Result<settings::HotkeyTiming, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    Ok(settings.hotkey_timing)
+}
+
+/// 保存组合序列超时、双击间隔和按住时长，立即对正在运行的快捷键监听生效
+#[tauri::command]
+pub fn save_hotkey_timing(
+    app: tauri::AppHandle,
+    timing: settings::HotkeyTiming,
+) -> Result<(), String> {
+    for (name, value) in [
+        ("组合序列超时", timing.chord_timeout_ms),
+        ("双击间隔", timing.double_tap_ms),
+        ("按住时长", timing.hold_ms),
+    ] {
+        if !(100..=5000).contains(&value) {
+            return Err(format!("{}需要在 100–5000 毫秒之间", name));
+        }
+    }
+
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings::modify_settings(&app_data_dir, |settings| {
+        settings.hotkey_timing = timing;
+        Ok(())
+    })
+}
+
+/// 列出设置中所有互相冲突的快捷键
+#[tauri::command]
+pub fn get_hotkey_conflicts(app: tauri::AppHandle) -> Result<Vec<hotkey_model::HotkeyConflict>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    Ok(hotkey_model::find_conflicts(&settings))
+}
+
+/// 已移除或禁用的插件，读取时清理它们遗留的快捷键
+pub const REMOVED_PLUGIN_HOTKEYS: [&str; 1] = ["color_picker"];
+
+/// 由多快捷键监听器处理的字段（插件、应用、应用中心和录制回放）
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+const MULTI_HOTKEY_FIELDS: [&str; 5] = [
+    "plugin_hotkeys",
+    "app_hotkeys",
+    "app_center_hotkey",
+    "recording_bindings",
+    "playback_panic_hotkey",
+];
+
+/// 多快捷键监听器需要注册的全部快捷键，按快捷键 ID 索引
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub fn multi_hotkeys(
+    settings: &settings::Settings,
+) -> std::collections::HashMap<String, settings::HotkeyConfig> {
+    let mut hotkeys = settings.plugin_hotkeys.clone();
+    for (app_path, hotkey) in &settings.app_hotkeys {
+        hotkeys.insert(format!("app:{}", app_path), hotkey.clone());
+    }
+    if let Some(ref hotkey) = settings.app_center_hotkey {
+        hotkeys.insert("app_center".to_string(), hotkey.clone());
+    }
+    hotkeys.extend(recording_hotkeys(settings));
+    hotkeys
+}
+
+/// 设置变化后重新注册受影响的快捷键，其它窗口或外部修改的快捷键立即生效
+///
+/// 监听器可能尚未启动或启动失败，此时只记录错误；多快捷键监听器启动时会按最新设置注册
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub fn reload_hotkeys(change: &settings_service::SettingsChange) {
+    let settings = &change.settings;
+    if change.touches("hotkey_timing") {
+        crate::hotkey_handler::windows::set_hotkey_timing(settings.hotkey_timing);
+    }
+    if change.touches("hotkey") {
+        let result = match settings.hotkey {
+            Some(ref hotkey) => crate::hotkey_handler::windows::update_hotkey(hotkey.clone()),
+            None => crate::hotkey_handler::windows::unregister_hotkey(),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to reload launcher hotkey: {}", e);
+        }
+    }
+    if change.touches_any(&MULTI_HOTKEY_FIELDS) {
+        if let Err(e) = crate::hotkey_handler::windows::update_plugin_hotkeys(multi_hotkeys(settings)) {
+            eprintln!("Failed to reload plugin hotkeys: {}", e);
+        }
+    }
+}
+
 #[tauri::command]
 pub fn get_hotkey_config(app: tauri::AppHandle) -> Result<Option<settings::HotkeyConfig>, String> {
     let app_data_dir = get_app_data_dir(&app)?;
@@ -5116,27 +5419,12 @@ pub fn save_hotkey_config(
     config: settings::HotkeyConfig,
 ) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    let mut settings = settings::load_settings(&app_data_dir)?;
-    settings.hotkey = Some(config.clone());
-    settings::save_settings(&app_data_dir, &settings)?;
-    
-    // 更新已注册的快捷键
-    #[cfg(target_os = "windows")]
-    {
-        match crate::hotkey_handler::windows::update_hotkey(config) {
-            Ok(_) => {
-                eprintln!("Hotkey updated successfully");
-            }
-            Err(e) => {
-                eprintln!("Failed to update hotkey: {}", e);
-                // 返回错误，让前端知道更新失败
-                // 但设置已经保存了，下次启动时会使用新设置
-                return Err(format!("快捷键设置已保存，但立即生效失败: {}. 请重启应用以使新快捷键生效。", e));
-            }
-        }
-    }
-    
-    Ok(())
+    // 保存后由设置服务通知 reload_hotkeys 重新注册，这里不再重复注册
+    settings::modify_settings(&app_data_dir, |settings| {
+        let config = validate_hotkey_config(settings, &config, &HotkeyOwner::Launcher)?;
+        settings.hotkey = Some(config);
+        Ok(())
+    })
 }
 
 #[tauri::command]
@@ -5175,29 +5463,14 @@ pub async fn show_hotkey_settings(app: tauri::AppHandle) -> Result<(), String> {
 #[tauri::command]
 pub fn get_plugin_hotkeys(app: tauri::AppHandle) -> Result<std::collections::HashMap<String, settings::HotkeyConfig>, String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    let mut settings = settings::load_settings(&app_data_dir)?;
-    
-    // 自动清理已被注释/禁用的插件的快捷键（如 color_picker）
-    let removed_plugins = vec!["color_picker"];
-    let mut cleaned = false;
-    for plugin_id in removed_plugins {
-        if settings.plugin_hotkeys.remove(plugin_id).is_some() {
-            cleaned = true;
-        }
-    }
-    
-    // 如果清理了快捷键，保存设置
-    if cleaned {
-        settings::save_settings(&app_data_dir, &settings)?;
-        
-        // 同步更新快捷键注册
-        #[cfg(target_os = "windows")]
-        {
-            let _ = crate::hotkey_handler::windows::update_plugin_hotkeys(settings.plugin_hotkeys.clone());
+    // 自动清理已被注释/禁用的插件的快捷键（如 color_picker）；
+    // 有清理时才会保存，快捷键注册由设置服务同步
+    settings::modify_settings(&app_data_dir, |settings| {
+        for plugin_id in REMOVED_PLUGIN_HOTKEYS {
+            settings.plugin_hotkeys.remove(plugin_id);
         }
-    }
-    
-    Ok(settings.plugin_hotkeys)
+        Ok(settings.plugin_hotkeys.clone())
+    })
 }
 
 #[tauri::command]
@@ -5206,17 +5479,14 @@ pub fn save_plugin_hotkeys(
     plugin_hotkeys: std::collections::HashMap<String, settings::HotkeyConfig>,
 ) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    let mut settings = settings::load_settings(&app_data_dir)?;
-    settings.plugin_hotkeys = plugin_hotkeys.clone();
-    settings::save_settings(&app_data_dir, &settings)?;
+    settings::modify_settings(&app_data_dir, |settings| {
+        settings.plugin_hotkeys = plugin_hotkeys.clone();
+        Ok(())
+    })?;
     
-    // 更新后端快捷键注册
-    #[cfg(target_os = "windows")]
+    // 后端快捷键由设置服务的订阅者重新注册
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
     {
-        if let Err(e) = crate::hotkey_handler::windows::update_plugin_hotkeys(plugin_hotkeys.clone()) {
-            eprintln!("Failed to update plugin hotkeys: {}", e);
-        }
-        
         // 通知前端更新插件快捷键（通过事件）
         if let Err(e) = app.emit("plugin-hotkeys-updated", plugin_hotkeys) {
             eprintln!("Failed to emit plugin-hotkeys-updated event: {}", e);
@@ -5233,99 +5503,24 @@ pub fn save_plugin_hotkey(
     config: Option<settings::HotkeyConfig>,
 ) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    let mut settings = settings::load_settings(&app_data_dir)?;
-    
-    // 如果设置了快捷键，检查冲突
-    if let Some(ref new_hotkey) = config {
-        // 检查与其他插件快捷键的冲突
-        for (other_plugin_id, other_hotkey) in settings.plugin_hotkeys.iter() {
-            if other_plugin_id != &plugin_id {
-                // 检查快捷键是否相同
-                let mut new_mods = new_hotkey.modifiers.clone();
-                new_mods.sort();
-                let mut other_mods = other_hotkey.modifiers.clone();
-                other_mods.sort();
-                
-                if new_mods == other_mods && new_hotkey.key == other_hotkey.key {
-                    return Err(format!(
-                        "快捷键冲突：与插件 \"{}\" 的快捷键相同，请修改后再保存",
-                        other_plugin_id
-                    ));
-                }
-            }
-        }
-        
-        // 检查与应用中心快捷键的冲突
-        if let Some(ref app_center_hotkey) = settings.app_center_hotkey {
-            let mut new_mods = new_hotkey.modifiers.clone();
-            new_mods.sort();
-            let mut center_mods = app_center_hotkey.modifiers.clone();
-            center_mods.sort();
-            
-            if new_mods == center_mods && new_hotkey.key == app_center_hotkey.key {
-                return Err("快捷键冲突：与应用中心快捷键相同，请修改后再保存".to_string());
-            }
-        }
-        
-        // 检查与启动器快捷键的冲突
-        if let Some(ref launcher_hotkey) = settings.hotkey {
-            let mut new_mods = new_hotkey.modifiers.clone();
-            new_mods.sort();
-            let mut launcher_mods = launcher_hotkey.modifiers.clone();
-            launcher_mods.sort();
-            
-            if new_mods == launcher_mods && new_hotkey.key == launcher_hotkey.key {
-                return Err("快捷键冲突：与启动器快捷键相同，请修改后再保存".to_string());
-            }
-        }
-        
-        // 检查与应用快捷键的冲突
-        for (app_path, app_hotkey) in settings.app_hotkeys.iter() {
-            let mut new_mods = new_hotkey.modifiers.clone();
-            new_mods.sort();
-            let mut app_mods = app_hotkey.modifiers.clone();
-            app_mods.sort();
-            
-            if new_mods == app_mods && new_hotkey.key == app_hotkey.key {
-                let app_name = app_path.split('/').last()
-                    .or_else(|| app_path.split('\\').last())
-                    .unwrap_or(app_path);
-                return Err(format!(
-                    "快捷键冲突：与应用 \"{}\" 的快捷键相同，请修改后再保存",
-                    app_name
-                ));
-            }
+    // 保存后由设置服务的订阅者重新注册后端快捷键
+    let plugin_hotkeys = settings::modify_settings(&app_data_dir, |settings| {
+        // 如果设置了快捷键，检查冲突
+        let config = config
+            .map(|c| validate_hotkey_config(settings, &c, &HotkeyOwner::Plugin(plugin_id.clone())))
+            .transpose()?;
+        if let Some(hotkey) = config {
+            settings.plugin_hotkeys.insert(plugin_id.clone(), hotkey);
+        } else {
+            settings.plugin_hotkeys.remove(&plugin_id);
         }
-    }
-    
-    // 先克隆 config 用于后端注册
-    let config_clone = config.clone();
+        Ok(settings.plugin_hotkeys.clone())
+    })?;
     
-    if let Some(hotkey) = config {
-        settings.plugin_hotkeys.insert(plugin_id.clone(), hotkey);
-    } else {
-        settings.plugin_hotkeys.remove(&plugin_id);
-    }
-    
-    settings::save_settings(&app_data_dir, &settings)?;
-    
-    // 更新后端快捷键注册
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
     {
-        if let Some(hotkey) = config_clone {
-            // 注册新的快捷键
-            if let Err(e) = crate::hotkey_handler::windows::register_plugin_hotkey(plugin_id.clone(), hotkey) {
-                eprintln!("Failed to register plugin hotkey: {}", e);
-            }
-        } else {
-            // 取消注册
-            if let Err(e) = crate::hotkey_handler::windows::unregister_plugin_hotkey(&plugin_id) {
-                eprintln!("Failed to unregister plugin hotkey: {}", e);
-            }
-        }
-        
         // 通知前端更新插件快捷键
-        if let Err(e) = app.emit("plugin-hotkeys-updated", settings.plugin_hotkeys.clone()) {
+        if let Err(e) = app.emit("plugin-hotkeys-updated", plugin_hotkeys) {
             eprintln!("Failed to emit plugin-hotkeys-updated event: {}", e);
         }
     }
@@ -5347,38 +5542,23 @@ pub fn save_app_hotkey(
     config: Option<settings::HotkeyConfig>,
 ) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    let mut settings = settings::load_settings(&app_data_dir)?;
-    
-    // 先克隆 config 用于后端注册
-    let config_clone = config.clone();
-    
-    if let Some(hotkey) = config {
-        settings.app_hotkeys.insert(app_path.clone(), hotkey);
-    } else {
-        settings.app_hotkeys.remove(&app_path);
-    }
-    
-    settings::save_settings(&app_data_dir, &settings)?;
-    
-    // 更新后端快捷键注册
-    #[cfg(target_os = "windows")]
-    {
-        // 使用 "app:" 前缀来区分应用快捷键
-        let hotkey_id = format!("app:{}", app_path);
-        if let Some(hotkey) = config_clone {
-            // 注册新的快捷键
-            if let Err(e) = crate::hotkey_handler::windows::register_plugin_hotkey(hotkey_id.clone(), hotkey) {
-                eprintln!("Failed to register app hotkey: {}", e);
-            }
+    // 保存后由设置服务的订阅者重新注册后端快捷键
+    let app_hotkeys = settings::modify_settings(&app_data_dir, |settings| {
+        let config = config
+            .map(|c| validate_hotkey_config(settings, &c, &HotkeyOwner::App(app_path.clone())))
+            .transpose()?;
+        if let Some(hotkey) = config {
+            settings.app_hotkeys.insert(app_path.clone(), hotkey);
         } else {
-            // 取消注册
-            if let Err(e) = crate::hotkey_handler::windows::unregister_plugin_hotkey(&hotkey_id) {
-                eprintln!("Failed to unregister app hotkey: {}", e);
-            }
+            settings.app_hotkeys.remove(&app_path);
         }
-        
+        Ok(settings.app_hotkeys.clone())
+    })?;
+    
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    {
         // 通知前端更新应用快捷键
-        if let Err(e) = app.emit("app-hotkeys-updated", settings.app_hotkeys.clone()) {
+        if let Err(e) = app.emit("app-hotkeys-updated", app_hotkeys) {
             eprintln!("Failed to emit app-hotkeys-updated event: {}", e);
         }
     }
@@ -5399,31 +5579,17 @@ pub fn save_app_center_hotkey(
     config: Option<settings::HotkeyConfig>,
 ) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    let mut settings = settings::load_settings(&app_data_dir)?;
-    
-    // 先克隆 config 用于后端注册
-    let config_clone = config.clone();
-    
-    settings.app_center_hotkey = config.clone();
-    settings::save_settings(&app_data_dir, &settings)?;
+    // 保存后由设置服务的订阅者重新注册后端快捷键
+    let config = settings::modify_settings(&app_data_dir, |settings| {
+        let config = config
+            .map(|c| validate_hotkey_config(settings, &c, &HotkeyOwner::AppCenter))
+            .transpose()?;
+        settings.app_center_hotkey = config.clone();
+        Ok(config)
+    })?;
     
-    // 更新后端快捷键注册
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
     {
-        // 使用 "app_center" 作为快捷键ID
-        let hotkey_id = "app_center".to_string();
-        if let Some(hotkey) = config_clone {
-            // 注册新的快捷键
-            if let Err(e) = crate::hotkey_handler::windows::register_plugin_hotkey(hotkey_id.clone(), hotkey) {
-                eprintln!("Failed to register app center hotkey: {}", e);
-            }
-        } else {
-            // 取消注册
-            if let Err(e) = crate::hotkey_handler::windows::unregister_plugin_hotkey(&hotkey_id) {
-                eprintln!("Failed to unregister app center hotkey: {}", e);
-            }
-        }
-        
         // 通知前端更新应用中心快捷键
         if let Err(e) = app.emit("app-center-hotkey-updated", config) {
             eprintln!("Failed to emit app-center-hotkey-updated event: {}", e);
@@ -5436,7 +5602,7 @@ pub fn save_app_center_hotkey(
 #[tauri::command]
 pub fn restart_app(app: tauri::AppHandle) -> Result<(), String> {
     // 清理快捷键钩子
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
     {
         crate::hotkey_handler::windows::cleanup_hotkeys();
     }
@@ -5979,7 +6145,7 @@ pub fn install_update(
 #[tauri::command]
 pub fn quit_app(app_handle: tauri::AppHandle) -> Result<(), String> {
     // 清理快捷键钩子
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
     {
         crate::hotkey_handler::windows::cleanup_hotkeys();
     }
@@ -6004,7 +6170,9 @@ pub async fn add_clipboard_item(
     app_handle: tauri::AppHandle,
 ) -> Result<crate::clipboard::ClipboardItem, String> {
     let app_data_dir = get_app_data_dir(&app_handle)?;
-    crate::clipboard::add_clipboard_item(content, content_type, &app_data_dir)
+    let item = crate::clipboard::add_clipboard_item(content, content_type, &app_data_dir)?;
+    crate::semantic_search::notify_changed();
+    Ok(item)
 }
 
 #[tauri::command]
@@ -6014,7 +6182,9 @@ pub async fn update_clipboard_item(
     app_handle: tauri::AppHandle,
 ) -> Result<crate::clipboard::ClipboardItem, String> {
     let app_data_dir = get_app_data_dir(&app_handle)?;
-    crate::clipboard::update_clipboard_item(id, content, &app_data_dir)
+    let item = crate::clipboard::update_clipboard_item(id, content, &app_data_dir)?;
+    crate::semantic_search::notify_changed();
+    Ok(item)
 }
 
 #[tauri::command]
@@ -6032,13 +6202,17 @@ pub async fn delete_clipboard_item(
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app_handle)?;
-    crate::clipboard::delete_clipboard_item(id, &app_data_dir)
+    crate::clipboard::delete_clipboard_item(id, &app_data_dir)?;
+    crate::semantic_search::notify_changed();
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn clear_clipboard_history(app_handle: tauri::AppHandle) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app_handle)?;
-    crate::clipboard::clear_clipboard_history(&app_data_dir)
+    crate::clipboard::clear_clipboard_history(&app_data_dir)?;
+    crate::semantic_search::notify_changed();
+    Ok(())
 }
 
 #[tauri::command]
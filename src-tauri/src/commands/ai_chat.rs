@@ -0,0 +1,219 @@
+//! 启动器 AI 问答相关命令模块
+//!
+//! 回答通过事件流式推送：
+//! - `ai-chat-chunk`：每段新内容
+//! - `ai-chat-done`：结束（包括被取消）
+//! - `ai-chat-error`：出错
+//!
+//! 取消请求复用 `cancel_ollama_request`。
+
+use crate::ai_chat::{self, AiConversation, AiMessage, AskContext, ResolvedContext};
+use crate::db;
+use crate::memos;
+use crate::ollama::{self, OllamaClient};
+use crate::settings;
+use super::get_app_data_dir;
+use serde::Serialize;
+use std::path::Path;
+use tauri::{async_runtime, AppHandle, Emitter};
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AiChatChunkEvent<'a> {
+    request_id: &'a str,
+    conversation_id: &'a str,
+    content: &'a str,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AiChatErrorEvent<'a> {
+    request_id: &'a str,
+    conversation_id: &'a str,
+    error: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AskAiResult {
+    pub request_id: String,
+    pub conversation_id: String,
+    /// 保存下来的回答；取消时为已生成的部分（标记为 cancelled，追问时不作为历史）
+    pub message: AiMessage,
+    pub cancelled: bool,
+}
+
+fn resolve_context(context: AskContext, app_data_dir: &Path) -> Result<Option<ResolvedContext>, String> {
+    let resolved = match context {
+        AskContext::Clipboard => super::get_clipboard_text()?.map(|content| ResolvedContext {
+            label: "剪切板".to_string(),
+            content,
+        }),
+        AskContext::File { path } => {
+            let content = super::read_text_file(path.clone())?;
+            let label = Path::new(&path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or(path);
+            Some(ResolvedContext { label, content })
+        }
+        AskContext::Memo { id } => {
+            let conn = db::get_readonly_connection(app_data_dir)?;
+            let memo = memos::load_memo(&conn, &id)?
+                .ok_or_else(|| format!("Memo {} not found", id))?;
+            let label = if memo.title.trim().is_empty() {
+                "备忘录".to_string()
+            } else {
+                format!("备忘录：{}", memo.title)
+            };
+            Some(ResolvedContext { label, content: memo.content })
+        }
+        AskContext::Text { text } => Some(ResolvedContext { label: "选中文本".to_string(), content: text }),
+    };
+    Ok(resolved.filter(|c| !c.content.trim().is_empty()))
+}
+
+/// 提问；`conversation_id` 为空时新建对话，否则作为追问带上历史消息。
+/// `query` 可以带或不带启动器前缀。
+#[tauri::command]
+pub async fn ask_ai(
+    request_id: String,
+    query: String,
+    conversation_id: Option<String>,
+    context: Option<AskContext>,
+    app: AppHandle,
+) -> Result<AskAiResult, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    let question = ai_chat::strip_ai_prefix(&query, &settings.ai_prefix)
+        .unwrap_or_else(|| query.trim())
+        .to_string();
+    if question.is_empty() {
+        return Err("问题不能为空".to_string());
+    }
+    let client = OllamaClient::from_settings(&settings.ollama)?;
+
+    let (conversation_id, messages, question_id) = {
+        let app_data_dir = app_data_dir.clone();
+        let question = question.clone();
+        async_runtime::spawn_blocking(move || -> Result<_, String> {
+            let context = match context {
+                Some(context) => resolve_context(context, &app_data_dir)?,
+                None => None,
+            };
+            let (conversation_id, history) = match conversation_id {
+                Some(id) => {
+                    if !ai_chat::conversation_exists(&id, &app_data_dir)? {
+                        return Err("对话不存在".to_string());
+                    }
+                    let history = ai_chat::get_messages(&id, &app_data_dir)?;
+                    (id, history)
+                }
+                None => (ai_chat::create_conversation(&question, &app_data_dir)?.id, Vec::new()),
+            };
+            let messages = ai_chat::build_messages(&history, &question, context.as_ref());
+            let question =
+                ai_chat::append_message(&conversation_id, "user", &question, context.as_ref(), &app_data_dir)?;
+            Ok((conversation_id, messages, question.id))
+        })
+        .await
+        .map_err(|e| format!("ask_ai join error: {}", e))??
+    };
+
+    let token = ollama::register_request(&request_id);
+    let result = client
+        .chat_stream(&messages, None, None, &token, |content| {
+            let _ = app.emit(
+                "ai-chat-chunk",
+                AiChatChunkEvent {
+                    request_id: &request_id,
+                    conversation_id: &conversation_id,
+                    content,
+                },
+            );
+        })
+        .await;
+    ollama::finish_request(&request_id, &token);
+
+    let completion = match result {
+        Ok(completion) => completion,
+        Err(error) => {
+            let marked = {
+                let app_data_dir = app_data_dir.clone();
+                async_runtime::spawn_blocking(move || ai_chat::mark_unanswered(question_id, &app_data_dir))
+                    .await
+                    .map_err(|e| format!("ask_ai join error: {}", e))
+                    .and_then(|r| r)
+            };
+            if let Err(e) = marked {
+                eprintln!("[AiChat] Failed to mark unanswered question: {}", e);
+            }
+            let _ = app.emit(
+                "ai-chat-error",
+                AiChatErrorEvent {
+                    request_id: &request_id,
+                    conversation_id: &conversation_id,
+                    error: &error,
+                },
+            );
+            return Err(error);
+        }
+    };
+
+    let message = {
+        let app_data_dir = app_data_dir.clone();
+        let conversation_id = conversation_id.clone();
+        let content = completion.content.clone();
+        let cancelled = completion.cancelled;
+        async_runtime::spawn_blocking(move || {
+            if cancelled {
+                // 不完整的回答不作为历史，对应的问题也一并排除
+                ai_chat::mark_unanswered(question_id, &app_data_dir)?;
+                ai_chat::append_cancelled_answer(&conversation_id, &content, &app_data_dir)
+            } else {
+                ai_chat::append_message(&conversation_id, "assistant", &content, None, &app_data_dir)
+            }
+        })
+        .await
+        .map_err(|e| format!("ask_ai join error: {}", e))??
+    };
+
+    let result = AskAiResult {
+        request_id,
+        conversation_id,
+        message,
+        cancelled: completion.cancelled,
+    };
+    let _ = app.emit("ai-chat-done", &result);
+    Ok(result)
+}
+
+/// 最近的 AI 对话
+#[tauri::command]
+pub fn list_ai_conversations(limit: Option<u32>, app: AppHandle) -> Result<Vec<AiConversation>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    ai_chat::list_conversations(limit.unwrap_or(50), &app_data_dir)
+}
+
+#[tauri::command]
+pub fn get_ai_conversation_messages(conversation_id: String, app: AppHandle) -> Result<Vec<AiMessage>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    ai_chat::get_messages(&conversation_id, &app_data_dir)
+}
+
+/// 按标题和消息内容搜索 AI 对话
+#[tauri::command]
+pub fn search_ai_conversations(
+    query: String,
+    limit: Option<u32>,
+    app: AppHandle,
+) -> Result<Vec<AiConversation>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    ai_chat::search_conversations(&query, limit.unwrap_or(50), &app_data_dir)
+}
+
+#[tauri::command]
+pub fn delete_ai_conversation(conversation_id: String, app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    ai_chat::delete_conversation(&conversation_id, &app_data_dir)
+}
@@ -1,8 +1,12 @@
 //! 备忘录相关命令模块
 //! 
-//! 提供备忘录的 CRUD 操作
+//! 提供备忘录的 CRUD 操作，以及标签、笔记本等组织功能、修订历史、导入导出和提醒
 
+use crate::memo_io;
+use crate::memo_reminders;
+use crate::memo_revisions;
 use crate::memos;
+use crate::semantic_search;
 use super::get_app_data_dir;
 use tauri::AppHandle;
 
@@ -21,7 +25,9 @@ pub fn add_memo(
     app: AppHandle,
 ) -> Result<memos::MemoItem, String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    memos::add_memo(title, content, &app_data_dir)
+    let memo = memos::add_memo(title, content, &app_data_dir)?;
+    semantic_search::notify_changed();
+    Ok(memo)
 }
 
 /// 更新备忘录
@@ -33,14 +39,18 @@ pub fn update_memo(
     app: AppHandle,
 ) -> Result<memos::MemoItem, String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    memos::update_memo(id, title, content, &app_data_dir)
+    let memo = memos::update_memo(id, title, content, &app_data_dir)?;
+    semantic_search::notify_changed();
+    Ok(memo)
 }
 
 /// 删除备忘录
 #[tauri::command]
 pub fn delete_memo(id: String, app: AppHandle) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    memos::delete_memo(id, &app_data_dir)
+    memos::delete_memo(id, &app_data_dir)?;
+    semantic_search::notify_changed();
+    Ok(())
 }
 
 /// 搜索备忘录
@@ -49,3 +59,231 @@ pub fn search_memos(query: String, app: AppHandle) -> Result<Vec<memos::MemoItem
     let app_data_dir = get_app_data_dir(&app)?;
     memos::search_memos(&query, &app_data_dir)
 }
+
+/// 按条件列出备忘录（标签、笔记本、置顶、归档、时间范围）
+#[tauri::command]
+pub fn list_memos(
+    filter: Option<memos::MemoFilter>,
+    app: AppHandle,
+) -> Result<Vec<memos::MemoItem>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::list_memos(&filter.unwrap_or_default(), &app_data_dir)
+}
+
+/// 更新备忘录的置顶、归档、笔记本、颜色和标签
+#[tauri::command]
+pub fn update_memo_meta(
+    id: String,
+    pinned: Option<bool>,
+    archived: Option<bool>,
+    notebook_id: Option<String>,
+    color: Option<String>,
+    tags: Option<Vec<String>>,
+    app: AppHandle,
+) -> Result<memos::MemoItem, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::update_memo_meta(id, pinned, archived, notebook_id, color, tags, &app_data_dir)
+}
+
+/// 获取所有笔记本
+#[tauri::command]
+pub fn get_memo_notebooks(app: AppHandle) -> Result<Vec<memos::MemoNotebook>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::get_memo_notebooks(&app_data_dir)
+}
+
+/// 添加笔记本
+#[tauri::command]
+pub fn add_memo_notebook(
+    name: String,
+    parent_id: Option<String>,
+    color: Option<String>,
+    app: AppHandle,
+) -> Result<memos::MemoNotebook, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::add_memo_notebook(name, parent_id, color, &app_data_dir)
+}
+
+/// 更新笔记本
+#[tauri::command]
+pub fn update_memo_notebook(
+    id: String,
+    name: Option<String>,
+    parent_id: Option<String>,
+    color: Option<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::update_memo_notebook(id, name, parent_id, color, &app_data_dir)
+}
+
+/// 删除笔记本
+#[tauri::command]
+pub fn delete_memo_notebook(id: String, app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::delete_memo_notebook(id, &app_data_dir)
+}
+
+/// 获取所有备忘录标签
+#[tauri::command]
+pub fn get_memo_tags(app: AppHandle) -> Result<Vec<memos::MemoTag>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::get_memo_tags(&app_data_dir)
+}
+
+/// 重命名标签或设置标签颜色
+#[tauri::command]
+pub fn update_memo_tag(
+    name: String,
+    new_name: Option<String>,
+    color: Option<String>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::update_memo_tag(name, new_name, color, &app_data_dir)
+}
+
+/// 获取备忘录的修订历史
+#[tauri::command]
+pub fn get_memo_revisions(
+    memo_id: String,
+    app: AppHandle,
+) -> Result<Vec<memo_revisions::MemoRevisionSummary>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_revisions::get_memo_revisions(&memo_id, &app_data_dir)
+}
+
+/// 查看某个修订的完整内容
+#[tauri::command]
+pub fn get_memo_revision(
+    revision_id: i64,
+    app: AppHandle,
+) -> Result<memo_revisions::MemoRevision, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_revisions::get_memo_revision(revision_id, &app_data_dir)
+}
+
+/// 行级比较两个修订，`to_revision_id` 为空时与当前内容比较
+#[tauri::command]
+pub fn diff_memo_revisions(
+    from_revision_id: i64,
+    to_revision_id: Option<i64>,
+    app: AppHandle,
+) -> Result<Vec<memo_revisions::DiffLine>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_revisions::diff_memo_revisions(from_revision_id, to_revision_id, &app_data_dir)
+}
+
+/// 恢复到指定修订（已删除的备忘录也可恢复）
+#[tauri::command]
+pub fn restore_memo_revision(revision_id: i64, app: AppHandle) -> Result<memos::MemoItem, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::restore_memo_revision(revision_id, &app_data_dir)
+}
+
+/// 列出已删除但可恢复的备忘录
+#[tauri::command]
+pub fn list_deleted_memos(
+    app: AppHandle,
+) -> Result<Vec<memo_revisions::MemoRevisionSummary>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_revisions::list_deleted_memos(&app_data_dir)
+}
+
+/// 导出备忘录，`format` 为 "markdown"（文件夹）、"zip"（打包的 Markdown 文件夹）或 "json"（单个归档文件）
+#[tauri::command]
+pub fn export_memos(
+    target_path: String,
+    format: String,
+    filter: Option<memos::MemoFilter>,
+    app: AppHandle,
+) -> Result<memo_io::MemoExportSummary, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let filter = filter.unwrap_or_default();
+    let target = std::path::Path::new(&target_path);
+    match format.as_str() {
+        "markdown" | "md" => memo_io::export_memos_markdown(target, &filter, &app_data_dir),
+        "json" => memo_io::export_memos_json(target, &filter, &app_data_dir),
+        "zip" => memo_io::export_memos_zip(target, &filter, &app_data_dir),
+        other => Err(format!("Unsupported export format: {}", other)),
+    }
+}
+
+/// 从 Markdown 文件夹、ZIP 或 JSON 归档导入备忘录，`dry_run` 时只预览合并结果
+#[tauri::command]
+pub fn import_memos(
+    source_path: String,
+    strategy: memo_io::ConflictStrategy,
+    dry_run: Option<bool>,
+    app: AppHandle,
+) -> Result<memo_io::MemoImportSummary, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_io::import_memos(
+        std::path::Path::new(&source_path),
+        strategy,
+        dry_run.unwrap_or(false),
+        &app_data_dir,
+    )
+}
+
+/// 切换待办模式或完成状态
+#[tauri::command]
+pub fn set_memo_todo(
+    id: String,
+    is_todo: Option<bool>,
+    completed: Option<bool>,
+    app: AppHandle,
+) -> Result<memos::MemoItem, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memos::set_memo_todo(id, is_todo, completed, &app_data_dir)
+}
+
+/// 设置备忘录提醒，`remind_at` / `due_at` 为 Unix 秒
+#[tauri::command]
+pub fn set_memo_reminder(
+    memo_id: String,
+    remind_at: u64,
+    due_at: Option<u64>,
+    recurrence: Option<memo_reminders::Recurrence>,
+    app: AppHandle,
+) -> Result<memo_reminders::MemoReminder, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_reminders::set_memo_reminder(memo_id, remind_at, due_at, recurrence, &app_data_dir)
+}
+
+#[tauri::command]
+pub fn clear_memo_reminder(memo_id: String, app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_reminders::clear_memo_reminder(memo_id, &app_data_dir)
+}
+
+/// 列出 `until` 之前待触发的提醒，为空时列出全部
+#[tauri::command]
+pub fn get_upcoming_memo_reminders(
+    until: Option<u64>,
+    app: AppHandle,
+) -> Result<Vec<memo_reminders::MemoReminder>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_reminders::get_upcoming_reminders(until, &app_data_dir)
+}
+
+/// 稍后提醒
+#[tauri::command]
+pub fn snooze_memo_reminder(
+    memo_id: String,
+    minutes: u32,
+    app: AppHandle,
+) -> Result<memo_reminders::MemoReminder, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_reminders::snooze_memo_reminder(memo_id, minutes, &app_data_dir)
+}
+
+/// 完成本次提醒，重复提醒会推进到下一次
+#[tauri::command]
+pub fn complete_memo_reminder(
+    memo_id: String,
+    app: AppHandle,
+) -> Result<memo_reminders::MemoReminder, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    memo_reminders::complete_memo_reminder(memo_id, &app_data_dir)
+}
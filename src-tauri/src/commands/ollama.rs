@@ -0,0 +1,124 @@
+//! Ollama 相关命令模块
+//!
+//! 流式请求通过事件推送：
+//! - `ollama-stream-chunk`：每段新内容
+//! - `ollama-stream-done`：结束（包括被取消）
+//! - `ollama-stream-error`：出错
+
+use crate::ollama::{self, ChatMessage, GenerateOptions, OllamaClient, OllamaCompletion};
+use crate::settings;
+use super::get_app_data_dir;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamChunkEvent<'a> {
+    request_id: &'a str,
+    content: &'a str,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamDoneEvent<'a> {
+    request_id: &'a str,
+    completion: &'a OllamaCompletion,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamErrorEvent<'a> {
+    request_id: &'a str,
+    error: &'a str,
+}
+
+fn client_from_settings(app: &AppHandle) -> Result<OllamaClient, String> {
+    let app_data_dir = get_app_data_dir(app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    OllamaClient::from_settings(&settings.ollama)
+}
+
+fn emit_result(app: &AppHandle, request_id: &str, result: &Result<OllamaCompletion, String>) {
+    match result {
+        Ok(completion) => {
+            let _ = app.emit("ollama-stream-done", StreamDoneEvent { request_id, completion });
+        }
+        Err(error) => {
+            let _ = app.emit("ollama-stream-error", StreamErrorEvent { request_id, error });
+        }
+    }
+}
+
+/// 获取 Ollama 已安装的模型列表
+#[tauri::command]
+pub async fn get_ollama_models(app: AppHandle) -> Result<Vec<ollama::OllamaModel>, String> {
+    client_from_settings(&app)?.list_models().await
+}
+
+/// 检查 Ollama 服务是否可用
+#[tauri::command]
+pub async fn check_ollama_health(app: AppHandle) -> Result<ollama::OllamaHealth, String> {
+    Ok(client_from_settings(&app)?.health().await)
+}
+
+/// 流式对话，`model` 为空时使用设置中的模型
+#[tauri::command]
+pub async fn ollama_chat_stream(
+    request_id: String,
+    messages: Vec<ChatMessage>,
+    model: Option<String>,
+    options: Option<GenerateOptions>,
+    app: AppHandle,
+) -> Result<OllamaCompletion, String> {
+    let client = client_from_settings(&app)?;
+    let token = ollama::register_request(&request_id);
+    let result = client
+        .chat_stream(&messages, model.as_deref(), options.as_ref(), &token, |content| {
+            let _ = app.emit(
+                "ollama-stream-chunk",
+                StreamChunkEvent { request_id: &request_id, content },
+            );
+        })
+        .await;
+    ollama::finish_request(&request_id, &token);
+    emit_result(&app, &request_id, &result);
+    result
+}
+
+/// 流式补全
+#[tauri::command]
+pub async fn ollama_generate_stream(
+    request_id: String,
+    prompt: String,
+    system: Option<String>,
+    model: Option<String>,
+    options: Option<GenerateOptions>,
+    app: AppHandle,
+) -> Result<OllamaCompletion, String> {
+    let client = client_from_settings(&app)?;
+    let token = ollama::register_request(&request_id);
+    let result = client
+        .generate_stream(
+            &prompt,
+            system.as_deref(),
+            model.as_deref(),
+            options.as_ref(),
+            &token,
+            |content| {
+                let _ = app.emit(
+                    "ollama-stream-chunk",
+                    StreamChunkEvent { request_id: &request_id, content },
+                );
+            },
+        )
+        .await;
+    ollama::finish_request(&request_id, &token);
+    emit_result(&app, &request_id, &result);
+    result
+}
+
+/// 取消流式请求，返回请求是否存在
+#[tauri::command]
+pub fn cancel_ollama_request(request_id: String) -> bool {
+    ollama::cancel_request(&request_id)
+}
@@ -1,11 +1,17 @@
 use crate::commands::get_app_data_dir;
 use crate::hooks;
-use crate::recording::{RecordingMeta, RecordingState};
-use crate::replay::ReplayState;
+use crate::hotkey_model::HotkeyOwner;
+use super::validate_hotkey_config;
+use crate::recording::{self, Recording, RecordingMeta, RecordingState};
+use crate::recording_editor::{self, EditOperation};
+use crate::input_backend;
+use crate::macro_script;
+use crate::replay::{self, ReplayState, MAX_REPLAY_EVENTS};
+use crate::settings;
 use std::fs;
-use std::path::Path;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock, Mutex};
-use std::time::Duration;
 use tauri::Manager;
 
 static RECORDING_STATE: LazyLock<Arc<Mutex<RecordingState>>> =
@@ -74,6 +80,7 @@ pub fn stop_recording(app: tauri::AppHandle) -> Result<String, String> {
         // Get events before stopping
         let events = state_guard.events.clone();
         let duration_ms = state_guard.get_time_offset_ms().unwrap_or(0);
+        let screen = state_guard.screen;
 
         state_guard.stop();
         drop(state_guard);
@@ -94,18 +101,14 @@ pub fn stop_recording(app: tauri::AppHandle) -> Result<String, String> {
         let filename = format!("recording_{}.json", timestamp);
         let file_path = recordings_dir.join(&filename);
 
-        // Create recording data structure
-        let recording_data = serde_json::json!({
-            "events": events,
-            "duration_ms": duration_ms,
-            "created_at": chrono::Local::now().to_rfc3339(),
-        });
+        // Create recording data structure; mouse positions are already normalised
+        // against the screen captured when recording started
+        let mut header = recording::RecordingHeader::for_current_system(duration_ms);
+        header.screen = screen;
+        let recording_data = recording::Recording { header, events };
 
         // Write to file
-        let json_string = serde_json::to_string_pretty(&recording_data)
-            .map_err(|e| format!("Failed to serialize recording data: {}", e))?;
-        fs::write(&file_path, json_string)
-            .map_err(|e| format!("Failed to write recording file: {}", e))?;
+        recording::save_recording(&file_path, &recording_data)?;
 
         // Return relative path for display
         Ok(format!("recordings/{}", filename))
@@ -115,11 +118,13 @@ pub fn stop_recording(app: tauri::AppHandle) -> Result<String, String> {
 #[tauri::command]
 pub fn list_recordings(app: tauri::AppHandle) -> Result<Vec<RecordingMeta>, String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    let recordings_dir = app_data_dir.join("recordings");
+    scan_recordings(&app_data_dir.join("recordings"))
+}
 
+fn scan_recordings(recordings_dir: &Path) -> Result<Vec<RecordingMeta>, String> {
     // Create directory if it doesn't exist
     if !recordings_dir.exists() {
-        fs::create_dir_all(&recordings_dir)
+        fs::create_dir_all(recordings_dir)
             .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
         return Ok(vec![]);
     }
@@ -127,7 +132,7 @@ pub fn list_recordings(app: tauri::AppHandle) -> Result<Vec<RecordingMeta>, Stri
     let mut recordings = Vec::new();
 
     // Read directory entries
-    let entries = fs::read_dir(&recordings_dir)
+    let entries = fs::read_dir(recordings_dir)
         .map_err(|e| format!("Failed to read recordings directory: {}", e))?;
 
     for entry in entries {
@@ -136,7 +141,7 @@ pub fn list_recordings(app: tauri::AppHandle) -> Result<Vec<RecordingMeta>, Stri
 
         // Only process JSON files
         if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            if let Ok(meta) = extract_recording_meta(&path, &recordings_dir) {
+            if let Ok(meta) = extract_recording_meta(&path, recordings_dir) {
                 recordings.push(meta);
             }
         }
@@ -173,8 +178,17 @@ pub fn delete_recording(app: tauri::AppHandle, path: String) -> Result<(), Strin
         return Err("Invalid file path: outside recordings directory".to_string());
     }
 
-    // Delete the file
+    // Delete the file and its edit history
     fs::remove_file(&file_path).map_err(|e| format!("Failed to delete recording file: {}", e))?;
+    recording_editor::remove_history(&file_path)?;
+
+    // Drop the hotkey binding so it doesn't point at a missing file
+    if let Some(file_name) = file_path.file_name().and_then(|n| n.to_str()) {
+        settings::modify_settings(&app_data_dir, |settings| {
+            settings.recording_bindings.remove(file_name);
+            Ok(())
+        })?;
+    }
 
     Ok(())
 }
@@ -183,31 +197,16 @@ fn extract_recording_meta(
     file_path: &Path,
     recordings_dir: &Path,
 ) -> Result<RecordingMeta, String> {
-    // Read file content
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("Failed to read file {}: {}", file_path.display(), e))?;
-
-    // Parse JSON
-    let json: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse JSON from {}: {}", file_path.display(), e))?;
-
-    // Extract metadata
-    let duration_ms = json["duration_ms"]
-        .as_u64()
-        .ok_or_else(|| format!("Missing or invalid duration_ms in {}", file_path.display()))?;
-
-    let event_count = json["events"].as_array().map(|arr| arr.len()).unwrap_or(0);
-
-    let created_at = json["created_at"]
-        .as_str()
-        .ok_or_else(|| format!("Missing or invalid created_at in {}", file_path.display()))?
-        .to_string();
+    // Older formats are upgraded by the loader
+    let recording = recording::load_recording(file_path)?;
+    Ok(recording_meta(&recording, file_path, recordings_dir))
+}
 
-    // Get file name and relative path
+fn recording_meta(recording: &Recording, file_path: &Path, recordings_dir: &Path) -> RecordingMeta {
     let file_name = file_path
         .file_name()
         .and_then(|n| n.to_str())
-        .ok_or_else(|| format!("Invalid file name: {}", file_path.display()))?
+        .unwrap_or_default()
         .to_string();
 
     let relative_path = file_path
@@ -217,183 +216,390 @@ fn extract_recording_meta(
         .map(|s| format!("recordings/{}", s))
         .unwrap_or_else(|| file_name.clone());
 
-    Ok(RecordingMeta {
+    // Fall back to the file name for recordings saved before names existed
+    let name = if recording.header.name.trim().is_empty() {
+        file_path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string()
+    } else {
+        recording.header.name.clone()
+    };
+
+    RecordingMeta {
         file_path: relative_path,
         file_name,
-        duration_ms,
-        event_count,
-        created_at,
+        name,
+        description: recording.header.description.clone(),
+        tags: recording.header.tags.clone(),
+        duration_ms: recording.header.duration_ms,
+        event_count: recording.events.len(),
+        created_at: recording.header.created_at.clone(),
+        revision: recording.header.revision,
+    }
+}
+
+/// Resolve a `recordings/...` path from the frontend to a file inside the recordings directory
+fn resolve_recording_path(app: &tauri::AppHandle, path: &str) -> Result<(PathBuf, PathBuf), String> {
+    let recordings_dir = get_app_data_dir(app)?.join("recordings");
+    let file_name = path.strip_prefix("recordings/").unwrap_or(path);
+    let file_path = recordings_dir.join(file_name);
+
+    if file_name.contains("..") || !file_path.starts_with(&recordings_dir) {
+        return Err("Invalid file path: outside recordings directory".to_string());
+    }
+    if !file_path.exists() {
+        return Err(format!("Recording file not found: {}", path));
+    }
+    Ok((recordings_dir, file_path))
+}
+
+/// A recording opened in the macro editor
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingDocument {
+    pub meta: RecordingMeta,
+    pub recording: Recording,
+    pub can_undo: bool,
+}
+
+fn recording_document(
+    recording: Recording,
+    file_path: &Path,
+    recordings_dir: &Path,
+) -> Result<RecordingDocument, String> {
+    Ok(RecordingDocument {
+        meta: recording_meta(&recording, file_path, recordings_dir),
+        can_undo: recording_editor::can_undo(file_path)?,
+        recording,
     })
 }
 
+/// Load a recording with all of its events for editing
 #[tauri::command]
-pub fn play_recording(app: tauri::AppHandle, path: String, speed: f32) -> Result<(), String> {
-    #[cfg(not(target_os = "windows"))]
-    {
-        return Err("Replay is only supported on Windows".to_string());
+pub fn load_recording_document(app: tauri::AppHandle, path: String) -> Result<RecordingDocument, String> {
+    let (recordings_dir, file_path) = resolve_recording_path(&app, &path)?;
+    let recording = recording::load_recording(&file_path)?;
+    recording_document(recording, &file_path, &recordings_dir)
+}
+
+/// Apply edit operations in order and save the result as a new revision
+#[tauri::command]
+pub fn edit_recording(
+    app: tauri::AppHandle,
+    path: String,
+    operations: Vec<EditOperation>,
+) -> Result<RecordingDocument, String> {
+    let (recordings_dir, file_path) = resolve_recording_path(&app, &path)?;
+    let mut recording = recording::load_recording(&file_path)?;
+    for operation in &operations {
+        recording_editor::apply_edit(&mut recording, operation)?;
     }
+    recording_editor::save_new_revision(&file_path, &mut recording)?;
+    recording_document(recording, &file_path, &recordings_dir)
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        let mut state = REPLAY_STATE.lock().map_err(|e| e.to_string())?;
+/// Restore the revision before the last edit
+#[tauri::command]
+pub fn undo_recording_edit(app: tauri::AppHandle, path: String) -> Result<RecordingDocument, String> {
+    let (recordings_dir, file_path) = resolve_recording_path(&app, &path)?;
+    let recording = recording_editor::undo(&file_path)?;
+    recording_document(recording, &file_path, &recordings_dir)
+}
 
-        if state.is_playing {
-            return Err("Already playing".to_string());
+/// Update name, description and tags (saved as a new revision so it can be undone)
+#[tauri::command]
+pub fn update_recording_info(
+    app: tauri::AppHandle,
+    path: String,
+    name: String,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<RecordingMeta, String> {
+    let (recordings_dir, file_path) = resolve_recording_path(&app, &path)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Recording name cannot be empty".to_string());
+    }
+
+    let mut recording = recording::load_recording(&file_path)?;
+    recording.header.name = name.to_string();
+    if let Some(description) = description {
+        recording.header.description = description.trim().to_string();
+    }
+    if let Some(tags) = tags {
+        let mut cleaned: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.trim().to_string();
+            if !tag.is_empty() && !cleaned.contains(&tag) {
+                cleaned.push(tag);
+            }
         }
+        recording.header.tags = cleaned;
+    }
+    recording_editor::save_new_revision(&file_path, &mut recording)?;
+    Ok(recording_meta(&recording, &file_path, &recordings_dir))
+}
 
-        // Convert relative path to absolute path
-        let app_data_dir = get_app_data_dir(&app)?;
-        let recordings_dir = app_data_dir.join("recordings");
+/// Join recordings one after another into a new recording file
+#[tauri::command]
+pub fn merge_recordings(
+    app: tauri::AppHandle,
+    paths: Vec<String>,
+    gap_ms: Option<u64>,
+    name: Option<String>,
+) -> Result<RecordingMeta, String> {
+    if paths.len() < 2 {
+        return Err("At least two recordings are required to merge".to_string());
+    }
 
-        // Remove "recordings/" prefix if present
-        let file_path = if path.starts_with("recordings/") {
-            let filename = path
-                .strip_prefix("recordings/")
-                .ok_or_else(|| format!("Invalid path format: {}", path))?;
-            recordings_dir.join(filename)
-        } else {
-            recordings_dir.join(&path)
-        };
+    let mut merged: Option<Recording> = None;
+    let mut recordings_dir = PathBuf::new();
+    for path in &paths {
+        let (dir, file_path) = resolve_recording_path(&app, path)?;
+        recordings_dir = dir;
+        let recording = recording::load_recording(&file_path)?;
+        merged = Some(match merged {
+            Some(first) => recording_editor::merge_recordings(&first, &recording, gap_ms.unwrap_or(500))?,
+            None => recording,
+        });
+    }
 
-        // Validate speed - limit to reasonable range to prevent system overload
-        if speed <= 0.0 || speed > 10.0 {
-            return Err("Speed must be between 0.1 and 10.0".to_string());
-        }
+    let mut merged = merged.ok_or_else(|| "No recordings to merge".to_string())?;
+    merged.header.created_at = chrono::Local::now().to_rfc3339();
+    merged.header.upgraded_from = None;
+    merged.header.name = match name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => format!("{} (合并)", merged.header.name),
+    };
 
-        state.load_recording(&file_path)?;
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let mut file_path = recordings_dir.join(format!("recording_{}_merged.json", timestamp));
+    let mut counter = 1;
+    while file_path.exists() {
+        counter += 1;
+        file_path = recordings_dir.join(format!("recording_{}_merged_{}.json", timestamp, counter));
+    }
+    recording::save_recording(&file_path, &merged)?;
+    Ok(recording_meta(&merged, &file_path, &recordings_dir))
+}
 
-        // Check if there are any events
-        if state.current_events.is_empty() {
-            return Err("Recording file contains no events".to_string());
-        }
+/// 最多连续重复播放的次数
+const MAX_PLAYBACK_REPEAT: u32 = 1000;
+
+/// 检查回放速度和重复次数，NaN 也会被拒绝
+fn validate_playback_options(speed: f32, repeat: u32) -> Result<(), String> {
+    // Limit to a reasonable range to prevent system overload
+    if !(replay::MIN_SPEED..=replay::MAX_SPEED).contains(&speed) {
+        return Err(format!(
+            "Speed must be between {} and {}",
+            replay::MIN_SPEED,
+            replay::MAX_SPEED
+        ));
+    }
+    if repeat == 0 || repeat > MAX_PLAYBACK_REPEAT {
+        return Err(format!("Repeat count must be between 1 and {}", MAX_PLAYBACK_REPEAT));
+    }
+    Ok(())
+}
+
+/// 录制快捷键的 ID 前缀，后接录制文件名
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub const RECORDING_HOTKEY_PREFIX: &str = "recording:";
+/// 停止回放快捷键的 ID
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub const PLAYBACK_PANIC_HOTKEY_ID: &str = "playback_panic";
+
+/// Load a recording into the shared replay state and play it on a background thread
+fn start_playback(file_path: &Path, speed: f32, repeat: u32) -> Result<(), String> {
+    validate_playback_options(speed, repeat)?;
+
+    let mut state = REPLAY_STATE.lock().map_err(|e| e.to_string())?;
+
+    if state.is_playing {
+        return Err("Already playing".to_string());
+    }
 
-        // Limit the number of events to prevent system overload
-        if state.current_events.len() > 100000 {
-            return Err(format!(
-                "Too many events ({}). Maximum allowed is 100000.",
-                state.current_events.len()
-            ));
+    state.load_recording(file_path)?;
+
+    // Check if there are any events
+    if state.current_events.is_empty() {
+        return Err("Recording file contains no events".to_string());
+    }
+
+    // Limit the number of events to prevent system overload
+    if state.current_events.len() > MAX_REPLAY_EVENTS {
+        return Err(format!(
+            "Too many events ({}). Maximum allowed is {}.",
+            state.current_events.len(),
+            MAX_REPLAY_EVENTS
+        ));
+    }
+
+    // Create the platform input backend before starting so unsupported
+    // platforms or missing permissions are reported to the caller
+    let mut backend = input_backend::default_backend(state.screen)?;
+
+    state.repeat_count = repeat;
+    state.start(speed);
+    drop(state);
+
+    // Replay in a separate thread (not async) since input injection
+    // should be done in a blocking context
+    let replay_state = Arc::clone(&REPLAY_STATE);
+    std::thread::spawn(move || {
+        let outcome = replay::run_replay(&replay_state, backend.as_mut());
+        eprintln!(
+            "Playback {}: {} executed, {} skipped, {} failed",
+            if outcome.aborted { "aborted" } else { "finished" },
+            outcome.executed,
+            outcome.skipped,
+            outcome.failed
+        );
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn play_recording(
+    app: tauri::AppHandle,
+    path: String,
+    speed: f32,
+    repeat: Option<u32>,
+) -> Result<(), String> {
+    let (_, file_path) = resolve_recording_path(&app, &path)?;
+    start_playback(&file_path, speed, repeat.unwrap_or(1))
+}
+
+/// 按录制的绑定设置播放（没有绑定时按 1 倍速播放一遍）
+fn play_with_binding(app_data_dir: &Path, file_name: &str) -> Result<(), String> {
+    if file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err("Invalid file path: outside recordings directory".to_string());
+    }
+    let settings = settings::load_settings(app_data_dir)?;
+    let binding = settings.recording_bindings.get(file_name).cloned().unwrap_or_default();
+    start_playback(
+        &app_data_dir.join("recordings").join(file_name),
+        binding.speed,
+        binding.repeat,
+    )
+}
+
+/// 启动器中按名字搜索录制
+#[tauri::command]
+pub fn search_recordings(app: tauri::AppHandle, query: String) -> Result<Vec<RecordingMeta>, String> {
+    let recordings = scan_recordings(&get_app_data_dir(&app)?.join("recordings"))?;
+    Ok(recording::match_recordings(&recordings, &query)
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+/// 启动器中按名字播放录制，使用该录制绑定的速度和次数；返回实际播放的录制
+#[tauri::command]
+pub fn play_recording_by_name(app: tauri::AppHandle, name: String) -> Result<RecordingMeta, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let recordings = scan_recordings(&app_data_dir.join("recordings"))?;
+    let meta = recording::match_recordings(&recordings, &name)
+        .into_iter()
+        .next()
+        .cloned()
+        .ok_or_else(|| format!("Recording not found: {}", name))?;
+    play_with_binding(&app_data_dir, &meta.file_name)?;
+    Ok(meta)
+}
+
+/// 全局快捷键触发录制回放，由快捷键监听线程调用
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub fn play_recording_hotkey(app_data_dir: &Path, hotkey_id: &str) -> Result<(), String> {
+    let file_name = hotkey_id
+        .strip_prefix(RECORDING_HOTKEY_PREFIX)
+        .ok_or_else(|| format!("Not a recording hotkey: {}", hotkey_id))?;
+    play_with_binding(app_data_dir, file_name)
+}
+
+/// 所有录制相关的全局快捷键，按快捷键 ID 索引
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub fn recording_hotkeys(
+    settings: &settings::Settings,
+) -> std::collections::HashMap<String, settings::HotkeyConfig> {
+    let mut hotkeys: std::collections::HashMap<String, settings::HotkeyConfig> = settings
+        .recording_bindings
+        .iter()
+        .filter_map(|(file_name, binding)| {
+            let hotkey = binding.hotkey.clone()?;
+            Some((format!("{}{}", RECORDING_HOTKEY_PREFIX, file_name), hotkey))
+        })
+        .collect();
+    if let Some(ref panic) = settings.playback_panic_hotkey {
+        hotkeys.insert(PLAYBACK_PANIC_HOTKEY_ID.to_string(), panic.clone());
+    }
+    hotkeys
+}
+
+#[tauri::command]
+pub fn get_recording_bindings(
+    app: tauri::AppHandle,
+) -> Result<std::collections::HashMap<String, settings::RecordingBinding>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    Ok(settings::load_settings(&app_data_dir)?.recording_bindings)
+}
+
+/// 保存录制的快捷键、速度和重复次数；`binding` 为 None 时删除绑定
+#[tauri::command]
+pub fn save_recording_binding(
+    app: tauri::AppHandle,
+    path: String,
+    binding: Option<settings::RecordingBinding>,
+) -> Result<(), String> {
+    let (_, file_path) = resolve_recording_path(&app, &path)?;
+    let file_name = file_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid path format: {}", path))?
+        .to_string();
+
+    if let Some(ref binding) = binding {
+        validate_playback_options(binding.speed, binding.repeat)?;
+    }
+
+    let app_data_dir = get_app_data_dir(&app)?;
+    // 快捷键由设置服务的订阅者重新注册
+    settings::modify_settings(&app_data_dir, |settings| {
+        if let Some(mut binding) = binding {
+            binding.hotkey = binding
+                .hotkey
+                .map(|h| validate_hotkey_config(settings, &h, &HotkeyOwner::Recording(file_name.clone())))
+                .transpose()?;
+            settings.recording_bindings.insert(file_name.clone(), binding);
+        } else {
+            settings.recording_bindings.remove(&file_name);
         }
+        Ok(())
+    })
+}
 
-        state.start(speed);
-
-        // Start replay task in a separate thread (not async) since Windows API calls
-        // should be done in a blocking context
-        let replay_state = Arc::clone(&REPLAY_STATE);
-        let speed_multiplier = speed.max(0.1).min(10.0); // Ensure speed is between 0.1 and 10.0
-
-        std::thread::spawn(move || {
-            let mut last_time = 0u64;
-            let mut last_mouse_move_time = 0u64;
-            let mut event_count = 0u64;
-            const MAX_EVENTS: u64 = 100000; // Safety limit
-                                            // Minimum interval between mouse move events in the recording (based on event time offset)
-                                            // This helps prevent system overload from too many rapid mouse moves
-            const MIN_MOUSE_MOVE_INTERVAL_MS: u64 = 5; // 5ms minimum between recorded mouse moves
-
-            loop {
-                // Check if Esc key is pressed to stop playback
-                #[cfg(target_os = "windows")]
-                {
-                    use windows_sys::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
-                    const VK_ESCAPE: i32 = 0x1B;
-                    unsafe {
-                        // GetAsyncKeyState returns negative value if key is currently pressed
-                        // The high bit (0x8000) indicates the key is currently down
-                        let key_state = GetAsyncKeyState(VK_ESCAPE) as u16;
-                        if key_state & 0x8000 != 0 {
-                            eprintln!("Esc key pressed, stopping playback");
-                            if let Ok(mut state) = replay_state.lock() {
-                                state.stop();
-                            }
-                            break;
-                        }
-                    }
-                }
-
-                // Safety check: prevent infinite loops
-                event_count += 1;
-                if event_count > MAX_EVENTS {
-                    eprintln!("Reached maximum event limit, stopping playback");
-                    if let Ok(mut state) = replay_state.lock() {
-                        state.stop();
-                    }
-                    break;
-                }
-
-                // Get event while holding lock briefly
-                let (event_opt, is_playing) = {
-                    let mut state = match replay_state.lock() {
-                        Ok(s) => s,
-                        Err(_) => break,
-                    };
-
-                    if !state.is_playing {
-                        break;
-                    }
-
-                    let event = state.get_next_event();
-                    let is_playing = state.is_playing;
-                    (event, is_playing)
-                };
-
-                if !is_playing {
-                    break;
-                }
-
-                if let Some(event) = event_opt {
-                    // For mouse move events, only skip if the time difference from last mouse move
-                    // is too small (based on recorded event times, not system time)
-                    if matches!(event.event_type, crate::recording::EventType::MouseMove) {
-                        if last_mouse_move_time > 0 {
-                            let time_diff = event.time_offset_ms.saturating_sub(last_mouse_move_time);
-                            // Skip only if the recorded interval is less than minimum
-                            if time_diff < MIN_MOUSE_MOVE_INTERVAL_MS && time_diff > 0 {
-                                // Update last_time but skip execution
-                                last_time = event.time_offset_ms;
-                                continue;
-                            }
-                        }
-                        last_mouse_move_time = event.time_offset_ms;
-                    }
-
-                    // Calculate delay based on time offset
-                    let delay_ms = if last_time == 0 {
-                        // First event, add a small delay to let system stabilize
-                        50
-                    } else {
-                        let diff = event.time_offset_ms.saturating_sub(last_time);
-                        // Use saturating cast to prevent overflow, ensure minimum delay
-                        let calculated = (diff as f32 / speed_multiplier) as u64;
-                        calculated.max(1).min(60000) // Between 1ms and 60 seconds
-                    };
-
-                    if delay_ms > 0 {
-                        std::thread::sleep(Duration::from_millis(delay_ms));
-                    }
-
-                    // Execute the event with error handling
-                    match crate::replay::ReplayState::execute_event(&event) {
-                        Ok(_) => {}
-                        Err(e) => {
-                            eprintln!("Failed to execute event: {}", e);
-                            // Continue with next event instead of crashing
-                        }
-                    }
-
-                    last_time = event.time_offset_ms;
-                } else {
-                    // No more events, stop playback
-                    if let Ok(mut state) = replay_state.lock() {
-                        state.stop();
-                    }
-                    break;
-                }
-            }
-        });
+#[tauri::command]
+pub fn get_playback_panic_hotkey(app: tauri::AppHandle) -> Result<Option<settings::HotkeyConfig>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    Ok(settings::load_settings(&app_data_dir)?.playback_panic_hotkey)
+}
 
+/// 设置停止回放的全局快捷键
+#[tauri::command]
+pub fn save_playback_panic_hotkey(
+    app: tauri::AppHandle,
+    config: Option<settings::HotkeyConfig>,
+) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings::modify_settings(&app_data_dir, |settings| {
+        settings.playback_panic_hotkey = config
+            .map(|c| validate_hotkey_config(settings, &c, &HotkeyOwner::PlaybackPanic))
+            .transpose()?;
         Ok(())
-    }
+    })
 }
 
 #[tauri::command]
@@ -419,3 +625,70 @@ pub fn get_playback_progress() -> Result<f32, String> {
     let state = REPLAY_STATE.lock().map_err(|e| e.to_string())?;
     Ok(state.get_progress())
 }
+
+/// 运行宏脚本，与回放共用播放状态，`stop_playback` 或 Esc 可以停止
+#[tauri::command]
+pub fn run_macro(script: String) -> Result<(), String> {
+    // Parse up front so syntax errors reach the caller instead of the log
+    let stmts = macro_script::parse(&script)?;
+
+    let mut state = REPLAY_STATE.lock().map_err(|e| e.to_string())?;
+    if state.is_playing {
+        return Err("Already playing".to_string());
+    }
+    let screen = recording::ScreenGeometry::current();
+    let mut backend = input_backend::default_backend(screen)?;
+    state.current_events.clear();
+    state.screen = screen;
+    state.start(1.0);
+    drop(state);
+
+    let replay_state = Arc::clone(&REPLAY_STATE);
+    std::thread::spawn(move || {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(1);
+        let context = macro_script::MacroContext {
+            screen,
+            clipboard: Box::new(|| super::get_clipboard_text().ok().flatten()),
+            seed,
+        };
+        let mut sink = macro_script::BackendSink {
+            backend: backend.as_mut(),
+            screen,
+            should_stop: || replay_state.lock().map(|s| !s.is_playing).unwrap_or(true),
+        };
+        match macro_script::run(&stmts, context, &mut sink) {
+            Ok(outcome) => eprintln!(
+                "Macro {}: {} events",
+                if outcome.stopped { "stopped" } else { "finished" },
+                outcome.events
+            ),
+            Err(e) => eprintln!("Macro failed: {}", e),
+        }
+        if let Ok(mut state) = replay_state.lock() {
+            state.stop();
+        }
+    });
+
+    Ok(())
+}
+
+/// 试运行宏脚本，返回解析后的事件列表而不实际输入
+#[tauri::command]
+pub fn dry_run_macro(
+    script: String,
+    limit: Option<usize>,
+    seed: Option<u64>,
+) -> Result<macro_script::MacroDryRun, String> {
+    let context = macro_script::MacroContext {
+        screen: recording::ScreenGeometry::current(),
+        clipboard: Box::new(|| super::get_clipboard_text().ok().flatten()),
+        seed: seed.unwrap_or(1),
+    };
+    let limit = limit
+        .unwrap_or(macro_script::DRY_RUN_EVENT_LIMIT)
+        .min(macro_script::DRY_RUN_EVENT_LIMIT);
+    macro_script::dry_run(&script, context, limit)
+}
@@ -0,0 +1,63 @@
+//! 语义搜索相关命令模块
+//!
+//! 在备忘录、剪切板和单词本中混合使用向量相似度和关键字进行搜索
+
+use crate::ollama::OllamaClient;
+use crate::semantic_search;
+use crate::settings;
+use super::get_app_data_dir;
+use tauri::{async_runtime, AppHandle};
+
+/// 语义搜索；向量服务不可用时只按关键字排序
+#[tauri::command]
+pub async fn search_semantic_index(
+    query: String,
+    options: Option<semantic_search::SemanticSearchOptions>,
+    app: AppHandle,
+) -> Result<Vec<semantic_search::SemanticHit>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let ollama = settings::load_settings(&app_data_dir)?.ollama;
+    let client = OllamaClient::from_settings(&ollama)?;
+    let query_vector = match client.embed(&query, &ollama.embedding_model).await {
+        Ok(vector) => Some(vector),
+        Err(e) => {
+            eprintln!("[SemanticSearch] Falling back to keyword search: {}", e);
+            None
+        }
+    };
+
+    async_runtime::spawn_blocking(move || {
+        semantic_search::search(
+            &query,
+            query_vector.as_deref(),
+            &ollama.embedding_model,
+            &options.unwrap_or_default(),
+            &app_data_dir,
+        )
+    })
+    .await
+    .map_err(|e| format!("semantic_search join error: {}", e))?
+}
+
+/// 立即同步语义索引，返回同步结果
+#[tauri::command]
+pub async fn rebuild_semantic_index(
+    app: AppHandle,
+) -> Result<semantic_search::IndexSyncSummary, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    async_runtime::spawn_blocking(move || {
+        let embedder = semantic_search::OllamaEmbedder::from_settings(&app_data_dir)?;
+        semantic_search::sync_index(&embedder, &app_data_dir)
+    })
+    .await
+    .map_err(|e| format!("rebuild_semantic_index join error: {}", e))?
+}
+
+/// 获取语义索引状态
+#[tauri::command]
+pub fn get_semantic_index_status(
+    app: AppHandle,
+) -> Result<semantic_search::IndexStatus, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    semantic_search::get_index_status(&app_data_dir)
+}
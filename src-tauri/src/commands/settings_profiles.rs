@@ -0,0 +1,81 @@
+//! 配置档案相关命令模块
+//!
+//! 提供档案的保存、切换和删除，以及导出/导入（含导入前的差异预览）
+
+use crate::settings_profiles;
+use super::get_app_data_dir;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// 列出所有档案（第一次调用时把当前配置保存为默认档案）
+#[tauri::command]
+pub fn list_settings_profiles(
+    app: AppHandle,
+) -> Result<Vec<settings_profiles::ProfileSummary>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings_profiles::list_profiles(&app_data_dir)
+}
+
+/// 把当前配置保存为指定档案
+#[tauri::command]
+pub fn save_settings_profile(name: String, app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings_profiles::save_profile(&name, &app_data_dir)
+}
+
+/// 切换档案，设置、Everything 过滤器、快捷方式和快捷键立即生效
+#[tauri::command]
+pub fn switch_settings_profile(name: String, app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings_profiles::switch_profile(&name, &app_data_dir)
+}
+
+#[tauri::command]
+pub fn delete_settings_profile(name: String, app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings_profiles::delete_profile(&name, &app_data_dir)
+}
+
+/// 导出档案为 JSON 文件，`name` 为空时导出当前配置
+#[tauri::command]
+pub fn export_settings_profile(
+    name: Option<String>,
+    target_path: String,
+    options: Option<settings_profiles::ProfileExportOptions>,
+    app: AppHandle,
+) -> Result<settings_profiles::ProfileExportSummary, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings_profiles::export_profile(
+        name.as_deref(),
+        Path::new(&target_path),
+        options.unwrap_or_default(),
+        &app_data_dir,
+    )
+}
+
+/// 预览导入档案文件后与当前配置的差异，不做任何修改
+#[tauri::command]
+pub fn preview_settings_profile_import(
+    source_path: String,
+    app: AppHandle,
+) -> Result<settings_profiles::ProfileImportPreview, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings_profiles::preview_import(Path::new(&source_path), &app_data_dir)
+}
+
+/// 导入档案文件，`name` 为空时使用文件中的档案名；`activate` 时导入后立即切换
+#[tauri::command]
+pub fn import_settings_profile(
+    source_path: String,
+    name: Option<String>,
+    activate: Option<bool>,
+    app: AppHandle,
+) -> Result<settings_profiles::ProfileSummary, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings_profiles::import_profile(
+        Path::new(&source_path),
+        name.as_deref(),
+        activate.unwrap_or(false),
+        &app_data_dir,
+    )
+}
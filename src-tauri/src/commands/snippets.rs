@@ -0,0 +1,201 @@
+//! 文本片段相关命令模块
+//!
+//! 提供片段的 CRUD、模板渲染以及启动器中的搜索和粘贴
+
+use crate::snippets;
+use super::get_app_data_dir;
+use std::collections::HashMap;
+use tauri::{async_runtime, AppHandle};
+
+/// 获取所有片段
+#[tauri::command]
+pub fn get_all_snippets(app: AppHandle) -> Result<Vec<snippets::SnippetItem>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    snippets::get_all_snippets(&app_data_dir)
+}
+
+/// 添加片段
+#[tauri::command]
+pub fn add_snippet(
+    name: String,
+    keyword: Option<String>,
+    folder: Option<String>,
+    content: String,
+    app: AppHandle,
+) -> Result<snippets::SnippetItem, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    snippets::add_snippet(name, keyword, folder, content, &app_data_dir)
+}
+
+/// 更新片段
+#[tauri::command]
+pub fn update_snippet(
+    id: String,
+    name: Option<String>,
+    keyword: Option<String>,
+    folder: Option<String>,
+    content: Option<String>,
+    app: AppHandle,
+) -> Result<snippets::SnippetItem, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    snippets::update_snippet(id, name, keyword, folder, content, &app_data_dir)
+}
+
+/// 删除片段
+#[tauri::command]
+pub fn delete_snippet(id: String, app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    snippets::delete_snippet(id, &app_data_dir)
+}
+
+/// 获取片段文件夹列表
+#[tauri::command]
+pub fn get_snippet_folders(app: AppHandle) -> Result<Vec<String>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    snippets::get_snippet_folders(&app_data_dir)
+}
+
+/// 搜索片段（启动器使用）
+#[tauri::command]
+pub fn search_snippets(query: String, app: AppHandle) -> Result<Vec<snippets::SnippetItem>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    snippets::search_snippets(&query, &app_data_dir)
+}
+
+/// 获取片段需要用户输入的字段（`{input:...}`）
+#[tauri::command]
+pub fn get_snippet_inputs(id: String, app: AppHandle) -> Result<Vec<String>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    snippets::get_snippet_inputs(&id, &app_data_dir)
+}
+
+/// 渲染片段（预览用，不粘贴）
+#[tauri::command]
+pub fn render_snippet(
+    id: String,
+    inputs: Option<HashMap<String, String>>,
+    app: AppHandle,
+) -> Result<snippets::RenderedSnippet, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let clipboard = super::get_clipboard_text().ok().flatten();
+    snippets::render_snippet(&id, &inputs.unwrap_or_default(), clipboard, &app_data_dir)
+}
+
+/// 将剪切板收藏导入为片段
+#[tauri::command]
+pub fn import_clipboard_favorites_as_snippets(
+    folder: Option<String>,
+    app: AppHandle,
+) -> Result<usize, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    snippets::import_clipboard_favorites(folder, &app_data_dir)
+}
+
+/// 渲染片段并粘贴到当前光标位置
+///
+/// 前端应先隐藏启动器，使焦点回到目标窗口。粘贴完成后会恢复原剪贴板内容，
+/// 如果片段包含 `{cursor}`，光标会移动到对应位置。
+#[tauri::command]
+pub async fn paste_snippet(
+    id: String,
+    inputs: Option<HashMap<String, String>>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    // 模拟按键和等待目标程序读取剪贴板都会阻塞，放到阻塞线程池中执行
+    async_runtime::spawn_blocking(move || paste_rendered_snippet(&id, inputs, &app_data_dir))
+        .await
+        .map_err(|e| format!("Failed to paste snippet: {}", e))?
+}
+
+/// 渲染片段并粘贴到光标处，之后恢复剪贴板（在阻塞线程中执行）
+fn paste_rendered_snippet(
+    id: &str,
+    inputs: Option<HashMap<String, String>>,
+    app_data_dir: &std::path::Path,
+) -> Result<(), String> {
+    let previous_clipboard = super::get_clipboard_text().ok().flatten();
+    let rendered = snippets::render_snippet(
+        id,
+        &inputs.unwrap_or_default(),
+        previous_clipboard.clone(),
+        app_data_dir,
+    )?;
+
+    super::set_clipboard_text(&rendered.text)?;
+    super::paste_text_to_cursor(rendered.text.clone())?;
+
+    if let Some(offset) = rendered.cursor_offset {
+        let chars_after = rendered.text.chars().count().saturating_sub(offset);
+        if chars_after > 0 {
+            move_caret_left(chars_after)?;
+        }
+    }
+
+    if let Some(previous) = previous_clipboard {
+        // 等待目标程序读取剪贴板后再恢复
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        let _ = super::set_clipboard_text(&previous);
+    }
+
+    snippets::mark_snippet_used(id, app_data_dir)
+}
+
+/// 模拟按下若干次左方向键
+fn move_caret_left(count: usize) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+            SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, VK_LEFT,
+        };
+
+        let make_input = |flags: u32| INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: VK_LEFT,
+                    wScan: 0,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let mut inputs = Vec::with_capacity(count * 2);
+        for _ in 0..count {
+            inputs.push(make_input(0));
+            inputs.push(make_input(KEYEVENTF_KEYUP));
+        }
+        let sent = unsafe {
+            SendInput(
+                inputs.len() as u32,
+                inputs.as_ptr(),
+                std::mem::size_of::<INPUT>() as i32,
+            )
+        };
+        if sent as usize != inputs.len() {
+            return Err("Failed to move caret".to_string());
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdotool")
+            .arg("key")
+            .arg("--repeat")
+            .arg(count.to_string())
+            .arg("Left")
+            .output()
+            .map_err(|e| format!("Failed to move caret: {}", e))?;
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = count;
+        Ok(())
+    }
+}
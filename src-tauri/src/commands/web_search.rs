@@ -0,0 +1,85 @@
+//! 网页搜索相关命令模块
+//!
+//! 提供搜索引擎前缀/bang 解析、bang 列表的导入和补全，以及搜索建议
+
+use super::get_app_data_dir;
+use crate::settings;
+use crate::web_search;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// 解析启动器输入对应的搜索 URL；`use_fallback` 为 true 时没有匹配的前缀或 bang 也会使用后备引擎
+#[tauri::command]
+pub fn resolve_web_search(
+    query: String,
+    use_fallback: Option<bool>,
+    app: AppHandle,
+) -> Result<Option<web_search::SearchTarget>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    let needs_clipboard = settings
+        .search_engines
+        .iter()
+        .any(|e| e.url.contains("{clipboard}"));
+    let clipboard = if needs_clipboard {
+        super::get_clipboard_text().ok().flatten()
+    } else {
+        None
+    };
+    Ok(web_search::resolve(
+        &query,
+        &settings,
+        |trigger| web_search::find_bang(trigger, &app_data_dir).ok().flatten(),
+        use_fallback.unwrap_or(false),
+        clipboard.as_deref(),
+    ))
+}
+
+/// 获取输入对应引擎的搜索建议；未开启建议或引擎没有配置建议接口时返回空列表
+#[tauri::command]
+pub async fn get_search_suggestions(query: String, app: AppHandle) -> Result<Vec<String>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    if !settings.search_suggestions_enabled {
+        return Ok(Vec::new());
+    }
+    // 建议接口只在设置的引擎上配置，不需要查询导入的 bang
+    let Some(target) = web_search::resolve(&query, &settings, |_| None, true, None) else {
+        return Ok(Vec::new());
+    };
+    let Some(engine) = settings
+        .search_engines
+        .iter()
+        .find(|e| e.name == target.engine)
+    else {
+        return Ok(Vec::new());
+    };
+    web_search::fetch_suggestions(engine, &target.keyword).await
+}
+
+/// 从 JSON 文件导入 bang 列表（DuckDuckGo bang.js 或 `[{trigger, name, url}]`）
+#[tauri::command]
+pub fn import_search_bangs(
+    source_path: String,
+    app: AppHandle,
+) -> Result<web_search::BangImportSummary, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    web_search::import_bangs(Path::new(&source_path), &app_data_dir)
+}
+
+/// 按前缀列出已导入的 bang，用于补全
+#[tauri::command]
+pub fn search_bangs(
+    query: String,
+    limit: Option<usize>,
+    app: AppHandle,
+) -> Result<Vec<web_search::SearchBang>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    web_search::list_bangs(&query, limit.unwrap_or(20), &app_data_dir)
+}
+
+#[tauri::command]
+pub fn clear_search_bangs(app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    web_search::clear_bangs(&app_data_dir)
+}
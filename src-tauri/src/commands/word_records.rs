@@ -1,10 +1,18 @@
 //! 单词记录相关命令模块
 //! 
-//! 提供单词记录的 CRUD 操作
+//! 提供单词记录的 CRUD 操作、间隔重复复习、导入导出、离线词典和 AI 解释
 
+use crate::dictionary;
+use crate::ollama::{self, OllamaClient};
+use crate::semantic_search;
+use crate::settings;
+use crate::word_explain;
+use crate::word_io;
 use crate::word_records;
+use crate::word_review;
+use crate::word_stats;
 use super::get_app_data_dir;
-use tauri::AppHandle;
+use tauri::{async_runtime, AppHandle, Emitter};
 
 /// 获取所有单词记录
 #[tauri::command]
@@ -25,7 +33,7 @@ pub fn add_word_record(
     app: AppHandle,
 ) -> Result<word_records::WordRecord, String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    word_records::add_word_record(
+    let record = word_records::add_word_record(
         word,
         translation,
         context,
@@ -33,7 +41,9 @@ pub fn add_word_record(
         example_sentence,
         tags,
         &app_data_dir,
-    )
+    )?;
+    semantic_search::notify_changed();
+    Ok(record)
 }
 
 /// 更新单词记录
@@ -53,7 +63,7 @@ pub fn update_word_record(
     app: AppHandle,
 ) -> Result<word_records::WordRecord, String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    word_records::update_word_record(
+    let record = word_records::update_word_record(
         id,
         word,
         translation,
@@ -66,14 +76,18 @@ pub fn update_word_record(
         is_favorite,
         is_mastered,
         &app_data_dir,
-    )
+    )?;
+    semantic_search::notify_changed();
+    Ok(record)
 }
 
 /// 删除单词记录
 #[tauri::command]
 pub fn delete_word_record(id: String, app: AppHandle) -> Result<(), String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    word_records::delete_word_record(id, &app_data_dir)
+    word_records::delete_word_record(id, &app_data_dir)?;
+    semantic_search::notify_changed();
+    Ok(())
 }
 
 /// 搜索单词记录
@@ -82,3 +96,238 @@ pub fn search_word_records(query: String, app: AppHandle) -> Result<Vec<word_rec
     let app_data_dir = get_app_data_dir(&app)?;
     word_records::search_word_records(&query, &app_data_dir)
 }
+
+/// 获取待复习的单词（到期的优先，不足时补充新单词）
+#[tauri::command]
+pub fn get_due_words(
+    limit: Option<u32>,
+    app: AppHandle,
+) -> Result<Vec<word_records::WordRecord>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    word_review::get_due_words(limit.unwrap_or(20), &app_data_dir)
+}
+
+/// 提交复习结果，`grade` 为 0–5
+#[tauri::command]
+pub fn submit_review(id: String, grade: u8, app: AppHandle) -> Result<word_records::WordRecord, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    word_review::submit_review(id, grade, &app_data_dir)
+}
+
+/// 获取复习统计，默认最近 30 天
+#[tauri::command]
+pub fn get_review_stats(days: Option<u32>, app: AppHandle) -> Result<word_review::ReviewStats, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    word_review::get_review_stats(days.unwrap_or(30), &app_data_dir)
+}
+
+/// 获取学习统计（新增、复习时间序列、保持率、掌握程度分布等）
+#[tauri::command]
+pub fn get_word_stats(
+    options: Option<word_stats::WordStatsOptions>,
+    app: AppHandle,
+) -> Result<word_stats::WordStats, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    word_stats::get_word_stats(&options.unwrap_or_default(), &app_data_dir)
+}
+
+/// 预览导入结果（新单词和与现有记录的冲突），不写入
+#[tauri::command]
+pub fn preview_word_import(
+    path: String,
+    options: Option<word_io::WordImportOptions>,
+    app: AppHandle,
+) -> Result<word_io::WordImportPreview, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    word_io::preview_word_import(
+        std::path::Path::new(&path),
+        &options.unwrap_or_default(),
+        &app_data_dir,
+    )
+}
+
+/// 导入单词（CSV/TSV、Anki 纯文本导出或单词列表）
+#[tauri::command]
+pub fn import_word_records(
+    path: String,
+    options: Option<word_io::WordImportOptions>,
+    strategy: word_io::WordConflictStrategy,
+    app: AppHandle,
+) -> Result<word_io::WordImportSummary, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let summary = word_io::import_words(
+        std::path::Path::new(&path),
+        &options.unwrap_or_default(),
+        strategy,
+        &app_data_dir,
+    )?;
+    semantic_search::notify_changed();
+    Ok(summary)
+}
+
+/// 导出单词记录，`ids` 为空时导出全部，返回导出条数
+#[tauri::command]
+pub fn export_word_records(
+    path: String,
+    format: word_io::WordExportFormat,
+    ids: Option<Vec<String>>,
+    app: AppHandle,
+) -> Result<usize, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    word_io::export_words(std::path::Path::new(&path), format, ids, &app_data_dir)
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DictionaryImportProgress {
+    processed: u64,
+    total: u64,
+}
+
+/// 导入离线词典（ECDICT `.csv` 或 StarDict `.ifo`），进度通过 `dictionary-import-progress` 事件推送
+#[tauri::command]
+pub async fn import_dictionary(
+    path: String,
+    app: AppHandle,
+) -> Result<dictionary::DictionaryInfo, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    async_runtime::spawn_blocking(move || {
+        dictionary::import_dictionary(
+            std::path::Path::new(&path),
+            &app_data_dir,
+            &mut |processed, total| {
+                let _ = app.emit(
+                    "dictionary-import-progress",
+                    DictionaryImportProgress { processed, total },
+                );
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("import_dictionary join error: {}", e))?
+}
+
+/// 查询离线词典，支持词形还原
+#[tauri::command]
+pub fn lookup_dictionary(
+    word: String,
+    app: AppHandle,
+) -> Result<Option<dictionary::DictionaryEntry>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    dictionary::lookup(&word, &app_data_dir)
+}
+
+/// 按前缀搜索离线词典
+#[tauri::command]
+pub fn search_dictionary(
+    prefix: String,
+    limit: Option<u32>,
+    app: AppHandle,
+) -> Result<Vec<dictionary::DictionaryEntry>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    dictionary::search_prefix(&prefix, limit.unwrap_or(20), &app_data_dir)
+}
+
+/// 获取离线词典状态
+#[tauri::command]
+pub fn get_dictionary_info(app: AppHandle) -> Result<dictionary::DictionaryInfo, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    dictionary::get_info(&app_data_dir)
+}
+
+/// 删除离线词典
+#[tauri::command]
+pub fn remove_dictionary(app: AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    dictionary::remove(&app_data_dir)
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WordExplanationProgress<'a> {
+    request_id: &'a str,
+    current: usize,
+    total: usize,
+    word_id: &'a str,
+    word: &'a str,
+    error: Option<&'a str>,
+}
+
+fn explanation_client(app_data_dir: &std::path::Path) -> Result<(OllamaClient, String), String> {
+    let settings = settings::load_settings(app_data_dir)?;
+    let client = OllamaClient::from_settings(&settings.ollama)?;
+    Ok((client, settings.word_explanation_prompt))
+}
+
+/// 用 Ollama 生成单词解释并保存到 `ai_explanation`，可通过 `cancel_ollama_request` 取消
+#[tauri::command]
+pub async fn generate_word_explanation(
+    id: String,
+    request_id: Option<String>,
+    app: AppHandle,
+) -> Result<word_records::WordRecord, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let (client, template) = explanation_client(&app_data_dir)?;
+    let record = word_explain::load_word_record(&id, &app_data_dir)?;
+
+    let request_id = request_id.unwrap_or_else(|| format!("word-explanation-{}", id));
+    let token = ollama::register_request(&request_id);
+    let result = word_explain::generate_explanation(&client, &template, &record, &token).await;
+    ollama::finish_request(&request_id, &token);
+
+    let record = word_explain::save_explanation(&id, result?, &app_data_dir)?;
+    semantic_search::notify_changed();
+    Ok(record)
+}
+
+/// 为所有还没有解释的单词批量生成解释，进度通过 `word-explanation-progress` 事件推送
+#[tauri::command]
+pub async fn generate_missing_word_explanations(
+    request_id: String,
+    limit: Option<u32>,
+    app: AppHandle,
+) -> Result<word_explain::ExplanationBatchSummary, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let (client, template) = explanation_client(&app_data_dir)?;
+    let records = word_explain::get_words_missing_explanation(limit, &app_data_dir)?;
+
+    let token = ollama::register_request(&request_id);
+    let mut summary = word_explain::ExplanationBatchSummary {
+        total: records.len(),
+        ..Default::default()
+    };
+    for (index, record) in records.iter().enumerate() {
+        if token.is_cancelled() {
+            summary.cancelled = true;
+            break;
+        }
+        let result = match word_explain::generate_explanation(&client, &template, record, &token).await {
+            Ok(text) => word_explain::save_explanation(&record.id, text, &app_data_dir).map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if token.is_cancelled() {
+            summary.cancelled = true;
+            break;
+        }
+        match &result {
+            Ok(()) => summary.succeeded += 1,
+            Err(_) => summary.failed += 1,
+        }
+        let _ = app.emit(
+            "word-explanation-progress",
+            WordExplanationProgress {
+                request_id: &request_id,
+                current: index + 1,
+                total: summary.total,
+                word_id: &record.id,
+                word: &record.word,
+                error: result.as_ref().err().map(String::as_str),
+            },
+        );
+    }
+    ollama::finish_request(&request_id, &token);
+    if summary.succeeded > 0 {
+        semantic_search::notify_changed();
+    }
+    Ok(summary)
+}
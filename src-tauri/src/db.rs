@@ -85,6 +85,65 @@ pub fn get_readonly_connection(app_data_dir: &Path) -> Result<Connection, String
     Ok(conn)
 }
 
+const DICTIONARY_DB_NAME: &str = "dictionary.db";
+
+/// Offline dictionary database path; kept separate from the main DB because it can be large.
+pub fn get_dictionary_db_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(DICTIONARY_DB_NAME)
+}
+
+/// Open the offline dictionary database, creating its tables if needed.
+pub fn get_dictionary_connection(app_data_dir: &Path) -> Result<Connection, String> {
+    if !app_data_dir.exists() {
+        fs::create_dir_all(app_data_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+    let flags = OpenFlags::SQLITE_OPEN_READ_WRITE
+        | OpenFlags::SQLITE_OPEN_CREATE
+        | OpenFlags::SQLITE_OPEN_FULL_MUTEX;
+
+    let conn = Connection::open_with_flags(get_dictionary_db_path(app_data_dir), flags)
+        .map_err(|e| format!("Failed to open dictionary database: {}", e))?;
+
+    conn.execute_batch(
+        r#"
+        PRAGMA journal_mode = WAL;
+        PRAGMA synchronous = NORMAL;
+        PRAGMA busy_timeout = 5000;
+
+        CREATE TABLE IF NOT EXISTS dict_entries (
+            word TEXT PRIMARY KEY,
+            phonetic TEXT,
+            definition TEXT,
+            translation TEXT,
+            pos TEXT,
+            tags TEXT,
+            collins INTEGER,
+            oxford INTEGER,
+            frequency INTEGER,
+            exchange TEXT,
+            examples TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_dict_entries_word_nocase ON dict_entries(word COLLATE NOCASE);
+
+        -- Inflected form -> lemma, built from ECDICT exchange data
+        CREATE TABLE IF NOT EXISTS dict_lemmas (
+            form TEXT NOT NULL COLLATE NOCASE,
+            lemma TEXT NOT NULL,
+            PRIMARY KEY (form, lemma)
+        ) WITHOUT ROWID;
+
+        CREATE TABLE IF NOT EXISTS dict_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+    "#,
+    )
+    .map_err(|e| format!("Failed to initialise dictionary database: {}", e))?;
+
+    Ok(conn)
+}
+
 fn run_migrations(conn: &Connection) -> Result<(), String> {
     conn.execute_batch(
         r#"
@@ -184,6 +243,20 @@ fn run_migrations(conn: &Connection) -> Result<(), String> {
         CREATE INDEX IF NOT EXISTS idx_word_records_created_at ON word_records(created_at);
         CREATE INDEX IF NOT EXISTS idx_word_records_mastery_level ON word_records(mastery_level);
         CREATE INDEX IF NOT EXISTS idx_word_records_is_favorite ON word_records(is_favorite);
+
+        CREATE TABLE IF NOT EXISTS snippets (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            keyword TEXT,
+            folder TEXT,
+            content TEXT NOT NULL,
+            use_count INTEGER NOT NULL DEFAULT 0,
+            last_used INTEGER,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_snippets_keyword ON snippets(keyword);
+        CREATE INDEX IF NOT EXISTS idx_snippets_folder ON snippets(folder);
         
         -- Migration: Add ai_explanation column if it doesn't exist
         -- SQLite doesn't support IF NOT EXISTS for ALTER TABLE ADD COLUMN
@@ -214,6 +287,91 @@ fn run_migrations(conn: &Connection) -> Result<(), String> {
         .map_err(|e| format!("Failed to add ai_explanation column: {}", e))?;
     }
 
+    // Migration: Add memo organisation columns (pinned / archived / notebook / colour)
+    let memo_pinned_exists = conn
+        .prepare("SELECT pinned FROM memos LIMIT 1")
+        .is_ok();
+
+    if !memo_pinned_exists {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE memos ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE memos ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE memos ADD COLUMN notebook_id TEXT;
+            ALTER TABLE memos ADD COLUMN color TEXT;
+            "#,
+        )
+        .map_err(|e| format!("Failed to add memo organisation columns: {}", e))?;
+    }
+
+    // Migration: Add memo todo columns
+    let memo_todo_exists = conn
+        .prepare("SELECT is_todo FROM memos LIMIT 1")
+        .is_ok();
+
+    if !memo_todo_exists {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE memos ADD COLUMN is_todo INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE memos ADD COLUMN completed_at INTEGER;
+            "#,
+        )
+        .map_err(|e| format!("Failed to add memo todo columns: {}", e))?;
+    }
+
+    conn.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_memos_notebook_id ON memos(notebook_id);
+        CREATE INDEX IF NOT EXISTS idx_memos_pinned ON memos(pinned);
+
+        CREATE TABLE IF NOT EXISTS memo_notebooks (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            parent_id TEXT,
+            color TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS memo_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            color TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS memo_tag_links (
+            memo_id TEXT NOT NULL REFERENCES memos(id) ON DELETE CASCADE,
+            tag_id INTEGER NOT NULL REFERENCES memo_tags(id) ON DELETE CASCADE,
+            PRIMARY KEY (memo_id, tag_id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_memo_tag_links_tag_id ON memo_tag_links(tag_id);
+
+        -- No foreign key to memos: revisions must survive memo deletion so it can be undone
+        CREATE TABLE IF NOT EXISTS memo_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            memo_id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            diff_size INTEGER NOT NULL DEFAULT 0,
+            meta TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_memo_revisions_memo_id ON memo_revisions(memo_id, id);
+
+        CREATE TABLE IF NOT EXISTS memo_reminders (
+            memo_id TEXT PRIMARY KEY REFERENCES memos(id) ON DELETE CASCADE,
+            remind_at INTEGER NOT NULL,
+            due_at INTEGER,
+            recurrence TEXT,
+            snoozed_until INTEGER,
+            last_fired_at INTEGER,
+            next_fire_at INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS idx_memo_reminders_next_fire_at ON memo_reminders(next_fire_at);
+        "#,
+    )
+    .map_err(|e| format!("Failed to create memo organisation tables: {}", e))?;
+
     // Migration: Remove source_lang and target_lang columns if they exist
     // SQLite doesn't support DROP COLUMN, so we need to recreate the table
     let old_columns_exist = conn
@@ -266,6 +424,130 @@ fn run_migrations(conn: &Connection) -> Result<(), String> {
         .map_err(|e| format!("Failed to migrate word_records table: {}", e))?;
     }
 
+    // Migration: Add spaced repetition scheduling columns
+    let review_columns_exist = conn
+        .prepare("SELECT ease_factor FROM word_records LIMIT 1")
+        .is_ok();
+
+    if !review_columns_exist {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE word_records ADD COLUMN ease_factor REAL NOT NULL DEFAULT 2.5;
+            ALTER TABLE word_records ADD COLUMN interval_days INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE word_records ADD COLUMN repetitions INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE word_records ADD COLUMN lapses INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE word_records ADD COLUMN due_at INTEGER;
+            "#,
+        )
+        .map_err(|e| format!("Failed to add word review columns: {}", e))?;
+    }
+
+    conn.execute_batch(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_word_records_due_at ON word_records(due_at);
+
+        -- No foreign key to word_records: review history is kept for statistics after deletion
+        CREATE TABLE IF NOT EXISTS word_reviews (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            word_id TEXT NOT NULL,
+            grade INTEGER NOT NULL,
+            reviewed_at INTEGER NOT NULL,
+            interval_days INTEGER NOT NULL,
+            ease_factor REAL NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_word_reviews_reviewed_at ON word_reviews(reviewed_at);
+        "#,
+    )
+    .map_err(|e| format!("Failed to create word review tables: {}", e))?;
+
+    conn.execute_batch(
+        r#"
+        -- Semantic search: one embedding per (source, item); re-embedded when content_hash or model changes
+        CREATE TABLE IF NOT EXISTS embeddings (
+            source TEXT NOT NULL,
+            item_id TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            model TEXT NOT NULL,
+            dim INTEGER NOT NULL,
+            vector BLOB NOT NULL,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (source, item_id)
+        );
+
+        -- Keyword side of hybrid search; trigram tokenizer so CJK text matches by substring
+        CREATE VIRTUAL TABLE IF NOT EXISTS search_fts USING fts5(
+            source UNINDEXED,
+            item_id UNINDEXED,
+            content,
+            tokenize = 'trigram'
+        );
+        -- Content hash of what is in search_fts, kept apart from embeddings so the keyword index
+        -- is maintained on its own when no embedding model is available
+        CREATE TABLE IF NOT EXISTS search_fts_state (
+            source TEXT NOT NULL,
+            item_id TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            PRIMARY KEY (source, item_id)
+        );
+        "#,
+    )
+    .map_err(|e| format!("Failed to create semantic search tables: {}", e))?;
+
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS ai_conversations (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_ai_conversations_updated_at ON ai_conversations(updated_at);
+
+        -- context holds the clipboard / file / memo text attached to a user message;
+        -- cancelled marks answers cut short by a cancelled request and the questions left without
+        -- a full answer: they are kept but not replayed as history
+        CREATE TABLE IF NOT EXISTS ai_messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            conversation_id TEXT NOT NULL REFERENCES ai_conversations(id) ON DELETE CASCADE,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            context TEXT,
+            context_label TEXT,
+            cancelled INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_ai_messages_conversation_id ON ai_messages(conversation_id);
+        "#,
+    )
+    .map_err(|e| format!("Failed to create AI conversation tables: {}", e))?;
+
+    conn.execute_batch(
+        r#"
+        -- Named configuration profiles; data holds settings, Everything filters and shortcuts as JSON.
+        -- The active profile name is stored in settings under 'active_profile'.
+        CREATE TABLE IF NOT EXISTS settings_profiles (
+            name TEXT PRIMARY KEY,
+            data TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        "#,
+    )
+    .map_err(|e| format!("Failed to create settings profile table: {}", e))?;
+
+    conn.execute_batch(
+        r#"
+        -- Imported web search bangs (e.g. DuckDuckGo bang.js); trigger is stored lowercase without '!'.
+        CREATE TABLE IF NOT EXISTS search_bangs (
+            trigger TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            url TEXT NOT NULL,
+            encoding TEXT NOT NULL DEFAULT 'percent'
+        );
+        "#,
+    )
+    .map_err(|e| format!("Failed to create search bang table: {}", e))?;
+
     Ok(())
 }
 
@@ -0,0 +1,821 @@
+//! 离线词典
+//!
+//! 支持导入 ECDICT CSV 和 StarDict（`.ifo` / `.idx[.gz]` / `.dict[.dz]`）到独立的
+//! `dictionary.db`，查询时支持词形还原（"running" → "run"）：
+//! 1. 精确匹配，再不区分大小写匹配
+//! 2. ECDICT `exchange` 字段生成的变形表
+//! 3. 规则推断的候选词根
+
+use crate::db;
+use crate::word_io::{html_to_text, parse_delimited};
+use flate2::read::MultiGzDecoder;
+use rusqlite::{params, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 每处理多少条回调一次进度
+const PROGRESS_INTERVAL: u64 = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordForm {
+    /// past / past_participle / present_participle / third_person / comparative / superlative / plural / lemma
+    pub kind: String,
+    pub form: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryEntry {
+    pub word: String,
+    pub phonetic: Option<String>,
+    /// 英文释义
+    pub definition: Option<String>,
+    /// 中文释义
+    pub translation: Option<String>,
+    pub pos: Option<String>,
+    pub tags: Vec<String>,
+    pub collins: Option<u8>,
+    pub oxford: bool,
+    pub frequency: Option<u32>,
+    pub exchange: Vec<WordForm>,
+    pub examples: Vec<String>,
+    /// 查询词经词形还原后命中时，为原始查询词
+    pub lookup_from: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictionaryInfo {
+    pub installed: bool,
+    pub entry_count: u64,
+    pub source: Option<String>,
+    pub imported_at: Option<u64>,
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn exchange_kind(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "p" => "past",
+        "d" => "past_participle",
+        "i" => "present_participle",
+        "3" => "third_person",
+        "r" => "comparative",
+        "t" => "superlative",
+        "s" => "plural",
+        "0" => "lemma",
+        _ => return None,
+    })
+}
+
+/// 解析 ECDICT exchange 字段，如 `p:went/d:gone/i:going/3:goes`
+pub fn parse_exchange(exchange: &str) -> Vec<WordForm> {
+    exchange
+        .split('/')
+        .filter_map(|part| {
+            let (code, form) = part.split_once(':')?;
+            let form = form.trim();
+            if form.is_empty() {
+                return None;
+            }
+            Some(WordForm {
+                kind: exchange_kind(code.trim())?.to_string(),
+                form: form.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// 基于英语屈折规则生成可能的词根，按可能性排序
+pub fn candidate_lemmas(word: &str) -> Vec<String> {
+    let w = word.trim().to_lowercase();
+    let mut out: Vec<String> = Vec::new();
+    let mut push = |s: String| {
+        if s.len() >= 2 && s != w && !out.contains(&s) {
+            out.push(s);
+        }
+    };
+
+    // 去掉后缀后的词干，附带双写辅音还原（running → runn → run）
+    let stem_variants = |stem: &str, push: &mut dyn FnMut(String)| {
+        push(stem.to_string());
+        push(format!("{}e", stem));
+        let chars: Vec<char> = stem.chars().collect();
+        if chars.len() >= 3 {
+            let (a, b) = (chars[chars.len() - 2], chars[chars.len() - 1]);
+            if a == b && !is_vowel(b) && b != 'l' && b != 's' {
+                push(chars[..chars.len() - 1].iter().collect());
+            }
+        }
+    };
+
+    if let Some(stem) = w.strip_suffix("ies").or_else(|| w.strip_suffix("ied")) {
+        push(format!("{}y", stem));
+    }
+    if let Some(stem) = w.strip_suffix("iest").or_else(|| w.strip_suffix("ier")) {
+        push(format!("{}y", stem));
+    }
+    if let Some(stem) = w.strip_suffix("ves") {
+        push(format!("{}f", stem));
+        push(format!("{}fe", stem));
+    }
+    if let Some(stem) = w.strip_suffix("ing") {
+        stem_variants(stem, &mut push);
+    }
+    if let Some(stem) = w.strip_suffix("ed") {
+        stem_variants(stem, &mut push);
+    }
+    if let Some(stem) = w.strip_suffix("est") {
+        stem_variants(stem, &mut push);
+    }
+    if let Some(stem) = w.strip_suffix("er") {
+        stem_variants(stem, &mut push);
+    }
+    if let Some(stem) = w.strip_suffix("es") {
+        push(stem.to_string());
+    }
+    if let Some(stem) = w.strip_suffix('s') {
+        if !stem.ends_with('s') {
+            push(stem.to_string());
+        }
+    }
+    out
+}
+
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<DictionaryEntry> {
+    let text = |i: usize| -> rusqlite::Result<Option<String>> {
+        Ok(row.get::<_, Option<String>>(i)?.filter(|s| !s.trim().is_empty()))
+    };
+    let tags = text(5)?
+        .map(|t| t.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default();
+    let examples = text(10)?
+        .map(|e| e.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default();
+    Ok(DictionaryEntry {
+        word: row.get(0)?,
+        phonetic: text(1)?,
+        definition: text(2)?,
+        translation: text(3)?,
+        pos: text(4)?,
+        tags,
+        collins: row.get::<_, Option<i64>>(6)?.filter(|v| *v > 0).map(|v| v as u8),
+        oxford: row.get::<_, Option<i64>>(7)?.unwrap_or(0) != 0,
+        frequency: row.get::<_, Option<i64>>(8)?.filter(|v| *v > 0).map(|v| v as u32),
+        exchange: text(9)?.map(|e| parse_exchange(&e)).unwrap_or_default(),
+        examples,
+        lookup_from: None,
+    })
+}
+
+const ENTRY_COLUMNS: &str =
+    "word, phonetic, definition, translation, pos, tags, collins, oxford, frequency, exchange, examples";
+
+fn find_entry(conn: &rusqlite::Connection, word: &str) -> Result<Option<DictionaryEntry>, String> {
+    // 优先精确匹配，其次小写形式，最后不区分大小写
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM dict_entries WHERE word = ?1 COLLATE NOCASE
+             ORDER BY word = ?1 DESC, word = lower(?1) DESC LIMIT 1",
+            ENTRY_COLUMNS
+        ),
+        params![word],
+        row_to_entry,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to look up dictionary: {}", e))
+}
+
+/// 词典是否已安装：文件是包含词典表的 SQLite 数据库（不会创建或修改数据库文件）
+pub fn is_installed(app_data_dir: &Path) -> bool {
+    let path = db::get_dictionary_db_path(app_data_dir);
+    if !path.exists() {
+        return false;
+    }
+    let Ok(conn) = rusqlite::Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+    else {
+        return false;
+    };
+    // 文件头不对（不是 SQLite 数据库或已损坏）时读取 schema 会失败
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master
+         WHERE type = 'table' AND name IN ('dict_entries', 'dict_lemmas', 'dict_meta')",
+        [],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|tables| tables == 3)
+    .unwrap_or(false)
+}
+
+/// 查询单词，未命中时尝试词形还原
+pub fn lookup(word: &str, app_data_dir: &Path) -> Result<Option<DictionaryEntry>, String> {
+    let word = word.trim();
+    if word.is_empty() || !is_installed(app_data_dir) {
+        return Ok(None);
+    }
+    let conn = db::get_dictionary_connection(app_data_dir)?;
+
+    if let Some(entry) = find_entry(&conn, word)? {
+        return Ok(Some(entry));
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT lemma FROM dict_lemmas WHERE form = ?1")
+        .map_err(|e| format!("Failed to prepare lemma query: {}", e))?;
+    let lemmas: Vec<String> = stmt
+        .query_map(params![word], |row| row.get(0))
+        .map_err(|e| format!("Failed to query lemmas: {}", e))?
+        .filter_map(Result::ok)
+        .collect();
+
+    for lemma in lemmas.into_iter().chain(candidate_lemmas(word)) {
+        if let Some(mut entry) = find_entry(&conn, &lemma)? {
+            entry.lookup_from = Some(word.to_string());
+            return Ok(Some(entry));
+        }
+    }
+    Ok(None)
+}
+
+/// 按前缀搜索词条，常用词（词频高）在前
+pub fn search_prefix(
+    prefix: &str,
+    limit: u32,
+    app_data_dir: &Path,
+) -> Result<Vec<DictionaryEntry>, String> {
+    let prefix = prefix.trim();
+    if prefix.is_empty() || !is_installed(app_data_dir) {
+        return Ok(Vec::new());
+    }
+    let conn = db::get_dictionary_connection(app_data_dir)?;
+    let escaped = prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM dict_entries WHERE word LIKE ?1 ESCAPE '\\'
+             ORDER BY frequency IS NULL OR frequency = 0, frequency ASC, length(word) ASC
+             LIMIT ?2",
+            ENTRY_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare dictionary search: {}", e))?;
+    let rows = stmt
+        .query_map(params![format!("{}%", escaped), limit as i64], row_to_entry)
+        .map_err(|e| format!("Failed to search dictionary: {}", e))?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read dictionary row: {}", e))?);
+    }
+    Ok(items)
+}
+
+pub fn get_info(app_data_dir: &Path) -> Result<DictionaryInfo, String> {
+    if !is_installed(app_data_dir) {
+        return Ok(DictionaryInfo::default());
+    }
+    let conn = db::get_dictionary_connection(app_data_dir)?;
+    let meta = |key: &str| -> Result<Option<String>, String> {
+        conn.query_row("SELECT value FROM dict_meta WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()
+            .map_err(|e| format!("Failed to read dictionary metadata: {}", e))
+    };
+    let entry_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM dict_entries", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to count dictionary entries: {}", e))?;
+    Ok(DictionaryInfo {
+        installed: entry_count > 0,
+        entry_count: entry_count.max(0) as u64,
+        source: meta("source")?,
+        imported_at: meta("imported_at")?.and_then(|v| v.parse().ok()),
+    })
+}
+
+/// 删除离线词典
+pub fn remove(app_data_dir: &Path) -> Result<(), String> {
+    let path = db::get_dictionary_db_path(app_data_dir);
+    for suffix in ["", "-wal", "-shm"] {
+        let file = PathBuf::from(format!("{}{}", path.to_string_lossy(), suffix));
+        if file.exists() {
+            fs::remove_file(&file)
+                .map_err(|e| format!("Failed to remove {}: {}", file.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 导入时写入的一条词条
+#[derive(Debug, Default)]
+struct RawEntry {
+    word: String,
+    phonetic: Option<String>,
+    definition: Option<String>,
+    translation: Option<String>,
+    pos: Option<String>,
+    tags: Option<String>,
+    collins: Option<i64>,
+    oxford: Option<i64>,
+    frequency: Option<i64>,
+    exchange: Option<String>,
+    examples: Option<String>,
+}
+
+struct EntryWriter<'a> {
+    tx: &'a rusqlite::Transaction<'a>,
+    count: u64,
+}
+
+impl EntryWriter<'_> {
+    fn write(&mut self, entry: &RawEntry) -> Result<(), String> {
+        self.tx
+            .execute(
+                "INSERT OR REPLACE INTO dict_entries
+                    (word, phonetic, definition, translation, pos, tags, collins, oxford, frequency, exchange, examples)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    entry.word,
+                    entry.phonetic,
+                    entry.definition,
+                    entry.translation,
+                    entry.pos,
+                    entry.tags,
+                    entry.collins,
+                    entry.oxford,
+                    entry.frequency,
+                    entry.exchange,
+                    entry.examples
+                ],
+            )
+            .map_err(|e| format!("Failed to insert dictionary entry: {}", e))?;
+
+        if let Some(ref exchange) = entry.exchange {
+            for form in parse_exchange(exchange) {
+                // "0:" 指向本词的词根；其余为本词的变形
+                let (form, lemma) = if form.kind == "lemma" {
+                    (entry.word.as_str(), form.form)
+                } else {
+                    (form.form.as_str(), entry.word.clone())
+                };
+                if form.eq_ignore_ascii_case(&lemma) {
+                    continue;
+                }
+                self.tx
+                    .execute(
+                        "INSERT OR IGNORE INTO dict_lemmas (form, lemma) VALUES (?1, ?2)",
+                        params![form, lemma],
+                    )
+                    .map_err(|e| format!("Failed to insert dictionary lemma: {}", e))?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// 逐条读取 CSV 记录（引号内的换行会被拼接到同一条记录）
+fn next_csv_record<R: BufRead>(
+    reader: &mut R,
+    buf: &mut String,
+    bytes_read: &mut u64,
+) -> Result<Option<Vec<String>>, String> {
+    buf.clear();
+    loop {
+        let n = reader
+            .read_line(buf)
+            .map_err(|e| format!("Failed to read dictionary file: {}", e))?;
+        *bytes_read += n as u64;
+        if n == 0 {
+            break;
+        }
+        if buf.chars().filter(|c| *c == '"').count() % 2 == 0 {
+            break;
+        }
+    }
+    if buf.trim().is_empty() {
+        return if buf.is_empty() { Ok(None) } else { Ok(Some(Vec::new())) };
+    }
+    Ok(parse_delimited(buf, ',').into_iter().next())
+}
+
+fn import_ecdict(
+    path: &Path,
+    writer: &mut EntryWriter,
+    progress: &mut dyn FnMut(u64, u64),
+) -> Result<(), String> {
+    let total = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let file = File::open(path).map_err(|e| format!("Failed to open dictionary file: {}", e))?;
+    let mut reader = BufReader::with_capacity(1 << 20, file);
+    let mut buf = String::new();
+    let mut bytes_read = 0u64;
+
+    let header = next_csv_record(&mut reader, &mut buf, &mut bytes_read)?
+        .ok_or_else(|| "Dictionary file is empty".to_string())?;
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.contains(&h.trim().to_lowercase().as_str()))
+    };
+    let word_col = column(&["word"]).ok_or_else(|| {
+        "Unrecognised dictionary CSV: expected an ECDICT-style header with a 'word' column"
+            .to_string()
+    })?;
+    let cols = [
+        column(&["phonetic"]),
+        column(&["definition"]),
+        column(&["translation"]),
+        column(&["pos"]),
+        column(&["tag", "tags"]),
+        column(&["collins"]),
+        column(&["oxford"]),
+        column(&["frq", "frequency"]),
+        column(&["exchange"]),
+        column(&["example", "examples"]),
+    ];
+
+    while let Some(record) = next_csv_record(&mut reader, &mut buf, &mut bytes_read)? {
+        let get = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+                // ECDICT 用字面量 "\n" 表示换行
+                .map(|v| v.replace("\\n", "\n"))
+        };
+        let number = |col: Option<usize>| get(col).and_then(|v| v.parse::<i64>().ok());
+        let Some(word) = get(Some(word_col)) else { continue };
+        writer.write(&RawEntry {
+            word,
+            phonetic: get(cols[0]),
+            definition: get(cols[1]),
+            translation: get(cols[2]),
+            pos: get(cols[3]),
+            tags: get(cols[4]),
+            collins: number(cols[5]),
+            oxford: number(cols[6]),
+            frequency: number(cols[7]),
+            exchange: get(cols[8]),
+            examples: get(cols[9]),
+        })?;
+        if writer.count % PROGRESS_INTERVAL == 0 {
+            progress(bytes_read, total);
+        }
+    }
+    progress(total, total);
+    Ok(())
+}
+
+/// 解析 StarDict 词条数据，返回 (音标, 释义)
+fn parse_stardict_data(data: &[u8], same_type_sequence: Option<&str>) -> (Option<String>, String) {
+    let mut phonetic = None;
+    let mut texts: Vec<String> = Vec::new();
+    let mut push = |kind: char, bytes: &[u8]| {
+        let text = String::from_utf8_lossy(bytes).trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+        match kind {
+            't' => phonetic = Some(text),
+            'm' | 'l' | 'y' => texts.push(text),
+            'g' | 'h' | 'x' => texts.push(html_to_text(&text)),
+            _ => {}
+        }
+    };
+
+    let mut rest = data;
+    match same_type_sequence {
+        Some(types) => {
+            let types: Vec<char> = types.chars().collect();
+            for (i, kind) in types.iter().enumerate() {
+                let last = i + 1 == types.len();
+                if kind.is_ascii_uppercase() {
+                    // 二进制资源（图片、音频等），跳过
+                    if last || rest.len() < 4 {
+                        break;
+                    }
+                    let size = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+                    rest = rest.get(4 + size..).unwrap_or(&[]);
+                } else if last {
+                    push(*kind, rest);
+                } else {
+                    let end = rest.iter().position(|b| *b == 0).unwrap_or(rest.len());
+                    push(*kind, &rest[..end]);
+                    rest = rest.get(end + 1..).unwrap_or(&[]);
+                }
+            }
+        }
+        None => {
+            while let Some((&kind, tail)) = rest.split_first() {
+                let kind = kind as char;
+                if kind.is_ascii_uppercase() {
+                    if tail.len() < 4 {
+                        break;
+                    }
+                    let size = u32::from_be_bytes([tail[0], tail[1], tail[2], tail[3]]) as usize;
+                    rest = tail.get(4 + size..).unwrap_or(&[]);
+                } else {
+                    let end = tail.iter().position(|b| *b == 0).unwrap_or(tail.len());
+                    push(kind, &tail[..end]);
+                    rest = tail.get(end + 1..).unwrap_or(&[]);
+                }
+            }
+        }
+    }
+    (phonetic, texts.join("\n"))
+}
+
+/// 读取 .ifo 旁边的 StarDict 文件，优先未压缩的版本，其次 gzip / dictzip 压缩的版本
+fn read_stardict_file(
+    ifo_path: &Path,
+    extension: &str,
+    compressed: &str,
+) -> Result<Vec<u8>, String> {
+    let plain = ifo_path.with_extension(extension);
+    if plain.exists() {
+        return fs::read(&plain)
+            .map_err(|e| format!("Failed to read StarDict .{} file: {}", extension, e));
+    }
+    let compressed_path = ifo_path.with_extension(format!("{}.{}", extension, compressed));
+    if !compressed_path.exists() {
+        return Err(format!(
+            "StarDict .{} file not found next to the .ifo file",
+            extension
+        ));
+    }
+    let file = File::open(&compressed_path).map_err(|e| {
+        format!("Failed to open StarDict .{}.{} file: {}", extension, compressed, e)
+    })?;
+    // dictzip 与 gzip 格式兼容，可以直接整体解压
+    let mut data = Vec::new();
+    MultiGzDecoder::new(BufReader::new(file))
+        .read_to_end(&mut data)
+        .map_err(|e| {
+            format!("Failed to decompress StarDict .{}.{} file: {}", extension, compressed, e)
+        })?;
+    Ok(data)
+}
+
+fn import_stardict(
+    ifo_path: &Path,
+    writer: &mut EntryWriter,
+    progress: &mut dyn FnMut(u64, u64),
+) -> Result<(), String> {
+    let ifo = fs::read_to_string(ifo_path)
+        .map_err(|e| format!("Failed to read StarDict .ifo file: {}", e))?;
+    if !ifo.starts_with("StarDict's dict ifo file") {
+        return Err("Not a StarDict .ifo file".to_string());
+    }
+    let field = |key: &str| {
+        ifo.lines()
+            .filter_map(|l| l.split_once('='))
+            .find(|(k, _)| k.trim() == key)
+            .map(|(_, v)| v.trim().to_string())
+    };
+    let offset_bits: u32 = field("idxoffsetbits").and_then(|v| v.parse().ok()).unwrap_or(32);
+    let same_type_sequence = field("sametypesequence");
+
+    let idx = read_stardict_file(ifo_path, "idx", "gz")?;
+    let dict = read_stardict_file(ifo_path, "dict", "dz")?;
+    let total = idx.len() as u64;
+    let offset_len = if offset_bits == 64 { 8 } else { 4 };
+
+    let mut pos = 0usize;
+    while pos < idx.len() {
+        let end = idx[pos..]
+            .iter()
+            .position(|b| *b == 0)
+            .map(|i| pos + i)
+            .ok_or_else(|| "Corrupt StarDict index: unterminated word".to_string())?;
+        let word = String::from_utf8_lossy(&idx[pos..end]).trim().to_string();
+        let meta = idx
+            .get(end + 1..end + 1 + offset_len + 4)
+            .ok_or_else(|| "Corrupt StarDict index: truncated entry".to_string())?;
+        let offset = if offset_len == 8 {
+            u64::from_be_bytes(meta[..8].try_into().unwrap_or_default()) as usize
+        } else {
+            u32::from_be_bytes(meta[..4].try_into().unwrap_or_default()) as usize
+        };
+        let size = u32::from_be_bytes(meta[offset_len..].try_into().unwrap_or_default()) as usize;
+        pos = end + 1 + offset_len + 4;
+
+        let data = offset
+            .checked_add(size)
+            .and_then(|data_end| dict.get(offset..data_end))
+            .ok_or_else(|| format!("Corrupt StarDict data for '{}'", word))?;
+        if word.is_empty() {
+            continue;
+        }
+        let (phonetic, definition) = parse_stardict_data(data, same_type_sequence.as_deref());
+        writer.write(&RawEntry {
+            word,
+            phonetic,
+            // StarDict 词典多为英汉词典，释义放在 translation 中
+            translation: Some(definition).filter(|d| !d.is_empty()),
+            ..Default::default()
+        })?;
+        if writer.count % PROGRESS_INTERVAL == 0 {
+            progress(pos as u64, total);
+        }
+    }
+    progress(total, total);
+    Ok(())
+}
+
+/// 导入词典文件（ECDICT `.csv` 或 StarDict `.ifo`），替换现有词典内容
+///
+/// `progress` 参数为 (已处理字节数, 总字节数)。
+pub fn import_dictionary(
+    path: &Path,
+    app_data_dir: &Path,
+    progress: &mut dyn FnMut(u64, u64),
+) -> Result<DictionaryInfo, String> {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if ext != "csv" && ext != "ifo" {
+        return Err(format!(
+            "Unsupported dictionary file: .{} (expected ECDICT .csv or StarDict .ifo)",
+            ext
+        ));
+    }
+
+    let mut conn = db::get_dictionary_connection(app_data_dir)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start dictionary import: {}", e))?;
+    tx.execute_batch("DELETE FROM dict_entries; DELETE FROM dict_lemmas; DELETE FROM dict_meta;")
+        .map_err(|e| format!("Failed to clear dictionary: {}", e))?;
+
+    let mut writer = EntryWriter { tx: &tx, count: 0 };
+    if ext == "csv" {
+        import_ecdict(path, &mut writer, progress)?;
+    } else {
+        import_stardict(path, &mut writer, progress)?;
+    }
+    if writer.count == 0 {
+        return Err("No dictionary entries found in file".to_string());
+    }
+
+    let source = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    tx.execute(
+        "INSERT OR REPLACE INTO dict_meta (key, value) VALUES ('source', ?1), ('imported_at', ?2)",
+        params![source, now_ts().to_string()],
+    )
+    .map_err(|e| format!("Failed to save dictionary metadata: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit dictionary import: {}", e))?;
+
+    get_info(app_data_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_exchange() {
+        let forms = parse_exchange("p:ran/d:run/i:running/3:runs/x:bad");
+        assert_eq!(forms.len(), 4);
+        assert_eq!(forms[2].kind, "present_participle");
+        assert_eq!(forms[2].form, "running");
+    }
+
+    #[test]
+    fn test_candidate_lemmas() {
+        assert!(candidate_lemmas("running").contains(&"run".to_string()));
+        assert!(candidate_lemmas("making").contains(&"make".to_string()));
+        assert!(candidate_lemmas("studies").contains(&"study".to_string()));
+        assert!(candidate_lemmas("happier").contains(&"happy".to_string()));
+        assert!(candidate_lemmas("wolves").contains(&"wolf".to_string()));
+        assert_eq!(candidate_lemmas("cats")[0], "cat");
+    }
+
+    #[test]
+    fn test_ecdict_import_and_lemmatised_lookup() {
+        let dir = temp_dir("dict-ecdict");
+        let csv = dir.join("ecdict.csv");
+        fs::write(
+            &csv,
+            "word,phonetic,definition,translation,pos,collins,oxford,tag,bnc,frq,exchange,detail,audio\n\
+             run,rʌn,\"v. move fast\\nn. a race\",\"v. 跑\\nn. 奔跑\",,5,1,zk gk,100,80,p:ran/d:run/i:running/3:runs,,\n\
+             went,,,,,,,,,,0:go/1:p,,\n",
+        )
+        .unwrap();
+
+        let mut calls = 0;
+        let info = import_dictionary(&csv, &dir, &mut |_, _| calls += 1).unwrap();
+        assert_eq!(info.entry_count, 2);
+        assert!(calls > 0);
+
+        let entry = lookup("Running", &dir).unwrap().unwrap();
+        assert_eq!(entry.word, "run");
+        assert_eq!(entry.lookup_from.as_deref(), Some("Running"));
+        assert_eq!(entry.translation.as_deref(), Some("v. 跑\nn. 奔跑"));
+        assert_eq!(entry.tags, vec!["zk", "gk"]);
+        assert!(entry.oxford);
+        // 规则推断
+        assert_eq!(lookup("runs", &dir).unwrap().unwrap().word, "run");
+        assert!(lookup("nothing", &dir).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_is_installed_checks_database() {
+        let dir = temp_dir("dict-installed");
+        let path = db::get_dictionary_db_path(&dir);
+        assert!(!is_installed(&dir));
+
+        // 不是 SQLite 数据库
+        fs::write(&path, "not a database").unwrap();
+        assert!(!is_installed(&dir));
+        assert!(lookup("run", &dir).unwrap().is_none());
+        assert!(!get_info(&dir).unwrap().installed);
+
+        // 数据库缺少词典表
+        fs::remove_file(&path).unwrap();
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE other (id INTEGER)")
+            .unwrap();
+        assert!(!is_installed(&dir));
+
+        fs::remove_file(&path).unwrap();
+        db::get_dictionary_connection(&dir).unwrap();
+        assert!(is_installed(&dir));
+    }
+
+    #[test]
+    fn test_stardict_import() {
+        let dir = temp_dir("dict-stardict");
+        let ifo = dir.join("test.ifo");
+        fs::write(
+            &ifo,
+            "StarDict's dict ifo file\nversion=2.4.2\nwordcount=2\nsametypesequence=tm\n",
+        )
+        .unwrap();
+        let mut dict = Vec::new();
+        let mut idx = Vec::new();
+        for (word, phonetic, meaning) in [("apple", "'æpl", "n. 苹果"), ("book", "buk", "n. 书")] {
+            let offset = dict.len() as u32;
+            dict.extend_from_slice(phonetic.as_bytes());
+            dict.push(0);
+            dict.extend_from_slice(meaning.as_bytes());
+            idx.extend_from_slice(word.as_bytes());
+            idx.push(0);
+            idx.extend_from_slice(&offset.to_be_bytes());
+            idx.extend_from_slice(&((dict.len() as u32 - offset).to_be_bytes()));
+        }
+        fs::write(dir.join("test.idx"), idx).unwrap();
+        fs::write(dir.join("test.dict"), dict).unwrap();
+
+        let info = import_dictionary(&ifo, &dir, &mut |_, _| {}).unwrap();
+        assert_eq!(info.entry_count, 2);
+        let entry = lookup("books", &dir).unwrap().unwrap();
+        assert_eq!(entry.word, "book");
+        assert_eq!(entry.phonetic.as_deref(), Some("buk"));
+        assert_eq!(entry.translation.as_deref(), Some("n. 书"));
+
+        // 压缩的 .idx.gz / .dict.dz 与未压缩的结果相同
+        let compressed_dir = temp_dir("dict-stardict_compressed");
+        let compressed_ifo = compressed_dir.join("test.ifo");
+        fs::copy(&ifo, &compressed_ifo).unwrap();
+        for (name, ext) in [("test.idx", "idx.gz"), ("test.dict", "dict.dz")] {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&fs::read(dir.join(name)).unwrap()).unwrap();
+            fs::write(compressed_ifo.with_extension(ext), encoder.finish().unwrap()).unwrap();
+        }
+        let info = import_dictionary(&compressed_ifo, &compressed_dir, &mut |_, _| {}).unwrap();
+        assert_eq!(info.entry_count, 2);
+        let entry = lookup("apple", &compressed_dir).unwrap().unwrap();
+        assert_eq!(entry.phonetic.as_deref(), Some("'æpl"));
+    }
+
+    #[test]
+    fn test_stardict_rejects_overflowing_offset() {
+        let dir = temp_dir("dict-stardict_overflow");
+        let ifo = dir.join("test.ifo");
+        fs::write(&ifo, "StarDict's dict ifo file\nversion=3.0.0\nidxoffsetbits=64\n").unwrap();
+        let mut idx = b"apple\0".to_vec();
+        idx.extend_from_slice(&u64::MAX.to_be_bytes());
+        idx.extend_from_slice(&16u32.to_be_bytes());
+        fs::write(dir.join("test.idx"), idx).unwrap();
+        fs::write(dir.join("test.dict"), b"n. apple").unwrap();
+
+        assert!(import_dictionary(&ifo, &dir, &mut |_, _| {}).is_err());
+    }
+}
@@ -34,7 +34,13 @@ pub fn load_custom_filters(app_data_dir: &Path) -> Result<Vec<CustomFilter>, Str
 /// 保存自定义过滤器列表
 pub fn save_custom_filters(app_data_dir: &Path, filters: &[CustomFilter]) -> Result<(), String> {
     let conn = db::get_connection(app_data_dir)?;
-    
+    save_custom_filters_with_conn(&conn, filters)
+}
+
+pub(crate) fn save_custom_filters_with_conn(
+    conn: &rusqlite::Connection,
+    filters: &[CustomFilter],
+) -> Result<(), String> {
     let filters_json = serde_json::to_string(filters)
         .map_err(|e| format!("Failed to serialize custom filters: {}", e))?;
 
@@ -1,13 +1,13 @@
 #[cfg(target_os = "windows")]
 pub mod windows {
-    use crate::recording::{EventType, MouseButton, RecordedEvent};
+    use crate::recording::{InputEvent, KeyCode, MouseButton, TimedEvent};
     use std::sync::{Arc, Mutex};
     use windows_sys::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
     use windows_sys::Win32::UI::WindowsAndMessaging::{
         CallNextHookEx, GetCursorPos, SetWindowsHookExA, UnhookWindowsHookEx, HHOOK,
-        KBDLLHOOKSTRUCT, MSLLHOOKSTRUCT, WH_KEYBOARD_LL, WH_MOUSE_LL, WM_KEYDOWN, WM_KEYUP,
-        WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL,
-        WM_RBUTTONDOWN, WM_RBUTTONUP,
+        KBDLLHOOKSTRUCT, LLKHF_EXTENDED, MSLLHOOKSTRUCT, WH_KEYBOARD_LL, WH_MOUSE_LL, WM_KEYDOWN,
+        WM_KEYUP, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE,
+        WM_MOUSEWHEEL, WM_RBUTTONDOWN, WM_RBUTTONUP,
     };
 
     static MOUSE_HOOK: std::sync::Mutex<Option<HHOOK>> = std::sync::Mutex::new(None);
@@ -44,40 +44,53 @@ pub mod windows {
                             }
                         }
 
-                        let event_type = match w_param as u32 {
-                            WM_MOUSEMOVE => Some(EventType::MouseMove),
-                            WM_LBUTTONDOWN => Some(EventType::MouseDown {
+                        let position = match (x, y) {
+                            (Some(x), Some(y)) => Some(state.screen.normalize(x, y)),
+                            _ => None,
+                        };
+
+                        let event = match w_param as u32 {
+                            WM_MOUSEMOVE => position.map(|position| InputEvent::MouseMove { position }),
+                            WM_LBUTTONDOWN => Some(InputEvent::MouseDown {
                                 button: MouseButton::Left,
+                                position,
                             }),
-                            WM_LBUTTONUP => Some(EventType::MouseUp {
+                            WM_LBUTTONUP => Some(InputEvent::MouseUp {
                                 button: MouseButton::Left,
+                                position,
                             }),
-                            WM_RBUTTONDOWN => Some(EventType::MouseDown {
+                            WM_RBUTTONDOWN => Some(InputEvent::MouseDown {
                                 button: MouseButton::Right,
+                                position,
                             }),
-                            WM_RBUTTONUP => Some(EventType::MouseUp {
+                            WM_RBUTTONUP => Some(InputEvent::MouseUp {
                                 button: MouseButton::Right,
+                                position,
                             }),
-                            WM_MBUTTONDOWN => Some(EventType::MouseDown {
+                            WM_MBUTTONDOWN => Some(InputEvent::MouseDown {
                                 button: MouseButton::Middle,
+                                position,
                             }),
-                            WM_MBUTTONUP => Some(EventType::MouseUp {
+                            WM_MBUTTONUP => Some(InputEvent::MouseUp {
                                 button: MouseButton::Middle,
+                                position,
                             }),
                             WM_MOUSEWHEEL => {
-                                // Extract wheel delta from l_param
-                                let delta = ((l_param >> 16) & 0xFFFF) as i16 as i32;
-                                Some(EventType::MouseWheel { delta })
+                                // Wheel delta is the high word of MSLLHOOKSTRUCT.mouseData
+                                let delta = if !hook_struct.is_null() {
+                                    ((*hook_struct).mouseData >> 16) as i16 as i32
+                                } else {
+                                    0
+                                };
+                                Some(InputEvent::MouseWheel { delta })
                             }
                             _ => None,
                         };
 
-                        if let Some(event_type) = event_type {
-                            state.add_event(RecordedEvent {
-                                event_type,
-                                x,
-                                y,
+                        if let Some(event) = event {
+                            state.add_event(TimedEvent {
                                 time_offset_ms,
+                                event,
                             });
                         }
                     }
@@ -102,27 +115,32 @@ pub mod windows {
                         let time_offset_ms = start.elapsed().as_millis() as u64;
 
                         // l_param points to KBDLLHOOKSTRUCT
-                        // Extract virtual key code from the structure
+                        // Extract virtual key code and scan code from the structure
                         let hook_struct = l_param as *const KBDLLHOOKSTRUCT;
-                        let vk_code = if !hook_struct.is_null() {
-                            (*hook_struct).vkCode as u32
+                        let key = if !hook_struct.is_null() {
+                            let info = &*hook_struct;
+                            // Extended keys (right Ctrl/Alt, arrows, ...) are stored with the E0 prefix
+                            let scan_code = if info.flags & LLKHF_EXTENDED != 0 {
+                                0xE000 | info.scanCode
+                            } else {
+                                info.scanCode
+                            };
+                            KeyCode::from_vk(info.vkCode, Some(scan_code))
                         } else {
                             let hook = KEYBOARD_HOOK.lock().ok().and_then(|h| *h).unwrap_or(0);
                             return CallNextHookEx(hook, n_code, w_param, l_param);
                         };
 
-                        let event_type = match w_param as u32 {
-                            WM_KEYDOWN => Some(EventType::KeyDown { vk_code }),
-                            WM_KEYUP => Some(EventType::KeyUp { vk_code }),
+                        let event = match w_param as u32 {
+                            WM_KEYDOWN => Some(InputEvent::KeyDown { key }),
+                            WM_KEYUP => Some(InputEvent::KeyUp { key }),
                             _ => None,
                         };
 
-                        if let Some(event_type) = event_type {
-                            state.add_event(RecordedEvent {
-                                event_type,
-                                x: None,
-                                y: None,
+                        if let Some(event) = event {
+                            state.add_event(TimedEvent {
                                 time_offset_ms,
+                                event,
                             });
                         }
                     }
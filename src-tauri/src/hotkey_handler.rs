@@ -7,6 +7,9 @@ pub mod windows {
     use std::io::Write;
     use std::path::PathBuf;
     use std::collections::HashMap;
+    use crate::hotkey_model::{Hotkey, HotkeyTrigger, Modifier};
+    use crate::hotkey_sequence::{KeyInput, SequenceMatcher};
+    use crate::settings::HotkeyTiming;
     use windows_sys::Win32::{
         Foundation::{HWND, LPARAM, LRESULT, WPARAM},
         UI::WindowsAndMessaging::{DispatchMessageW, GetMessageW, TranslateMessage, MSG},
@@ -145,17 +148,18 @@ pub mod windows {
     const WM_KEYUP: u32 = 0x0101;
     const WM_SYSKEYDOWN: u32 = 0x0104;
     const WM_SYSKEYUP: u32 = 0x0105;
+    /// 事件由 SendInput 等方式注入（例如宏和录制回放），不是用户按下的
+    const LLKHF_INJECTED: u32 = 0x10;
     const WM_HOTKEY: u32 = 0x0312;
 
     const MOD_ALT: u32 = 0x0001;
-    const MOD_CONTROL: u32 = 0x0002;
-    const MOD_SHIFT: u32 = 0x0004;
-    const MOD_WIN: u32 = 0x0008;
 
     const HOTKEY_ID: i32 = 1;
     
     // 自定义消息：更新热键
     const WM_UPDATE_HOTKEY: u32 = windows_sys::Win32::UI::WindowsAndMessaging::WM_APP + 1;
+    // 自定义消息：取消注册热键
+    const WM_UNREGISTER_HOTKEY: u32 = windows_sys::Win32::UI::WindowsAndMessaging::WM_APP + 2;
 
     // 存储当前的快捷键配置和窗口句柄
     struct HotkeyState {
@@ -164,38 +168,62 @@ pub mod windows {
         vk: u32,
         is_double_modifier: bool, // 是否是重复修饰键（如 Ctrl+Ctrl）
         hook: Option<windows_sys::Win32::UI::WindowsAndMessaging::HHOOK>, // 键盘钩子句柄（用于重复修饰键）
-        last_keyup_time: Option<std::time::Instant>, // 上次按键抬起时间（用于检测重复）
-        waiting_for_second: bool, // 是否正在等待第二次按键抬起
-        other_key_pressed: bool, // 是否按下了其他键（需要重置状态）
     }
 
     static HOTKEY_STATE: Mutex<Option<Arc<Mutex<HotkeyState>>>> = Mutex::new(None);
 
-    // 检查虚拟键码是否匹配目标键（包括左右键变体）
-    fn is_target_key(vk_code: u32, target_vk: u32) -> bool {
-        if vk_code == target_vk {
-            return true;
+    // 启动器的双击修饰键检测，仅在启动器快捷键是双击时存在
+    static LAUNCHER_MATCHER: Mutex<Option<SequenceMatcher<()>>> = Mutex::new(None);
+
+    // 组合序列、双击和按住触发的时间阈值
+    static HOTKEY_TIMING: LazyLock<Mutex<HotkeyTiming>> =
+        LazyLock::new(|| Mutex::new(HotkeyTiming::default()));
+
+    // 键盘钩子和定时轮询共用的时钟
+    static HOOK_CLOCK: LazyLock<std::time::Instant> = LazyLock::new(std::time::Instant::now);
+
+    fn now_ms() -> u64 {
+        HOOK_CLOCK.elapsed().as_millis() as u64
+    }
+
+    /// 更新时间阈值，立即应用到启动器和多快捷键监听器
+    pub fn set_hotkey_timing(timing: HotkeyTiming) {
+        *HOTKEY_TIMING.lock().unwrap() = timing;
+        if let Some(matcher) = LAUNCHER_MATCHER.lock().unwrap().as_mut() {
+            matcher.set_timing(timing);
         }
-        
-        // 对于修饰键，需要检查左右键变体
-        match target_vk {
-            0x12 => {
-                // VK_MENU (Alt) - 检查左 Alt 和右 Alt
-                vk_code == 0xA4 || vk_code == 0xA5 // VK_LMENU, VK_RMENU
-            }
-            0x11 => {
-                // VK_CONTROL (Ctrl) - 检查左 Ctrl 和右 Ctrl
-                vk_code == 0xA2 || vk_code == 0xA3 // VK_LCONTROL, VK_RCONTROL
-            }
-            0x10 => {
-                // VK_SHIFT (Shift) - 检查左 Shift 和右 Shift
-                vk_code == 0xA0 || vk_code == 0xA1 // VK_LSHIFT, VK_RSHIFT
-            }
-            _ => false,
+        MULTI_HOTKEY_MANAGER.matcher.lock().unwrap().set_timing(timing);
+    }
+
+    // 设置启动器要检测的双击修饰键，None 表示不检测
+    fn set_launcher_double_tap(vk: Option<u32>) {
+        let timing = *HOTKEY_TIMING.lock().unwrap();
+        *LAUNCHER_MATCHER.lock().unwrap() = vk.and_then(Modifier::from_vk).map(|modifier| {
+            SequenceMatcher::new(vec![((), Hotkey::DoubleTap(modifier).into())], timing)
+        });
+    }
+
+    // 把低级键盘钩子的事件转换成序列匹配的输入；注入的事件不参与匹配，回放时不会触发快捷键
+    fn key_input(
+        wParam: WPARAM,
+        hook_struct: &windows_sys::Win32::UI::WindowsAndMessaging::KBDLLHOOKSTRUCT,
+    ) -> Option<KeyInput> {
+        if hook_struct.flags & LLKHF_INJECTED != 0 {
+            return None;
         }
+        let pressed = match wParam as u32 {
+            WM_KEYDOWN | WM_SYSKEYDOWN => true,
+            WM_KEYUP | WM_SYSKEYUP => false,
+            _ => return None,
+        };
+        Some(KeyInput {
+            vk: hook_struct.vkCode,
+            pressed,
+            time_ms: now_ms(),
+        })
     }
 
-    // 键盘钩子回调函数：检测重复修饰键（使用企业微信的实现方式）
+    // 键盘钩子回调函数：交给序列匹配检测双击修饰键，事件始终放行
     unsafe extern "system" fn keyboard_hook_proc(nCode: i32, wParam: WPARAM, lParam: LPARAM) -> LRESULT {
         use windows_sys::Win32::UI::WindowsAndMessaging::{PostMessageW, HHOOK, KBDLLHOOKSTRUCT};
         
@@ -204,259 +232,48 @@ pub mod windows {
             return CallNextHookEx(HHOOK::default(), nCode, wParam, lParam);
         }
         
-        // 解析 KBDLLHOOKSTRUCT（先解析，用于日志）
         let hook_struct = &*(lParam as *const KBDLLHOOKSTRUCT);
-        let vk_code = hook_struct.vkCode as u32;
-        let is_keydown = wParam == WM_KEYDOWN as WPARAM || wParam == WM_SYSKEYDOWN as WPARAM;
-        let is_keyup = wParam == WM_KEYUP as WPARAM || wParam == WM_SYSKEYUP as WPARAM;
-        
-        // 获取全局状态
-        let global_state = HOTKEY_STATE.lock().unwrap();
-        if let Some(state) = global_state.as_ref() {
-            let mut state_guard = state.lock().unwrap();
-            
-            // 检查是否是重复修饰键模式
-            if state_guard.is_double_modifier {
-                // 检查是否是目标键（包括左右键变体）
-                let is_target = is_target_key(vk_code, state_guard.vk);
-                
-                // 记录所有键盘事件（用于调试）
-                if is_target {
-                    if is_keydown {
-                        log_hotkey!("[Hotkey] Keyboard hook: Target key DOWN detected, vk_code={} (target={}), waiting_for_second={}", vk_code, state_guard.vk, state_guard.waiting_for_second);
-                    } else if is_keyup {
-                        log_hotkey!("[Hotkey] Keyboard hook: Target key UP detected, vk_code={} (target={}), waiting_for_second={}", vk_code, state_guard.vk, state_guard.waiting_for_second);
-                    }
-                } else if is_keydown || is_keyup {
-                    // 只在等待第二次时记录其他键，避免日志过多
-                    if state_guard.waiting_for_second {
-                        log_hotkey!("[Hotkey] Keyboard hook: Other key event while waiting, vk_code={}, is_keydown={}", vk_code, is_keydown);
-                    }
-                }
-                
-                // 检查是否是目标修饰键（包括左右键变体）
-                if is_target {
-                    let now = std::time::Instant::now();
-                    
-                    // 处理按键按下事件（KeyDown）- 检查超时
-                    if wParam == WM_KEYDOWN as WPARAM || wParam == WM_SYSKEYDOWN as WPARAM {
-                        if state_guard.waiting_for_second {
-                            // 正在等待第二次，检查是否超时
-                            if let Some(last_time) = state_guard.last_keyup_time {
-                                let delta = now.duration_since(last_time).as_millis();
-                                if delta >= 500 {
-                                    // 超时，重置状态并开始新的序列
-                                    log_hotkey!("[Hotkey] Keyboard hook: Timeout detected on keydown ({}ms >= 500ms), resetting and starting new sequence", delta);
-                                    state_guard.waiting_for_second = false;
-                                    state_guard.last_keyup_time = None;
-                                    state_guard.other_key_pressed = false;
-                                } else if state_guard.other_key_pressed {
-                                    // 按了其他键，重置状态
-                                    log_hotkey!("[Hotkey] Keyboard hook: Other key was pressed before this keydown, resetting state");
-                                    state_guard.waiting_for_second = false;
-                                    state_guard.last_keyup_time = None;
-                                    state_guard.other_key_pressed = false;
-                                }
-                            }
-                        }
-                    }
-                    
-                    // 处理按键抬起事件（KeyUp）
-                    if wParam == WM_KEYUP as WPARAM || wParam == WM_SYSKEYUP as WPARAM {
-                        log_hotkey!("[Hotkey] Keyboard hook: Modifier keyup detected, vk_code={}, waiting_for_second={}, other_key_pressed={}", 
-                                 vk_code, state_guard.waiting_for_second, state_guard.other_key_pressed);
-                        
-                        if state_guard.waiting_for_second {
-                            // 正在等待第二次抬起
-                            if !state_guard.other_key_pressed {
-                                // 没有按下其他键，检查时间差
-                                if let Some(last_time) = state_guard.last_keyup_time {
-                                    let delta = now.duration_since(last_time).as_millis();
-                                    log_hotkey!("[Hotkey] Keyboard hook: Checking delta: {}ms (threshold: 500ms)", delta);
-                                    if delta < 500 {
-                                        // 检测到双击！触发热键
-                                        log_hotkey!("[Hotkey] Keyboard hook: ✅ Double modifier detected! Delta: {}ms, triggering hotkey", delta);
-                                        if let Some(hwnd) = state_guard.hwnd {
-                                            PostMessageW(hwnd, WM_HOTKEY, HOTKEY_ID as WPARAM, 0);
-                                        }
-                                        // 重置状态
-                                        state_guard.waiting_for_second = false;
-                                        state_guard.last_keyup_time = None;
-                                        state_guard.other_key_pressed = false;
-                                        drop(state_guard);
-                                        drop(global_state);
-                                        // 放行消息，让其他程序也能响应
-                                        return CallNextHookEx(HHOOK::default(), nCode, wParam, lParam);
-                                    } else {
-                                        log_hotkey!("[Hotkey] Keyboard hook: Delta {}ms >= 500ms, timeout, resetting state", delta);
-                                    }
-                                } else {
-                                    log_hotkey!("[Hotkey] Keyboard hook: ⚠️ waiting_for_second=true but last_keyup_time is None, resetting");
-                                }
-                            } else {
-                                log_hotkey!("[Hotkey] Keyboard hook: Other key was pressed, resetting state");
-                            }
-                            // 超时或按了其他键，重置状态
-                            state_guard.waiting_for_second = false;
-                            state_guard.last_keyup_time = None;
-                            state_guard.other_key_pressed = false;
-                        } else {
-                            // 第一次抬起，记录时间戳
-                            log_hotkey!("[Hotkey] Keyboard hook: First modifier keyup detected, recording timestamp, waiting for second");
-                            state_guard.last_keyup_time = Some(now);
-                            state_guard.waiting_for_second = true;
-                            state_guard.other_key_pressed = false;
-                        }
-                    }
-                } else {
-                    // 按下了其他键
-                    if wParam == WM_KEYDOWN as WPARAM || wParam == WM_SYSKEYDOWN as WPARAM {
-                        // 如果正在等待第二次，检查超时或标记为按了其他键
-                        if state_guard.waiting_for_second {
-                            let now = std::time::Instant::now();
-                            if let Some(last_time) = state_guard.last_keyup_time {
-                                let delta = now.duration_since(last_time).as_millis();
-                                if delta >= 500 {
-                                    // 超时，直接重置状态
-                                    log_hotkey!("[Hotkey] Keyboard hook: Timeout detected on other key ({}ms >= 500ms), resetting state", delta);
-                                    state_guard.waiting_for_second = false;
-                                    state_guard.last_keyup_time = None;
-                                    state_guard.other_key_pressed = false;
-                                } else {
-                                    // 未超时，标记为按了其他键
-                                    log_hotkey!("[Hotkey] Keyboard hook: Other key pressed while waiting ({}ms < 500ms), marking as interference", delta);
-                                    state_guard.other_key_pressed = true;
-                                }
-                            } else {
-                                // 没有时间戳，直接重置
-                                log_hotkey!("[Hotkey] Keyboard hook: Other key pressed, no timestamp, resetting state");
-                                state_guard.waiting_for_second = false;
-                                state_guard.other_key_pressed = false;
-                            }
-                        }
-                    }
-                }
-            } else {
-                // 不是重复修饰键模式，但钩子已安装（可能是状态不一致）
-                if is_keydown || is_keyup {
-                    log_hotkey!("[Hotkey] Keyboard hook: Hook installed but is_double_modifier=false, vk_code={}, target_vk={}", vk_code, state_guard.vk);
+        if let Some(input) = key_input(wParam, hook_struct) {
+            let fired = LAUNCHER_MATCHER
+                .lock()
+                .unwrap()
+                .as_mut()
+                .is_some_and(|matcher| !matcher.handle(input).fired.is_empty());
+            if fired {
+                log_hotkey!("[Hotkey] Keyboard hook: ✅ Double modifier detected, triggering hotkey");
+                let hwnd = HOTKEY_STATE
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .and_then(|state| state.lock().unwrap().hwnd);
+                if let Some(hwnd) = hwnd {
+                    PostMessageW(hwnd, WM_HOTKEY, HOTKEY_ID as WPARAM, 0);
                 }
             }
-        } else {
-            // 状态未初始化
-            if is_keydown || is_keyup {
-                log_hotkey!("[Hotkey] Keyboard hook: State not initialized, vk_code={}", vk_code);
-            }
         }
         
         // 调用下一个钩子（关键：必须放行消息）
         CallNextHookEx(HHOOK::default(), nCode, wParam, lParam)
     }
 
-    // 将字符串格式的修饰符转换为 Windows 修饰符标志
-    // 返回 (flags, is_double_modifier)
-    fn parse_modifiers(modifiers: &[String]) -> Result<(u32, bool), String> {
-        let mut flags = 0u32;
-        let mut is_double = false;
-        
-        // 检查是否是重复修饰键（如 ["Ctrl", "Ctrl"]）
-        if modifiers.len() == 2 && modifiers[0] == modifiers[1] {
-            is_double = true;
-            // 对于重复修饰键，只设置一次标志
-            match modifiers[0].as_str() {
-                "Alt" => flags = MOD_ALT,
-                "Ctrl" => flags = MOD_CONTROL,
-                "Shift" => flags = MOD_SHIFT,
-                "Meta" => flags = MOD_WIN,
-                _ => return Err(format!("Unknown modifier: {}", modifiers[0])),
-            }
-        } else {
-            // 普通组合键
-            for mod_str in modifiers {
-                match mod_str.as_str() {
-                    "Alt" => flags |= MOD_ALT,
-                    "Ctrl" => flags |= MOD_CONTROL,
-                    "Shift" => flags |= MOD_SHIFT,
-                    "Meta" => flags |= MOD_WIN,
-                    _ => return Err(format!("Unknown modifier: {}", mod_str)),
-                }
-            }
-        }
-        
-        if flags == 0 {
-            return Err("At least one modifier is required".to_string());
-        }
-        Ok((flags, is_double))
-    }
-
-    // 将字符串格式的键转换为 Windows 虚拟键码
-    // 对于重复修饰键，key 可能是修饰键名称（如 "Ctrl"）
-    fn parse_virtual_key(key: &str) -> Result<u32, String> {
-        // 处理修饰键作为键的情况（用于重复修饰键）
-        if key == "Ctrl" {
-            return Ok(0x11); // VK_CONTROL
-        }
-        if key == "Alt" {
-            return Ok(0x12); // VK_MENU (Alt key)
-        }
-        if key == "Shift" {
-            return Ok(0x10); // VK_SHIFT
-        }
-        if key == "Meta" {
-            return Ok(0x5B); // VK_LWIN (Left Windows key)
-        }
-        
-        // 处理特殊键
-        match key {
-            "Space" => Ok(0x20), // VK_SPACE
-            "Enter" => Ok(0x0D), // VK_RETURN
-            "Escape" => Ok(0x1B), // VK_ESCAPE
-            "Tab" => Ok(0x09),   // VK_TAB
-            "Backspace" => Ok(0x08), // VK_BACK
-            "Delete" => Ok(0x2E), // VK_DELETE
-            "Insert" => Ok(0x2D), // VK_INSERT
-            "Home" => Ok(0x24),   // VK_HOME
-            "End" => Ok(0x23),    // VK_END
-            "PageUp" => Ok(0x21), // VK_PRIOR
-            "PageDown" => Ok(0x22), // VK_NEXT
-            "ArrowUp" => Ok(0x26), // VK_UP
-            "ArrowDown" => Ok(0x28), // VK_DOWN
-            "ArrowLeft" => Ok(0x25), // VK_LEFT
-            "ArrowRight" => Ok(0x27), // VK_RIGHT
-            "F1" => Ok(0x70),
-            "F2" => Ok(0x71),
-            "F3" => Ok(0x72),
-            "F4" => Ok(0x73),
-            "F5" => Ok(0x74),
-            "F6" => Ok(0x75),
-            "F7" => Ok(0x76),
-            "F8" => Ok(0x77),
-            "F9" => Ok(0x78),
-            "F10" => Ok(0x79),
-            "F11" => Ok(0x7A),
-            "F12" => Ok(0x7B),
-            _ => {
-                // 处理字母和数字
-                if key.len() == 1 {
-                    let ch = key.chars().next().unwrap();
-                    if ch.is_ascii_alphanumeric() {
-                        // A-Z: 0x41-0x5A, 0-9: 0x30-0x39
-                        let code = ch.to_ascii_uppercase() as u32;
-                        if code >= 0x30 && code <= 0x39 {
-                            Ok(code) // 0-9
-                        } else if code >= 0x41 && code <= 0x5A {
-                            Ok(code) // A-Z
-                        } else {
-                            Err(format!("Unsupported key: {}", key))
-                        }
-                    } else {
-                        Err(format!("Unsupported key: {}", key))
-                    }
-                } else {
-                    Err(format!("Unsupported key: {}", key))
-                }
+    // 将快捷键配置转换为 Windows 修饰符标志和虚拟键码
+    // 返回 (flags, vk, is_double_modifier)
+    fn parse_hotkey_config(config: &crate::settings::HotkeyConfig) -> Result<(u32, u32, bool), String> {
+        let trigger = HotkeyTrigger::from_config(config)?;
+        let hotkey = trigger
+            .single()
+            .ok_or_else(|| "Launcher hotkey cannot be a sequence or hold".to_string())?;
+        if let Hotkey::Combo { modifiers, .. } = hotkey {
+            // RegisterHotKey 路径需要修饰键，单独的 F13–F24/媒体键只支持多快捷键监听器
+            if modifiers.is_empty() {
+                return Err("At least one modifier is required".to_string());
             }
         }
+        Ok((
+            hotkey.win_modifiers(),
+            hotkey.vk(),
+            matches!(hotkey, Hotkey::DoubleTap(_)),
+        ))
     }
 
     pub fn start_hotkey_listener(
@@ -465,9 +282,7 @@ pub mod windows {
     ) -> Result<thread::JoinHandle<()>, String> {
         // 解析快捷键配置，默认使用 Alt+Space
         let (modifiers, vk, is_double) = if let Some(config) = hotkey_config {
-            let (mods, is_double_mod) = parse_modifiers(&config.modifiers)?;
-            let vk_code = parse_virtual_key(&config.key)?;
-            (mods, vk_code, is_double_mod)
+            parse_hotkey_config(&config)?
         } else {
             (MOD_ALT, 0x20, false) // 默认 Alt+Space
         };
@@ -479,9 +294,6 @@ pub mod windows {
             vk,
             is_double_modifier: is_double,
             hook: None,
-            last_keyup_time: None,
-            waiting_for_second: false,
-            other_key_pressed: false,
         }));
 
         // 保存到全局状态
@@ -570,6 +382,7 @@ pub mod windows {
                 // 对于重复修饰键（如双击 Alt），使用键盘钩子而不是 RegisterHotKey
                 if is_double {
                     log_hotkey!("[Hotkey] Initial setup: Double modifier hotkey detected (modifiers={:x}, vk={:x}), using keyboard hook", mods, vk_code);
+                    set_launcher_double_tap(Some(vk_code));
                     
                     // 安装键盘钩子
                     use windows_sys::Win32::Foundation::HINSTANCE;
@@ -690,8 +503,7 @@ pub mod windows {
     // 更新快捷键配置
     // 使用 PostMessage 发送消息到窗口线程，让窗口线程自己执行注册操作
     pub fn update_hotkey(config: crate::settings::HotkeyConfig) -> Result<(), String> {
-        let (modifiers, is_double) = parse_modifiers(&config.modifiers)?;
-        let vk = parse_virtual_key(&config.key)?;
+        let (modifiers, vk, is_double) = parse_hotkey_config(&config)?;
 
         // 等待 hwnd 初始化（最多等待 2 秒）
         let mut retries = 0;
@@ -767,6 +579,34 @@ pub mod windows {
         }
     }
 
+    // 取消注册启动器快捷键（设置中清除快捷键时调用）
+    // 与 update_hotkey 一样交给窗口线程执行
+    pub fn unregister_hotkey() -> Result<(), String> {
+        let hwnd = {
+            let global_state = HOTKEY_STATE.lock().unwrap();
+            let Some(state) = global_state.as_ref() else {
+                return Err("热键监听器未启动".to_string());
+            };
+            let hwnd = state.lock().unwrap().hwnd;
+            hwnd
+        };
+        let Some(hwnd) = hwnd else {
+            return Err("热键窗口未初始化".to_string());
+        };
+
+        unsafe {
+            use windows_sys::Win32::UI::WindowsAndMessaging::PostMessageW;
+            if PostMessageW(hwnd, WM_UNREGISTER_HOTKEY, 0, 0) == 0 {
+                use windows_sys::Win32::Foundation::GetLastError;
+                return Err(format!(
+                    "发送热键取消注册消息失败 (错误代码: {})",
+                    GetLastError()
+                ));
+            }
+        }
+        Ok(())
+    }
+
     unsafe extern "system" fn hotkey_wnd_proc(
         hwnd: HWND,
         msg: u32,
@@ -822,11 +662,9 @@ pub mod windows {
                                 UnhookWindowsHookEx(old_hook);
                                 state_guard.hook = None;
                             }
-                            state_guard.last_keyup_time = None;
-                            state_guard.waiting_for_second = false;
-                            state_guard.other_key_pressed = false;
                         }
                     }
+                    set_launcher_double_tap(Some(vk));
                     
                     // 安装新的键盘钩子
                     use windows_sys::Win32::Foundation::HINSTANCE;
@@ -865,11 +703,9 @@ pub mod windows {
                             UnhookWindowsHookEx(old_hook);
                             state_guard.hook = None;
                         }
-                         state_guard.last_keyup_time = None;
-                        state_guard.waiting_for_second = false;
-                        state_guard.other_key_pressed = false;
                     }
                 }
+                set_launcher_double_tap(None);
                 
                 // 注册新热键（在窗口线程中执行，符合线程亲和性要求）
                 let result = RegisterHotKey(hwnd, HOTKEY_ID, modifiers, vk);
@@ -891,6 +727,26 @@ pub mod windows {
                 
                 0
             }
+            WM_UNREGISTER_HOTKEY => {
+                log_hotkey!("[Hotkey] Window thread: Received hotkey unregister message");
+
+                // 卸载重复修饰键使用的钩子（如果存在）
+                {
+                    let global_state = HOTKEY_STATE.lock().unwrap();
+                    if let Some(state) = global_state.as_ref() {
+                        let mut state_guard = state.lock().unwrap();
+                        if let Some(old_hook) = state_guard.hook {
+                            UnhookWindowsHookEx(old_hook);
+                            state_guard.hook = None;
+                        }
+                    }
+                }
+                set_launcher_double_tap(None);
+
+                // 忽略错误，可能本来就未注册
+                let _ = UnregisterHotKey(hwnd, HOTKEY_ID);
+                0
+            }
             WM_HOTKEY => {
                 if wparam == HOTKEY_ID as usize {
                     // Get sender from window user data
@@ -937,7 +793,7 @@ pub mod windows {
         hook: Arc<Mutex<Option<windows_sys::Win32::UI::WindowsAndMessaging::HHOOK>>>,
         launcher_hwnd: Arc<Mutex<Option<HWND>>>, // 启动器窗口的 HWND
         last_triggered: Arc<Mutex<Option<(String, std::time::Instant)>>>, // 防抖：记录上次触发的插件和时间
-        last_hotkey_triggered: Arc<Mutex<Option<(String, std::time::Instant)>>>, // 防抖：记录上次触发的快捷键组合和时间（用于防止同一快捷键重复触发）
+        matcher: Arc<Mutex<SequenceMatcher<String>>>, // 根据 hotkeys 构建的序列匹配
     }
     
     static MULTI_HOTKEY_MANAGER: LazyLock<Arc<MultiHotkeyManager>> = LazyLock::new(|| {
@@ -948,9 +804,54 @@ pub mod windows {
             hook: Arc::new(Mutex::new(None)),
             launcher_hwnd: Arc::new(Mutex::new(None)),
             last_triggered: Arc::new(Mutex::new(None)),
-            last_hotkey_triggered: Arc::new(Mutex::new(None)),
+            matcher: Arc::new(Mutex::new(SequenceMatcher::new(
+                Vec::new(),
+                *HOTKEY_TIMING.lock().unwrap(),
+            ))),
         })
     });
+
+    // 根据已注册的快捷键重建序列匹配，无效的配置跳过
+    fn rebuild_matcher(
+        manager: &MultiHotkeyManager,
+        hotkeys: &HashMap<String, crate::settings::HotkeyConfig>,
+    ) {
+        let mut triggers: Vec<(String, HotkeyTrigger)> = hotkeys
+            .iter()
+            .filter_map(|(id, config)| match HotkeyTrigger::from_config(config) {
+                Ok(trigger) => Some((id.clone(), trigger)),
+                Err(e) => {
+                    eprintln!("[MultiHotkey] ⚠️  快捷键 {} 无效: {}", id, e);
+                    None
+                }
+            })
+            .collect();
+        // HashMap 的顺序是随机的，排序后共用快捷键时固定触发同一个
+        triggers.sort_by(|a, b| a.0.cmp(&b.0));
+        manager.matcher.lock().unwrap().set_triggers(triggers);
+    }
+
+    // 发送触发的快捷键 ID；插件级防抖：500ms 内重复触发同一个 ID 忽略
+    fn dispatch_fired(manager: &MultiHotkeyManager, fired: Vec<String>) {
+        if fired.is_empty() {
+            return;
+        }
+        let sender_guard = manager.sender.lock().unwrap();
+        let Some(ref sender) = *sender_guard else {
+            return;
+        };
+        let mut last_triggered_guard = manager.last_triggered.lock().unwrap();
+        let now = std::time::Instant::now();
+        for id in fired {
+            if let Some((last_id, last_time)) = last_triggered_guard.as_ref() {
+                if last_id == &id && now.duration_since(*last_time).as_millis() < 500 {
+                    continue;
+                }
+            }
+            *last_triggered_guard = Some((id.clone(), now));
+            let _ = sender.send(id);
+        }
+    }
     
     /// 设置启动器窗口的 HWND
     pub fn set_launcher_hwnd(hwnd: HWND) {
@@ -966,180 +867,50 @@ pub mod windows {
         *sender_guard = Some(sender);
     }
     
-    // 全局键盘钩子回调 - 检查所有已注册的快捷键
+    // 全局键盘钩子回调 - 把按键事件交给序列匹配，检查所有已注册的快捷键
     unsafe extern "system" fn global_keyboard_hook_proc(nCode: i32, wParam: WPARAM, lParam: LPARAM) -> LRESULT {
-        use windows_sys::Win32::UI::WindowsAndMessaging::KBDLLHOOKSTRUCT;
-        use windows_sys::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+        use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, HHOOK, KBDLLHOOKSTRUCT};
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
         
         if nCode < 0 {
-            return CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam);
-        }
-        
-        // 只处理 WM_KEYDOWN，忽略 WM_SYSKEYDOWN，避免重复触发
-        // WM_SYSKEYDOWN 通常用于系统快捷键（如 Alt+Tab），我们只处理普通按键
-        let is_keydown = wParam == WM_KEYDOWN as WPARAM;
-        if !is_keydown {
-            return CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam);
-        }
-        
-        // 检查当前活动窗口是否是启动器窗口
-        // 如果是启动器窗口，允许事件正常传播，不拦截插件快捷键
-        let manager = MULTI_HOTKEY_MANAGER.clone();
-        let launcher_hwnd_guard = manager.launcher_hwnd.lock().unwrap();
-        if let Some(launcher_hwnd) = *launcher_hwnd_guard {
-            if launcher_hwnd != 0 {
-                let foreground_hwnd = GetForegroundWindow();
-                if foreground_hwnd == launcher_hwnd {
-                    // 当前活动窗口是启动器窗口，允许事件正常传播
-                    drop(launcher_hwnd_guard);
-                    return CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam);
-                }
-            }
+            return CallNextHookEx(HHOOK::default(), nCode, wParam, lParam);
         }
-        drop(launcher_hwnd_guard);
         
         let hook_struct = &*(lParam as *const KBDLLHOOKSTRUCT);
-        let vk_code = hook_struct.vkCode as u32;
-        
-        // 获取当前按下的修饰键（使用 GetAsyncKeyState）
-        let mut modifiers: Vec<String> = Vec::new();
-        unsafe {
-            use windows_sys::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
-            // 检查修饰键状态（高位表示当前按下）
-            // 使用 u16 字面量然后转换为 i16 进行位运算
-            const KEY_PRESSED_MASK: i16 = 0x8000u16 as i16;
-            if (GetAsyncKeyState(0xA2) & KEY_PRESSED_MASK) != 0 || (GetAsyncKeyState(0xA3) & KEY_PRESSED_MASK) != 0 { // VK_LCONTROL, VK_RCONTROL
-                modifiers.push("Ctrl".to_string());
-            }
-            if (GetAsyncKeyState(0xA4) & KEY_PRESSED_MASK) != 0 || (GetAsyncKeyState(0xA5) & KEY_PRESSED_MASK) != 0 { // VK_LMENU, VK_RMENU
-                modifiers.push("Alt".to_string());
-            }
-            if (GetAsyncKeyState(0xA0) & KEY_PRESSED_MASK) != 0 || (GetAsyncKeyState(0xA1) & KEY_PRESSED_MASK) != 0 { // VK_LSHIFT, VK_RSHIFT
-                modifiers.push("Shift".to_string());
-            }
-            if (GetAsyncKeyState(0x5B) & KEY_PRESSED_MASK) != 0 || (GetAsyncKeyState(0x5C) & KEY_PRESSED_MASK) != 0 { // VK_LWIN, VK_RWIN
-                modifiers.push("Meta".to_string());
-            }
-        }
-        
-        // 转换虚拟键码为键名
-        let key_name_opt: Option<String> = match vk_code {
-            0x20 => Some("Space".to_string()),
-            0x0D => Some("Enter".to_string()),
-            0x1B => Some("Escape".to_string()),
-            0x09 => Some("Tab".to_string()),
-            0x08 => Some("Backspace".to_string()),
-            0x2E => Some("Delete".to_string()),
-            0x2D => Some("Insert".to_string()),
-            0x24 => Some("Home".to_string()),
-            0x23 => Some("End".to_string()),
-            0x21 => Some("PageUp".to_string()),
-            0x22 => Some("PageDown".to_string()),
-            0x26 => Some("ArrowUp".to_string()),
-            0x28 => Some("ArrowDown".to_string()),
-            0x25 => Some("ArrowLeft".to_string()),
-            0x27 => Some("ArrowRight".to_string()),
-            0x70..=0x7B => {
-                let f_keys = ["F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12"];
-                Some(f_keys[(vk_code - 0x70) as usize].to_string())
-            },
-            0x30..=0x39 => {
-                // 数字键 0-9
-                let digit = ((vk_code - 0x30) as u8 + b'0') as char;
-                Some(digit.to_string())
-            },
-            0x41..=0x5A => {
-                // 字母键 A-Z
-                let letter = (vk_code as u8) as char;
-                Some(letter.to_string())
-            },
-            _ => None,
-        };
-        
-        // 如果没有匹配的键名，继续传递消息
-        let key_name = match key_name_opt {
-            Some(name) => name,
-            None => return CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam),
+        let Some(input) = key_input(wParam, hook_struct) else {
+            return CallNextHookEx(HHOOK::default(), nCode, wParam, lParam);
         };
         
-        // 检查是否匹配任何已注册的快捷键
         let manager = MULTI_HOTKEY_MANAGER.clone();
-        let hotkeys_guard = manager.hotkeys.lock().unwrap();
-        let sender_guard = manager.sender.lock().unwrap();
-        let mut last_triggered_guard = manager.last_triggered.lock().unwrap();
-        let mut last_hotkey_triggered_guard = manager.last_hotkey_triggered.lock().unwrap();
-        
-        // 构建当前快捷键的唯一标识（用于防抖）
-        let mut pressed_modifiers_sorted = modifiers.clone();
-        pressed_modifiers_sorted.sort();
-        let hotkey_signature = format!("{:?}:{}", pressed_modifiers_sorted, key_name);
+        let result = {
+            let mut matcher = manager.matcher.lock().unwrap();
+            // 钩子可能漏掉修饰键的抬起事件（如 Win+L 锁屏），以实际按键状态为准
+            // 普通键可能被我们拦截过，GetAsyncKeyState 不可靠，不做检查
+            const KEY_PRESSED_MASK: i16 = 0x8000u16 as i16;
+            matcher.release_stale(|vk| {
+                vk == input.vk
+                    || Modifier::from_vk(vk).is_none()
+                    || (GetAsyncKeyState(vk as i32) & KEY_PRESSED_MASK) != 0
+            });
+            matcher.handle(input)
+        };
         
-        // 全局防抖：检查是否在 500ms 内重复触发同一个快捷键组合（无论插件ID）
-        // 增加防抖时间，防止 Windows 键盘钩子多次触发导致重复打开窗口
-        let now = std::time::Instant::now();
-        if let Some((last_signature, last_time)) = last_hotkey_triggered_guard.as_ref() {
-            if last_signature == &hotkey_signature && now.duration_since(*last_time).as_millis() < 500 {
-                // 在 500ms 内重复触发相同的快捷键组合，忽略
-                return CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam);
+        // 检查当前活动窗口是否是启动器窗口
+        // 如果是启动器窗口，允许事件正常传播，不拦截插件快捷键（按键状态仍然交给序列匹配记录）
+        let launcher_hwnd = *manager.launcher_hwnd.lock().unwrap();
+        if let Some(launcher_hwnd) = launcher_hwnd {
+            if launcher_hwnd != 0 && GetForegroundWindow() == launcher_hwnd {
+                return CallNextHookEx(HHOOK::default(), nCode, wParam, lParam);
             }
         }
         
-        if let Some(ref sender) = *sender_guard {
-            // 先找到所有匹配的快捷键，检测冲突
-            let mut matched_ids: Vec<String> = Vec::new();
-            
-            for (id, config) in hotkeys_guard.iter() {
-                // 检查修饰键是否匹配
-                let mut config_modifiers = config.modifiers.clone();
-                config_modifiers.sort();
-                let mut pressed_modifiers = modifiers.clone();
-                pressed_modifiers.sort();
-                
-                if config_modifiers == pressed_modifiers && config.key == key_name {
-                    matched_ids.push(id.clone());
-                }
-            }
-            
-            // 如果找到匹配的快捷键
-            if !matched_ids.is_empty() {
-                // 检测冲突：如果有多个插件使用相同快捷键，记录警告
-                if matched_ids.len() > 1 {
-                    eprintln!(
-                        "[MultiHotkey] ⚠️  检测到快捷键 {} 被 {} 个插件共享: {:?}",
-                        hotkey_signature,
-                        matched_ids.len(),
-                        matched_ids
-                    );
-                    eprintln!(
-                        "[MultiHotkey] ⚠️  将触发第一个插件: {}",
-                        matched_ids[0]
-                    );
-                }
-                
-                // 只使用第一个匹配的插件 ID
-                let id = matched_ids[0].clone();
-                
-                // 插件级防抖：检查是否在 500ms 内重复触发同一个插件
-                if let Some((last_id, last_time)) = last_triggered_guard.as_ref() {
-                    if last_id == &id && now.duration_since(*last_time).as_millis() < 500 {
-                        // 在 500ms 内重复触发，忽略
-                        return CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam);
-                    }
-                }
-                
-                // 记录触发时间和插件 ID
-                *last_triggered_guard = Some((id.clone(), now));
-                // 记录触发时间和快捷键签名
-                *last_hotkey_triggered_guard = Some((hotkey_signature.clone(), now));
-                
-                // 匹配！发送事件（只发送一次）
-                let _ = sender.send(id);
-                // 阻止消息传递，防止其他程序响应相同的快捷键
-                return 1; // 返回非零值阻止事件继续传播
-            }
+        dispatch_fired(&manager, result.fired);
+        if result.consume {
+            // 阻止消息传递，防止其他程序响应相同的快捷键或序列中的按键
+            return 1;
         }
         
-        CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam)
+        CallNextHookEx(HHOOK::default(), nCode, wParam, lParam)
     }
     
     /// 启动多快捷键监听器（用于插件快捷键）
@@ -1234,6 +1005,17 @@ pub mod windows {
                     *hook_guard = Some(hook);
                 }
                 
+                // 按住触发和等待超时的组合序列需要定时推进，钩子卸载后退出
+                thread::spawn(|| loop {
+                    thread::sleep(std::time::Duration::from_millis(50));
+                    let manager = MULTI_HOTKEY_MANAGER.clone();
+                    if manager.hook.lock().unwrap().is_none() {
+                        break;
+                    }
+                    let fired = manager.matcher.lock().unwrap().poll(now_ms());
+                    dispatch_fired(&manager, fired);
+                });
+                
                 // 消息循环
                 let mut msg = MSG {
                     hwnd: 0,
@@ -1284,6 +1066,7 @@ pub mod windows {
         let manager = MULTI_HOTKEY_MANAGER.clone();
         let mut hotkeys_guard = manager.hotkeys.lock().unwrap();
         hotkeys_guard.insert(plugin_id.clone(), config);
+        rebuild_matcher(&manager, &hotkeys_guard);
         Ok(())
     }
     
@@ -1292,6 +1075,7 @@ pub mod windows {
         let manager = MULTI_HOTKEY_MANAGER.clone();
         let mut hotkeys_guard = manager.hotkeys.lock().unwrap();
         hotkeys_guard.remove(plugin_id);
+        rebuild_matcher(&manager, &hotkeys_guard);
         Ok(())
     }
     
@@ -1322,6 +1106,7 @@ pub mod windows {
         {
             let mut hotkeys_guard = manager.hotkeys.lock().unwrap();
             hotkeys_guard.clear();
+            rebuild_matcher(&manager, &hotkeys_guard);
         }
         
         eprintln!("[MultiHotkey] All hotkeys cleaned up");
@@ -1335,10 +1120,14 @@ pub mod windows {
         let mut hotkey_to_plugins: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
         
         for (plugin_id, config) in hotkeys.iter() {
-            // 构建快捷键的唯一标识
-            let mut modifiers_sorted = config.modifiers.clone();
-            modifiers_sorted.sort();
-            let hotkey_signature = format!("{:?}:{}", modifiers_sorted, config.key);
+            // 构建快捷键的唯一标识（规范写法）
+            let hotkey_signature = match HotkeyTrigger::from_config(config) {
+                Ok(trigger) => trigger.to_string(),
+                Err(e) => {
+                    eprintln!("[MultiHotkey] ⚠️  快捷键 {} 无效: {}", plugin_id, e);
+                    continue;
+                }
+            };
             
             hotkey_to_plugins
                 .entry(hotkey_signature)
@@ -1368,6 +1157,7 @@ pub mod windows {
         for (plugin_id, config) in hotkeys {
             hotkeys_guard.insert(plugin_id.clone(), config);
         }
+        rebuild_matcher(&manager, &hotkeys_guard);
         
         Ok(())
     }
@@ -1390,7 +1180,17 @@ pub mod windows {
     }
 }
 
-#[cfg(not(target_os = "windows"))]
+// Linux 沿用同名模块，转发到 X11 / GlobalShortcuts portal 实现
+#[cfg(target_os = "linux")]
+pub mod windows {
+    pub use crate::hotkey_linux::{
+        cleanup_hotkeys, register_plugin_hotkey, set_hotkey_timing, start_hotkey_listener,
+        start_multi_hotkey_listener, unregister_hotkey, unregister_plugin_hotkey, update_hotkey,
+        update_plugin_hotkeys,
+    };
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub mod windows {
     use std::sync::mpsc;
     use std::thread;
@@ -1406,6 +1206,10 @@ pub mod windows {
     pub fn update_hotkey(_config: crate::settings::HotkeyConfig) -> Result<(), String> {
         Err("Hotkey listener is only supported on Windows".to_string())
     }
+
+    pub fn unregister_hotkey() -> Result<(), String> {
+        Err("Hotkey listener is only supported on Windows".to_string())
+    }
     
     pub fn start_multi_hotkey_listener(
         _sender: mpsc::Sender<String>,
@@ -1416,6 +1220,10 @@ pub mod windows {
     pub fn set_global_sender(_sender: mpsc::Sender<String>) {
         // No-op on non-Windows
     }
+
+    pub fn set_hotkey_timing(_timing: crate::settings::HotkeyTiming) {
+        // No-op on non-Windows
+    }
     
     pub fn register_plugin_hotkey(
         _plugin_id: String,
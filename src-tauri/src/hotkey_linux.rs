@@ -0,0 +1,877 @@
+//! Linux 全局快捷键
+//!
+//! Wayland 下通过 XDG GlobalShortcuts portal 注册，portal 不可用或在 X11 会话中时改用
+//! `XGrabKey` 抓取。两种后端都只上报单个组合键的按下 / 松开，再交给与 Windows 相同的
+//! 序列匹配，所以组合序列和按住触发在 Linux 上同样可用；按快捷键 ID 发送到与 Windows
+//! 监听器相同的通道。
+//!
+//! 系统只能注册组合键，组合序列的每一步都会单独注册为全局快捷键，单独按下时也会被拦截；
+//! 看不到单独按下的修饰键，所以不支持双击修饰键。
+
+use std::collections::HashMap;
+use std::os::raw::{c_int, c_uint};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use x11_dl::xlib::{self, Display, Xlib};
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::message::Type as MessageType;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+use zbus::MatchRule;
+
+use crate::hotkey_model::{Hotkey, HotkeyTrigger, Modifier};
+use crate::hotkey_sequence::{KeyInput, SequenceMatcher};
+use crate::settings::{HotkeyConfig, HotkeyTiming};
+
+/// 启动器快捷键在匹配器中的 ID，与插件快捷键共用一个匹配器
+const LAUNCHER_HOTKEY_ID: &str = "__launcher";
+
+/// 没有按键事件时推进按住触发和组合序列超时的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 后端上报的一次组合键按下或松开
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stroke {
+    hotkey: Hotkey,
+    pressed: bool,
+}
+
+/// 需要向系统注册的组合键
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BoundShortcut {
+    hotkey: Hotkey,
+    /// 显示在系统快捷键设置中的说明
+    description: String,
+}
+
+struct LinuxHotkeys {
+    /// 所有快捷键配置，启动器快捷键使用 `LAUNCHER_HOTKEY_ID`
+    hotkeys: HashMap<String, HotkeyConfig>,
+    matcher: SequenceMatcher<String>,
+    launcher: Option<Sender<()>>,
+    sender: Option<Sender<String>>,
+    /// 向后端线程发送要注册的组合键；丢弃后后端取消注册并退出
+    backend: Option<Sender<Vec<BoundShortcut>>>,
+}
+
+static HOTKEYS: LazyLock<Mutex<LinuxHotkeys>> = LazyLock::new(|| {
+    Mutex::new(LinuxHotkeys {
+        hotkeys: HashMap::new(),
+        matcher: SequenceMatcher::new(Vec::new(), HotkeyTiming::default()),
+        launcher: None,
+        sender: None,
+        backend: None,
+    })
+});
+
+static CLOCK: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+fn now_ms() -> u64 {
+    CLOCK.elapsed().as_millis() as u64
+}
+
+impl LinuxHotkeys {
+    /// 重建匹配器并把用到的组合键交给后端注册
+    fn apply(&mut self) {
+        let mut triggers = Vec::new();
+        for (id, config) in &self.hotkeys {
+            match HotkeyTrigger::from_config(config) {
+                Ok(trigger) if matches!(trigger.first(), Hotkey::DoubleTap(_)) => {
+                    eprintln!(
+                        "[LinuxHotkey] ⚠️  Linux 不支持双击修饰键，已忽略快捷键 {}: {}",
+                        id, trigger
+                    );
+                }
+                Ok(trigger) => triggers.push((id.clone(), trigger)),
+                Err(e) => eprintln!("[LinuxHotkey] ⚠️  快捷键 {} 无效: {}", id, e),
+            }
+        }
+        triggers.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let shortcuts = bound_shortcuts(&triggers);
+        self.matcher.set_triggers(triggers);
+
+        if self.backend.is_none() && (self.launcher.is_some() || self.sender.is_some()) {
+            let (stroke_tx, stroke_rx) = mpsc::channel();
+            self.backend = Some(spawn_backend(stroke_tx));
+            thread::spawn(move || run_dispatcher(stroke_rx));
+        }
+        if let Some(backend) = &self.backend {
+            let _ = backend.send(shortcuts);
+        }
+    }
+
+    fn dispatch(&self, id: String) {
+        if id == LAUNCHER_HOTKEY_ID {
+            if let Some(launcher) = &self.launcher {
+                let _ = launcher.send(());
+            }
+        } else if let Some(sender) = &self.sender {
+            let _ = sender.send(id);
+        }
+    }
+}
+
+/// 所有触发方式中出现的组合键（去重），说明取第一个用到它的快捷键 ID
+fn bound_shortcuts(triggers: &[(String, HotkeyTrigger)]) -> Vec<BoundShortcut> {
+    let mut shortcuts: Vec<BoundShortcut> = Vec::new();
+    for (id, trigger) in triggers {
+        let steps = match trigger {
+            HotkeyTrigger::Sequence(steps) => steps.as_slice(),
+            HotkeyTrigger::Hold(hotkey) => std::slice::from_ref(hotkey),
+        };
+        for hotkey in steps {
+            if !shortcuts.iter().any(|s| s.hotkey == *hotkey) {
+                let description = if id == LAUNCHER_HOTKEY_ID {
+                    "ReFast 启动器".to_string()
+                } else {
+                    format!("ReFast {}", id)
+                };
+                shortcuts.push(BoundShortcut {
+                    hotkey: hotkey.clone(),
+                    description,
+                });
+            }
+        }
+    }
+    shortcuts
+}
+
+/// 把组合键的按下 / 松开展开成序列匹配的按键事件：先按修饰键后按主键，松开时相反
+fn stroke_inputs(stroke: &Stroke, time_ms: u64) -> Vec<KeyInput> {
+    let Hotkey::Combo { modifiers, key } = &stroke.hotkey else {
+        return Vec::new();
+    };
+    let mut inputs: Vec<KeyInput> = modifiers
+        .iter()
+        .map(|m| m.vk())
+        .chain([*key])
+        .map(|vk| KeyInput {
+            vk,
+            pressed: stroke.pressed,
+            time_ms,
+        })
+        .collect();
+    if !stroke.pressed {
+        inputs.reverse();
+    }
+    inputs
+}
+
+fn feed(matcher: &mut SequenceMatcher<String>, stroke: &Stroke, time_ms: u64) -> Vec<String> {
+    stroke_inputs(stroke, time_ms)
+        .into_iter()
+        .flat_map(|input| matcher.handle(input).fired)
+        .collect()
+}
+
+/// 分发线程：后端退出（通道断开）后结束
+fn run_dispatcher(strokes: Receiver<Stroke>) {
+    loop {
+        let stroke = match strokes.recv_timeout(POLL_INTERVAL) {
+            Ok(stroke) => Some(stroke),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let mut hotkeys = HOTKEYS.lock().unwrap();
+        let now = now_ms();
+        let fired = match stroke {
+            Some(stroke) => feed(&mut hotkeys.matcher, &stroke, now),
+            None => hotkeys.matcher.poll(now),
+        };
+        for id in fired {
+            hotkeys.dispatch(id);
+        }
+    }
+}
+
+fn is_wayland_session() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"))
+}
+
+/// 启动后端线程，返回用于更新注册的通道
+fn spawn_backend(strokes: Sender<Stroke>) -> Sender<Vec<BoundShortcut>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        if is_wayland_session() {
+            let portal = Connection::session()
+                .map_err(|e| e.to_string())
+                .and_then(|conn| PortalBackend::connect(conn, strokes.clone()));
+            match portal {
+                Ok(portal) => return portal.run(rx),
+                Err(e) => eprintln!(
+                    "[LinuxHotkey] GlobalShortcuts portal 不可用，改用 X11: {}",
+                    e
+                ),
+            }
+        }
+        match X11Backend::open(strokes) {
+            Ok(x11) => x11.run(rx),
+            Err(e) => eprintln!("[LinuxHotkey] 无法注册全局快捷键: {}", e),
+        }
+    });
+    tx
+}
+
+/// 取出通道中最新的一次注册请求，中间的请求已经过时
+fn latest(
+    commands: &Receiver<Vec<BoundShortcut>>,
+    first: Vec<BoundShortcut>,
+) -> Vec<BoundShortcut> {
+    commands.try_iter().last().unwrap_or(first)
+}
+
+// ---------------------------------------------------------------------------
+// X11：XGrabKey
+// ---------------------------------------------------------------------------
+
+/// 抓取时需要同时覆盖的 CapsLock / NumLock 组合
+const IGNORED_MASKS: [c_uint; 4] = [
+    0,
+    xlib::LockMask,
+    xlib::Mod2Mask,
+    xlib::LockMask | xlib::Mod2Mask,
+];
+
+const MODIFIER_MASK: c_uint = xlib::ShiftMask | xlib::ControlMask | xlib::Mod1Mask | xlib::Mod4Mask;
+
+static GRAB_FAILED: AtomicBool = AtomicBool::new(false);
+
+// 组合键已被其它程序抓取时 XGrabKey 异步返回 BadAccess，默认的错误处理会退出进程
+unsafe extern "C" fn grab_error_handler(
+    _display: *mut Display,
+    event: *mut xlib::XErrorEvent,
+) -> c_int {
+    if (*event).error_code == xlib::BadAccess {
+        GRAB_FAILED.store(true, Ordering::SeqCst);
+    }
+    0
+}
+
+struct X11Backend {
+    xlib: Xlib,
+    display: *mut Display,
+    root: xlib::Window,
+    /// (keycode, 修饰键掩码) -> 组合键
+    grabs: HashMap<(c_uint, c_uint), Hotkey>,
+    /// 正在按住的 keycode，松开时按它上报，不受期间修饰键变化的影响
+    pressed: HashMap<c_uint, Hotkey>,
+    strokes: Sender<Stroke>,
+}
+
+impl X11Backend {
+    fn open(strokes: Sender<Stroke>) -> Result<Self, String> {
+        let xlib = Xlib::open().map_err(|e| format!("无法加载 libX11: {}", e))?;
+        unsafe {
+            let display = (xlib.XOpenDisplay)(ptr::null());
+            if display.is_null() {
+                return Err("无法连接 X11 显示服务器".to_string());
+            }
+            // 按住不放时只产生一次 KeyRelease
+            (xlib.XkbSetDetectableAutoRepeat)(display, xlib::True, ptr::null_mut());
+            let root = (xlib.XDefaultRootWindow)(display);
+            Ok(X11Backend {
+                xlib,
+                display,
+                root,
+                grabs: HashMap::new(),
+                pressed: HashMap::new(),
+                strokes,
+            })
+        }
+    }
+
+    fn bind(&mut self, shortcuts: &[BoundShortcut]) {
+        for (keycode, mask) in std::mem::take(&mut self.grabs).into_keys() {
+            self.ungrab(keycode, mask);
+        }
+        self.pressed.clear();
+
+        for shortcut in shortcuts {
+            let hotkey = &shortcut.hotkey;
+            let Some(keysym) = hotkey.x11_keysym() else {
+                eprintln!("[LinuxHotkey] ⚠️  X11 不支持快捷键 {}", hotkey);
+                continue;
+            };
+            let keycode = unsafe { (self.xlib.XKeysymToKeycode)(self.display, keysym.into()) };
+            if keycode == 0 {
+                eprintln!("[LinuxHotkey] ⚠️  当前键盘布局没有快捷键 {} 的按键", hotkey);
+                continue;
+            }
+            let keycode = keycode as c_uint;
+            let mask = hotkey.x11_modifier_mask();
+            if self.grab(keycode, mask) {
+                self.grabs.insert((keycode, mask), hotkey.clone());
+            } else {
+                eprintln!("[LinuxHotkey] ⚠️  快捷键 {} 已被其它程序占用", hotkey);
+                self.ungrab(keycode, mask);
+            }
+        }
+        unsafe {
+            (self.xlib.XFlush)(self.display);
+        }
+    }
+
+    fn grab(&self, keycode: c_uint, mask: c_uint) -> bool {
+        unsafe {
+            GRAB_FAILED.store(false, Ordering::SeqCst);
+            let previous = (self.xlib.XSetErrorHandler)(Some(grab_error_handler));
+            for ignored in IGNORED_MASKS {
+                (self.xlib.XGrabKey)(
+                    self.display,
+                    keycode as c_int,
+                    mask | ignored,
+                    self.root,
+                    xlib::False,
+                    xlib::GrabModeAsync,
+                    xlib::GrabModeAsync,
+                );
+            }
+            (self.xlib.XSync)(self.display, xlib::False);
+            (self.xlib.XSetErrorHandler)(previous);
+        }
+        !GRAB_FAILED.load(Ordering::SeqCst)
+    }
+
+    fn ungrab(&self, keycode: c_uint, mask: c_uint) {
+        for ignored in IGNORED_MASKS {
+            unsafe {
+                (self.xlib.XUngrabKey)(self.display, keycode as c_int, mask | ignored, self.root);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: &xlib::XEvent) {
+        let (pressed, key) = match event.get_type() {
+            xlib::KeyPress => (true, unsafe { event.key }),
+            xlib::KeyRelease => (false, unsafe { event.key }),
+            _ => return,
+        };
+        let hotkey = if pressed {
+            let Some(hotkey) = self.grabs.get(&(key.keycode, key.state & MODIFIER_MASK)) else {
+                return;
+            };
+            self.pressed.insert(key.keycode, hotkey.clone());
+            hotkey.clone()
+        } else {
+            let Some(hotkey) = self.pressed.remove(&key.keycode) else {
+                return;
+            };
+            hotkey
+        };
+        let _ = self.strokes.send(Stroke { hotkey, pressed });
+    }
+
+    fn run(mut self, commands: Receiver<Vec<BoundShortcut>>) {
+        let fd = unsafe { (self.xlib.XConnectionNumber)(self.display) };
+        loop {
+            match commands.try_recv() {
+                Ok(shortcuts) => {
+                    let shortcuts = latest(&commands, shortcuts);
+                    self.bind(&shortcuts);
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => break,
+            }
+
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            unsafe {
+                libc::poll(&mut pollfd, 1, POLL_INTERVAL.as_millis() as c_int);
+                while (self.xlib.XPending)(self.display) > 0 {
+                    let mut event: xlib::XEvent = std::mem::zeroed();
+                    (self.xlib.XNextEvent)(self.display, &mut event);
+                    self.handle_event(&event);
+                }
+            }
+        }
+
+        self.bind(&[]);
+        unsafe {
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Wayland：XDG GlobalShortcuts portal
+// ---------------------------------------------------------------------------
+
+const PORTAL_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SHORTCUTS_INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+const SESSION_INTERFACE: &str = "org.freedesktop.portal.Session";
+
+static PORTAL_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+fn portal_token() -> String {
+    format!("refast{}", PORTAL_TOKEN.fetch_add(1, Ordering::Relaxed))
+}
+
+struct PortalBackend {
+    conn: Connection,
+    /// 点对点连接（测试）时没有目标服务名
+    destination: Option<&'static str>,
+    /// 当前会话，信号线程只接受这个会话的激活事件
+    session: Arc<Mutex<Option<OwnedObjectPath>>>,
+}
+
+impl PortalBackend {
+    /// 检查 portal 版本并开始监听激活信号
+    fn connect(conn: Connection, strokes: Sender<Stroke>) -> Result<Self, String> {
+        let destination = conn.is_bus().then_some(PORTAL_DESTINATION);
+        let reply = conn
+            .call_method(
+                destination,
+                PORTAL_PATH,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &(SHORTCUTS_INTERFACE, "version"),
+            )
+            .map_err(|e| format!("GlobalShortcuts portal 不存在: {}", e))?;
+        let version: OwnedValue = reply.body().deserialize().map_err(|e| e.to_string())?;
+        let version = u32::try_from(version).map_err(|e| e.to_string())?;
+        eprintln!("[LinuxHotkey] GlobalShortcuts portal version {}", version);
+
+        let session = Arc::new(Mutex::new(None));
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface(SHORTCUTS_INTERFACE)
+            .map_err(|e| e.to_string())?
+            .build();
+        let signals =
+            MessageIterator::for_match_rule(rule, &conn, None).map_err(|e| e.to_string())?;
+        let current = session.clone();
+        thread::spawn(move || {
+            for message in signals {
+                let Ok(message) = message else { break };
+                let header = message.header();
+                let pressed = match header.member().map(|m| m.as_str()) {
+                    Some("Activated") => true,
+                    Some("Deactivated") => false,
+                    _ => continue,
+                };
+                let Ok((session, id, _timestamp, _options)) =
+                    message
+                        .body()
+                        .deserialize::<(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>)>(
+                        )
+                else {
+                    continue;
+                };
+                if current.lock().unwrap().as_ref() != Some(&session) {
+                    continue;
+                }
+                let Ok(hotkey) = id.parse::<Hotkey>() else {
+                    continue;
+                };
+                if strokes.send(Stroke { hotkey, pressed }).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(PortalBackend {
+            conn,
+            destination,
+            session,
+        })
+    }
+
+    fn run(self, commands: Receiver<Vec<BoundShortcut>>) {
+        while let Ok(shortcuts) = commands.recv() {
+            let shortcuts = latest(&commands, shortcuts);
+            if let Err(e) = self.bind(&shortcuts) {
+                eprintln!("[LinuxHotkey] 注册全局快捷键失败: {}", e);
+            }
+        }
+        self.close_session();
+    }
+
+    /// portal 不能修改已绑定的快捷键，每次都关闭旧会话重新创建
+    fn bind(&self, shortcuts: &[BoundShortcut]) -> Result<(), String> {
+        self.close_session();
+        if shortcuts.is_empty() {
+            return Ok(());
+        }
+
+        let session_token = portal_token();
+        let handle_token = portal_token();
+        let options: HashMap<&str, Value> = HashMap::from([
+            ("handle_token", Value::from(handle_token.as_str())),
+            ("session_handle_token", Value::from(session_token.as_str())),
+        ]);
+        let results = self.request("CreateSession", &(options,))?;
+        let session = match results.get("session_handle").map(|v| &**v) {
+            Some(Value::Str(path)) => OwnedObjectPath::try_from(path.as_str()),
+            Some(Value::ObjectPath(path)) => Ok(OwnedObjectPath::from(path.to_owned())),
+            _ => return Err("CreateSession 没有返回会话".to_string()),
+        }
+        .map_err(|e| e.to_string())?;
+        *self.session.lock().unwrap() = Some(session.clone());
+
+        let entries: Vec<(String, HashMap<&str, Value>)> = shortcuts
+            .iter()
+            .filter_map(|shortcut| {
+                let Some(trigger) = shortcut.hotkey.xdg_trigger() else {
+                    eprintln!("[LinuxHotkey] ⚠️  portal 不支持快捷键 {}", shortcut.hotkey);
+                    return None;
+                };
+                let properties = HashMap::from([
+                    ("description", Value::from(shortcut.description.clone())),
+                    ("preferred_trigger", Value::from(trigger)),
+                ]);
+                Some((shortcut.hotkey.to_string(), properties))
+            })
+            .collect();
+        let handle_token = portal_token();
+        let options: HashMap<&str, Value> =
+            HashMap::from([("handle_token", Value::from(handle_token.as_str()))]);
+        let results = self.request("BindShortcuts", &(&session, entries, "", options))?;
+
+        if let Some(Value::Array(bound)) = results.get("shortcuts").map(|v| &**v) {
+            eprintln!("[LinuxHotkey] Bound {} shortcuts via portal", bound.len());
+        }
+        Ok(())
+    }
+
+    fn close_session(&self) {
+        if let Some(session) = self.session.lock().unwrap().take() {
+            let _ = self.conn.call_method(
+                self.destination,
+                session.as_str(),
+                Some(SESSION_INTERFACE),
+                "Close",
+                &(),
+            );
+        }
+    }
+
+    /// 调用返回 Request 的方法，等待对应的 Response 信号
+    fn request<B>(&self, method: &str, body: &B) -> Result<HashMap<String, OwnedValue>, String>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        // 先订阅再调用，避免 Response 在拿到请求路径之前就已发出
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface(REQUEST_INTERFACE)
+            .and_then(|rule| rule.member("Response"))
+            .map_err(|e| e.to_string())?
+            .build();
+        let responses =
+            MessageIterator::for_match_rule(rule, &self.conn, None).map_err(|e| e.to_string())?;
+
+        let reply = self
+            .conn
+            .call_method(
+                self.destination,
+                PORTAL_PATH,
+                Some(SHORTCUTS_INTERFACE),
+                method,
+                body,
+            )
+            .map_err(|e| format!("{} 失败: {}", method, e))?;
+        let handle: OwnedObjectPath = reply.body().deserialize().map_err(|e| e.to_string())?;
+
+        for message in responses {
+            let message = message.map_err(|e| e.to_string())?;
+            if message.header().path().map(|p| p.as_str()) != Some(handle.as_str()) {
+                continue;
+            }
+            let (code, results): (u32, HashMap<String, OwnedValue>) =
+                message.body().deserialize().map_err(|e| e.to_string())?;
+            return match code {
+                0 => Ok(results),
+                1 => Err(format!("{} 被用户取消", method)),
+                _ => Err(format!("{} 失败", method)),
+            };
+        }
+        Err("portal 连接已断开".to_string())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 与 Windows 监听器相同的接口
+// ---------------------------------------------------------------------------
+
+pub fn start_hotkey_listener(
+    sender: Sender<()>,
+    hotkey_config: Option<HotkeyConfig>,
+) -> Result<(), String> {
+    // 默认使用 Alt+Space
+    let config = match hotkey_config {
+        Some(config) => config,
+        None => Hotkey::combo(&[Modifier::Alt], 0x20)?.to_config(),
+    };
+    HotkeyTrigger::from_config(&config)?;
+
+    let mut hotkeys = HOTKEYS.lock().unwrap();
+    hotkeys.launcher = Some(sender);
+    hotkeys
+        .hotkeys
+        .insert(LAUNCHER_HOTKEY_ID.to_string(), config);
+    hotkeys.apply();
+    Ok(())
+}
+
+pub fn update_hotkey(config: HotkeyConfig) -> Result<(), String> {
+    HotkeyTrigger::from_config(&config)?;
+    let mut hotkeys = HOTKEYS.lock().unwrap();
+    hotkeys
+        .hotkeys
+        .insert(LAUNCHER_HOTKEY_ID.to_string(), config);
+    hotkeys.apply();
+    Ok(())
+}
+
+/// 取消注册启动器快捷键（设置中清除快捷键时调用）
+pub fn unregister_hotkey() -> Result<(), String> {
+    let mut hotkeys = HOTKEYS.lock().unwrap();
+    hotkeys.hotkeys.remove(LAUNCHER_HOTKEY_ID);
+    hotkeys.apply();
+    Ok(())
+}
+
+pub fn start_multi_hotkey_listener(sender: Sender<String>) -> Result<(), String> {
+    let mut hotkeys = HOTKEYS.lock().unwrap();
+    hotkeys.sender = Some(sender);
+    hotkeys.apply();
+    Ok(())
+}
+
+pub fn set_hotkey_timing(timing: HotkeyTiming) {
+    HOTKEYS.lock().unwrap().matcher.set_timing(timing);
+}
+
+pub fn register_plugin_hotkey(plugin_id: String, config: HotkeyConfig) -> Result<(), String> {
+    let mut hotkeys = HOTKEYS.lock().unwrap();
+    hotkeys.hotkeys.insert(plugin_id, config);
+    hotkeys.apply();
+    Ok(())
+}
+
+pub fn unregister_plugin_hotkey(plugin_id: &str) -> Result<(), String> {
+    let mut hotkeys = HOTKEYS.lock().unwrap();
+    hotkeys.hotkeys.remove(plugin_id);
+    hotkeys.apply();
+    Ok(())
+}
+
+/// 替换所有插件快捷键，启动器快捷键保留
+pub fn update_plugin_hotkeys(plugin_hotkeys: HashMap<String, HotkeyConfig>) -> Result<(), String> {
+    let mut hotkeys = HOTKEYS.lock().unwrap();
+    hotkeys.hotkeys.retain(|id, _| id == LAUNCHER_HOTKEY_ID);
+    hotkeys.hotkeys.extend(plugin_hotkeys);
+    hotkeys.apply();
+    Ok(())
+}
+
+/// 取消所有注册并停止后端（在程序退出时调用）
+pub fn cleanup_hotkeys() {
+    let mut hotkeys = HOTKEYS.lock().unwrap();
+    hotkeys.hotkeys.clear();
+    hotkeys.launcher = None;
+    hotkeys.sender = None;
+    hotkeys.apply();
+    hotkeys.backend = None;
+    eprintln!("[LinuxHotkey] All hotkeys cleaned up");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zbus::object_server::SignalEmitter;
+    use zbus::zvariant::ObjectPath;
+
+    fn combo(s: &str) -> Hotkey {
+        s.parse().unwrap()
+    }
+
+    fn stroke(s: &str, pressed: bool) -> Stroke {
+        Stroke {
+            hotkey: combo(s),
+            pressed,
+        }
+    }
+
+    #[test]
+    fn test_strokes_drive_chords_and_hold() {
+        let triggers = vec![
+            ("save".to_string(), "Ctrl+K, Ctrl+S".parse().unwrap()),
+            ("talk".to_string(), "Hold Ctrl+Space".parse().unwrap()),
+        ];
+        let mut matcher = SequenceMatcher::new(triggers, HotkeyTiming::default());
+
+        assert!(feed(&mut matcher, &stroke("Ctrl+K", true), 0).is_empty());
+        assert!(feed(&mut matcher, &stroke("Ctrl+K", false), 50).is_empty());
+        assert_eq!(
+            feed(&mut matcher, &stroke("Ctrl+S", true), 200),
+            vec!["save"]
+        );
+        assert!(feed(&mut matcher, &stroke("Ctrl+S", false), 250).is_empty());
+
+        assert!(feed(&mut matcher, &stroke("Ctrl+Space", true), 1000).is_empty());
+        assert!(matcher.poll(1500).is_empty());
+        assert_eq!(matcher.poll(1900), vec!["talk"]);
+        assert!(feed(&mut matcher, &stroke("Ctrl+Space", false), 2000).is_empty());
+    }
+
+    #[test]
+    fn test_bound_shortcuts_cover_every_step_once() {
+        let triggers = vec![
+            ("a".to_string(), "Ctrl+K, Ctrl+S".parse().unwrap()),
+            ("b".to_string(), "Ctrl+K, Ctrl+D".parse().unwrap()),
+            ("c".to_string(), "Hold Alt+F1".parse().unwrap()),
+        ];
+        let shortcuts = bound_shortcuts(&triggers);
+        let names: Vec<String> = shortcuts.iter().map(|s| s.hotkey.to_string()).collect();
+        assert_eq!(names, ["Ctrl+K", "Ctrl+S", "Ctrl+D", "Alt+F1"]);
+        assert_eq!(shortcuts[0].description, "ReFast a");
+    }
+
+    const SESSION: &str = "/org/freedesktop/portal/desktop/session/1_0/fake";
+
+    /// 记录收到的绑定请求，立即以成功响应
+    struct FakePortal {
+        bound: Sender<Vec<(String, String)>>,
+        requests: AtomicU64,
+    }
+
+    impl FakePortal {
+        async fn respond(
+            &self,
+            conn: &zbus::Connection,
+            results: HashMap<&str, Value<'_>>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            let n = self.requests.fetch_add(1, Ordering::SeqCst);
+            let handle =
+                OwnedObjectPath::try_from(format!("{}/request/fake{}", PORTAL_PATH, n)).unwrap();
+            let emitter = SignalEmitter::new(conn, handle.clone())?;
+            emitter
+                .emit(REQUEST_INTERFACE, "Response", &(0u32, results))
+                .await?;
+            Ok(handle)
+        }
+    }
+
+    #[zbus::interface(name = "org.freedesktop.portal.GlobalShortcuts")]
+    impl FakePortal {
+        #[zbus(property, name = "version")]
+        fn version(&self) -> u32 {
+            1
+        }
+
+        async fn create_session(
+            &self,
+            #[zbus(connection)] conn: &zbus::Connection,
+            _options: HashMap<String, OwnedValue>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            let results = HashMap::from([("session_handle", Value::from(SESSION))]);
+            self.respond(conn, results).await
+        }
+
+        async fn bind_shortcuts(
+            &self,
+            #[zbus(connection)] conn: &zbus::Connection,
+            session_handle: OwnedObjectPath,
+            shortcuts: Vec<(String, HashMap<String, OwnedValue>)>,
+            _parent_window: String,
+            _options: HashMap<String, OwnedValue>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            assert_eq!(session_handle.as_str(), SESSION);
+            let bound = shortcuts
+                .iter()
+                .map(|(id, properties)| {
+                    let trigger = properties
+                        .get("preferred_trigger")
+                        .and_then(|v| String::try_from(v.clone()).ok())
+                        .unwrap_or_default();
+                    (id.clone(), trigger)
+                })
+                .collect();
+            self.bound.send(bound).unwrap();
+            self.respond(conn, HashMap::new()).await
+        }
+    }
+
+    #[test]
+    fn test_portal_binds_shortcuts_and_reports_activation() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let (bound_tx, bound_rx) = mpsc::channel();
+        let portal = FakePortal {
+            bound: bound_tx,
+            requests: AtomicU64::new(0),
+        };
+        let (server, client) = runtime.block_on(async {
+            let (a, b) = tokio::net::UnixStream::pair().unwrap();
+            let server = zbus::connection::Builder::unix_stream(a)
+                .server(zbus::Guid::generate())
+                .unwrap()
+                .p2p()
+                .serve_at(PORTAL_PATH, portal)
+                .unwrap()
+                .build();
+            let client = zbus::connection::Builder::unix_stream(b).p2p().build();
+            let (server, client) = tokio::join!(server, client);
+            (server.unwrap(), client.unwrap())
+        });
+
+        let (stroke_tx, stroke_rx) = mpsc::channel();
+        let backend = PortalBackend::connect(Connection::from(client), stroke_tx).unwrap();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || backend.run(rx));
+        tx.send(vec![BoundShortcut {
+            hotkey: combo("Ctrl+Alt+M"),
+            description: "ReFast 启动器".to_string(),
+        }])
+        .unwrap();
+
+        let bound = bound_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(
+            bound,
+            [("Ctrl+Alt+M".to_string(), "CTRL+ALT+m".to_string())]
+        );
+
+        let emit = |session: &str, member: &str| {
+            let session = ObjectPath::try_from(session).unwrap();
+            let options: HashMap<&str, Value> = HashMap::new();
+            runtime
+                .block_on(server.emit_signal(
+                    None::<zbus::names::BusName>,
+                    PORTAL_PATH,
+                    SHORTCUTS_INTERFACE,
+                    member,
+                    &(session, "Ctrl+Alt+M", 0u64, options),
+                ))
+                .unwrap();
+        };
+        // 其它会话的信号被忽略
+        emit(
+            "/org/freedesktop/portal/desktop/session/1_0/other",
+            "Activated",
+        );
+        emit(SESSION, "Activated");
+        emit(SESSION, "Deactivated");
+
+        let timeout = Duration::from_secs(5);
+        assert_eq!(
+            stroke_rx.recv_timeout(timeout).unwrap(),
+            stroke("Ctrl+Alt+M", true)
+        );
+        assert_eq!(
+            stroke_rx.recv_timeout(timeout).unwrap(),
+            stroke("Ctrl+Alt+M", false)
+        );
+        drop(tx);
+    }
+}
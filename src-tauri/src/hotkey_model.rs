@@ -0,0 +1,839 @@
+//! 与平台无关的快捷键模型
+//!
+//! 快捷键的规范写法：组合键为 "Ctrl+Shift+K"（修饰键按 Ctrl、Alt、Shift、Meta 排序），
+//! 双击修饰键为 "Ctrl Ctrl"。按键名沿用 `keymap` 的逻辑键名，
+//! 另外接受不需要 Shift 的单个标点字符（"Ctrl+;"）。
+//! 触发方式 `HotkeyTrigger` 在此基础上支持依次按下的组合序列（"Ctrl+K, Ctrl+S"）
+//! 和按住触发（"Hold Ctrl+Space"）。
+//! 设置里仍保存旧的 `HotkeyConfig`，通过 `from_config` / `to_config` 互相转换。
+
+use crate::keymap;
+use crate::settings::{HotkeyConfig, Settings};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Modifier {
+    Ctrl,
+    Alt,
+    Shift,
+    Meta,
+}
+
+impl Modifier {
+    pub fn name(self) -> &'static str {
+        match self {
+            Modifier::Ctrl => "Ctrl",
+            Modifier::Alt => "Alt",
+            Modifier::Shift => "Shift",
+            Modifier::Meta => "Meta",
+        }
+    }
+
+    /// 解析修饰键名，不区分大小写，接受常见别名
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Some(Modifier::Ctrl),
+            "alt" | "option" => Some(Modifier::Alt),
+            "shift" => Some(Modifier::Shift),
+            "meta" | "win" | "super" | "cmd" | "command" => Some(Modifier::Meta),
+            _ => None,
+        }
+    }
+
+    /// 虚拟键码对应的修饰键，包括左右变体
+    pub fn from_vk(vk: u32) -> Option<Self> {
+        match vk {
+            0x11 | 0xA2 | 0xA3 => Some(Modifier::Ctrl),
+            0x12 | 0xA4 | 0xA5 => Some(Modifier::Alt),
+            0x10 | 0xA0 | 0xA1 => Some(Modifier::Shift),
+            0x5B | 0x5C => Some(Modifier::Meta),
+            _ => None,
+        }
+    }
+
+    /// 不区分左右的虚拟键码（Meta 取左 Win 键）
+    pub fn vk(self) -> u32 {
+        match self {
+            Modifier::Ctrl => 0x11,
+            Modifier::Alt => 0x12,
+            Modifier::Shift => 0x10,
+            Modifier::Meta => 0x5B,
+        }
+    }
+
+    /// Windows `RegisterHotKey` 的修饰键标志
+    pub fn win_flag(self) -> u32 {
+        match self {
+            Modifier::Alt => 0x0001,
+            Modifier::Ctrl => 0x0002,
+            Modifier::Shift => 0x0004,
+            Modifier::Meta => 0x0008,
+        }
+    }
+
+    /// XDG GlobalShortcuts portal 触发键写法中的修饰键名
+    pub fn xdg_name(self) -> &'static str {
+        match self {
+            Modifier::Ctrl => "CTRL",
+            Modifier::Alt => "ALT",
+            Modifier::Shift => "SHIFT",
+            Modifier::Meta => "LOGO",
+        }
+    }
+
+    /// X11 修饰键掩码（Alt 为 Mod1，Meta 为 Mod4）
+    pub fn x11_mask(self) -> u32 {
+        match self {
+            Modifier::Shift => 1 << 0,
+            Modifier::Ctrl => 1 << 2,
+            Modifier::Alt => 1 << 3,
+            Modifier::Meta => 1 << 6,
+        }
+    }
+}
+
+/// 不需要修饰键也可以单独作为全局快捷键的键：F13–F24 和媒体键
+fn is_standalone_vk(vk: u32) -> bool {
+    matches!(vk, 0x7C..=0x87 | 0xAD..=0xB3)
+}
+
+/// 按下会输入字符的键
+fn is_character_vk(vk: u32) -> bool {
+    matches!(vk, 0x20 | 0x30..=0x39 | 0x41..=0x5A | 0xBA..=0xC0 | 0xDB..=0xDE)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Hotkey {
+    /// 修饰键（已排序去重）加一个普通键，`key` 为虚拟键码
+    Combo { modifiers: Vec<Modifier>, key: u32 },
+    /// 快速连按两次同一个修饰键
+    DoubleTap(Modifier),
+}
+
+impl Hotkey {
+    /// 创建并校验组合键
+    pub fn combo(modifiers: &[Modifier], key: u32) -> Result<Self, String> {
+        let mut modifiers = modifiers.to_vec();
+        modifiers.sort();
+        modifiers.dedup();
+
+        if Modifier::from_vk(key).is_some() {
+            return Err(
+                "A modifier cannot be the main key; use a double tap like \"Ctrl Ctrl\""
+                    .to_string(),
+            );
+        }
+        if key > 0xFF || keymap::key_name_from_vk(key).starts_with("0x") {
+            return Err(format!("Unsupported key code: 0x{:02X}", key));
+        }
+        if modifiers.is_empty() && !is_standalone_vk(key) {
+            return Err("At least one modifier is required".to_string());
+        }
+        if modifiers == [Modifier::Shift] && is_character_vk(key) {
+            return Err(
+                "Shift with a character key would block typing; add another modifier".to_string(),
+            );
+        }
+        Ok(Hotkey::Combo { modifiers, key })
+    }
+
+    /// 从设置中的 `HotkeyConfig` 转换；`["Ctrl", "Ctrl"]` + "Ctrl" 表示双击
+    pub fn from_config(config: &HotkeyConfig) -> Result<Self, String> {
+        let modifiers = config
+            .modifiers
+            .iter()
+            .map(|m| Modifier::parse(m).ok_or_else(|| format!("Unknown modifier: {}", m)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let [first, second] = modifiers[..] {
+            if first == second {
+                let key = config.key.trim();
+                if !key.is_empty() && Modifier::parse(key) != Some(first) {
+                    return Err(format!("Double tap key must be {}", first.name()));
+                }
+                return Ok(Hotkey::DoubleTap(first));
+            }
+        }
+        Hotkey::combo(&modifiers, parse_key(&config.key)?)
+    }
+
+    /// 转换成设置中保存的格式
+    pub fn to_config(&self) -> HotkeyConfig {
+        match self {
+            Hotkey::Combo { modifiers, key } => HotkeyConfig {
+                modifiers: modifiers.iter().map(|m| m.name().to_string()).collect(),
+                key: keymap::key_name_from_vk(*key),
+                sequence: None,
+            },
+            Hotkey::DoubleTap(modifier) => HotkeyConfig {
+                modifiers: vec![modifier.name().to_string(); 2],
+                key: modifier.name().to_string(),
+                sequence: None,
+            },
+        }
+    }
+
+    /// Windows `RegisterHotKey` 的修饰键标志；双击时为该修饰键自身的标志
+    pub fn win_modifiers(&self) -> u32 {
+        match self {
+            Hotkey::Combo { modifiers, .. } => {
+                modifiers.iter().fold(0, |flags, m| flags | m.win_flag())
+            }
+            Hotkey::DoubleTap(modifier) => modifier.win_flag(),
+        }
+    }
+
+    /// 主键的 Windows 虚拟键码
+    pub fn vk(&self) -> u32 {
+        match self {
+            Hotkey::Combo { key, .. } => *key,
+            Hotkey::DoubleTap(modifier) => modifier.vk(),
+        }
+    }
+
+    /// 主键的 X11 keysym
+    pub fn x11_keysym(&self) -> Option<u32> {
+        keymap::x11_keysym_from_vk(self.vk())
+    }
+
+    /// XDG GlobalShortcuts portal 的触发键写法（如 "CTRL+SHIFT+k"）；双击不支持
+    pub fn xdg_trigger(&self) -> Option<String> {
+        let Hotkey::Combo { modifiers, key } = self else {
+            return None;
+        };
+        let mut parts: Vec<String> = modifiers.iter().map(|m| m.xdg_name().to_string()).collect();
+        parts.push(keymap::xkb_key_name_from_vk(*key)?);
+        Some(parts.join("+"))
+    }
+
+    /// X11 修饰键掩码；双击没有修饰键
+    pub fn x11_modifier_mask(&self) -> u32 {
+        match self {
+            Hotkey::Combo { modifiers, .. } => {
+                modifiers.iter().fold(0, |mask, m| mask | m.x11_mask())
+            }
+            Hotkey::DoubleTap(_) => 0,
+        }
+    }
+}
+
+/// 解析主键名：逻辑键名（含别名）或不需要 Shift 的单个标点字符
+fn parse_key(name: &str) -> Result<u32, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Key is required".to_string());
+    }
+    // Hex codes are for recordings only; hotkeys must use a named key
+    if !name.starts_with("0x") && !name.starts_with("0X") {
+        if let Some(vk) = keymap::vk_from_key_name(name) {
+            return Ok(vk);
+        }
+    }
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        if let Some((vk, false)) = keymap::vk_for_char(ch) {
+            return Ok(vk);
+        }
+    }
+    Err(format!("Unsupported key: {}", name))
+}
+
+impl FromStr for Hotkey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // "Ctrl Ctrl"
+        let words: Vec<&str> = s.split_whitespace().collect();
+        if let [first, second] = words[..] {
+            if let (Some(a), Some(b)) = (Modifier::parse(first), Modifier::parse(second)) {
+                if a == b {
+                    return Ok(Hotkey::DoubleTap(a));
+                }
+            }
+        }
+
+        let parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts
+            .split_last()
+            .ok_or_else(|| "Hotkey is empty".to_string())?;
+        let modifiers = modifiers
+            .iter()
+            .map(|m| Modifier::parse(m).ok_or_else(|| format!("Unknown modifier: {}", m)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Hotkey::combo(&modifiers, parse_key(key)?)
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hotkey::Combo { modifiers, key } => {
+                for modifier in modifiers {
+                    write!(f, "{}+", modifier.name())?;
+                }
+                write!(f, "{}", keymap::key_name_from_vk(*key))
+            }
+            Hotkey::DoubleTap(modifier) => write!(f, "{} {}", modifier.name(), modifier.name()),
+        }
+    }
+}
+
+impl TryFrom<String> for Hotkey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Hotkey> for String {
+    fn from(hotkey: Hotkey) -> Self {
+        hotkey.to_string()
+    }
+}
+
+/// 组合序列最多的步数
+pub const MAX_SEQUENCE_STEPS: usize = 4;
+
+/// 快捷键的触发方式
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum HotkeyTrigger {
+    /// 依次按下的一个或多个快捷键；只有一步时就是普通快捷键
+    Sequence(Vec<Hotkey>),
+    /// 按住组合键一段时间
+    Hold(Hotkey),
+}
+
+impl HotkeyTrigger {
+    /// 创建并校验组合序列
+    pub fn sequence(steps: Vec<Hotkey>) -> Result<Self, String> {
+        if steps.is_empty() {
+            return Err("Hotkey is empty".to_string());
+        }
+        if steps.len() > MAX_SEQUENCE_STEPS {
+            return Err(format!(
+                "A sequence can have at most {} steps",
+                MAX_SEQUENCE_STEPS
+            ));
+        }
+        if steps.len() > 1 && steps.iter().any(|h| matches!(h, Hotkey::DoubleTap(_))) {
+            return Err("A double tap cannot be part of a sequence".to_string());
+        }
+        Ok(HotkeyTrigger::Sequence(steps))
+    }
+
+    /// 创建按住触发，只支持组合键
+    pub fn hold(hotkey: Hotkey) -> Result<Self, String> {
+        match hotkey {
+            Hotkey::Combo { .. } => Ok(HotkeyTrigger::Hold(hotkey)),
+            Hotkey::DoubleTap(_) => Err("A double tap cannot be held".to_string()),
+        }
+    }
+
+    /// 只有一步的普通快捷键
+    pub fn single(&self) -> Option<&Hotkey> {
+        match self {
+            HotkeyTrigger::Sequence(steps) if steps.len() == 1 => steps.first(),
+            _ => None,
+        }
+    }
+
+    /// 第一步的快捷键
+    pub fn first(&self) -> &Hotkey {
+        match self {
+            HotkeyTrigger::Sequence(steps) => &steps[0],
+            HotkeyTrigger::Hold(hotkey) => hotkey,
+        }
+    }
+
+    /// 是否与另一个快捷键重叠：相同，或一个组合序列是另一个的前缀，
+    /// 或按住触发的组合键与组合序列的第一步相同（按下时两者会同时触发）
+    pub fn overlaps(&self, other: &HotkeyTrigger) -> bool {
+        match (self, other) {
+            (HotkeyTrigger::Sequence(a), HotkeyTrigger::Sequence(b)) => {
+                a.starts_with(b) || b.starts_with(a)
+            }
+            (HotkeyTrigger::Hold(a), HotkeyTrigger::Hold(b)) => a == b,
+            (HotkeyTrigger::Hold(hold), HotkeyTrigger::Sequence(steps))
+            | (HotkeyTrigger::Sequence(steps), HotkeyTrigger::Hold(hold)) => {
+                steps.first() == Some(hold)
+            }
+        }
+    }
+
+    /// 从设置转换；有 `sequence` 时以它为准
+    pub fn from_config(config: &HotkeyConfig) -> Result<Self, String> {
+        match config.sequence.as_deref().map(str::trim) {
+            Some(sequence) if !sequence.is_empty() => sequence.parse(),
+            _ => Ok(HotkeyTrigger::Sequence(vec![Hotkey::from_config(config)?])),
+        }
+    }
+
+    /// 转换成设置中保存的格式；普通快捷键不写 `sequence`，保持旧格式
+    pub fn to_config(&self) -> HotkeyConfig {
+        let mut config = self.first().to_config();
+        if self.single().is_none() {
+            config.sequence = Some(self.to_string());
+        }
+        config
+    }
+}
+
+impl From<Hotkey> for HotkeyTrigger {
+    fn from(hotkey: Hotkey) -> Self {
+        HotkeyTrigger::Sequence(vec![hotkey])
+    }
+}
+
+impl FromStr for HotkeyTrigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((word, rest)) = s.split_once(char::is_whitespace) {
+            if word.eq_ignore_ascii_case("hold") {
+                return HotkeyTrigger::hold(rest.parse()?);
+            }
+        }
+        let steps = s
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<Hotkey>, _>>()?;
+        HotkeyTrigger::sequence(steps)
+    }
+}
+
+impl fmt::Display for HotkeyTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HotkeyTrigger::Sequence(steps) => {
+                for (i, step) in steps.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", step)?;
+                }
+                Ok(())
+            }
+            HotkeyTrigger::Hold(hotkey) => write!(f, "Hold {}", hotkey),
+        }
+    }
+}
+
+impl TryFrom<String> for HotkeyTrigger {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<HotkeyTrigger> for String {
+    fn from(trigger: HotkeyTrigger) -> Self {
+        trigger.to_string()
+    }
+}
+
+/// 快捷键解析结果，附带各平台的按键编码，供设置界面显示和排查
+///
+/// 组合序列和按住触发的按键编码取第一步
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyInfo {
+    /// 规范写法
+    pub display: String,
+    pub config: HotkeyConfig,
+    pub is_double_tap: bool,
+    pub is_sequence: bool,
+    pub is_hold: bool,
+    pub win_modifiers: u32,
+    pub win_vk: u32,
+    pub x11_keysym: Option<u32>,
+    pub x11_modifier_mask: u32,
+    /// GlobalShortcuts portal 的触发键写法，双击修饰键为空
+    pub xdg_trigger: Option<String>,
+}
+
+impl From<&HotkeyTrigger> for HotkeyInfo {
+    fn from(trigger: &HotkeyTrigger) -> Self {
+        let hotkey = trigger.first();
+        Self {
+            display: trigger.to_string(),
+            config: trigger.to_config(),
+            is_double_tap: matches!(hotkey, Hotkey::DoubleTap(_)),
+            is_sequence: matches!(trigger, HotkeyTrigger::Sequence(steps) if steps.len() > 1),
+            is_hold: matches!(trigger, HotkeyTrigger::Hold(_)),
+            win_modifiers: hotkey.win_modifiers(),
+            win_vk: hotkey.vk(),
+            x11_keysym: hotkey.x11_keysym(),
+            x11_modifier_mask: hotkey.x11_modifier_mask(),
+            xdg_trigger: hotkey.xdg_trigger(),
+        }
+    }
+}
+
+/// 快捷键的使用者
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "id", rename_all = "camelCase")]
+pub enum HotkeyOwner {
+    Launcher,
+    AppCenter,
+    Plugin(String),
+    App(String),
+    Recording(String),
+    PlaybackPanic,
+}
+
+impl HotkeyOwner {
+    fn description(&self) -> String {
+        match self {
+            HotkeyOwner::Launcher => "启动器快捷键".to_string(),
+            HotkeyOwner::AppCenter => "应用中心快捷键".to_string(),
+            HotkeyOwner::Plugin(id) => format!("插件 \"{}\" 的快捷键", id),
+            HotkeyOwner::App(path) => {
+                let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+                format!("应用 \"{}\" 的快捷键", name)
+            }
+            HotkeyOwner::Recording(file_name) => format!("录制 \"{}\" 的快捷键", file_name),
+            HotkeyOwner::PlaybackPanic => "停止回放快捷键".to_string(),
+        }
+    }
+
+    /// 冲突时提示给用户的文字
+    pub fn conflict_message(&self) -> String {
+        format!("快捷键冲突：与{}相同，请修改后再保存", self.description())
+    }
+
+    /// 前缀重叠时提示给用户的文字，`hotkey` 为该使用者的快捷键
+    pub fn overlap_message(&self, hotkey: &HotkeyTrigger) -> String {
+        format!(
+            "快捷键冲突：与{}（{}）前缀重叠，按下时会同时触发，请修改后再保存",
+            self.description(),
+            hotkey
+        )
+    }
+}
+
+/// 设置中所有已配置的快捷键；无法解析的配置也一并返回，便于提示
+pub fn configured_hotkeys(
+    settings: &Settings,
+) -> Vec<(HotkeyOwner, Result<HotkeyTrigger, String>)> {
+    let mut hotkeys = Vec::new();
+    let mut push = |owner: HotkeyOwner, config: &HotkeyConfig| {
+        hotkeys.push((owner, HotkeyTrigger::from_config(config)));
+    };
+    if let Some(ref config) = settings.hotkey {
+        push(HotkeyOwner::Launcher, config);
+    }
+    if let Some(ref config) = settings.app_center_hotkey {
+        push(HotkeyOwner::AppCenter, config);
+    }
+    for (id, config) in &settings.plugin_hotkeys {
+        push(HotkeyOwner::Plugin(id.clone()), config);
+    }
+    for (path, config) in &settings.app_hotkeys {
+        push(HotkeyOwner::App(path.clone()), config);
+    }
+    for (file_name, binding) in &settings.recording_bindings {
+        if let Some(ref config) = binding.hotkey {
+            push(HotkeyOwner::Recording(file_name.clone()), config);
+        }
+    }
+    if let Some(ref config) = settings.playback_panic_hotkey {
+        push(HotkeyOwner::PlaybackPanic, config);
+    }
+    // HashMap iteration order is random; keep results stable for the UI
+    hotkeys.sort_by_key(|(owner, _)| format!("{:?}", owner));
+    hotkeys
+}
+
+/// 找出与 `hotkey` 冲突的其它快捷键的使用者及其快捷键
+///
+/// 优先返回完全相同的，其次是前缀重叠的（见 [`HotkeyTrigger::overlaps`]）
+pub fn find_conflict(
+    settings: &Settings,
+    hotkey: &HotkeyTrigger,
+    own: &HotkeyOwner,
+) -> Option<(HotkeyOwner, HotkeyTrigger)> {
+    let others: Vec<(HotkeyOwner, HotkeyTrigger)> = configured_hotkeys(settings)
+        .into_iter()
+        .filter(|(owner, _)| owner != own)
+        .filter_map(|(owner, other)| other.ok().map(|other| (owner, other)))
+        .collect();
+    others
+        .iter()
+        .find(|(_, other)| other == hotkey)
+        .or_else(|| others.iter().find(|(_, other)| other.overlaps(hotkey)))
+        .cloned()
+}
+
+/// 多个使用者的快捷键相同或互相重叠（见 [`HotkeyTrigger::overlaps`]）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyConflict {
+    /// 组内最短的快捷键，其余快捷键都以它开头
+    pub hotkey: HotkeyTrigger,
+    pub owners: Vec<HotkeyOwner>,
+}
+
+/// 检查设置中所有快捷键的冲突，间接重叠的快捷键也归到同一组
+pub fn find_conflicts(settings: &Settings) -> Vec<HotkeyConflict> {
+    let hotkeys: Vec<(HotkeyOwner, HotkeyTrigger)> = configured_hotkeys(settings)
+        .into_iter()
+        .filter_map(|(owner, hotkey)| Some((owner, hotkey.ok()?)))
+        .collect();
+
+    // 每个快捷键所在的组，重叠时把两组合并
+    let mut groups: Vec<usize> = (0..hotkeys.len()).collect();
+    for i in 0..hotkeys.len() {
+        for j in 0..i {
+            if groups[i] != groups[j] && hotkeys[i].1.overlaps(&hotkeys[j].1) {
+                let (from, to) = (groups[i], groups[j]);
+                for group in groups.iter_mut().filter(|g| **g == from) {
+                    *group = to;
+                }
+            }
+        }
+    }
+
+    let steps = |trigger: &HotkeyTrigger| match trigger {
+        HotkeyTrigger::Sequence(steps) => steps.len(),
+        HotkeyTrigger::Hold(_) => 1,
+    };
+    let mut conflicts: Vec<(usize, HotkeyConflict)> = Vec::new();
+    for ((owner, hotkey), group) in hotkeys.into_iter().zip(groups) {
+        match conflicts.iter_mut().find(|(g, _)| *g == group) {
+            Some((_, conflict)) => {
+                if steps(&hotkey) < steps(&conflict.hotkey) {
+                    conflict.hotkey = hotkey;
+                }
+                conflict.owners.push(owner);
+            }
+            None => conflicts.push((
+                group,
+                HotkeyConflict {
+                    hotkey,
+                    owners: vec![owner],
+                },
+            )),
+        }
+    }
+    conflicts
+        .into_iter()
+        .map(|(_, conflict)| conflict)
+        .filter(|conflict| conflict.owners.len() > 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::RecordingBinding;
+
+    fn config(modifiers: &[&str], key: &str) -> HotkeyConfig {
+        HotkeyConfig {
+            modifiers: modifiers.iter().map(|m| m.to_string()).collect(),
+            key: key.to_string(),
+            sequence: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_and_canonical_form() {
+        let cases = [
+            ("ctrl + shift + k", "Ctrl+Shift+K"),
+            ("Shift+Control+K", "Ctrl+Shift+K"),
+            ("Win+Alt+F13", "Alt+Meta+F13"),
+            ("Ctrl+Numpad5", "Ctrl+Numpad5"),
+            ("Ctrl+;", "Ctrl+Semicolon"),
+            ("Alt+Esc", "Alt+Escape"),
+            ("F24", "F24"),
+            ("MediaPlayPause", "MediaPlayPause"),
+            ("Ctrl Ctrl", "Ctrl Ctrl"),
+            ("shift  shift", "Shift Shift"),
+        ];
+        for (input, canonical) in cases {
+            let hotkey: Hotkey = input.parse().unwrap_or_else(|e| panic!("{}: {}", input, e));
+            assert_eq!(hotkey.to_string(), canonical);
+            assert_eq!(canonical.parse::<Hotkey>().unwrap(), hotkey);
+        }
+
+        for invalid in [
+            "K",
+            "F5",
+            "Ctrl+Shift",
+            "Hyper+K",
+            "Ctrl+Nope",
+            "Shift+A",
+            "Ctrl+0xE7",
+            "Ctrl+:",
+            "",
+        ] {
+            assert!(invalid.parse::<Hotkey>().is_err(), "{}", invalid);
+        }
+        assert!("Shift+Ctrl+A".parse::<Hotkey>().is_ok());
+    }
+
+    #[test]
+    fn test_config_round_trip_and_platform_codes() {
+        let hotkey = Hotkey::from_config(&config(&["Shift", "Ctrl"], "k")).unwrap();
+        assert_eq!(hotkey.to_config().modifiers, vec!["Ctrl", "Shift"]);
+        assert_eq!(hotkey.to_config().key, "K");
+        assert_eq!(hotkey.win_modifiers(), 0x0002 | 0x0004);
+        assert_eq!(hotkey.vk(), 0x4B);
+        assert_eq!(hotkey.x11_keysym(), Some(0x6B));
+        assert_eq!(hotkey.x11_modifier_mask(), 4 | 1);
+        assert_eq!(hotkey.xdg_trigger().as_deref(), Some("CTRL+SHIFT+k"));
+
+        let double = Hotkey::from_config(&config(&["Ctrl", "Ctrl"], "Ctrl")).unwrap();
+        assert_eq!(double, Hotkey::DoubleTap(Modifier::Ctrl));
+        assert_eq!(Hotkey::from_config(&double.to_config()).unwrap(), double);
+        assert_eq!(double.vk(), 0x11);
+        assert_eq!(double.xdg_trigger(), None);
+        assert!(Hotkey::from_config(&config(&["Ctrl", "Ctrl"], "K")).is_err());
+
+        let json = serde_json::to_string(&hotkey).unwrap();
+        assert_eq!(json, "\"Ctrl+Shift+K\"");
+        assert_eq!(serde_json::from_str::<Hotkey>(&json).unwrap(), hotkey);
+    }
+
+    #[test]
+    fn test_trigger_sequences_and_hold() {
+        let chord: HotkeyTrigger = "ctrl+k,ctrl+shift+s".parse().unwrap();
+        assert_eq!(chord.to_string(), "Ctrl+K, Ctrl+Shift+S");
+        assert!(chord.single().is_none());
+        let saved = chord.to_config();
+        assert_eq!(saved.key, "K");
+        assert_eq!(saved.sequence.as_deref(), Some("Ctrl+K, Ctrl+Shift+S"));
+        assert_eq!(HotkeyTrigger::from_config(&saved).unwrap(), chord);
+
+        let hold: HotkeyTrigger = "hold Ctrl+Space".parse().unwrap();
+        assert_eq!(hold.to_string(), "Hold Ctrl+Space");
+        assert_eq!(serde_json::to_string(&hold).unwrap(), "\"Hold Ctrl+Space\"");
+
+        // Plain hotkeys keep the old settings shape
+        let single: HotkeyTrigger = "Shift Shift".parse().unwrap();
+        assert_eq!(single.single(), Some(&Hotkey::DoubleTap(Modifier::Shift)));
+        assert_eq!(single.to_config().sequence, None);
+        assert_eq!(
+            HotkeyTrigger::from_config(&config(&["Ctrl"], "K")).unwrap(),
+            HotkeyTrigger::from("Ctrl+K".parse::<Hotkey>().unwrap())
+        );
+
+        for invalid in [
+            "Ctrl+K, Ctrl Ctrl",
+            "Hold Ctrl Ctrl",
+            "Ctrl+K,",
+            "Ctrl+A, Ctrl+B, Ctrl+C, Ctrl+D, Ctrl+E",
+        ] {
+            assert!(invalid.parse::<HotkeyTrigger>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_conflicts_across_settings() {
+        let mut settings = Settings {
+            hotkey: Some(config(&["Alt"], "Space")),
+            app_center_hotkey: Some(config(&["Ctrl", "Ctrl"], "Ctrl")),
+            ..Default::default()
+        };
+        settings
+            .plugin_hotkeys
+            .insert("memo".to_string(), config(&["Ctrl", "Alt"], "M"));
+        settings.app_hotkeys.insert(
+            "C:\\Apps\\notes.exe".to_string(),
+            config(&["Alt", "Ctrl"], "m"),
+        );
+        settings.recording_bindings.insert(
+            "login.json".to_string(),
+            RecordingBinding {
+                hotkey: Some(config(&["Alt"], "Space")),
+                ..Default::default()
+            },
+        );
+
+        let conflicts = find_conflicts(&settings);
+        assert_eq!(conflicts.len(), 2);
+        let memo = conflicts
+            .iter()
+            .find(|c| c.hotkey.to_string() == "Ctrl+Alt+M")
+            .unwrap();
+        assert_eq!(
+            memo.owners,
+            vec![
+                HotkeyOwner::App("C:\\Apps\\notes.exe".to_string()),
+                HotkeyOwner::Plugin("memo".to_string())
+            ]
+        );
+
+        let candidate: HotkeyTrigger = "Ctrl Ctrl".parse().unwrap();
+        assert_eq!(
+            find_conflict(&settings, &candidate, &HotkeyOwner::AppCenter),
+            None
+        );
+        let (owner, _) = find_conflict(
+            &settings,
+            &candidate,
+            &HotkeyOwner::Plugin("memo".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            owner.conflict_message(),
+            "快捷键冲突：与应用中心快捷键相同，请修改后再保存"
+        );
+        assert_eq!(
+            HotkeyOwner::App("C:\\Apps\\notes.exe".to_string()).conflict_message(),
+            "快捷键冲突：与应用 \"notes.exe\" 的快捷键相同，请修改后再保存"
+        );
+    }
+
+    #[test]
+    fn test_prefix_overlaps_conflict() {
+        let trigger = |s: &str| s.parse::<HotkeyTrigger>().unwrap();
+        assert!(trigger("Ctrl+K").overlaps(&trigger("Ctrl+K, Ctrl+C")));
+        assert!(trigger("Ctrl+K, Ctrl+C, Ctrl+V").overlaps(&trigger("Ctrl+K, Ctrl+C")));
+        assert!(trigger("Hold Ctrl+K").overlaps(&trigger("Ctrl+K")));
+        assert!(trigger("Ctrl+K, Ctrl+C").overlaps(&trigger("Hold Ctrl+K")));
+        assert!(!trigger("Ctrl+K, Ctrl+C").overlaps(&trigger("Ctrl+K, Ctrl+V")));
+        assert!(!trigger("Ctrl+C, Ctrl+K").overlaps(&trigger("Ctrl+K")));
+        assert!(!trigger("Hold Ctrl+K").overlaps(&trigger("Hold Ctrl+J")));
+
+        let mut settings = Settings::default();
+        settings
+            .plugin_hotkeys
+            .insert("memo".to_string(), config(&["Ctrl"], "K"));
+        let (owner, hotkey) = find_conflict(
+            &settings,
+            &trigger("Ctrl+K, Ctrl+S"),
+            &HotkeyOwner::AppCenter,
+        )
+        .unwrap();
+        assert_eq!(owner, HotkeyOwner::Plugin("memo".to_string()));
+        assert_eq!(
+            owner.overlap_message(&hotkey),
+            "快捷键冲突：与插件 \"memo\" 的快捷键（Ctrl+K）前缀重叠，按下时会同时触发，请修改后再保存"
+        );
+        assert!(find_conflict(&settings, &trigger("Hold Ctrl+K"), &HotkeyOwner::AppCenter).is_some());
+        assert!(find_conflict(&settings, &trigger("Ctrl+J, Ctrl+K"), &HotkeyOwner::AppCenter).is_none());
+
+        // 重叠的快捷键归为一组，报告最短的那个
+        settings.hotkey = Some(config(&["Ctrl"], "J"));
+        settings.app_center_hotkey = Some(trigger("Ctrl+K, Ctrl+S").to_config());
+        settings.recording_bindings.insert(
+            "login.json".to_string(),
+            RecordingBinding {
+                hotkey: Some(trigger("Ctrl+K, Ctrl+D").to_config()),
+                ..Default::default()
+            },
+        );
+        let conflicts = find_conflicts(&settings);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].hotkey.to_string(), "Ctrl+K");
+        assert_eq!(conflicts[0].owners.len(), 3);
+    }
+}
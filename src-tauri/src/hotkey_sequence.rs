@@ -0,0 +1,467 @@
+//! 快捷键序列匹配
+//!
+//! 与系统键盘钩子无关的状态机：依次输入带时间戳的按键按下 / 抬起事件，
+//! 输出被触发的快捷键。支持组合序列（"Ctrl+K, Ctrl+S"）、双击修饰键（"Shift Shift"）
+//! 和按住触发（"Hold Ctrl+Space"）。
+//!
+//! 如果一个已完整匹配的序列同时是更长序列的开头，会等到超时或被其它按键打断后才触发；
+//! 按住触发同样依赖时间推进，所以钩子需要定时调用 `poll`。
+
+use crate::hotkey_model::{Hotkey, HotkeyTrigger, Modifier};
+use crate::settings::HotkeyTiming;
+
+/// 一次按键事件，`vk` 为虚拟键码，左右修饰键都可以
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyInput {
+    pub vk: u32,
+    pub pressed: bool,
+    pub time_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult<Id> {
+    /// 本次事件触发的快捷键
+    pub fired: Vec<Id>,
+    /// 按键属于某个快捷键，钩子应当拦截，不再传给其它程序
+    pub consume: bool,
+}
+
+struct TapCandidate {
+    modifier: Modifier,
+    /// 按下期间没有按其它键
+    clean: bool,
+}
+
+struct HoldState {
+    index: usize,
+    since_ms: u64,
+    fired: bool,
+}
+
+pub struct SequenceMatcher<Id> {
+    triggers: Vec<(Id, HotkeyTrigger)>,
+    timing: HotkeyTiming,
+    /// 当前按下的键（原始虚拟键码，按按下顺序）
+    held: Vec<u32>,
+    /// 当前序列已按下的步骤
+    strokes: Vec<Hotkey>,
+    last_stroke_ms: u64,
+    /// 已完整匹配、但还可能是更长序列开头的触发器
+    pending: Option<usize>,
+    tap: Option<TapCandidate>,
+    /// 上一次单独点击的修饰键和抬起时间
+    last_tap: Option<(Modifier, u64)>,
+    hold: Option<HoldState>,
+}
+
+impl<Id: Clone> SequenceMatcher<Id> {
+    pub fn new(triggers: Vec<(Id, HotkeyTrigger)>, timing: HotkeyTiming) -> Self {
+        Self {
+            triggers,
+            timing,
+            held: Vec::new(),
+            strokes: Vec::new(),
+            last_stroke_ms: 0,
+            pending: None,
+            tap: None,
+            last_tap: None,
+            hold: None,
+        }
+    }
+
+    /// 替换快捷键，丢弃进行中的序列，保留按键状态
+    pub fn set_triggers(&mut self, triggers: Vec<(Id, HotkeyTrigger)>) {
+        self.triggers = triggers;
+        self.strokes.clear();
+        self.pending = None;
+        self.tap = None;
+        self.last_tap = None;
+        self.hold = None;
+    }
+
+    pub fn set_timing(&mut self, timing: HotkeyTiming) {
+        self.timing = timing;
+    }
+
+    /// 丢弃实际已经松开的键，用于钩子漏掉抬起事件的情况（如锁屏）
+    pub fn release_stale(&mut self, is_down: impl Fn(u32) -> bool) {
+        self.held.retain(|vk| is_down(*vk));
+    }
+
+    pub fn handle(&mut self, input: KeyInput) -> MatchResult<Id> {
+        let mut result = MatchResult {
+            fired: self.poll(input.time_ms),
+            consume: false,
+        };
+        if input.pressed {
+            self.key_down(input, &mut result);
+        } else {
+            self.key_up(input, &mut result);
+        }
+        result
+    }
+
+    /// 推进时间：触发超时的序列和按住足够久的快捷键
+    pub fn poll(&mut self, now_ms: u64) -> Vec<Id> {
+        let mut fired = Vec::new();
+        if !self.strokes.is_empty()
+            && now_ms.saturating_sub(self.last_stroke_ms) > self.timing.chord_timeout_ms
+        {
+            self.flush(&mut fired);
+        }
+        if let Some(hold) = self.hold.as_mut() {
+            if !hold.fired && now_ms.saturating_sub(hold.since_ms) >= self.timing.hold_ms {
+                hold.fired = true;
+                fired.push(self.triggers[hold.index].0.clone());
+            }
+        }
+        fired
+    }
+
+    fn key_down(&mut self, input: KeyInput, result: &mut MatchResult<Id>) {
+        // Auto-repeat: only time matters, which poll() already handled
+        if self.held.contains(&input.vk) {
+            if self.hold.as_ref().is_some_and(|h| !h.fired) {
+                result.consume = true;
+            }
+            return;
+        }
+        self.held.push(input.vk);
+        self.hold = None;
+        if let Some(tap) = self.tap.as_mut() {
+            tap.clean = false;
+        }
+
+        match Modifier::from_vk(input.vk) {
+            Some(modifier) => {
+                if self.last_tap.is_some_and(|(m, _)| m != modifier) {
+                    self.last_tap = None;
+                }
+                if self.held.len() == 1 {
+                    self.tap = Some(TapCandidate {
+                        modifier,
+                        clean: true,
+                    });
+                }
+            }
+            None => {
+                self.last_tap = None;
+                self.stroke(input, result);
+            }
+        }
+    }
+
+    fn key_up(&mut self, input: KeyInput, result: &mut MatchResult<Id>) {
+        self.held.retain(|vk| *vk != input.vk);
+        self.hold = None;
+
+        let Some(modifier) = Modifier::from_vk(input.vk) else {
+            return;
+        };
+        let Some(tap) = self.tap.take() else {
+            return;
+        };
+        if !tap.clean || tap.modifier != modifier {
+            self.last_tap = None;
+            return;
+        }
+        match self.last_tap {
+            Some((last, at))
+                if last == modifier
+                    && input.time_ms.saturating_sub(at) <= self.timing.double_tap_ms =>
+            {
+                self.last_tap = None;
+                let double = Hotkey::DoubleTap(modifier);
+                if let Some((id, _)) = self
+                    .triggers
+                    .iter()
+                    .find(|(_, t)| t.single() == Some(&double))
+                {
+                    result.fired.push(id.clone());
+                }
+            }
+            _ => self.last_tap = Some((modifier, input.time_ms)),
+        }
+    }
+
+    /// 按下了一个非修饰键
+    fn stroke(&mut self, input: KeyInput, result: &mut MatchResult<Id>) {
+        let modifiers: Vec<Modifier> = self
+            .held
+            .iter()
+            .filter_map(|vk| Modifier::from_vk(*vk))
+            .collect();
+        let Ok(stroke) = Hotkey::combo(&modifiers, input.vk) else {
+            // Plain typing interrupts any sequence in progress
+            self.flush(&mut result.fired);
+            return;
+        };
+
+        self.hold = self
+            .triggers
+            .iter()
+            .position(|(_, t)| matches!(t, HotkeyTrigger::Hold(h) if *h == stroke))
+            .map(|index| HoldState {
+                index,
+                since_ms: input.time_ms,
+                fired: false,
+            });
+        if self.hold.is_some() {
+            result.consume = true;
+        }
+
+        self.strokes.push(stroke);
+        self.last_stroke_ms = input.time_ms;
+        loop {
+            let complete = self.triggers.iter().position(
+                |(_, t)| matches!(t, HotkeyTrigger::Sequence(steps) if *steps == self.strokes),
+            );
+            let is_prefix = self.triggers.iter().any(|(_, t)| {
+                matches!(t, HotkeyTrigger::Sequence(steps)
+                    if steps.len() > self.strokes.len() && steps.starts_with(&self.strokes))
+            });
+            match (complete, is_prefix) {
+                (Some(index), false) => {
+                    self.pending = None;
+                    self.strokes.clear();
+                    result.fired.push(self.triggers[index].0.clone());
+                    result.consume = true;
+                    return;
+                }
+                (complete, true) => {
+                    // Continuing a longer sequence abandons a shorter complete one
+                    self.pending = complete;
+                    result.consume = true;
+                    return;
+                }
+                (None, false) => {
+                    if self.strokes.len() == 1 {
+                        self.strokes.clear();
+                        return;
+                    }
+                    // The sequence was broken: fire what was complete, then treat
+                    // this stroke as the start of a new sequence
+                    let Some(last) = self.strokes.pop() else {
+                        return;
+                    };
+                    self.flush(&mut result.fired);
+                    self.strokes.push(last);
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self, fired: &mut Vec<Id>) {
+        if let Some(index) = self.pending.take() {
+            fired.push(self.triggers[index].0.clone());
+        }
+        self.strokes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTRL: u32 = 0xA2;
+    const RCTRL: u32 = 0xA3;
+    const SHIFT: u32 = 0xA0;
+    const ALT: u32 = 0xA4;
+    const SPACE: u32 = 0x20;
+    const KEY_A: u32 = 0x41;
+    const KEY_K: u32 = 0x4B;
+    const KEY_S: u32 = 0x53;
+
+    fn matcher(triggers: &[(&'static str, &str)]) -> SequenceMatcher<&'static str> {
+        SequenceMatcher::new(
+            triggers
+                .iter()
+                .map(|(id, text)| (*id, text.parse().unwrap()))
+                .collect(),
+            HotkeyTiming {
+                chord_timeout_ms: 1000,
+                double_tap_ms: 400,
+                hold_ms: 800,
+            },
+        )
+    }
+
+    fn down(vk: u32, time_ms: u64) -> KeyInput {
+        KeyInput {
+            vk,
+            pressed: true,
+            time_ms,
+        }
+    }
+
+    fn up(vk: u32, time_ms: u64) -> KeyInput {
+        KeyInput {
+            vk,
+            pressed: false,
+            time_ms,
+        }
+    }
+
+    /// 依次输入事件，返回所有被触发的快捷键
+    fn feed(m: &mut SequenceMatcher<&'static str>, events: &[KeyInput]) -> Vec<&'static str> {
+        events.iter().flat_map(|e| m.handle(*e).fired).collect()
+    }
+
+    fn tap(vk: u32, at: u64) -> [KeyInput; 2] {
+        [down(vk, at), up(vk, at + 50)]
+    }
+
+    #[test]
+    fn test_single_combo_and_chord() {
+        let mut m = matcher(&[("save", "Ctrl+K, Ctrl+S"), ("memo", "Ctrl+M")]);
+        assert_eq!(
+            feed(
+                &mut m,
+                &[down(CTRL, 0), down(0x4D, 10), up(0x4D, 60), up(CTRL, 70)]
+            ),
+            vec!["memo"]
+        );
+
+        // Ctrl held across both steps, and left/right Ctrl are interchangeable
+        let first = m.handle(down(CTRL, 1000));
+        assert!(first.fired.is_empty());
+        assert!(m.handle(down(KEY_K, 1010)).consume);
+        let events = [up(KEY_K, 1050), up(CTRL, 1060), down(RCTRL, 1500)];
+        assert!(feed(&mut m, &events).is_empty());
+        let last = m.handle(down(KEY_S, 1600));
+        assert_eq!(last.fired, vec!["save"]);
+        assert!(last.consume);
+
+        // Too slow: the second step starts nothing
+        let events = [down(KEY_K, 5000), up(KEY_K, 5050), down(KEY_S, 6200)];
+        m.handle(up(RCTRL, 1700));
+        m.handle(down(CTRL, 4990));
+        assert!(feed(&mut m, &events).is_empty());
+
+        // Typing interrupts a chord
+        let mut m = matcher(&[("save", "Ctrl+K, Ctrl+S")]);
+        let events = [
+            down(CTRL, 0),
+            down(KEY_K, 10),
+            up(KEY_K, 20),
+            up(CTRL, 30),
+            down(KEY_A, 40),
+            up(KEY_A, 50),
+            down(CTRL, 60),
+            down(KEY_S, 70),
+        ];
+        assert!(feed(&mut m, &events).is_empty());
+    }
+
+    #[test]
+    fn test_prefix_waits_for_timeout_or_interruption() {
+        let mut m = matcher(&[("comment", "Ctrl+K"), ("save", "Ctrl+K, Ctrl+S")]);
+        let events = [down(CTRL, 0), down(KEY_K, 10), up(KEY_K, 50)];
+        assert!(feed(&mut m, &events).is_empty());
+        assert!(m.poll(900).is_empty());
+        assert_eq!(m.poll(1011), vec!["comment"]);
+        assert!(m.poll(3000).is_empty());
+
+        // A different combo fires the pending one and is matched on its own
+        let mut m = matcher(&[
+            ("comment", "Ctrl+K"),
+            ("save", "Ctrl+K, Ctrl+S"),
+            ("all", "Ctrl+A"),
+        ]);
+        let events = [
+            down(CTRL, 0),
+            down(KEY_K, 10),
+            up(KEY_K, 50),
+            down(KEY_A, 100),
+        ];
+        assert_eq!(feed(&mut m, &events), vec!["comment", "all"]);
+
+        let events = [
+            up(KEY_A, 150),
+            down(KEY_K, 200),
+            up(KEY_K, 250),
+            down(KEY_S, 300),
+        ];
+        assert_eq!(feed(&mut m, &events), vec!["save"]);
+        assert!(m.poll(5000).is_empty());
+    }
+
+    #[test]
+    fn test_double_tap_any_modifier() {
+        let mut m = matcher(&[("launcher", "Ctrl Ctrl"), ("search", "Shift Shift")]);
+        let mut events = tap(SHIFT, 0).to_vec();
+        events.extend(tap(0xA1, 200));
+        assert_eq!(feed(&mut m, &events), vec!["search"]);
+
+        // Third tap starts over instead of firing again
+        assert!(feed(&mut m, &tap(SHIFT, 400)).is_empty());
+
+        // Too slow
+        let mut events = tap(CTRL, 1000).to_vec();
+        events.extend(tap(CTRL, 1500));
+        assert!(feed(&mut m, &events).is_empty());
+
+        // Another key in between, or used as a modifier for another key
+        let mut events = tap(CTRL, 3000).to_vec();
+        events.extend(tap(KEY_A, 3100));
+        events.extend(tap(CTRL, 3200));
+        assert!(feed(&mut m, &events).is_empty());
+        let events = [
+            down(CTRL, 4000),
+            down(KEY_A, 4010),
+            up(KEY_A, 4020),
+            up(CTRL, 4030),
+            down(CTRL, 4100),
+            up(CTRL, 4150),
+        ];
+        assert!(feed(&mut m, &events).is_empty());
+
+        // Alternating modifiers is not a double tap
+        let mut events = tap(CTRL, 5000).to_vec();
+        events.extend(tap(SHIFT, 5100));
+        events.extend(tap(CTRL, 5200));
+        assert!(feed(&mut m, &events).is_empty());
+
+        // Unconfigured modifiers never fire
+        let mut events = tap(ALT, 6000).to_vec();
+        events.extend(tap(ALT, 6100));
+        assert!(feed(&mut m, &events).is_empty());
+    }
+
+    #[test]
+    fn test_hold_to_trigger() {
+        let mut m = matcher(&[("record", "Hold Ctrl+Space")]);
+        m.handle(down(CTRL, 0));
+        assert!(m.handle(down(SPACE, 10)).consume);
+        assert!(m.poll(500).is_empty());
+        // Auto-repeat keeps the hold alive
+        assert!(m.handle(down(SPACE, 540)).fired.is_empty());
+        assert_eq!(m.poll(810), vec!["record"]);
+        assert!(m.poll(2000).is_empty());
+        assert!(!m.handle(down(SPACE, 2010)).consume);
+
+        // Released too early
+        let events = [up(SPACE, 2100), down(SPACE, 3000), up(SPACE, 3500)];
+        assert!(feed(&mut m, &events).is_empty());
+        assert!(m.poll(5000).is_empty());
+
+        // A repeated key-down after the threshold fires without a poll
+        let mut m = matcher(&[("record", "Hold Ctrl+Space")]);
+        let events = [down(CTRL, 0), down(SPACE, 10), down(SPACE, 900)];
+        assert_eq!(feed(&mut m, &events), vec!["record"]);
+    }
+
+    #[test]
+    fn test_set_triggers_and_stale_keys() {
+        let mut m = matcher(&[("a", "Ctrl+K, Ctrl+S")]);
+        m.handle(down(CTRL, 0));
+        m.handle(down(KEY_K, 10));
+        m.set_triggers(vec![("b", "Ctrl+S".parse().unwrap())]);
+        assert_eq!(m.handle(down(KEY_S, 20)).fired, vec!["b"]);
+
+        // Ctrl's key-up was lost; it must not stay held forever
+        m.release_stale(|vk| vk == KEY_S);
+        m.handle(up(KEY_S, 30));
+        assert!(m.handle(down(KEY_S, 40)).fired.is_empty());
+    }
+}
@@ -0,0 +1,462 @@
+//! 键鼠输入注入
+//!
+//! 回放和宏通过 `InputBackend` 发送输入，不直接调用系统 API：
+//! - Windows：`SendInput`
+//! - Linux：`/dev/uinput` 虚拟设备（需要对该设备有写权限）；按 Esc 中止需要能读取
+//!   `/dev/input/event*`（通常要加入 input 组），否则只能用停止回放快捷键中止
+//! - `MockBackend`：只记录调用和虚拟时间，用于测试
+
+use crate::recording::{KeyCode, MouseButton, ScreenGeometry};
+use std::time::Duration;
+
+pub trait InputBackend: Send {
+    /// 把光标移动到虚拟屏幕上的绝对坐标
+    fn move_to(&mut self, x: i32, y: i32) -> Result<(), String>;
+
+    fn mouse_button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String>;
+
+    /// 滚轮，`delta` 为 120 的倍数，正数向上
+    fn wheel(&mut self, delta: i32) -> Result<(), String>;
+
+    fn key(&mut self, key: &KeyCode, pressed: bool) -> Result<(), String>;
+
+    /// 事件之间的等待；测试用的后端只累计虚拟时间
+    fn wait(&mut self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+
+    /// 用户是否要求中止（例如按下 Esc）
+    fn abort_requested(&mut self) -> bool {
+        false
+    }
+}
+
+/// 当前平台的输入后端
+pub fn default_backend(screen: ScreenGeometry) -> Result<Box<dyn InputBackend>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = screen;
+        Ok(Box::new(windows::SendInputBackend))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Ok(Box::new(uinput::UinputBackend::new(screen)?))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = screen;
+        Err("Input injection is not supported on this platform".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub mod windows {
+    use super::InputBackend;
+    use crate::recording::{KeyCode, MouseButton};
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT,
+        KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
+        MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP,
+        MOUSEEVENTF_WHEEL, MOUSEINPUT,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::SetCursorPos;
+
+    const VK_ESCAPE: i32 = 0x1B;
+
+    pub struct SendInputBackend;
+
+    fn send(mut input: INPUT, what: &str) -> Result<(), String> {
+        if unsafe { SendInput(1, &mut input, std::mem::size_of::<INPUT>() as i32) } == 0 {
+            return Err(format!("Failed to send {} event", what));
+        }
+        Ok(())
+    }
+
+    fn send_mouse(flags: u32, mouse_data: u32, what: &str) -> Result<(), String> {
+        send(
+            INPUT {
+                r#type: INPUT_MOUSE,
+                Anonymous: INPUT_0 {
+                    mi: MOUSEINPUT {
+                        dx: 0,
+                        dy: 0,
+                        mouseData: mouse_data,
+                        dwFlags: flags,
+                        time: 0,
+                        dwExtraInfo: 0,
+                    },
+                },
+            },
+            what,
+        )
+    }
+
+    impl InputBackend for SendInputBackend {
+        fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+            if unsafe { SetCursorPos(x, y) } == 0 {
+                return Err("Failed to move cursor".to_string());
+            }
+            Ok(())
+        }
+
+        fn mouse_button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String> {
+            let flags = match (button, pressed) {
+                (MouseButton::Left, true) => MOUSEEVENTF_LEFTDOWN,
+                (MouseButton::Left, false) => MOUSEEVENTF_LEFTUP,
+                (MouseButton::Right, true) => MOUSEEVENTF_RIGHTDOWN,
+                (MouseButton::Right, false) => MOUSEEVENTF_RIGHTUP,
+                (MouseButton::Middle, true) => MOUSEEVENTF_MIDDLEDOWN,
+                (MouseButton::Middle, false) => MOUSEEVENTF_MIDDLEUP,
+            };
+            send_mouse(flags, 0, if pressed { "mouse down" } else { "mouse up" })
+        }
+
+        fn wheel(&mut self, delta: i32) -> Result<(), String> {
+            send_mouse(MOUSEEVENTF_WHEEL, delta as u32, "mouse wheel")
+        }
+
+        fn key(&mut self, key: &KeyCode, pressed: bool) -> Result<(), String> {
+            let vk_code = key
+                .resolve_vk()
+                .ok_or_else(|| format!("Unknown key: {}", key.key))?;
+            // Validate virtual key code
+            if vk_code > 255 {
+                return Err(format!("Invalid virtual key code: {}", vk_code));
+            }
+
+            let scan_code = key.scan_code.unwrap_or(0);
+            let mut flags = if pressed { 0 } else { KEYEVENTF_KEYUP };
+            if scan_code & 0xE000 == 0xE000 {
+                flags |= KEYEVENTF_EXTENDEDKEY;
+            }
+
+            send(
+                INPUT {
+                    r#type: INPUT_KEYBOARD,
+                    Anonymous: INPUT_0 {
+                        ki: KEYBDINPUT {
+                            wVk: vk_code as u16,
+                            wScan: (scan_code & 0xFF) as u16,
+                            dwFlags: flags,
+                            time: 0,
+                            dwExtraInfo: 0,
+                        },
+                    },
+                },
+                &format!("key {} ({})", if pressed { "down" } else { "up" }, key.key),
+            )
+        }
+
+        fn abort_requested(&mut self) -> bool {
+            // The high bit indicates the key is currently down
+            unsafe { (GetAsyncKeyState(VK_ESCAPE) as u16) & 0x8000 != 0 }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub mod uinput {
+    //! 通过 `/dev/uinput` 创建一个同时带键盘、鼠标按键、滚轮和绝对坐标轴的虚拟设备。
+    //! 绝对坐标轴的范围设为虚拟屏幕大小，由显示服务器映射到屏幕上。
+    //! 中止检测直接查询 `/dev/input/event*` 中各设备当前的按键状态，X11 和 Wayland 下都可用。
+
+    use super::InputBackend;
+    use crate::keymap;
+    use crate::recording::{KeyCode, MouseButton, ScreenGeometry};
+    use std::fs::{File, OpenOptions};
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::io::AsRawFd;
+    use std::time::Duration;
+
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const EV_REL: u16 = 0x02;
+    const EV_ABS: u16 = 0x03;
+    const SYN_REPORT: u16 = 0;
+    const REL_WHEEL: u16 = 0x08;
+    const ABS_X: u16 = 0x00;
+    const ABS_Y: u16 = 0x01;
+    const BTN_LEFT: u16 = 0x110;
+    const BTN_RIGHT: u16 = 0x111;
+    const BTN_MIDDLE: u16 = 0x112;
+    const BUS_USB: u16 = 0x03;
+
+    // _IO('U', n) / _IOW('U', n, int)
+    const UI_DEV_CREATE: libc::c_ulong = 0x5501;
+    const UI_DEV_DESTROY: libc::c_ulong = 0x5502;
+    const UI_SET_EVBIT: libc::c_ulong = 0x4004_5564;
+    const UI_SET_KEYBIT: libc::c_ulong = 0x4004_5565;
+    const UI_SET_RELBIT: libc::c_ulong = 0x4004_5566;
+    const UI_SET_ABSBIT: libc::c_ulong = 0x4004_5567;
+
+    const UINPUT_MAX_NAME_SIZE: usize = 80;
+    const ABS_CNT: usize = 0x40;
+
+    const KEY_ESC: usize = 1;
+    /// 按键状态位图的字节数，覆盖到 KEY_MAX (0x2FF)
+    const KEY_STATE_BYTES: usize = 96;
+    /// _IOC(_IOC_READ, 'E', 0x18, KEY_STATE_BYTES)，即 EVIOCGKEY
+    const EVIOCGKEY: libc::c_ulong = 0x8060_4518;
+
+    #[repr(C)]
+    struct InputId {
+        bustype: u16,
+        vendor: u16,
+        product: u16,
+        version: u16,
+    }
+
+    /// `struct uinput_user_dev`
+    #[repr(C)]
+    struct UinputUserDev {
+        name: [u8; UINPUT_MAX_NAME_SIZE],
+        id: InputId,
+        ff_effects_max: u32,
+        absmax: [i32; ABS_CNT],
+        absmin: [i32; ABS_CNT],
+        absfuzz: [i32; ABS_CNT],
+        absflat: [i32; ABS_CNT],
+    }
+
+    /// `struct input_event`
+    #[repr(C)]
+    struct RawInputEvent {
+        time: libc::timeval,
+        kind: u16,
+        code: u16,
+        value: i32,
+    }
+
+    fn as_bytes<T>(value: &T) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>()) }
+    }
+
+    /// 打开所有能读取的输入设备；没有权限时返回空列表
+    fn open_input_devices() -> Vec<File> {
+        let Ok(entries) = std::fs::read_dir("/dev/input") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("event"))
+            })
+            .filter_map(|path| {
+                OpenOptions::new()
+                    .read(true)
+                    .custom_flags(libc::O_NONBLOCK)
+                    .open(path)
+                    .ok()
+            })
+            .collect()
+    }
+
+    fn esc_pressed(device: &File) -> bool {
+        let mut state = [0u8; KEY_STATE_BYTES];
+        let result = unsafe { libc::ioctl(device.as_raw_fd(), EVIOCGKEY as _, state.as_mut_ptr()) };
+        result >= 0 && state[KEY_ESC / 8] & (1 << (KEY_ESC % 8)) != 0
+    }
+
+    pub struct UinputBackend {
+        device: File,
+        screen: ScreenGeometry,
+        /// 用于检测 Esc 的物理输入设备，在创建虚拟设备之前打开，回放的 Esc 不会触发中止
+        input_devices: Vec<File>,
+    }
+
+    impl UinputBackend {
+        pub fn new(screen: ScreenGeometry) -> Result<Self, String> {
+            let input_devices = open_input_devices();
+            if input_devices.is_empty() {
+                eprintln!(
+                    "[Replay] Cannot read /dev/input devices (add the user to the input group); \
+                     Esc will not abort playback, use the stop playback hotkey instead"
+                );
+            }
+            let device = OpenOptions::new()
+                .write(true)
+                .custom_flags(libc::O_NONBLOCK)
+                .open("/dev/uinput")
+                .map_err(|e| format!("Failed to open /dev/uinput (check permissions): {}", e))?;
+            let backend = Self { device, screen, input_devices };
+            backend.setup()?;
+            // 新设备需要一点时间才会被显示服务器识别
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(backend)
+        }
+
+        fn ioctl(&self, request: libc::c_ulong, value: libc::c_int) -> Result<(), String> {
+            if unsafe { libc::ioctl(self.device.as_raw_fd(), request as _, value) } < 0 {
+                return Err(format!(
+                    "uinput ioctl 0x{:X} failed: {}",
+                    request,
+                    std::io::Error::last_os_error()
+                ));
+            }
+            Ok(())
+        }
+
+        fn setup(&self) -> Result<(), String> {
+            for ev in [EV_SYN, EV_KEY, EV_REL, EV_ABS] {
+                self.ioctl(UI_SET_EVBIT, ev as libc::c_int)?;
+            }
+            let mut keys: Vec<u16> = (0u32..=0xFF).filter_map(keymap::evdev_from_vk).collect();
+            keys.sort_unstable();
+            keys.dedup();
+            keys.extend([BTN_LEFT, BTN_RIGHT, BTN_MIDDLE]);
+            for key in keys {
+                self.ioctl(UI_SET_KEYBIT, key as libc::c_int)?;
+            }
+            self.ioctl(UI_SET_RELBIT, REL_WHEEL as libc::c_int)?;
+            self.ioctl(UI_SET_ABSBIT, ABS_X as libc::c_int)?;
+            self.ioctl(UI_SET_ABSBIT, ABS_Y as libc::c_int)?;
+
+            let mut dev = UinputUserDev {
+                name: [0; UINPUT_MAX_NAME_SIZE],
+                id: InputId { bustype: BUS_USB, vendor: 0x1234, product: 0x5678, version: 1 },
+                ff_effects_max: 0,
+                absmax: [0; ABS_CNT],
+                absmin: [0; ABS_CNT],
+                absfuzz: [0; ABS_CNT],
+                absflat: [0; ABS_CNT],
+            };
+            let name = b"ReFast virtual input";
+            dev.name[..name.len()].copy_from_slice(name);
+            dev.absmax[ABS_X as usize] = self.screen.width.saturating_sub(1) as i32;
+            dev.absmax[ABS_Y as usize] = self.screen.height.saturating_sub(1) as i32;
+
+            (&self.device)
+                .write_all(as_bytes(&dev))
+                .map_err(|e| format!("Failed to configure uinput device: {}", e))?;
+            self.ioctl(UI_DEV_CREATE, 0)
+        }
+
+        fn emit(&mut self, kind: u16, code: u16, value: i32) -> Result<(), String> {
+            let event = RawInputEvent {
+                time: libc::timeval { tv_sec: 0, tv_usec: 0 },
+                kind,
+                code,
+                value,
+            };
+            self.device
+                .write_all(as_bytes(&event))
+                .map_err(|e| format!("Failed to write uinput event: {}", e))
+        }
+
+        fn sync(&mut self) -> Result<(), String> {
+            self.emit(EV_SYN, SYN_REPORT, 0)
+        }
+    }
+
+    impl InputBackend for UinputBackend {
+        fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+            self.emit(EV_ABS, ABS_X, x - self.screen.x)?;
+            self.emit(EV_ABS, ABS_Y, y - self.screen.y)?;
+            self.sync()
+        }
+
+        fn mouse_button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String> {
+            let code = match button {
+                MouseButton::Left => BTN_LEFT,
+                MouseButton::Right => BTN_RIGHT,
+                MouseButton::Middle => BTN_MIDDLE,
+            };
+            self.emit(EV_KEY, code, pressed as i32)?;
+            self.sync()
+        }
+
+        fn wheel(&mut self, delta: i32) -> Result<(), String> {
+            // evdev 的滚轮以格为单位
+            let notches = delta / 120;
+            let notches = if notches == 0 { delta.signum() } else { notches };
+            self.emit(EV_REL, REL_WHEEL, notches)?;
+            self.sync()
+        }
+
+        fn key(&mut self, key: &KeyCode, pressed: bool) -> Result<(), String> {
+            let code = key
+                .resolve_vk()
+                .and_then(keymap::evdev_from_vk)
+                .ok_or_else(|| format!("Unknown key: {}", key.key))?;
+            self.emit(EV_KEY, code, pressed as i32)?;
+            self.sync()
+        }
+
+        fn abort_requested(&mut self) -> bool {
+            self.input_devices.iter().any(esc_pressed)
+        }
+    }
+
+    impl Drop for UinputBackend {
+        fn drop(&mut self) {
+            let _ = self.ioctl(UI_DEV_DESTROY, 0);
+        }
+    }
+}
+
+/// 记录调用的后端，等待只累计虚拟时间
+#[cfg(test)]
+pub mod mock {
+    use super::InputBackend;
+    use crate::recording::{KeyCode, MouseButton};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum MockAction {
+        MoveTo(i32, i32),
+        Button(MouseButton, bool),
+        Wheel(i32),
+        Key(String, bool),
+    }
+
+    #[derive(Debug, Default)]
+    pub struct MockBackend {
+        /// 每个动作及其发生时的虚拟时间（毫秒）
+        pub actions: Vec<(u64, MockAction)>,
+        pub elapsed_ms: u64,
+        /// 第几次检查时报告中止
+        pub abort_on_check: Option<usize>,
+        /// 已检查中止的次数
+        pub checks: usize,
+    }
+
+    impl MockBackend {
+        fn record(&mut self, action: MockAction) -> Result<(), String> {
+            self.actions.push((self.elapsed_ms, action));
+            Ok(())
+        }
+    }
+
+    impl InputBackend for MockBackend {
+        fn move_to(&mut self, x: i32, y: i32) -> Result<(), String> {
+            self.record(MockAction::MoveTo(x, y))
+        }
+
+        fn mouse_button(&mut self, button: MouseButton, pressed: bool) -> Result<(), String> {
+            self.record(MockAction::Button(button, pressed))
+        }
+
+        fn wheel(&mut self, delta: i32) -> Result<(), String> {
+            self.record(MockAction::Wheel(delta))
+        }
+
+        fn key(&mut self, key: &KeyCode, pressed: bool) -> Result<(), String> {
+            self.record(MockAction::Key(key.key.clone(), pressed))
+        }
+
+        fn wait(&mut self, duration: Duration) {
+            self.elapsed_ms += duration.as_millis() as u64;
+        }
+
+        fn abort_requested(&mut self) -> bool {
+            self.checks += 1;
+            self.abort_on_check.is_some_and(|n| self.checks >= n)
+        }
+    }
+}
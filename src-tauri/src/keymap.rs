@@ -0,0 +1,411 @@
+//! 逻辑键名与 Windows 虚拟键码的对应关系
+//!
+//! 键名沿用快捷键设置里的写法（"Ctrl"、"ArrowUp"、"F5"、"A"），
+//! 区分左右的修饰键使用 "ShiftLeft" / "CtrlRight" 这种形式。
+//! 没有名字的键码写成十六进制（"0xE7"），可以原样解析回来。
+//! Linux 回放时再把虚拟键码换算成 evdev 键码。
+
+/// 固定名字的按键；字母、数字、F 键和小键盘数字单独按区间处理
+const NAMED_KEYS: &[(&str, u32)] = &[
+    ("Backspace", 0x08),
+    ("Tab", 0x09),
+    ("Enter", 0x0D),
+    ("Shift", 0x10),
+    ("Ctrl", 0x11),
+    ("Alt", 0x12),
+    ("Pause", 0x13),
+    ("CapsLock", 0x14),
+    ("Escape", 0x1B),
+    ("Space", 0x20),
+    ("PageUp", 0x21),
+    ("PageDown", 0x22),
+    ("End", 0x23),
+    ("Home", 0x24),
+    ("ArrowLeft", 0x25),
+    ("ArrowUp", 0x26),
+    ("ArrowRight", 0x27),
+    ("ArrowDown", 0x28),
+    ("PrintScreen", 0x2C),
+    ("Insert", 0x2D),
+    ("Delete", 0x2E),
+    ("MetaLeft", 0x5B),
+    ("MetaRight", 0x5C),
+    ("ContextMenu", 0x5D),
+    ("NumpadMultiply", 0x6A),
+    ("NumpadAdd", 0x6B),
+    ("NumpadSubtract", 0x6D),
+    ("NumpadDecimal", 0x6E),
+    ("NumpadDivide", 0x6F),
+    ("NumLock", 0x90),
+    ("ScrollLock", 0x91),
+    ("ShiftLeft", 0xA0),
+    ("ShiftRight", 0xA1),
+    ("CtrlLeft", 0xA2),
+    ("CtrlRight", 0xA3),
+    ("AltLeft", 0xA4),
+    ("AltRight", 0xA5),
+    ("VolumeMute", 0xAD),
+    ("VolumeDown", 0xAE),
+    ("VolumeUp", 0xAF),
+    ("MediaNext", 0xB0),
+    ("MediaPrevious", 0xB1),
+    ("MediaStop", 0xB2),
+    ("MediaPlayPause", 0xB3),
+    ("Semicolon", 0xBA),
+    ("Equal", 0xBB),
+    ("Comma", 0xBC),
+    ("Minus", 0xBD),
+    ("Period", 0xBE),
+    ("Slash", 0xBF),
+    ("Backquote", 0xC0),
+    ("BracketLeft", 0xDB),
+    ("Backslash", 0xDC),
+    ("BracketRight", 0xDD),
+    ("Quote", 0xDE),
+];
+
+/// 只用于解析的别名
+const ALIASES: &[(&str, u32)] = &[
+    ("Control", 0x11),
+    ("Meta", 0x5B),
+    ("Win", 0x5B),
+    ("Super", 0x5B),
+    ("Cmd", 0x5B),
+    ("Esc", 0x1B),
+    ("Return", 0x0D),
+    ("Del", 0x2E),
+    ("Up", 0x26),
+    ("Down", 0x28),
+    ("Left", 0x25),
+    ("Right", 0x27),
+];
+
+/// 虚拟键码对应的逻辑键名
+pub fn key_name_from_vk(vk_code: u32) -> String {
+    match vk_code {
+        0x30..=0x39 | 0x41..=0x5A => char::from(vk_code as u8).to_string(),
+        0x60..=0x69 => format!("Numpad{}", vk_code - 0x60),
+        0x70..=0x87 => format!("F{}", vk_code - 0x70 + 1),
+        _ => NAMED_KEYS
+            .iter()
+            .find(|(_, vk)| *vk == vk_code)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_else(|| format!("0x{:02X}", vk_code)),
+    }
+}
+
+/// 逻辑键名对应的虚拟键码，不区分大小写
+pub fn vk_from_key_name(name: &str) -> Option<u32> {
+    let name = name.trim();
+    if let Some(hex) = name.strip_prefix("0x").or_else(|| name.strip_prefix("0X")) {
+        return u32::from_str_radix(hex, 16).ok().filter(|vk| *vk <= 0xFF);
+    }
+
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        if ch.is_ascii_alphanumeric() {
+            return Some(ch.to_ascii_uppercase() as u32);
+        }
+    }
+
+    let lower = name.to_ascii_lowercase();
+    if let Some(n) = lower.strip_prefix("numpad").and_then(|n| n.parse::<u32>().ok()) {
+        return (n <= 9).then_some(0x60 + n);
+    }
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+        return (1..=24).contains(&n).then_some(0x70 + n - 1);
+    }
+
+    NAMED_KEYS
+        .iter()
+        .chain(ALIASES.iter())
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, vk)| *vk)
+}
+
+/// 字母 A-Z 对应的 evdev 键码（按 QWERTY 布局排列，不是连续的）
+const EVDEV_LETTERS: [u16; 26] = [
+    30, 48, 46, 32, 18, 33, 34, 35, 23, 36, 37, 38, 50, 49, 24, 25, 16, 19, 31, 20, 22, 47, 17,
+    45, 21, 44,
+];
+
+/// 小键盘 0-9 对应的 evdev 键码
+const EVDEV_NUMPAD: [u16; 10] = [82, 79, 80, 81, 75, 76, 77, 71, 72, 73];
+
+/// 虚拟键码对应的 Linux evdev 键码（`KEY_*`），不区分左右的修饰键按左侧处理
+pub fn evdev_from_vk(vk_code: u32) -> Option<u16> {
+    let code = match vk_code {
+        0x41..=0x5A => EVDEV_LETTERS[(vk_code - 0x41) as usize],
+        0x30 => 11,
+        0x31..=0x39 => (vk_code - 0x31 + 2) as u16,
+        0x60..=0x69 => EVDEV_NUMPAD[(vk_code - 0x60) as usize],
+        0x70..=0x79 => (vk_code - 0x70 + 59) as u16,
+        0x7A => 87,
+        0x7B => 88,
+        0x7C..=0x87 => (vk_code - 0x7C + 183) as u16,
+        0x08 => 14,
+        0x09 => 15,
+        0x0D => 28,
+        0x10 | 0xA0 => 42,
+        0xA1 => 54,
+        0x11 | 0xA2 => 29,
+        0xA3 => 97,
+        0x12 | 0xA4 => 56,
+        0xA5 => 100,
+        0x13 => 119,
+        0x14 => 58,
+        0x1B => 1,
+        0x20 => 57,
+        0x21 => 104,
+        0x22 => 109,
+        0x23 => 107,
+        0x24 => 102,
+        0x25 => 105,
+        0x26 => 103,
+        0x27 => 106,
+        0x28 => 108,
+        0x2C => 99,
+        0x2D => 110,
+        0x2E => 111,
+        0x5B => 125,
+        0x5C => 126,
+        0x5D => 127,
+        0x6A => 55,
+        0x6B => 78,
+        0x6D => 74,
+        0x6E => 83,
+        0x6F => 98,
+        0x90 => 69,
+        0x91 => 70,
+        0xAD => 113,
+        0xAE => 114,
+        0xAF => 115,
+        0xB0 => 163,
+        0xB1 => 165,
+        0xB2 => 166,
+        0xB3 => 164,
+        0xBA => 39,
+        0xBB => 13,
+        0xBC => 51,
+        0xBD => 12,
+        0xBE => 52,
+        0xBF => 53,
+        0xC0 => 41,
+        0xDB => 26,
+        0xDC => 43,
+        0xDD => 27,
+        0xDE => 40,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// 虚拟键码对应的 X11 keysym，字母取小写形式
+pub fn x11_keysym_from_vk(vk_code: u32) -> Option<u32> {
+    let keysym = match vk_code {
+        0x41..=0x5A => vk_code + 0x20,
+        0x30..=0x39 | 0x20 => vk_code,
+        0x60..=0x69 => 0xFFB0 + vk_code - 0x60,
+        0x70..=0x87 => 0xFFBE + vk_code - 0x70,
+        0x08 => 0xFF08,
+        0x09 => 0xFF09,
+        0x0D => 0xFF0D,
+        0x10 | 0xA0 => 0xFFE1,
+        0xA1 => 0xFFE2,
+        0x11 | 0xA2 => 0xFFE3,
+        0xA3 => 0xFFE4,
+        0x12 | 0xA4 => 0xFFE9,
+        0xA5 => 0xFFEA,
+        0x13 => 0xFF13,
+        0x14 => 0xFFE5,
+        0x1B => 0xFF1B,
+        0x21 => 0xFF55,
+        0x22 => 0xFF56,
+        0x23 => 0xFF57,
+        0x24 => 0xFF50,
+        0x25 => 0xFF51,
+        0x26 => 0xFF52,
+        0x27 => 0xFF53,
+        0x28 => 0xFF54,
+        0x2C => 0xFF61,
+        0x2D => 0xFF63,
+        0x2E => 0xFFFF,
+        0x5B => 0xFFEB,
+        0x5C => 0xFFEC,
+        0x5D => 0xFF67,
+        0x6A => 0xFFAA,
+        0x6B => 0xFFAB,
+        0x6D => 0xFFAD,
+        0x6E => 0xFFAE,
+        0x6F => 0xFFAF,
+        0x90 => 0xFF7F,
+        0x91 => 0xFF14,
+        0xAD => 0x1008FF12,
+        0xAE => 0x1008FF11,
+        0xAF => 0x1008FF13,
+        0xB0 => 0x1008FF17,
+        0xB1 => 0x1008FF16,
+        0xB2 => 0x1008FF15,
+        0xB3 => 0x1008FF14,
+        0xBA => 0x3B,
+        0xBB => 0x3D,
+        0xBC => 0x2C,
+        0xBD => 0x2D,
+        0xBE => 0x2E,
+        0xBF => 0x2F,
+        0xC0 => 0x60,
+        0xDB => 0x5B,
+        0xDC => 0x5C,
+        0xDD => 0x5D,
+        0xDE => 0x27,
+        _ => return None,
+    };
+    Some(keysym)
+}
+
+/// 虚拟键码对应的 xkb keysym 名称（如 "a"、"Return"、"XF86AudioPlay"），
+/// 用于 XDG GlobalShortcuts portal 的触发键写法
+pub fn xkb_key_name_from_vk(vk_code: u32) -> Option<String> {
+    let name = match vk_code {
+        0x41..=0x5A => return char::from_u32(vk_code + 0x20).map(String::from),
+        0x30..=0x39 => return char::from_u32(vk_code).map(String::from),
+        0x60..=0x69 => return Some(format!("KP_{}", vk_code - 0x60)),
+        0x70..=0x87 => return Some(format!("F{}", vk_code - 0x6F)),
+        0x08 => "BackSpace",
+        0x09 => "Tab",
+        0x0D => "Return",
+        0x13 => "Pause",
+        0x1B => "Escape",
+        0x20 => "space",
+        0x21 => "Prior",
+        0x22 => "Next",
+        0x23 => "End",
+        0x24 => "Home",
+        0x25 => "Left",
+        0x26 => "Up",
+        0x27 => "Right",
+        0x28 => "Down",
+        0x2C => "Print",
+        0x2D => "Insert",
+        0x2E => "Delete",
+        0x5D => "Menu",
+        0x6A => "KP_Multiply",
+        0x6B => "KP_Add",
+        0x6D => "KP_Subtract",
+        0x6E => "KP_Decimal",
+        0x6F => "KP_Divide",
+        0x90 => "Num_Lock",
+        0x91 => "Scroll_Lock",
+        0xAD => "XF86AudioMute",
+        0xAE => "XF86AudioLowerVolume",
+        0xAF => "XF86AudioRaiseVolume",
+        0xB0 => "XF86AudioNext",
+        0xB1 => "XF86AudioPrev",
+        0xB2 => "XF86AudioStop",
+        0xB3 => "XF86AudioPlay",
+        0xBA => "semicolon",
+        0xBB => "equal",
+        0xBC => "comma",
+        0xBD => "minus",
+        0xBE => "period",
+        0xBF => "slash",
+        0xC0 => "grave",
+        0xDB => "bracketleft",
+        0xDC => "backslash",
+        0xDD => "bracketright",
+        0xDE => "apostrophe",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// 输入字符对应的虚拟键码和是否需要按住 Shift（按美式键盘布局）
+pub fn vk_for_char(ch: char) -> Option<(u32, bool)> {
+    let key = match ch {
+        'a'..='z' => (ch.to_ascii_uppercase() as u32, false),
+        'A'..='Z' | '0'..='9' => (ch as u32, ch.is_ascii_uppercase()),
+        ' ' => (0x20, false),
+        '\n' => (0x0D, false),
+        '\t' => (0x09, false),
+        ')' => (0x30, true),
+        '!' => (0x31, true),
+        '@' => (0x32, true),
+        '#' => (0x33, true),
+        '$' => (0x34, true),
+        '%' => (0x35, true),
+        '^' => (0x36, true),
+        '&' => (0x37, true),
+        '*' => (0x38, true),
+        '(' => (0x39, true),
+        ';' => (0xBA, false),
+        ':' => (0xBA, true),
+        '=' => (0xBB, false),
+        '+' => (0xBB, true),
+        ',' => (0xBC, false),
+        '<' => (0xBC, true),
+        '-' => (0xBD, false),
+        '_' => (0xBD, true),
+        '.' => (0xBE, false),
+        '>' => (0xBE, true),
+        '/' => (0xBF, false),
+        '?' => (0xBF, true),
+        '`' => (0xC0, false),
+        '~' => (0xC0, true),
+        '[' => (0xDB, false),
+        '{' => (0xDB, true),
+        '\\' => (0xDC, false),
+        '|' => (0xDC, true),
+        ']' => (0xDD, false),
+        '}' => (0xDD, true),
+        '\'' => (0xDE, false),
+        '"' => (0xDE, true),
+        _ => return None,
+    };
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_names_round_trip() {
+        for vk in 0u32..=0xFF {
+            let name = key_name_from_vk(vk);
+            assert_eq!(vk_from_key_name(&name), Some(vk), "{}", name);
+        }
+        assert_eq!(key_name_from_vk(0x41), "A");
+        assert_eq!(key_name_from_vk(0x7B), "F12");
+        assert_eq!(key_name_from_vk(0xA3), "CtrlRight");
+        assert_eq!(vk_from_key_name("esc"), Some(0x1B));
+        assert_eq!(vk_from_key_name("Meta"), Some(0x5B));
+        assert_eq!(vk_from_key_name("F25"), None);
+        assert_eq!(vk_from_key_name("Hyper"), None);
+    }
+
+    #[test]
+    fn test_evdev_codes() {
+        assert_eq!(evdev_from_vk(0x41), Some(30)); // KEY_A
+        assert_eq!(evdev_from_vk(0x30), Some(11)); // KEY_0
+        assert_eq!(evdev_from_vk(0x7B), Some(88)); // KEY_F12
+        assert_eq!(evdev_from_vk(0xA3), Some(97)); // KEY_RIGHTCTRL
+        assert_eq!(evdev_from_vk(0xE7), None);
+        assert_eq!(x11_keysym_from_vk(0x4B), Some(0x6B)); // k
+        assert_eq!(x11_keysym_from_vk(0x87), Some(0xFFD5)); // F24
+        assert_eq!(x11_keysym_from_vk(0x60), Some(0xFFB0)); // KP_0
+        assert_eq!(x11_keysym_from_vk(0xB3), Some(0x1008FF14)); // XF86AudioPlay
+        assert_eq!(xkb_key_name_from_vk(0x4B).as_deref(), Some("k"));
+        assert_eq!(xkb_key_name_from_vk(0x87).as_deref(), Some("F24"));
+        assert_eq!(xkb_key_name_from_vk(0x65).as_deref(), Some("KP_5"));
+        assert_eq!(xkb_key_name_from_vk(0xBA).as_deref(), Some("semicolon"));
+        assert_eq!(xkb_key_name_from_vk(0xE7), None);
+
+        // 不同的键不会映射到同一个 evdev 键码（通用修饰键除外）
+        let mut seen = std::collections::HashMap::new();
+        for vk in (0u32..=0xFF).filter(|vk| !matches!(vk, 0x10..=0x12)) {
+            if let Some(code) = evdev_from_vk(vk) {
+                assert_eq!(seen.insert(code, vk), None, "vk 0x{:02X}", vk);
+            }
+        }
+    }
+}
@@ -0,0 +1,1071 @@
+//! 宏脚本
+//!
+//! 一行一条语句，`#` 开头为注释，`{ ... }` 包住循环体：
+//!
+//! ```text
+//! set name = "world"
+//! set clip = clipboard          # 运行时读取剪切板
+//! jitter 20                     # 之后所有等待随机加减 0~20ms
+//! repeat 3 {                    # 或 repeat forever { ... }，直到被停止
+//!     type "Hello {name}!\n"    # 按美式键盘布局转换成按键，自动处理 Shift
+//!     press Ctrl+Shift+T        # 组合键
+//!     wait 500 ~100             # 等待 400~600ms
+//!     move 50% 50%              # 像素坐标或屏幕百分比
+//!     click left at 100 200
+//!     scroll -3                 # 滚轮格数，负数向下
+//! }
+//! ```
+//!
+//! 脚本先解析成语句树，再由解释器逐条执行，事件交给 `MacroSink`：
+//! 回放时发给输入后端，试运行时只记录解析后的事件列表。
+
+use crate::input_backend::InputBackend;
+use crate::keymap;
+use crate::recording::{InputEvent, KeyCode, MouseButton, Position, ScreenGeometry, TimedEvent};
+use crate::replay::{self, HeldInput};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 默认的按键间隔
+const DEFAULT_KEY_DELAY_MS: u64 = 30;
+/// 试运行最多输出的事件数
+pub const DRY_RUN_EVENT_LIMIT: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Var(String),
+}
+
+/// 带 `{变量}` 插值的字符串
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template(Vec<Part>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(Template),
+    Clipboard,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Coord {
+    Pixel(i32),
+    /// 屏幕百分比，0..1
+    Ratio(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Set {
+        name: String,
+        value: Value,
+    },
+    Type(Template),
+    Wait {
+        ms: u64,
+        jitter: Option<u64>,
+    },
+    /// 组合键：依次按下，再倒序松开
+    Press(Vec<KeyCode>),
+    KeyDown(KeyCode),
+    KeyUp(KeyCode),
+    Move {
+        x: Coord,
+        y: Coord,
+    },
+    Click {
+        button: MouseButton,
+        at: Option<(Coord, Coord)>,
+    },
+    MouseDown(MouseButton),
+    MouseUp(MouseButton),
+    /// 滚轮格数，正数向上
+    Scroll(i32),
+    Jitter(u64),
+    KeyDelay(u64),
+    /// `count` 为 None 时一直重复到被停止
+    Repeat {
+        count: Option<u32>,
+        body: Vec<Stmt>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(Template),
+    Open,
+    Close,
+    Equals,
+}
+
+fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<Template, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            None => return Err("Unterminated string".to_string()),
+            Some('"') => break,
+            Some('\\') => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some(c @ ('"' | '\\' | '{' | '}')) => text.push(c),
+                Some(c) => return Err(format!("Unknown escape \\{}", c)),
+                None => return Err("Unterminated string".to_string()),
+            },
+            Some('{') => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) if c.is_alphanumeric() || c == '_' => name.push(c),
+                        _ => return Err("Invalid variable reference in string".to_string()),
+                    }
+                }
+                if name.is_empty() {
+                    return Err("Empty variable reference in string".to_string());
+                }
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(Part::Var(name));
+            }
+            Some(c) => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    Ok(Template(parts))
+}
+
+fn tokenize_line(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '#' => break,
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                tokens.push(Token::Str(parse_string(&mut chars)?));
+            }
+            '{' | '}' | '=' => {
+                chars.next();
+                tokens.push(match c {
+                    '{' => Token::Open,
+                    '}' => Token::Close,
+                    _ => Token::Equals,
+                });
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '"' | '{' | '}' | '=' | '#') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn word(tokens: &[Token], index: usize) -> Option<&str> {
+    match tokens.get(index) {
+        Some(Token::Word(w)) => Some(w.as_str()),
+        _ => None,
+    }
+}
+
+fn number<T: std::str::FromStr>(tokens: &[Token], index: usize, what: &str) -> Result<T, String> {
+    word(tokens, index)
+        .and_then(|w| w.parse::<T>().ok())
+        .ok_or_else(|| format!("Expected {}", what))
+}
+
+fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let vk = keymap::vk_from_key_name(name).ok_or_else(|| format!("Unknown key: {}", name))?;
+    Ok(KeyCode::from_vk(vk, None))
+}
+
+fn parse_button(name: &str) -> Result<MouseButton, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "left" => Ok(MouseButton::Left),
+        "right" => Ok(MouseButton::Right),
+        "middle" => Ok(MouseButton::Middle),
+        _ => Err(format!("Unknown mouse button: {}", name)),
+    }
+}
+
+fn parse_coord(tokens: &[Token], index: usize) -> Result<Coord, String> {
+    let w = word(tokens, index).ok_or_else(|| "Expected coordinate".to_string())?;
+    if let Some(percent) = w.strip_suffix('%') {
+        let value: f64 = percent
+            .parse()
+            .map_err(|_| format!("Invalid coordinate: {}", w))?;
+        return Ok(Coord::Ratio((value / 100.0).clamp(0.0, 1.0)));
+    }
+    w.parse::<i32>()
+        .map(Coord::Pixel)
+        .map_err(|_| format!("Invalid coordinate: {}", w))
+}
+
+fn expect_end(tokens: &[Token], index: usize) -> Result<(), String> {
+    if index < tokens.len() {
+        return Err("Unexpected text at end of line".to_string());
+    }
+    Ok(())
+}
+
+/// 解析一行语句；`repeat` 的循环体由 `parse_block` 处理
+fn parse_statement(tokens: &[Token]) -> Result<Stmt, String> {
+    let command = word(tokens, 0).ok_or_else(|| "Expected a command".to_string())?;
+    let stmt = match command.to_ascii_lowercase().as_str() {
+        "set" => {
+            let name = word(tokens, 1)
+                .filter(|n| n.chars().all(|c| c.is_alphanumeric() || c == '_'))
+                .ok_or_else(|| "Expected variable name".to_string())?;
+            if tokens.get(2) != Some(&Token::Equals) {
+                return Err("Expected '=' after variable name".to_string());
+            }
+            let value = match tokens.get(3) {
+                Some(Token::Str(template)) => Value::Text(template.clone()),
+                Some(Token::Word(w)) if w.eq_ignore_ascii_case("clipboard") => Value::Clipboard,
+                Some(Token::Word(w)) => Value::Text(Template(vec![Part::Text(w.clone())])),
+                _ => return Err("Expected a value".to_string()),
+            };
+            expect_end(tokens, 4)?;
+            Stmt::Set {
+                name: name.to_string(),
+                value,
+            }
+        }
+        "type" => {
+            let template = match tokens.get(1) {
+                Some(Token::Str(template)) => template.clone(),
+                _ => return Err("Expected quoted text".to_string()),
+            };
+            expect_end(tokens, 2)?;
+            Stmt::Type(template)
+        }
+        "wait" => {
+            let ms = number(tokens, 1, "milliseconds")?;
+            let jitter = match word(tokens, 2) {
+                Some(w) => Some(
+                    w.strip_prefix('~')
+                        .and_then(|j| j.parse().ok())
+                        .ok_or_else(|| format!("Invalid jitter: {}", w))?,
+                ),
+                None => None,
+            };
+            expect_end(tokens, if jitter.is_some() { 3 } else { 2 })?;
+            Stmt::Wait { ms, jitter }
+        }
+        "press" => {
+            let chord = word(tokens, 1).ok_or_else(|| "Expected keys, e.g. Ctrl+C".to_string())?;
+            let keys = chord
+                .split('+')
+                .map(parse_key)
+                .collect::<Result<Vec<_>, _>>()?;
+            expect_end(tokens, 2)?;
+            Stmt::Press(keys)
+        }
+        "keydown" | "keyup" => {
+            let key = parse_key(word(tokens, 1).ok_or_else(|| "Expected a key".to_string())?)?;
+            expect_end(tokens, 2)?;
+            if command.eq_ignore_ascii_case("keydown") {
+                Stmt::KeyDown(key)
+            } else {
+                Stmt::KeyUp(key)
+            }
+        }
+        "move" => {
+            let (x, y) = (parse_coord(tokens, 1)?, parse_coord(tokens, 2)?);
+            expect_end(tokens, 3)?;
+            Stmt::Move { x, y }
+        }
+        "click" => {
+            let mut index = 1;
+            let button = match word(tokens, index) {
+                Some(w) if !w.eq_ignore_ascii_case("at") => {
+                    index += 1;
+                    parse_button(w)?
+                }
+                _ => MouseButton::Left,
+            };
+            let at = match word(tokens, index) {
+                Some(w) if w.eq_ignore_ascii_case("at") => {
+                    let at = (
+                        parse_coord(tokens, index + 1)?,
+                        parse_coord(tokens, index + 2)?,
+                    );
+                    index += 3;
+                    Some(at)
+                }
+                _ => None,
+            };
+            expect_end(tokens, index)?;
+            Stmt::Click { button, at }
+        }
+        "mousedown" | "mouseup" => {
+            let button = word(tokens, 1)
+                .map(parse_button)
+                .transpose()?
+                .unwrap_or(MouseButton::Left);
+            expect_end(tokens, if tokens.len() > 1 { 2 } else { 1 })?;
+            if command.eq_ignore_ascii_case("mousedown") {
+                Stmt::MouseDown(button)
+            } else {
+                Stmt::MouseUp(button)
+            }
+        }
+        "scroll" => {
+            let notches: i32 = number(tokens, 1, "scroll amount")?;
+            expect_end(tokens, 2)?;
+            // 每格滚动对应 120 的滚轮增量，超出 i32 范围的格数直接拒绝
+            if notches.checked_mul(120).is_none() {
+                return Err(format!("Scroll amount out of range: {}", notches));
+            }
+            Stmt::Scroll(notches)
+        }
+        "jitter" => {
+            let ms = number(tokens, 1, "milliseconds")?;
+            expect_end(tokens, 2)?;
+            Stmt::Jitter(ms)
+        }
+        "keydelay" => {
+            let ms = number(tokens, 1, "milliseconds")?;
+            expect_end(tokens, 2)?;
+            Stmt::KeyDelay(ms)
+        }
+        other => return Err(format!("Unknown command: {}", other)),
+    };
+    Ok(stmt)
+}
+
+/// `repeat` 行的次数：`repeat 3 {`、`repeat 3 times {`、`repeat forever {`、`repeat {`
+fn parse_repeat_header(tokens: &[Token]) -> Result<Option<u32>, String> {
+    if tokens.last() != Some(&Token::Open) {
+        return Err("Expected '{' at end of repeat line".to_string());
+    }
+    let args = &tokens[1..tokens.len() - 1];
+    match args {
+        [] => Ok(None),
+        [Token::Word(w)] if w.eq_ignore_ascii_case("forever") => Ok(None),
+        [Token::Word(n)] | [Token::Word(n), Token::Word(_)] => {
+            if let [_, Token::Word(times)] = args {
+                if !times.eq_ignore_ascii_case("times") {
+                    return Err(format!("Unexpected '{}' in repeat", times));
+                }
+            }
+            n.parse::<u32>()
+                .map(Some)
+                .map_err(|_| format!("Invalid repeat count: {}", n))
+        }
+        _ => Err("Invalid repeat syntax".to_string()),
+    }
+}
+
+fn parse_block(
+    lines: &[(usize, Vec<Token>)],
+    index: &mut usize,
+    opened_at: Option<usize>,
+) -> Result<Vec<Stmt>, String> {
+    let mut stmts = Vec::new();
+    while *index < lines.len() {
+        let (line_no, tokens) = &lines[*index];
+        *index += 1;
+        let at_line = |e: String| format!("Line {}: {}", line_no, e);
+
+        if tokens == &[Token::Close] {
+            return match opened_at {
+                Some(_) => Ok(stmts),
+                None => Err(at_line("Unmatched '}'".to_string())),
+            };
+        }
+        if word(tokens, 0).is_some_and(|w| w.eq_ignore_ascii_case("repeat")) {
+            let count = parse_repeat_header(tokens).map_err(at_line)?;
+            let body = parse_block(lines, index, Some(*line_no))?;
+            stmts.push(Stmt::Repeat { count, body });
+            continue;
+        }
+        stmts.push(parse_statement(tokens).map_err(at_line)?);
+    }
+    match opened_at {
+        Some(line_no) => Err(format!("Line {}: Missing '}}' for repeat", line_no)),
+        None => Ok(stmts),
+    }
+}
+
+/// 解析宏脚本
+pub fn parse(source: &str) -> Result<Vec<Stmt>, String> {
+    let mut lines = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let tokens = tokenize_line(line).map_err(|e| format!("Line {}: {}", i + 1, e))?;
+        if !tokens.is_empty() {
+            lines.push((i + 1, tokens));
+        }
+    }
+    let mut index = 0;
+    parse_block(&lines, &mut index, None)
+}
+
+/// 解释器产生的事件和等待的去处
+pub trait MacroSink {
+    /// 等待指定时间，返回 false 表示应停止
+    fn wait(&mut self, ms: u64) -> bool;
+    /// 执行一个输入事件，返回 false 表示应停止
+    fn emit(&mut self, event: InputEvent) -> Result<bool, String>;
+    /// 松开仍按着的键或鼠标键，已被停止时也要执行
+    fn release(&mut self, event: InputEvent) -> Result<(), String>;
+    /// 是否已被停止
+    fn cancelled(&mut self) -> bool;
+}
+
+/// 把事件发给输入后端；`should_stop` 返回 true 或后端报告中止时停止
+pub struct BackendSink<'a, F: FnMut() -> bool> {
+    pub backend: &'a mut dyn InputBackend,
+    pub screen: ScreenGeometry,
+    pub should_stop: F,
+}
+
+impl<F: FnMut() -> bool> MacroSink for BackendSink<'_, F> {
+    fn wait(&mut self, ms: u64) -> bool {
+        let mut remaining = ms;
+        while remaining > 0 {
+            if self.cancelled() {
+                return false;
+            }
+            let slice = remaining.min(replay::CANCEL_CHECK_MS);
+            self.backend.wait(Duration::from_millis(slice));
+            remaining -= slice;
+        }
+        !self.cancelled()
+    }
+
+    fn emit(&mut self, event: InputEvent) -> Result<bool, String> {
+        if self.cancelled() {
+            return Ok(false);
+        }
+        replay::execute_event(self.backend, &event, &self.screen)?;
+        Ok(true)
+    }
+
+    fn release(&mut self, event: InputEvent) -> Result<(), String> {
+        replay::execute_event(self.backend, &event, &self.screen)
+    }
+
+    fn cancelled(&mut self) -> bool {
+        (self.should_stop)() || self.backend.abort_requested()
+    }
+}
+
+/// 试运行：只记录事件和累计时间
+#[derive(Debug, Default)]
+pub struct DryRunSink {
+    pub events: Vec<TimedEvent>,
+    pub elapsed_ms: u64,
+    pub limit: usize,
+    pub truncated: bool,
+}
+
+impl MacroSink for DryRunSink {
+    fn wait(&mut self, ms: u64) -> bool {
+        self.elapsed_ms = self.elapsed_ms.saturating_add(ms);
+        !self.truncated
+    }
+
+    fn emit(&mut self, event: InputEvent) -> Result<bool, String> {
+        if self.events.len() >= self.limit {
+            self.truncated = true;
+            return Ok(false);
+        }
+        self.events.push(TimedEvent {
+            time_offset_ms: self.elapsed_ms,
+            event,
+        });
+        Ok(true)
+    }
+
+    fn release(&mut self, event: InputEvent) -> Result<(), String> {
+        // 截断后也记录，让预览能看到收尾的松开事件
+        self.events.push(TimedEvent {
+            time_offset_ms: self.elapsed_ms,
+            event,
+        });
+        Ok(())
+    }
+
+    fn cancelled(&mut self) -> bool {
+        self.truncated
+    }
+}
+
+/// 运行环境
+pub struct MacroContext<'a> {
+    pub screen: ScreenGeometry,
+    /// 读取剪切板，只在脚本用到时调用
+    pub clipboard: Box<dyn FnMut() -> Option<String> + 'a>,
+    /// 随机数种子，试运行时固定以便结果可复现
+    pub seed: u64,
+}
+
+struct Interpreter<'a, 'b> {
+    context: MacroContext<'a>,
+    sink: &'b mut dyn MacroSink,
+    variables: HashMap<String, String>,
+    jitter_ms: u64,
+    key_delay_ms: u64,
+    rng: u64,
+    emitted: usize,
+    /// 已按下还没松开的键和鼠标键，脚本结束、停止或出错时统一松开
+    held: HeldInput,
+}
+
+/// 解释器返回的控制流：继续或停止
+type Flow = Result<bool, String>;
+
+impl Interpreter<'_, '_> {
+    /// xorshift64，够用且不引入依赖
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng = x;
+        x
+    }
+
+    fn jittered(&mut self, ms: u64, jitter: u64) -> u64 {
+        if jitter == 0 {
+            return ms;
+        }
+        // offset 落在 0..=2*jitter，减去 jitter 后即为 -jitter..=jitter 的随机偏移
+        let offset = self.next_random() % jitter.saturating_mul(2).saturating_add(1);
+        ms.saturating_add(offset).saturating_sub(jitter)
+    }
+
+    fn wait(&mut self, ms: u64, jitter: Option<u64>) -> bool {
+        let jitter = jitter.unwrap_or(self.jitter_ms);
+        let ms = self.jittered(ms, jitter);
+        ms == 0 || self.sink.wait(ms)
+    }
+
+    fn emit(&mut self, event: InputEvent) -> Flow {
+        self.emitted += 1;
+        let sent = self.sink.emit(event.clone())?;
+        if sent {
+            self.held.track(&event);
+        }
+        Ok(sent)
+    }
+
+    /// 松开所有仍按着的键和鼠标键，某个失败时仍继续松开其余的
+    fn release_held(&mut self) -> Result<(), String> {
+        let mut result = Ok(());
+        for event in self.held.take_releases() {
+            self.emitted += 1;
+            result = result.and(self.sink.release(event));
+        }
+        result
+    }
+
+    fn key(&mut self, key: &KeyCode, pressed: bool) -> Flow {
+        let key = key.clone();
+        self.emit(if pressed {
+            InputEvent::KeyDown { key }
+        } else {
+            InputEvent::KeyUp { key }
+        })
+    }
+
+    fn render(&self, template: &Template) -> Result<String, String> {
+        let mut text = String::new();
+        for part in &template.0 {
+            match part {
+                Part::Text(t) => text.push_str(t),
+                Part::Var(name) => text.push_str(
+                    self.variables
+                        .get(name)
+                        .ok_or_else(|| format!("Undefined variable: {}", name))?,
+                ),
+            }
+        }
+        Ok(text)
+    }
+
+    fn position(&self, x: &Coord, y: &Coord) -> Position {
+        let screen = &self.context.screen;
+        let ratio = |c: &Coord, origin: i32, size: u32| match c {
+            Coord::Ratio(r) => *r,
+            Coord::Pixel(p) => {
+                ((*p as i64 - origin as i64) as f64 / (size.max(2) - 1) as f64).clamp(0.0, 1.0)
+            }
+        };
+        Position {
+            x: ratio(x, screen.x, screen.width),
+            y: ratio(y, screen.y, screen.height),
+        }
+    }
+
+    /// 逐字输入文本，连续需要 Shift 的字符只按一次 Shift
+    fn type_text(&mut self, text: &str) -> Flow {
+        let shift = KeyCode::from_vk(0xA0, None);
+        let mut shift_held = false;
+        let mut first = true;
+        for ch in text.chars().filter(|c| *c != '\r') {
+            let (vk, needs_shift) = keymap::vk_for_char(ch).ok_or_else(|| {
+                format!(
+                    "Cannot type character '{}'; paste it from the clipboard instead",
+                    ch
+                )
+            })?;
+            if !first && !self.wait(self.key_delay_ms, None) {
+                return Ok(false);
+            }
+            first = false;
+            if needs_shift != shift_held {
+                if !self.key(&shift, needs_shift)? {
+                    return Ok(false);
+                }
+                shift_held = needs_shift;
+            }
+            let key = KeyCode::from_vk(vk, None);
+            if !self.key(&key, true)? || !self.key(&key, false)? {
+                return Ok(false);
+            }
+        }
+        if shift_held && !self.key(&shift, false)? {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn run_block(&mut self, stmts: &[Stmt]) -> Flow {
+        for stmt in stmts {
+            if self.sink.cancelled() || !self.run_stmt(stmt)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn run_stmt(&mut self, stmt: &Stmt) -> Flow {
+        match stmt {
+            Stmt::Set { name, value } => {
+                let value = match value {
+                    Value::Text(template) => self.render(template)?,
+                    Value::Clipboard => (self.context.clipboard)().unwrap_or_default(),
+                };
+                self.variables.insert(name.clone(), value);
+                Ok(true)
+            }
+            Stmt::Type(template) => {
+                let text = self.render(template)?;
+                self.type_text(&text)
+            }
+            Stmt::Wait { ms, jitter } => Ok(self.wait(*ms, *jitter)),
+            Stmt::Press(keys) => {
+                for key in keys {
+                    if !self.key(key, true)? {
+                        return Ok(false);
+                    }
+                }
+                if !self.wait(self.key_delay_ms, None) {
+                    return Ok(false);
+                }
+                for key in keys.iter().rev() {
+                    if !self.key(key, false)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Stmt::KeyDown(key) => self.key(key, true),
+            Stmt::KeyUp(key) => self.key(key, false),
+            Stmt::Move { x, y } => {
+                let position = self.position(x, y);
+                self.emit(InputEvent::MouseMove { position })
+            }
+            Stmt::Click { button, at } => {
+                let position = at.as_ref().map(|(x, y)| self.position(x, y));
+                Ok(self.emit(InputEvent::MouseDown {
+                    button: *button,
+                    position,
+                })? && self.emit(InputEvent::MouseUp {
+                    button: *button,
+                    position: None,
+                })?)
+            }
+            Stmt::MouseDown(button) => self.emit(InputEvent::MouseDown {
+                button: *button,
+                position: None,
+            }),
+            Stmt::MouseUp(button) => self.emit(InputEvent::MouseUp {
+                button: *button,
+                position: None,
+            }),
+            Stmt::Scroll(notches) => self.emit(InputEvent::MouseWheel {
+                delta: notches * 120,
+            }),
+            Stmt::Jitter(ms) => {
+                self.jitter_ms = *ms;
+                Ok(true)
+            }
+            Stmt::KeyDelay(ms) => {
+                self.key_delay_ms = *ms;
+                Ok(true)
+            }
+            Stmt::Repeat {
+                count: Some(count),
+                body,
+            } => {
+                for _ in 0..*count {
+                    if !self.run_block(body)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Stmt::Repeat { count: None, body } => loop {
+                let before = self.emitted;
+                if !self.run_block(body)? {
+                    return Ok(false);
+                }
+                // 没有任何输入的无限循环永远不会检查停止，直接报错
+                if self.emitted == before {
+                    return Err("'repeat forever' body must produce input".to_string());
+                }
+            },
+        }
+    }
+}
+
+/// 运行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroOutcome {
+    pub events: usize,
+    /// 是否提前停止（被取消或试运行达到上限）
+    pub stopped: bool,
+}
+
+/// 执行解析好的宏
+pub fn run(
+    stmts: &[Stmt],
+    context: MacroContext,
+    sink: &mut dyn MacroSink,
+) -> Result<MacroOutcome, String> {
+    let rng = context.seed.max(1);
+    let mut interpreter = Interpreter {
+        context,
+        sink,
+        variables: HashMap::new(),
+        jitter_ms: 0,
+        key_delay_ms: DEFAULT_KEY_DELAY_MS,
+        rng,
+        emitted: 0,
+        held: HeldInput::default(),
+    };
+    let finished = interpreter.run_block(stmts);
+    // 无论正常结束、被停止还是出错，都不能让键一直按着
+    let released = interpreter.release_held();
+    let finished = finished?;
+    released?;
+    Ok(MacroOutcome {
+        events: interpreter.emitted,
+        stopped: !finished,
+    })
+}
+
+/// 试运行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroDryRun {
+    pub events: Vec<TimedEvent>,
+    pub duration_ms: u64,
+    /// 是否因达到事件上限而截断（例如 `repeat forever`）
+    pub truncated: bool,
+}
+
+/// 解析并试运行，返回解析后的事件列表
+pub fn dry_run(source: &str, context: MacroContext, limit: usize) -> Result<MacroDryRun, String> {
+    let stmts = parse(source)?;
+    let mut sink = DryRunSink {
+        limit,
+        ..Default::default()
+    };
+    run(&stmts, context, &mut sink)?;
+    Ok(MacroDryRun {
+        events: sink.events,
+        duration_ms: sink.elapsed_ms,
+        truncated: sink.truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_backend::mock::{MockAction, MockBackend};
+
+    fn context(clipboard: Option<&str>) -> MacroContext<'static> {
+        let clipboard = clipboard.map(str::to_string);
+        MacroContext {
+            screen: ScreenGeometry {
+                x: 0,
+                y: 0,
+                width: 1001,
+                height: 501,
+            },
+            clipboard: Box::new(move || clipboard.clone()),
+            seed: 42,
+        }
+    }
+
+    fn keys(run: &MacroDryRun) -> Vec<String> {
+        run.events
+            .iter()
+            .filter_map(|e| match &e.event {
+                InputEvent::KeyDown { key } => Some(format!("+{}", key.key)),
+                InputEvent::KeyUp { key } => Some(format!("-{}", key.key)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        assert_eq!(
+            parse("wait 10\nbogus 1").unwrap_err(),
+            "Line 2: Unknown command: bogus"
+        );
+        assert_eq!(
+            parse("repeat 2 {\nwait 1").unwrap_err(),
+            "Line 1: Missing '}' for repeat"
+        );
+        assert_eq!(parse("}").unwrap_err(), "Line 1: Unmatched '}'");
+        assert!(parse("press Ctrl+Nope")
+            .unwrap_err()
+            .contains("Unknown key: Nope"));
+        assert!(parse("type \"abc").is_err());
+        assert_eq!(
+            parse("scroll 20000000").unwrap_err(),
+            "Line 1: Scroll amount out of range: 20000000"
+        );
+        assert_eq!(
+            parse("# comment\nrepeat 3 times {\n  press Ctrl+C # copy\n}\n").unwrap(),
+            vec![Stmt::Repeat {
+                count: Some(3),
+                body: vec![Stmt::Press(vec![
+                    KeyCode::from_vk(0x11, None),
+                    KeyCode::from_vk(0x43, None)
+                ])]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_type_text_with_shift_and_variables() {
+        let script = "set who = clipboard\nkeydelay 0\ntype \"aB{who}!\"";
+        let run = dry_run(script, context(Some("CD")), 100).unwrap();
+        assert_eq!(
+            keys(&run),
+            vec![
+                "+A",
+                "-A",
+                "+ShiftLeft",
+                "+B",
+                "-B",
+                "+C",
+                "-C",
+                "+D",
+                "-D",
+                "+1",
+                "-1",
+                "-ShiftLeft"
+            ]
+        );
+        assert!(dry_run("type \"{missing}\"", context(None), 100)
+            .unwrap_err()
+            .contains("missing"));
+        assert!(dry_run("type \"中文\"", context(None), 100).is_err());
+    }
+
+    #[test]
+    fn test_repeat_wait_and_jitter() {
+        let script =
+            "repeat 3 {\n  press Ctrl+V\n  wait 100\n}\nmove 50% 100\nclick right at 1000 0";
+        let run = dry_run(script, context(None), 100).unwrap();
+        assert_eq!(run.events.len(), 3 * 4 + 3);
+        let times: Vec<u64> = run.events.iter().map(|e| e.time_offset_ms).collect();
+        assert_eq!(&times[..8], &[0, 0, 30, 30, 130, 130, 160, 160]);
+        assert_eq!(run.duration_ms, 3 * 130);
+        assert_eq!(
+            run.events[12].event,
+            InputEvent::MouseMove {
+                position: Position { x: 0.5, y: 0.2 }
+            }
+        );
+        assert_eq!(
+            run.events[13].event,
+            InputEvent::MouseDown {
+                button: MouseButton::Right,
+                position: Some(Position { x: 1.0, y: 0.0 })
+            }
+        );
+
+        let jittered = dry_run(
+            "repeat 20 {\n wait 100 ~10\n scroll 1\n}",
+            context(None),
+            100,
+        )
+        .unwrap();
+        let mut previous = 0;
+        for event in &jittered.events {
+            let gap = event.time_offset_ms - previous;
+            assert!((90..=110).contains(&gap), "gap {}", gap);
+            previous = event.time_offset_ms;
+        }
+        // 同一个种子结果可复现
+        let again = dry_run(
+            "repeat 20 {\n wait 100 ~10\n scroll 1\n}",
+            context(None),
+            100,
+        )
+        .unwrap();
+        assert_eq!(jittered.events, again.events);
+
+        // 极大的等待和抖动不会溢出
+        let huge = format!("wait {} ~{}\nscroll 1", u64::MAX, u64::MAX);
+        assert!(dry_run(&huge, context(None), 100).is_ok());
+    }
+
+    #[test]
+    fn test_repeat_forever_is_bounded() {
+        let run = dry_run(
+            "repeat forever {\n scroll -1\n wait 10\n}",
+            context(None),
+            50,
+        )
+        .unwrap();
+        assert!(run.truncated);
+        assert_eq!(run.events.len(), 50);
+        assert!(dry_run("repeat {\n wait 10\n}", context(None), 50).is_err());
+    }
+
+    #[test]
+    fn test_runs_against_backend_until_cancelled() {
+        let stmts = parse("repeat forever {\n press Alt+Tab\n wait 1000\n}").unwrap();
+        let mut backend = MockBackend::default();
+        let mut checks = 0;
+        let outcome = {
+            let mut sink = BackendSink {
+                backend: &mut backend,
+                screen: ScreenGeometry::default(),
+                should_stop: || {
+                    checks += 1;
+                    checks > 100
+                },
+            };
+            run(&stmts, context(None), &mut sink).unwrap()
+        };
+        assert!(outcome.stopped);
+        assert!(backend.elapsed_ms >= 1000);
+        assert_eq!(
+            backend.actions[0],
+            (0, MockAction::Key("Alt".to_string(), true))
+        );
+        assert_eq!(
+            backend.actions[3],
+            (30, MockAction::Key("Alt".to_string(), false))
+        );
+    }
+
+    #[test]
+    fn test_releases_held_input_on_every_exit() {
+        // 脚本结束时仍按着的键和鼠标键按相反顺序松开
+        let run = dry_run(
+            "keydown Ctrl\nmousedown right\nscroll 1",
+            context(None),
+            100,
+        )
+        .unwrap();
+        assert_eq!(keys(&run), vec!["+Ctrl", "-Ctrl"]);
+        assert_eq!(
+            run.events[3].event,
+            InputEvent::MouseUp {
+                button: MouseButton::Right,
+                position: None
+            }
+        );
+
+        // 出错时也松开
+        let stmts = parse("keydown Shift\ntype \"{missing}\"").unwrap();
+        let mut sink = DryRunSink {
+            limit: 100,
+            ..Default::default()
+        };
+        assert!(run_script(&stmts, &mut sink).is_err());
+        assert_eq!(sink.events.len(), 2);
+        assert!(matches!(&sink.events[1].event, InputEvent::KeyUp { key } if key.key == "Shift"));
+
+        // 输入大写字母时被停止，Shift 不会一直按着
+        let stmts = parse("keydelay 100\ntype \"ABCDEFGH\"").unwrap();
+        let mut backend = MockBackend::default();
+        let mut checks = 0;
+        let outcome = {
+            let mut sink = BackendSink {
+                backend: &mut backend,
+                screen: ScreenGeometry::default(),
+                should_stop: || {
+                    checks += 1;
+                    checks > 10
+                },
+            };
+            run_script(&stmts, &mut sink).unwrap()
+        };
+        assert!(outcome.stopped);
+        let shift = |pressed| MockAction::Key("ShiftLeft".to_string(), pressed);
+        let downs = backend
+            .actions
+            .iter()
+            .filter(|(_, a)| *a == shift(true))
+            .count();
+        let ups = backend
+            .actions
+            .iter()
+            .filter(|(_, a)| *a == shift(false))
+            .count();
+        assert_eq!((downs, ups), (1, 1));
+        assert_eq!(backend.actions.last().unwrap().1, shift(false));
+    }
+
+    #[test]
+    fn test_pixel_position_with_negative_origin() {
+        let mut ctx = context(None);
+        ctx.screen = ScreenGeometry {
+            x: -1920,
+            y: -10,
+            width: 3841,
+            height: 1081,
+        };
+        let run = dry_run("move 0 2147483647", ctx, 10).unwrap();
+        assert_eq!(
+            run.events[0].event,
+            InputEvent::MouseMove {
+                position: Position { x: 0.5, y: 1.0 }
+            }
+        );
+    }
+
+    fn run_script(stmts: &[Stmt], sink: &mut dyn MacroSink) -> Result<MacroOutcome, String> {
+        run(stmts, context(None), sink)
+    }
+}
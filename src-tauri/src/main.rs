@@ -10,22 +10,51 @@ mod file_history;
 mod hooks;
 mod hotkey;
 mod hotkey_handler;
-// mod keyboard_hook; // 已不再需要，hotkey_handler 已支持双击修饰键
+#[cfg(target_os = "linux")]
+mod hotkey_linux;
+mod hotkey_model;
+// 目前只有 Windows 的键盘钩子和 Linux 的全局快捷键使用序列匹配
+#[cfg_attr(
+    not(any(target_os = "windows", target_os = "linux")),
+    allow(dead_code)
+)]
+mod hotkey_sequence;
 mod db;
 mod logger;
 mod plugin_usage;
 mod memos;
+mod memo_revisions;
+mod memo_io;
+mod memo_reminders;
 mod open_history;
+mod keymap;
 mod recording;
+mod recording_editor;
 mod replay;
+mod input_backend;
+mod macro_script;
 mod settings;
+mod settings_service;
+mod settings_profiles;
 mod shortcuts;
 mod system_folders_search;
+mod web_search;
 mod window_config;
 mod clipboard;
 mod word_records;
+mod word_review;
+mod word_io;
+mod dictionary;
+mod word_stats;
+mod ollama;
+mod word_explain;
+mod semantic_search;
+mod ai_chat;
+mod snippets;
 mod file_watcher;
 mod markdown_recent_files;
+#[cfg(test)]
+mod test_support;
 
 use crate::commands::get_app_data_dir;
 use commands::*;
@@ -271,6 +300,24 @@ fn main() {
             // Get app_data_dir early for use in closures
             let app_data_dir = get_app_data_dir(app.handle())?;
 
+            // 设置服务：内存中的当前设置、变化广播和外部修改监听
+            if let Err(e) = settings_service::start(app.handle().clone(), app_data_dir.clone()) {
+                eprintln!("[Main] Failed to start settings service: {}", e);
+            }
+            let app_data_dir_settings = app_data_dir.clone();
+            settings_service::subscribe(move |change| {
+                // 调小剪切板历史上限后立即清理超出的记录
+                if change.touches("clipboard_max_items") {
+                    match clipboard::enforce_max_items(&app_data_dir_settings) {
+                        Ok(()) => semantic_search::notify_changed(),
+                        Err(e) => eprintln!("[Main] Failed to trim clipboard history: {}", e),
+                    }
+                }
+            });
+            // 任何途径修改快捷键设置都会自动重新注册；监听器未启动时 reload_hotkeys 只记录错误
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            settings_service::subscribe(commands::reload_hotkeys);
+
             let app_data_dir_clone1 = app_data_dir.clone();
 
             let _tray = tray_builder
@@ -352,7 +399,7 @@ fn main() {
                     }
                     "restart" => {
                         // 清理快捷键钩子
-                        #[cfg(target_os = "windows")]
+                        #[cfg(any(target_os = "windows", target_os = "linux"))]
                         {
                             hotkey_handler::windows::cleanup_hotkeys();
                         }
@@ -362,7 +409,7 @@ fn main() {
                     }
                     "quit" => {
                         // 清理快捷键钩子
-                        #[cfg(target_os = "windows")]
+                        #[cfg(any(target_os = "windows", target_os = "linux"))]
                         {
                             hotkey_handler::windows::cleanup_hotkeys();
                         }
@@ -380,7 +427,7 @@ fn main() {
             }
 
             // Register global hotkey for launcher window
-            #[cfg(target_os = "windows")]
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
             {
                 use std::sync::mpsc;
                 use std::time::Duration;
@@ -390,11 +437,14 @@ fn main() {
                 let (tx, rx) = mpsc::channel();
 
                 // Load hotkey config from settings
-                let hotkey_config = settings::load_settings(&app_data_dir)
-                    .ok()
-                    .and_then(|s| s.hotkey);
+                let startup_settings = settings::load_settings(&app_data_dir).ok();
+                if let Some(ref s) = startup_settings {
+                    hotkey_handler::windows::set_hotkey_timing(s.hotkey_timing);
+                }
+                let hotkey_config = startup_settings.and_then(|s| s.hotkey);
 
                 // Initialize hotkey log file
+                #[cfg(target_os = "windows")]
                 if let Some(log_path) = hotkey_handler::windows::init_hotkey_log() {
                     eprintln!("[Main] Hotkey log file: {}", log_path.display());
                 }
@@ -430,13 +480,10 @@ fn main() {
                         eprintln!("Failed to start hotkey listener: {}", e);
                     }
                 }
-
-                // 注意：keyboard_hook 模块已不再需要，因为 hotkey_handler 已经支持双击修饰键
-                // 如果保留此代码，会导致双击 Ctrl 和 Alt 都会触发，造成冲突
             }
 
             // 启动插件快捷键监听器
-            #[cfg(target_os = "windows")]
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
             {
                 use std::sync::mpsc;
                 
@@ -447,7 +494,9 @@ fn main() {
                 match hotkey_handler::windows::start_multi_hotkey_listener(tx_plugin) {
                     Ok(_handle) => {
                         // 定期更新启动器窗口的 HWND（当窗口显示时）
+                        #[cfg(target_os = "windows")]
                         let app_handle_launcher = app.handle().clone();
+                        #[cfg(target_os = "windows")]
                         std::thread::spawn(move || {
                             use std::time::Duration;
                             loop {
@@ -508,6 +557,13 @@ fn main() {
                                             eprintln!("[Main] Failed to show app center via hotkey: {}", e);
                                         }
                                     });
+                                } else if hotkey_id == commands::PLAYBACK_PANIC_HOTKEY_ID {
+                                    // 停止任何正在进行的回放（没有回放时忽略）
+                                    let _ = commands::stop_playback();
+                                } else if hotkey_id.starts_with(commands::RECORDING_HOTKEY_PREFIX) {
+                                    if let Err(e) = commands::play_recording_hotkey(&app_data_dir_hotkey, &hotkey_id) {
+                                        eprintln!("[Main] Failed to play recording via hotkey: {}", e);
+                                    }
                                 } else if hotkey_id.starts_with("app:") {
                                     // 提取应用路径
                                     let app_path = hotkey_id.strip_prefix("app:").unwrap_or(&hotkey_id);
@@ -533,52 +589,22 @@ fn main() {
                         let app_data_dir_plugin = app_data_dir.clone();
                         std::thread::spawn(move || {
                             std::thread::sleep(std::time::Duration::from_millis(500)); // 等待监听器完全启动
-                            if let Ok(mut settings) = settings::load_settings(&app_data_dir_plugin) {
-                                // 清理已被移除/禁用的插件的快捷键
-                                let removed_plugins = vec!["color_picker"];
-                                let mut cleaned = false;
-                                for plugin_id in removed_plugins {
-                                    if settings.plugin_hotkeys.remove(plugin_id).is_some() {
-                                        cleaned = true;
-                                    }
-                                }
-                                
-                                // 如果清理了快捷键，保存设置
-                                if cleaned {
-                                    let _ = settings::save_settings(&app_data_dir_plugin, &settings);
+                            // 清理已被移除/禁用的插件的快捷键，有清理时才保存
+                            let cleaned = settings::modify_settings(&app_data_dir_plugin, |settings| {
+                                for plugin_id in commands::REMOVED_PLUGIN_HOTKEYS {
+                                    settings.plugin_hotkeys.remove(plugin_id);
                                 }
-                                
-                                // 注册插件快捷键（会自动检测冲突并记录警告）
-                                let plugin_hotkeys = settings.plugin_hotkeys.clone();
-                                if !plugin_hotkeys.is_empty() {
-                                    if let Err(e) = hotkey_handler::windows::update_plugin_hotkeys(plugin_hotkeys) {
+                                Ok(settings.clone())
+                            });
+                            if let Ok(settings) = cleaned.or_else(|_| settings::load_settings(&app_data_dir_plugin)) {
+                                // 一次注册插件、应用中心、应用和录制回放快捷键（会自动检测冲突并记录警告）
+                                let hotkeys = commands::multi_hotkeys(&settings);
+                                let hotkey_count = hotkeys.len();
+                                if !hotkeys.is_empty() {
+                                    if let Err(e) = hotkey_handler::windows::update_plugin_hotkeys(hotkeys) {
                                         eprintln!("[Main] Failed to register plugin hotkeys: {}", e);
                                     } else {
-                                        eprintln!("[Main] Registered {} plugin hotkeys", settings.plugin_hotkeys.len());
-                                    }
-                                }
-                                
-                                // 注册应用中心快捷键
-                                if let Some(ref app_center_hotkey) = settings.app_center_hotkey {
-                                    if let Err(e) = hotkey_handler::windows::register_plugin_hotkey("app_center".to_string(), app_center_hotkey.clone()) {
-                                        eprintln!("[Main] Failed to register app center hotkey: {}", e);
-                                    } else {
-                                        eprintln!("[Main] Registered app center hotkey");
-                                    }
-                                }
-                                
-                                // 注册应用快捷键（使用 "app:" 前缀）
-                                let mut all_hotkeys = std::collections::HashMap::new();
-                                for (app_path, hotkey) in settings.app_hotkeys.iter() {
-                                    let hotkey_id = format!("app:{}", app_path);
-                                    all_hotkeys.insert(hotkey_id, hotkey.clone());
-                                }
-                                let app_hotkey_count = all_hotkeys.len();
-                                if !all_hotkeys.is_empty() {
-                                    if let Err(e) = hotkey_handler::windows::update_plugin_hotkeys(all_hotkeys) {
-                                        eprintln!("[Main] Failed to register app hotkeys: {}", e);
-                                    } else {
-                                        eprintln!("[Main] Registered {} app hotkeys", app_hotkey_count);
+                                        eprintln!("[Main] Registered {} plugin and app hotkeys", hotkey_count);
                                     }
                                 }
                             }
@@ -628,6 +654,10 @@ fn main() {
                 logger::init_log_file_early();
             }
 
+            // 备忘录提醒调度（错过的提醒会在启动后补发）
+            memo_reminders::start_scheduler(app.handle().clone(), app_data_dir.clone());
+            semantic_search::start_indexer(app_data_dir.clone());
+
             // Load app cache on startup and start background scan
             let app_data_dir_clone = app_data_dir.clone();
             std::thread::spawn(move || {
@@ -685,7 +715,20 @@ fn main() {
             list_recordings,
             delete_recording,
             play_recording,
+            load_recording_document,
+            edit_recording,
+            undo_recording_edit,
+            update_recording_info,
+            merge_recordings,
             stop_playback,
+            search_recordings,
+            play_recording_by_name,
+            get_recording_bindings,
+            save_recording_binding,
+            get_playback_panic_hotkey,
+            save_playback_panic_hotkey,
+            run_macro,
+            dry_run_macro,
             get_playback_status,
             get_playback_progress,
             scan_applications,
@@ -748,11 +791,69 @@ fn main() {
             update_memo,
             delete_memo,
             search_memos,
+            list_memos,
+            update_memo_meta,
+            get_memo_notebooks,
+            add_memo_notebook,
+            update_memo_notebook,
+            delete_memo_notebook,
+            get_memo_tags,
+            update_memo_tag,
+            get_memo_revisions,
+            get_memo_revision,
+            diff_memo_revisions,
+            restore_memo_revision,
+            list_deleted_memos,
+            export_memos,
+            import_memos,
+            set_memo_todo,
+            set_memo_reminder,
+            clear_memo_reminder,
+            get_upcoming_memo_reminders,
+            snooze_memo_reminder,
+            complete_memo_reminder,
             get_all_word_records,
             add_word_record,
             update_word_record,
             delete_word_record,
             search_word_records,
+            get_due_words,
+            submit_review,
+            get_review_stats,
+            get_word_stats,
+            preview_word_import,
+            import_word_records,
+            export_word_records,
+            import_dictionary,
+            lookup_dictionary,
+            search_dictionary,
+            get_dictionary_info,
+            remove_dictionary,
+            generate_word_explanation,
+            generate_missing_word_explanations,
+            get_ollama_models,
+            check_ollama_health,
+            ollama_chat_stream,
+            ollama_generate_stream,
+            cancel_ollama_request,
+            search_semantic_index,
+            rebuild_semantic_index,
+            get_semantic_index_status,
+            ask_ai,
+            list_ai_conversations,
+            get_ai_conversation_messages,
+            search_ai_conversations,
+            delete_ai_conversation,
+            get_all_snippets,
+            add_snippet,
+            update_snippet,
+            delete_snippet,
+            get_snippet_folders,
+            search_snippets,
+            get_snippet_inputs,
+            render_snippet,
+            import_clipboard_favorites_as_snippets,
+            paste_snippet,
             show_shortcuts_config,
             show_main_window,
             open_url,
@@ -789,11 +890,28 @@ fn main() {
             remove_markdown_recent_file,
             get_settings,
             save_settings,
+            update_settings,
+            list_settings_profiles,
+            save_settings_profile,
+            switch_settings_profile,
+            delete_settings_profile,
+            export_settings_profile,
+            preview_settings_profile_import,
+            import_settings_profile,
+            resolve_web_search,
+            get_search_suggestions,
+            import_search_bangs,
+            search_bangs,
+            clear_search_bangs,
             get_everything_custom_filters,
             save_everything_custom_filters,
             is_startup_enabled,
             set_startup_enabled,
             get_hotkey_config,
+            parse_hotkey,
+            get_hotkey_conflicts,
+            get_hotkey_timing,
+            save_hotkey_timing,
             save_hotkey_config,
             get_plugin_hotkeys,
             save_plugin_hotkeys,
@@ -0,0 +1,998 @@
+//! 备忘录导入/导出
+//!
+//! - Markdown 文件夹：每条备忘录一个 `.md` 文件，带 YAML front matter，
+//!   笔记本映射为子文件夹，便于放进 git 管理
+//! - JSON 归档：单个文件，包含备忘录和笔记本
+//! - ZIP 归档：把 Markdown 文件夹打包成单个文件
+//!
+//! 重新导入时按 id 合并，冲突按 [`ConflictStrategy`] 处理；没有 id 的文件按标题和内容去重。
+
+use crate::db;
+use crate::memo_revisions;
+use crate::memos::{self, MemoFilter, MemoItem, MemoNotebook};
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ARCHIVE_VERSION: u32 = 1;
+
+/// 导入时同 id 备忘录内容不同的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// 保留更新时间较新的一方
+    KeepNewer,
+    /// 两者都保留，导入的一方使用新 id
+    KeepBoth,
+    /// 使用导入的内容覆盖
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoArchive {
+    pub version: u32,
+    pub exported_at: u64,
+    pub notebooks: Vec<MemoNotebook>,
+    pub memos: Vec<MemoItem>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoExportSummary {
+    pub path: String,
+    pub exported: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub duplicated: usize,
+    pub unchanged: usize,
+    pub skipped: usize,
+    /// 内容不同的同 id 备忘录
+    pub conflicts: Vec<String>,
+    /// 无法解析的文件
+    pub errors: Vec<String>,
+    pub dry_run: bool,
+}
+
+/// 导入时携带笔记本路径（如 "工作/周报"）的备忘录
+struct ImportedMemo {
+    memo: MemoItem,
+    notebook_path: Option<String>,
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 转义笔记本名称中的 `\` 和 `/`，使名称本身不会被当成层级分隔符
+fn escape_notebook_name(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('/', "\\/")
+}
+
+/// 按未转义的 `/` 拆分笔记本路径，返回还原后的各级名称
+fn split_notebook_path(path: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => parts.last_mut().unwrap().push(chars.next().unwrap_or('\\')),
+            '/' => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts
+}
+
+/// 笔记本 id -> 完整路径（父级在前，以 "/" 分隔，名称经过 [`escape_notebook_name`] 转义）
+fn notebook_paths(notebooks: &[MemoNotebook]) -> HashMap<String, String> {
+    let by_id: HashMap<&str, &MemoNotebook> =
+        notebooks.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut paths = HashMap::new();
+    for notebook in notebooks {
+        let mut parts = vec![escape_notebook_name(&notebook.name)];
+        let mut seen = HashSet::new();
+        let mut parent = notebook.parent_id.as_deref();
+        while let Some(parent_id) = parent {
+            if !seen.insert(parent_id) {
+                break;
+            }
+            match by_id.get(parent_id) {
+                Some(p) => {
+                    parts.push(escape_notebook_name(&p.name));
+                    parent = p.parent_id.as_deref();
+                }
+                None => break,
+            }
+        }
+        parts.reverse();
+        paths.insert(notebook.id.clone(), parts.join("/"));
+    }
+    paths
+}
+
+/// 去掉文件名中的非法字符
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').trim();
+    if cleaned.is_empty() {
+        "untitled".to_string()
+    } else {
+        cleaned.chars().take(80).collect()
+    }
+}
+
+fn format_time(ts: u64) -> String {
+    Utc.timestamp_opt(ts as i64, 0)
+        .single()
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn parse_time(value: &str) -> Option<u64> {
+    if let Ok(ts) = value.parse::<u64>() {
+        return Some(ts);
+    }
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.timestamp().max(0) as u64)
+}
+
+fn yaml_string(value: &str) -> String {
+    // JSON 字符串同时也是合法的 YAML 双引号字符串
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// 生成带 front matter 的 Markdown
+pub fn memo_to_markdown(memo: &MemoItem, notebook_path: Option<&str>) -> String {
+    let mut out = String::from("---\n");
+    out.push_str(&format!("id: {}\n", yaml_string(&memo.id)));
+    out.push_str(&format!("title: {}\n", yaml_string(&memo.title)));
+    out.push_str(&format!("created: {}\n", format_time(memo.created_at)));
+    out.push_str(&format!("updated: {}\n", format_time(memo.updated_at)));
+    out.push_str(&format!(
+        "tags: {}\n",
+        serde_json::to_string(&memo.tags).unwrap_or_else(|_| "[]".to_string())
+    ));
+    if let Some(path) = notebook_path {
+        out.push_str(&format!("notebook: {}\n", yaml_string(path)));
+    }
+    if memo.pinned {
+        out.push_str("pinned: true\n");
+    }
+    if memo.archived {
+        out.push_str("archived: true\n");
+    }
+    if let Some(ref color) = memo.color {
+        out.push_str(&format!("color: {}\n", yaml_string(color)));
+    }
+    if memo.is_todo {
+        out.push_str("todo: true\n");
+    }
+    if let Some(completed_at) = memo.completed_at {
+        out.push_str(&format!("completed: {}\n", format_time(completed_at)));
+    }
+    out.push_str("---\n\n");
+    out.push_str(&memo.content);
+    // 总是补一个换行，导入时再去掉，保证正文原样往返
+    out.push('\n');
+    out
+}
+
+fn parse_yaml_scalar(value: &str) -> String {
+    let value = value.trim();
+    if value.starts_with('"') {
+        if let Ok(s) = serde_json::from_str::<String>(value) {
+            return s;
+        }
+    }
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].replace("''", "'");
+    }
+    value.to_string()
+}
+
+fn parse_yaml_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+    if let Ok(list) = serde_json::from_str::<Vec<String>>(value) {
+        return list;
+    }
+    value
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(parse_yaml_scalar)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// 解析带 front matter 的 Markdown；没有 front matter 时整个文件作为正文
+fn markdown_to_memo(source: &str, fallback_title: &str, fallback_time: u64) -> ImportedMemo {
+    let source = source.trim_start_matches('\u{feff}');
+    let mut fields: HashMap<String, String> = HashMap::new();
+    let mut list_key: Option<String> = None;
+    let mut tags: Vec<String> = Vec::new();
+    let mut body = source;
+
+    let normalized_start = source.strip_prefix("---\n").or_else(|| source.strip_prefix("---\r\n"));
+    if let Some(rest) = normalized_start {
+        let mut offset = source.len() - rest.len();
+        let mut closed = false;
+        for line in rest.split_inclusive('\n') {
+            offset += line.len();
+            let trimmed = line.trim_end();
+            if trimmed == "---" || trimmed == "..." {
+                closed = true;
+                break;
+            }
+            // 兼容块式列表：
+            // tags:
+            //   - a
+            if let Some(item) = trimmed.trim_start().strip_prefix("- ") {
+                if list_key.as_deref() == Some("tags") {
+                    tags.push(parse_yaml_scalar(item));
+                }
+                continue;
+            }
+            if let Some((key, value)) = trimmed.split_once(':') {
+                let key = key.trim().to_string();
+                let value = value.trim();
+                if value.is_empty() {
+                    list_key = Some(key);
+                    continue;
+                }
+                list_key = None;
+                if key == "tags" {
+                    tags = parse_yaml_list(value);
+                } else {
+                    fields.insert(key, value.to_string());
+                }
+            }
+        }
+        if closed {
+            body = &source[offset..];
+            body = body
+                .strip_prefix("\r\n")
+                .or_else(|| body.strip_prefix('\n'))
+                .unwrap_or(body);
+        } else {
+            fields.clear();
+            tags.clear();
+        }
+    }
+    let body = body
+        .strip_suffix("\r\n")
+        .or_else(|| body.strip_suffix('\n'))
+        .unwrap_or(body);
+
+    let field = |key: &str| fields.get(key).map(|v| parse_yaml_scalar(v));
+    let flag = |key: &str| field(key).map(|v| v == "true").unwrap_or(false);
+    let created_at = field("created").and_then(|v| parse_time(&v)).unwrap_or(fallback_time);
+    let updated_at = field("updated").and_then(|v| parse_time(&v)).unwrap_or(created_at);
+
+    ImportedMemo {
+        memo: MemoItem {
+            id: field("id").filter(|v| !v.is_empty()).unwrap_or_default(),
+            title: field("title").unwrap_or_else(|| fallback_title.to_string()),
+            content: body.to_string(),
+            created_at,
+            updated_at,
+            tags: memos::normalize_tags(tags),
+            pinned: flag("pinned"),
+            archived: flag("archived"),
+            notebook_id: None,
+            color: field("color").filter(|v| !v.is_empty()),
+            is_todo: flag("todo"),
+            completed_at: field("completed").and_then(|v| parse_time(&v)),
+            reminder: None,
+        },
+        notebook_path: field("notebook").filter(|v| !v.is_empty()),
+    }
+}
+
+fn unique_path(dir: &Path, stem: &str, used: &mut HashSet<PathBuf>) -> PathBuf {
+    let mut candidate = dir.join(format!("{}.md", stem));
+    let mut n = 2;
+    while used.contains(&candidate) {
+        candidate = dir.join(format!("{}-{}.md", stem, n));
+        n += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+/// 每条备忘录对应的 Markdown 文件（相对路径，笔记本对应子文件夹）
+fn markdown_files(items: &[MemoItem], paths: &HashMap<String, String>) -> Vec<(PathBuf, String)> {
+    let mut used = HashSet::new();
+    items
+        .iter()
+        .map(|memo| {
+            let notebook_path = memo.notebook_id.as_ref().and_then(|id| paths.get(id));
+            let mut dir = PathBuf::new();
+            if let Some(path) = notebook_path {
+                for part in split_notebook_path(path) {
+                    dir.push(sanitize_file_name(&part));
+                }
+            }
+            let file = unique_path(&dir, &sanitize_file_name(&memo.title), &mut used);
+            (file, memo_to_markdown(memo, notebook_path.map(|s| s.as_str())))
+        })
+        .collect()
+}
+
+/// 导出为 Markdown 文件夹，笔记本对应子文件夹
+pub fn export_memos_markdown(
+    target_dir: &Path,
+    filter: &MemoFilter,
+    app_data_dir: &PathBuf,
+) -> Result<MemoExportSummary, String> {
+    let items = memos::list_memos(filter, app_data_dir)?;
+    let paths = notebook_paths(&memos::get_memo_notebooks(app_data_dir)?);
+
+    fs::create_dir_all(target_dir)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    for (relative, markdown) in markdown_files(&items, &paths) {
+        let file = target_dir.join(relative);
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+        }
+        fs::write(&file, markdown)
+            .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+    }
+
+    Ok(MemoExportSummary {
+        path: target_dir.to_string_lossy().to_string(),
+        exported: items.len(),
+    })
+}
+
+/// 导出为 ZIP 归档，内容与 Markdown 文件夹相同
+pub fn export_memos_zip(
+    target_file: &Path,
+    filter: &MemoFilter,
+    app_data_dir: &PathBuf,
+) -> Result<MemoExportSummary, String> {
+    let items = memos::list_memos(filter, app_data_dir)?;
+    let paths = notebook_paths(&memos::get_memo_notebooks(app_data_dir)?);
+
+    if let Some(parent) = target_file.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    let file =
+        fs::File::create(target_file).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (relative, markdown) in markdown_files(&items, &paths) {
+        // ZIP 内统一使用 "/" 分隔
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        zip.start_file(name.as_str(), options)
+            .and_then(|_| zip.write_all(markdown.as_bytes()).map_err(Into::into))
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish archive: {}", e))?;
+
+    Ok(MemoExportSummary {
+        path: target_file.to_string_lossy().to_string(),
+        exported: items.len(),
+    })
+}
+
+/// 导出为单个 JSON 归档
+pub fn export_memos_json(
+    target_file: &Path,
+    filter: &MemoFilter,
+    app_data_dir: &PathBuf,
+) -> Result<MemoExportSummary, String> {
+    let items = memos::list_memos(filter, app_data_dir)?;
+    let used_notebooks: HashSet<&str> =
+        items.iter().filter_map(|m| m.notebook_id.as_deref()).collect();
+    let all_notebooks = memos::get_memo_notebooks(app_data_dir)?;
+
+    // 带上被引用笔记本的所有上级，保证导入后层级完整
+    let by_id: HashMap<&str, &MemoNotebook> =
+        all_notebooks.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut keep: HashSet<&str> = HashSet::new();
+    for id in used_notebooks {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if !keep.insert(id) {
+                break;
+            }
+            current = by_id.get(id).and_then(|n| n.parent_id.as_deref());
+        }
+    }
+    let notebooks = all_notebooks
+        .iter()
+        .filter(|n| keep.contains(n.id.as_str()))
+        .cloned()
+        .collect();
+
+    let archive = MemoArchive {
+        version: ARCHIVE_VERSION,
+        exported_at: now_ts(),
+        notebooks,
+        memos: items,
+    };
+    let json = serde_json::to_string_pretty(&archive)
+        .map_err(|e| format!("Failed to serialize memos: {}", e))?;
+
+    if let Some(parent) = target_file.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    fs::write(target_file, json).map_err(|e| format!("Failed to write archive: {}", e))?;
+
+    Ok(MemoExportSummary {
+        path: target_file.to_string_lossy().to_string(),
+        exported: archive.memos.len(),
+    })
+}
+
+fn collect_markdown_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with('.') {
+            continue; // 跳过 .git 等隐藏目录
+        }
+        if path.is_dir() {
+            collect_markdown_files(&path, out)?;
+        } else if path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+            .unwrap_or(false)
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn read_markdown_folder(dir: &Path, summary: &mut MemoImportSummary) -> Result<Vec<ImportedMemo>, String> {
+    let mut files = Vec::new();
+    collect_markdown_files(dir, &mut files)?;
+    files.sort();
+
+    let mut items = Vec::new();
+    for file in files {
+        let content = match fs::read_to_string(&file) {
+            Ok(c) => c,
+            Err(e) => {
+                summary.errors.push(format!("{}: {}", file.display(), e));
+                continue;
+            }
+        };
+        let title = file
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let modified = fs::metadata(&file)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or_else(now_ts);
+
+        let relative = file.strip_prefix(dir).unwrap_or(&file);
+        items.push(imported_markdown_file(relative, &content, &title, modified));
+    }
+    Ok(items)
+}
+
+/// 解析文件夹或 ZIP 中的一个 Markdown 文件；没有 front matter 时，用相对文件夹推断笔记本
+fn imported_markdown_file(relative: &Path, content: &str, title: &str, modified: u64) -> ImportedMemo {
+    let mut imported = markdown_to_memo(content, title, modified);
+    if imported.notebook_path.is_none() {
+        if let Some(parent) = relative.parent() {
+            let path = parent
+                .components()
+                .map(|c| escape_notebook_name(&c.as_os_str().to_string_lossy()))
+                .collect::<Vec<_>>()
+                .join("/");
+            if !path.is_empty() {
+                imported.notebook_path = Some(path);
+            }
+        }
+    }
+    imported
+}
+
+fn read_markdown_zip(file: &Path, summary: &mut MemoImportSummary) -> Result<Vec<ImportedMemo>, String> {
+    let reader = fs::File::open(file).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut zip =
+        zip::ZipArchive::new(reader).map_err(|e| format!("Failed to read ZIP archive: {}", e))?;
+
+    let mut items = Vec::new();
+    for index in 0..zip.len() {
+        let mut entry = match zip.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => {
+                summary.errors.push(format!("#{}: {}", index, e));
+                continue;
+            }
+        };
+        // enclosed_name 会拒绝 "../" 之类逃出归档的路径
+        let Some(relative) = entry.enclosed_name() else { continue };
+        let is_markdown = relative
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"));
+        let hidden = relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+        if entry.is_dir() || !is_markdown || hidden {
+            continue;
+        }
+
+        let mut content = String::new();
+        if let Err(e) = entry.read_to_string(&mut content) {
+            summary.errors.push(format!("{}: {}", relative.display(), e));
+            continue;
+        }
+        let title = relative
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        items.push(imported_markdown_file(&relative, &content, &title, now_ts()));
+    }
+    Ok(items)
+}
+
+fn read_json_archive(file: &Path) -> Result<Vec<ImportedMemo>, String> {
+    let content =
+        fs::read_to_string(file).map_err(|e| format!("Failed to read archive: {}", e))?;
+    // 兼容直接导出的备忘录数组（旧版 memos.json）
+    let archive = match serde_json::from_str::<MemoArchive>(&content) {
+        Ok(archive) => archive,
+        Err(_) => MemoArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: 0,
+            notebooks: Vec::new(),
+            memos: serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse memo archive: {}", e))?,
+        },
+    };
+    if archive.version > ARCHIVE_VERSION {
+        return Err(format!("Unsupported memo archive version {}", archive.version));
+    }
+
+    let paths = notebook_paths(&archive.notebooks);
+    Ok(archive
+        .memos
+        .into_iter()
+        .map(|mut memo| {
+            let notebook_path = memo.notebook_id.take().and_then(|id| paths.get(&id).cloned());
+            memo.tags = memos::normalize_tags(memo.tags);
+            ImportedMemo {
+                memo,
+                notebook_path,
+            }
+        })
+        .collect())
+}
+
+/// 按路径查找或创建笔记本，返回最末级笔记本 id
+fn ensure_notebook_path(conn: &rusqlite::Connection, path: &str) -> Result<String, String> {
+    let mut parent: Option<String> = None;
+    for name in split_notebook_path(path) {
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let existing: Option<String> = conn
+            .query_row(
+                "SELECT id FROM memo_notebooks
+                 WHERE lower(name) = lower(?1) AND parent_id IS ?2
+                 LIMIT 1",
+                params![name, parent],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to look up notebook: {}", e))?;
+
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                let nanos = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos();
+                let id = format!("notebook-{}", nanos);
+                let now = now_ts() as i64;
+                conn.execute(
+                    "INSERT INTO memo_notebooks (id, name, parent_id, color, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, NULL, ?4, ?4)",
+                    params![id, name, parent, now],
+                )
+                .map_err(|e| format!("Failed to create notebook: {}", e))?;
+                id
+            }
+        };
+        parent = Some(id);
+    }
+    parent.ok_or_else(|| "Empty notebook path".to_string())
+}
+
+fn write_memo(
+    conn: &rusqlite::Connection,
+    memo: &MemoItem,
+    notebook_path: Option<&str>,
+) -> Result<(), String> {
+    let notebook_id = match notebook_path {
+        Some(path) => Some(ensure_notebook_path(conn, path)?),
+        None => None,
+    };
+    conn.execute(
+        "INSERT INTO memos (id, title, content, created_at, updated_at, pinned, archived, notebook_id, color, is_todo, completed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT(id) DO UPDATE SET
+            title = excluded.title,
+            content = excluded.content,
+            created_at = excluded.created_at,
+            updated_at = excluded.updated_at,
+            pinned = excluded.pinned,
+            archived = excluded.archived,
+            notebook_id = excluded.notebook_id,
+            color = excluded.color,
+            is_todo = excluded.is_todo,
+            completed_at = excluded.completed_at",
+        params![
+            memo.id,
+            memo.title,
+            memo.content,
+            memo.created_at as i64,
+            memo.updated_at as i64,
+            if memo.pinned { 1 } else { 0 },
+            if memo.archived { 1 } else { 0 },
+            notebook_id,
+            memo.color,
+            if memo.is_todo { 1 } else { 0 },
+            memo.completed_at.map(|v| v as i64)
+        ],
+    )
+    .map_err(|e| format!("Failed to import memo {}: {}", memo.id, e))?;
+    memos::write_memo_tags(conn, &memo.id, &memo.tags)
+}
+
+/// 导入的备忘录与现有的是否完全相同（内容、标签、笔记本和其它元数据）
+fn same_memo(current: &MemoItem, current_notebook: Option<&str>, incoming: &ImportedMemo) -> bool {
+    let memo = &incoming.memo;
+    let same_notebook = match (current_notebook, incoming.notebook_path.as_deref()) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.to_lowercase() == b.trim_matches('/').to_lowercase(),
+        _ => false,
+    };
+    same_notebook
+        && current.title == memo.title
+        && current.content == memo.content
+        && current.tags == memo.tags
+        && current.updated_at == memo.updated_at
+        && current.pinned == memo.pinned
+        && current.archived == memo.archived
+        && current.color == memo.color
+        && current.is_todo == memo.is_todo
+        && current.completed_at == memo.completed_at
+}
+
+fn new_memo_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("memo-{}", nanos)
+}
+
+/// 从 Markdown 文件夹、ZIP 或 JSON 归档导入，`dry_run` 为 true 时只返回合并结果不写入
+pub fn import_memos(
+    source: &Path,
+    strategy: ConflictStrategy,
+    dry_run: bool,
+    app_data_dir: &PathBuf,
+) -> Result<MemoImportSummary, String> {
+    let mut summary = MemoImportSummary {
+        dry_run,
+        ..Default::default()
+    };
+
+    let is_zip = source
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    let items = if source.is_dir() {
+        read_markdown_folder(source, &mut summary)?
+    } else if is_zip {
+        read_markdown_zip(source, &mut summary)?
+    } else {
+        read_json_archive(source)?
+    };
+
+    // 确保旧版 memos.json 已迁移，避免后续迁移覆盖导入结果
+    memos::get_all_memos(app_data_dir)?;
+    let current_paths = notebook_paths(&memos::get_memo_notebooks(app_data_dir)?);
+    let retention = memos::revision_retention(app_data_dir);
+    let mut conn = db::get_connection(app_data_dir)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start import transaction: {}", e))?;
+
+    for mut imported in items {
+        if imported.memo.id.is_empty() {
+            // 没有 front matter 的文件没有 id，按标题和内容去重，避免每次重新导入都多一份
+            let duplicate: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM memos WHERE title = ?1 AND content = ?2 LIMIT 1",
+                    params![imported.memo.title, imported.memo.content],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to look up memo: {}", e))?;
+            if duplicate.is_some() {
+                summary.unchanged += 1;
+                continue;
+            }
+            imported.memo.id = new_memo_id();
+        }
+
+        let existing = memos::load_memo(&tx, &imported.memo.id)?;
+        let action = match existing {
+            None => Some(false),
+            Some(ref current)
+                if same_memo(
+                    current,
+                    current.notebook_id.as_ref().and_then(|id| current_paths.get(id)).map(String::as_str),
+                    &imported,
+                ) =>
+            {
+                summary.unchanged += 1;
+                None
+            }
+            Some(ref current) => {
+                let memo = &mut imported.memo;
+                summary.conflicts.push(memo.id.clone());
+                match strategy {
+                    ConflictStrategy::Overwrite => Some(true),
+                    ConflictStrategy::KeepNewer if memo.updated_at > current.updated_at => {
+                        Some(true)
+                    }
+                    ConflictStrategy::KeepNewer => {
+                        summary.skipped += 1;
+                        None
+                    }
+                    ConflictStrategy::KeepBoth => {
+                        memo.id = new_memo_id();
+                        memo.title = format!("{}（导入）", memo.title);
+                        summary.duplicated += 1;
+                        Some(false)
+                    }
+                }
+            }
+        };
+
+        let Some(overwrite) = action else { continue };
+        let ImportedMemo { memo, notebook_path } = imported;
+        if overwrite {
+            summary.updated += 1;
+        } else if existing.is_none() {
+            summary.created += 1;
+        }
+        if dry_run {
+            continue;
+        }
+
+        if let Some(ref current) = existing {
+            if overwrite {
+                memo_revisions::record_revision(
+                    &tx,
+                    &current.id,
+                    &current.title,
+                    &current.content,
+                    &retention,
+                )?;
+            }
+        }
+        write_memo(&tx, &memo, notebook_path.as_deref())?;
+        memo_revisions::record_revision(&tx, &memo.id, &memo.title, &memo.content, &retention)?;
+    }
+
+    if dry_run {
+        tx.rollback()
+            .map_err(|e| format!("Failed to roll back import preview: {}", e))?;
+    } else {
+        tx.commit()
+            .map_err(|e| format!("Failed to commit import: {}", e))?;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn sample() -> MemoItem {
+        MemoItem {
+            id: "memo-1".to_string(),
+            title: "周报: \"草稿\"".to_string(),
+            content: "---\n第一行\n".to_string(),
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_600,
+            tags: vec!["work".to_string(), "周报".to_string()],
+            pinned: true,
+            archived: false,
+            notebook_id: None,
+            color: Some("#ff0000".to_string()),
+            is_todo: true,
+            completed_at: None,
+            reminder: None,
+        }
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let memo = sample();
+        let md = memo_to_markdown(&memo, Some("工作/周报"));
+        let parsed = markdown_to_memo(&md, "fallback", 0);
+
+        assert_eq!(parsed.memo.id, memo.id);
+        assert_eq!(parsed.memo.title, memo.title);
+        assert_eq!(parsed.memo.content, memo.content);
+        assert_eq!(parsed.memo.created_at, memo.created_at);
+        assert_eq!(parsed.memo.updated_at, memo.updated_at);
+        assert_eq!(parsed.memo.tags, memo.tags);
+        assert!(parsed.memo.pinned);
+        assert_eq!(parsed.memo.color, memo.color);
+        assert!(parsed.memo.is_todo);
+        assert_eq!(parsed.notebook_path.as_deref(), Some("工作/周报"));
+    }
+
+    #[test]
+    fn test_markdown_without_front_matter() {
+        let parsed = markdown_to_memo("# 标题\n正文", "note", 42);
+        assert!(parsed.memo.id.is_empty());
+        assert_eq!(parsed.memo.title, "note");
+        assert_eq!(parsed.memo.content, "# 标题\n正文");
+        assert_eq!(parsed.memo.created_at, 42);
+    }
+
+    #[test]
+    fn test_block_style_tags() {
+        let source = "---\ntitle: 'it''s'\ntags:\n  - a\n  - b\n---\nbody";
+        let parsed = markdown_to_memo(source, "x", 0);
+        assert_eq!(parsed.memo.title, "it's");
+        assert_eq!(parsed.memo.tags, vec!["a", "b"]);
+        assert_eq!(parsed.memo.content, "body");
+    }
+
+    #[test]
+    fn test_metadata_changes_are_not_unchanged() {
+        let current = sample();
+        let mut incoming = ImportedMemo { memo: sample(), notebook_path: Some("工作".to_string()) };
+        assert!(same_memo(&current, Some("工作"), &incoming));
+        assert!(!same_memo(&current, None, &incoming));
+
+        incoming.memo.tags.push("新标签".to_string());
+        assert!(!same_memo(&current, Some("工作"), &incoming));
+        incoming.memo.tags = current.tags.clone();
+        incoming.memo.updated_at += 1;
+        assert!(!same_memo(&current, Some("工作"), &incoming));
+    }
+
+    #[test]
+    fn test_zip_archive_round_trip() {
+        let dir = temp_dir("memo-zip");
+        let archive = dir.join("memos.zip");
+
+        let memo = sample();
+        let paths = HashMap::from([("nb".to_string(), "工作/周报".to_string())]);
+        let mut in_notebook = memo.clone();
+        in_notebook.notebook_id = Some("nb".to_string());
+        let mut plain = MemoItem { id: "memo-2".to_string(), title: "plain".to_string(), ..memo };
+        plain.tags.clear();
+        {
+            let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+            for (relative, markdown) in markdown_files(&[in_notebook, plain], &paths) {
+                let name = relative.to_string_lossy().replace('\\', "/");
+                zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+                zip.write_all(markdown.as_bytes()).unwrap();
+            }
+            zip.start_file("../escape.md", zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(b"x").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let mut summary = MemoImportSummary::default();
+        let mut items = read_markdown_zip(&archive, &mut summary).unwrap();
+        items.sort_by(|a, b| a.memo.id.cmp(&b.memo.id));
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].memo.title, sample().title);
+        assert_eq!(items[0].notebook_path.as_deref(), Some("工作/周报"));
+        assert_eq!(items[1].memo.id, "memo-2");
+        assert_eq!(items[1].notebook_path, None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_notebook_names_with_slash() {
+        let notebook = |id: &str, name: &str, parent: Option<&str>| MemoNotebook {
+            id: id.to_string(),
+            name: name.to_string(),
+            parent_id: parent.map(str::to_string),
+            color: None,
+            created_at: 0,
+            updated_at: 0,
+            memo_count: 0,
+        };
+        let paths = notebook_paths(&[notebook("a", "读书/观影", None), notebook("b", "2024", Some("a"))]);
+        assert_eq!(paths["b"], "读书\\/观影/2024");
+        assert_eq!(split_notebook_path(&paths["b"]), vec!["读书/观影", "2024"]);
+        assert_eq!(split_notebook_path("a\\\\b/c"), vec!["a\\b", "c"]);
+
+        // 名称中的 "/" 不会变成多一级文件夹
+        let memo = MemoItem { notebook_id: Some("b".to_string()), ..sample() };
+        let files = markdown_files(&[memo], &paths);
+        assert_eq!(files[0].0.parent(), Some(Path::new("读书_观影").join("2024").as_path()));
+
+        let dir = temp_dir("memo-notebook-slash");
+        let conn = db::get_connection(&dir).unwrap();
+        let id = ensure_notebook_path(&conn, &paths["b"]).unwrap();
+        let (name, parent): (String, String) = conn
+            .query_row(
+                "SELECT n.name, p.name FROM memo_notebooks n JOIN memo_notebooks p ON p.id = n.parent_id WHERE n.id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((name.as_str(), parent.as_str()), ("2024", "读书/观影"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_reimport_without_front_matter_is_deduplicated() {
+        let dir = temp_dir("memo-reimport");
+        let source = dir.join("notes");
+        fs::create_dir_all(source.join("日记")).unwrap();
+        fs::write(source.join("日记").join("today.md"), "# 今天\n正文\n").unwrap();
+
+        let first = import_memos(&source, ConflictStrategy::KeepNewer, false, &dir).unwrap();
+        assert_eq!(first.created, 1);
+        let second = import_memos(&source, ConflictStrategy::KeepNewer, false, &dir).unwrap();
+        assert_eq!(second.created, 0);
+        assert_eq!(second.unchanged, 1);
+
+        let all = memos::get_all_memos(&dir).unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].title, "today");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sanitizes_file_names() {
+        assert_eq!(sanitize_file_name("a/b:c?"), "a_b_c_");
+        assert_eq!(sanitize_file_name("  .. "), "untitled");
+    }
+}
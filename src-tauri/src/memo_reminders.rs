@@ -0,0 +1,795 @@
+//! 备忘录提醒与调度
+//!
+//! 提醒状态保存在 SQLite 的 `memo_reminders` 表中，应用重启后继续生效；
+//! 离线期间错过的提醒会在下次启动时补发一次。后台调度线程在提醒到期时
+//! 发送 `memo-reminder-due` 事件，由前端展示通知。
+
+use crate::db;
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike,
+};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+/// 调度线程最长休眠时间（秒），防止系统休眠/时间调整后长时间不检查
+const MAX_SLEEP_SECS: u64 = 60;
+
+/// 重复规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Recurrence {
+    /// 每隔 `interval` 天
+    Daily {
+        #[serde(default = "default_interval")]
+        interval: u32,
+    },
+    /// 每隔 `interval` 周，在 `weekdays`（0 = 周一 … 6 = 周日）重复；为空时使用首次提醒的星期
+    Weekly {
+        #[serde(default = "default_interval")]
+        interval: u32,
+        #[serde(default)]
+        weekdays: Vec<u8>,
+    },
+    /// 五段式 cron 表达式：分 时 日 月 周
+    Cron { expr: String },
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoReminder {
+    pub memo_id: String,
+    /// 本次提醒时间（重复提醒会随每次触发向后推进）
+    pub remind_at: u64,
+    /// 截止时间，仅用于展示，随重复规则一同推进
+    pub due_at: Option<u64>,
+    pub recurrence: Option<Recurrence>,
+    pub snoozed_until: Option<u64>,
+    pub last_fired_at: Option<u64>,
+    /// 下次触发时间；一次性提醒触发后为空
+    pub next_fire_at: Option<u64>,
+    /// 保存的重复规则无法解析时的错误；此时提醒不再触发，需要重新设置
+    #[serde(default)]
+    pub recurrence_error: Option<String>,
+}
+
+/// `memo-reminder-due` 事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DueReminder {
+    pub memo_id: String,
+    pub title: String,
+    pub remind_at: u64,
+    pub due_at: Option<u64>,
+    pub fired_at: u64,
+    pub is_todo: bool,
+}
+
+static SCHEDULER_WAKE: LazyLock<(Mutex<bool>, Condvar)> =
+    LazyLock::new(|| (Mutex::new(false), Condvar::new()));
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 提醒发生变化时唤醒调度线程重新计算休眠时间
+fn notify_scheduler() {
+    let (lock, cvar) = &*SCHEDULER_WAKE;
+    if let Ok(mut dirty) = lock.lock() {
+        *dirty = true;
+        cvar.notify_all();
+    }
+}
+
+impl Recurrence {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Recurrence::Daily { interval } | Recurrence::Weekly { interval, .. }
+                if *interval == 0 =>
+            {
+                Err("Recurrence interval must be at least 1".to_string())
+            }
+            Recurrence::Weekly { weekdays, .. } if weekdays.iter().any(|d| *d > 6) => {
+                Err("Weekday must be between 0 (Monday) and 6 (Sunday)".to_string())
+            }
+            Recurrence::Cron { expr } => cron::CronSchedule::parse(expr).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
+    /// 从 `anchor`（上一次提醒时间）出发，计算晚于 `after` 的下一次提醒时间
+    pub fn next_after<Tz: TimeZone>(
+        &self,
+        anchor: &DateTime<Tz>,
+        after: &DateTime<Tz>,
+    ) -> Option<DateTime<Tz>> {
+        let tz = anchor.timezone();
+        let time = anchor.naive_local().time();
+        match self {
+            Recurrence::Daily { interval } => {
+                let step = (*interval).max(1) as i64;
+                let start = anchor.naive_local().date();
+                // 直接跳到 `after` 附近，避免长时间离线后逐日循环
+                let behind = (after.naive_local().date() - start).num_days().max(0);
+                let first = (behind / step).max(1);
+                (first..first + 3).find_map(|n| {
+                    localize(&tz, (start + Duration::days(n * step)).and_time(time))
+                        .filter(|candidate| candidate > after)
+                })
+            }
+            Recurrence::Weekly { interval, weekdays } => {
+                let step = (*interval).max(1) as i64;
+                let start = anchor.naive_local().date();
+                let days: Vec<u32> = if weekdays.is_empty() {
+                    vec![start.weekday().num_days_from_monday()]
+                } else {
+                    weekdays.iter().map(|d| *d as u32).collect()
+                };
+                let week_start =
+                    start - Duration::days(start.weekday().num_days_from_monday() as i64);
+                let from = after.naive_local().date().max(start);
+                // 最多查找两个完整周期，加一周余量
+                for offset in 0..(7 * (2 * step + 1)) {
+                    let date = from + Duration::days(offset);
+                    if !days.contains(&date.weekday().num_days_from_monday()) {
+                        continue;
+                    }
+                    let week = (date - week_start).num_days() / 7;
+                    if week % step != 0 {
+                        continue;
+                    }
+                    if let Some(candidate) = localize(&tz, date.and_time(time)) {
+                        if candidate > *anchor && candidate > *after {
+                            return Some(candidate);
+                        }
+                    }
+                }
+                None
+            }
+            Recurrence::Cron { expr } => {
+                let schedule = cron::CronSchedule::parse(expr).ok()?;
+                let from = if anchor > after { anchor } else { after };
+                schedule.next_after(from)
+            }
+        }
+    }
+}
+
+/// 将本地时间转换为时区时间；夏令时跳过的时间点向后顺延
+fn localize<Tz: TimeZone>(tz: &Tz, naive: NaiveDateTime) -> Option<DateTime<Tz>> {
+    for shift in 0..=2 {
+        let candidate = naive + Duration::hours(shift);
+        if let Some(dt) = tz.from_local_datetime(&candidate).earliest() {
+            return Some(dt);
+        }
+    }
+    None
+}
+
+fn to_local(ts: u64) -> DateTime<Local> {
+    Local
+        .timestamp_opt(ts as i64, 0)
+        .single()
+        .unwrap_or_else(Local::now)
+}
+
+fn parse_recurrence(json: &str) -> Result<Recurrence, String> {
+    let recurrence: Recurrence = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse recurrence: {}", e))?;
+    recurrence.validate()?;
+    Ok(recurrence)
+}
+
+fn row_to_reminder(row: &rusqlite::Row) -> rusqlite::Result<MemoReminder> {
+    // 无法解析的重复规则不能当作一次性提醒，单独记下错误
+    let (recurrence, recurrence_error) = match row.get::<_, Option<String>>(3)? {
+        Some(json) => match parse_recurrence(&json) {
+            Ok(recurrence) => (Some(recurrence), None),
+            Err(e) => (None, Some(e)),
+        },
+        None => (None, None),
+    };
+    Ok(MemoReminder {
+        memo_id: row.get(0)?,
+        remind_at: row.get::<_, i64>(1)? as u64,
+        due_at: row.get::<_, Option<i64>>(2)?.map(|v| v as u64),
+        recurrence,
+        snoozed_until: row.get::<_, Option<i64>>(4)?.map(|v| v as u64),
+        last_fired_at: row.get::<_, Option<i64>>(5)?.map(|v| v as u64),
+        next_fire_at: row.get::<_, Option<i64>>(6)?.map(|v| v as u64),
+        recurrence_error,
+    })
+}
+
+const REMINDER_COLUMNS: &str =
+    "memo_id, remind_at, due_at, recurrence, snoozed_until, last_fired_at, next_fire_at";
+
+fn load_reminder(
+    conn: &rusqlite::Connection,
+    memo_id: &str,
+) -> Result<Option<MemoReminder>, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM memo_reminders WHERE memo_id = ?1", REMINDER_COLUMNS),
+        params![memo_id],
+        row_to_reminder,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load memo reminder: {}", e))
+}
+
+fn write_reminder(conn: &rusqlite::Connection, reminder: &MemoReminder) -> Result<(), String> {
+    // 写回会丢掉原始的重复规则，只能通过重新设置提醒来替换
+    if let Some(ref error) = reminder.recurrence_error {
+        return Err(format!(
+            "Memo {} has an invalid reminder recurrence, set the reminder again: {}",
+            reminder.memo_id, error
+        ));
+    }
+    let recurrence = reminder
+        .recurrence
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| format!("Failed to serialize recurrence: {}", e))?;
+    conn.execute(
+        "INSERT INTO memo_reminders
+            (memo_id, remind_at, due_at, recurrence, snoozed_until, last_fired_at, next_fire_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(memo_id) DO UPDATE SET
+            remind_at = excluded.remind_at,
+            due_at = excluded.due_at,
+            recurrence = excluded.recurrence,
+            snoozed_until = excluded.snoozed_until,
+            last_fired_at = excluded.last_fired_at,
+            next_fire_at = excluded.next_fire_at",
+        params![
+            reminder.memo_id,
+            reminder.remind_at as i64,
+            reminder.due_at.map(|v| v as i64),
+            recurrence,
+            reminder.snoozed_until.map(|v| v as i64),
+            reminder.last_fired_at.map(|v| v as i64),
+            reminder.next_fire_at.map(|v| v as i64)
+        ],
+    )
+    .map_err(|e| format!("Failed to save memo reminder: {}", e))?;
+    Ok(())
+}
+
+/// 读取所有提醒，按备忘录 id 索引
+pub(crate) fn load_reminders(
+    conn: &rusqlite::Connection,
+) -> Result<HashMap<String, MemoReminder>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM memo_reminders", REMINDER_COLUMNS))
+        .map_err(|e| format!("Failed to prepare memo reminders query: {}", e))?;
+    let rows = stmt
+        .query_map([], row_to_reminder)
+        .map_err(|e| format!("Failed to iterate memo reminders: {}", e))?;
+
+    let mut map = HashMap::new();
+    for row in rows {
+        let reminder = row.map_err(|e| format!("Failed to read memo reminder row: {}", e))?;
+        map.insert(reminder.memo_id.clone(), reminder);
+    }
+    Ok(map)
+}
+
+/// 将提醒推进到 `after` 之后的下一次；没有下一次时返回 false
+fn advance(reminder: &mut MemoReminder, after: u64) -> bool {
+    let Some(ref recurrence) = reminder.recurrence else {
+        reminder.next_fire_at = None;
+        return false;
+    };
+    let anchor = to_local(reminder.remind_at);
+    match recurrence.next_after(&anchor, &to_local(after)) {
+        Some(next) => {
+            let next = next.timestamp().max(0) as u64;
+            if let Some(due) = reminder.due_at {
+                // 截止时间与提醒时间保持相同间隔
+                let shifted = due as i64 + next as i64 - reminder.remind_at as i64;
+                reminder.due_at = Some(shifted.max(0) as u64);
+            }
+            reminder.remind_at = next;
+            reminder.next_fire_at = Some(next);
+            true
+        }
+        None => {
+            reminder.next_fire_at = None;
+            false
+        }
+    }
+}
+
+/// 设置（或替换）备忘录提醒
+pub fn set_memo_reminder(
+    memo_id: String,
+    remind_at: u64,
+    due_at: Option<u64>,
+    recurrence: Option<Recurrence>,
+    app_data_dir: &Path,
+) -> Result<MemoReminder, String> {
+    if let Some(ref r) = recurrence {
+        r.validate()?;
+    }
+    let conn = db::get_connection(app_data_dir)?;
+    let exists = conn
+        .query_row("SELECT 1 FROM memos WHERE id = ?1", params![memo_id], |_| Ok(()))
+        .optional()
+        .map_err(|e| format!("Failed to check memo: {}", e))?
+        .is_some();
+    if !exists {
+        return Err(format!("Memo {} not found", memo_id));
+    }
+
+    let reminder = MemoReminder {
+        memo_id,
+        remind_at,
+        due_at,
+        recurrence,
+        snoozed_until: None,
+        last_fired_at: None,
+        next_fire_at: Some(remind_at),
+        recurrence_error: None,
+    };
+    write_reminder(&conn, &reminder)?;
+    notify_scheduler();
+    Ok(reminder)
+}
+
+pub fn clear_memo_reminder(memo_id: String, app_data_dir: &Path) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute("DELETE FROM memo_reminders WHERE memo_id = ?1", params![memo_id])
+        .map_err(|e| format!("Failed to delete memo reminder: {}", e))?;
+    notify_scheduler();
+    Ok(())
+}
+
+pub fn get_memo_reminder(
+    memo_id: String,
+    app_data_dir: &Path,
+) -> Result<Option<MemoReminder>, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    load_reminder(&conn, &memo_id)
+}
+
+/// 列出 `until`（Unix 秒）之前将要触发的提醒，按触发时间排序
+pub fn get_upcoming_reminders(
+    until: Option<u64>,
+    app_data_dir: &Path,
+) -> Result<Vec<MemoReminder>, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM memo_reminders
+             WHERE next_fire_at IS NOT NULL AND next_fire_at <= ?1
+             ORDER BY next_fire_at ASC",
+            REMINDER_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare upcoming reminders query: {}", e))?;
+    let rows = stmt
+        .query_map(params![until.unwrap_or(i64::MAX as u64) as i64], row_to_reminder)
+        .map_err(|e| format!("Failed to iterate upcoming reminders: {}", e))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read memo reminder row: {}", e))?);
+    }
+    Ok(items)
+}
+
+/// 稍后提醒
+pub fn snooze_memo_reminder(
+    memo_id: String,
+    minutes: u32,
+    app_data_dir: &Path,
+) -> Result<MemoReminder, String> {
+    if minutes == 0 {
+        return Err("Snooze duration must be at least 1 minute".to_string());
+    }
+    let conn = db::get_connection(app_data_dir)?;
+    let mut reminder = load_reminder(&conn, &memo_id)?
+        .ok_or_else(|| format!("Memo {} has no reminder", memo_id))?;
+    let until = now_ts() + minutes as u64 * 60;
+    reminder.snoozed_until = Some(until);
+    reminder.next_fire_at = Some(until);
+    write_reminder(&conn, &reminder)?;
+    notify_scheduler();
+    Ok(reminder)
+}
+
+/// 完成本次提醒
+///
+/// 重复提醒推进到下一次；一次性提醒停止，若备忘录为待办则同时标记为已完成。
+/// 返回更新后的提醒（一次性提醒完成后仍保留记录，`next_fire_at` 为空）。
+pub fn complete_memo_reminder(
+    memo_id: String,
+    app_data_dir: &Path,
+) -> Result<MemoReminder, String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start reminder transaction: {}", e))?;
+    let mut reminder = load_reminder(&tx, &memo_id)?
+        .ok_or_else(|| format!("Memo {} has no reminder", memo_id))?;
+
+    reminder.snoozed_until = None;
+    // 提前完成时也跳过当前这一次
+    let after = now_ts().max(reminder.remind_at);
+    if !advance(&mut reminder, after) {
+        tx.execute(
+            "UPDATE memos SET completed_at = ?1 WHERE id = ?2 AND is_todo = 1 AND completed_at IS NULL",
+            params![now_ts() as i64, memo_id],
+        )
+        .map_err(|e| format!("Failed to complete memo: {}", e))?;
+    }
+    write_reminder(&tx, &reminder)?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit reminder update: {}", e))?;
+    notify_scheduler();
+    Ok(reminder)
+}
+
+/// 取出所有已到期的提醒并推进其状态
+///
+/// 重复提醒直接跳到 `now` 之后的下一次，离线期间错过的多次只补发一次。
+/// 重复规则无法解析的提醒不触发，记录错误后停止调度，保留原始规则等待重新设置。
+pub fn take_due_reminders(now: u64, app_data_dir: &Path) -> Result<Vec<DueReminder>, String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start reminder transaction: {}", e))?;
+
+    let due: Vec<(MemoReminder, String, bool)> = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT r.memo_id, r.remind_at, r.due_at, r.recurrence, r.snoozed_until,
+                        r.last_fired_at, r.next_fire_at, m.title, m.is_todo
+                 FROM memo_reminders r JOIN memos m ON m.id = r.memo_id
+                 WHERE r.next_fire_at IS NOT NULL AND r.next_fire_at <= ?1
+                   AND m.completed_at IS NULL
+                 ORDER BY r.next_fire_at ASC",
+            )
+            .map_err(|e| format!("Failed to prepare due reminders query: {}", e))?;
+        let rows = stmt
+            .query_map(params![now as i64], |row| {
+                Ok((
+                    row_to_reminder(row)?,
+                    row.get::<_, String>(7)?,
+                    row.get::<_, i64>(8)? != 0,
+                ))
+            })
+            .map_err(|e| format!("Failed to iterate due reminders: {}", e))?;
+        let mut items = Vec::new();
+        for row in rows {
+            items.push(row.map_err(|e| format!("Failed to read due reminder row: {}", e))?);
+        }
+        items
+    };
+
+    let mut fired = Vec::with_capacity(due.len());
+    for (mut reminder, title, is_todo) in due {
+        if let Some(ref error) = reminder.recurrence_error {
+            eprintln!(
+                "[MemoReminders] Skipping reminder for memo {}: {}",
+                reminder.memo_id, error
+            );
+            tx.execute(
+                "UPDATE memo_reminders SET next_fire_at = NULL WHERE memo_id = ?1",
+                params![reminder.memo_id],
+            )
+            .map_err(|e| format!("Failed to save memo reminder: {}", e))?;
+            continue;
+        }
+        fired.push(DueReminder {
+            memo_id: reminder.memo_id.clone(),
+            title,
+            remind_at: reminder.remind_at,
+            due_at: reminder.due_at,
+            fired_at: now,
+            is_todo,
+        });
+        reminder.last_fired_at = Some(now);
+        reminder.snoozed_until = None;
+        advance(&mut reminder, now);
+        write_reminder(&tx, &reminder)?;
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit reminder update: {}", e))?;
+    Ok(fired)
+}
+
+/// 最近一次待触发的时间
+fn next_wakeup(app_data_dir: &Path) -> Result<Option<u64>, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    conn.query_row(
+        "SELECT MIN(r.next_fire_at) FROM memo_reminders r
+         JOIN memos m ON m.id = r.memo_id
+         WHERE r.next_fire_at IS NOT NULL AND m.completed_at IS NULL",
+        [],
+        |row| row.get::<_, Option<i64>>(0),
+    )
+    .map(|v| v.map(|v| v.max(0) as u64))
+    .map_err(|e| format!("Failed to query next reminder: {}", e))
+}
+
+/// 启动后台调度线程
+pub fn start_scheduler(app: AppHandle, app_data_dir: PathBuf) {
+    std::thread::spawn(move || loop {
+        let now = now_ts();
+        match take_due_reminders(now, &app_data_dir) {
+            Ok(due) => {
+                for reminder in due {
+                    if let Err(e) = app.emit("memo-reminder-due", &reminder) {
+                        eprintln!("[MemoReminders] Failed to emit reminder event: {}", e);
+                    }
+                }
+            }
+            Err(e) => eprintln!("[MemoReminders] Failed to check reminders: {}", e),
+        }
+
+        let sleep_secs = match next_wakeup(&app_data_dir) {
+            Ok(Some(next)) => next.saturating_sub(now_ts()).clamp(1, MAX_SLEEP_SECS),
+            _ => MAX_SLEEP_SECS,
+        };
+
+        let (lock, cvar) = &*SCHEDULER_WAKE;
+        let mut dirty = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if !*dirty {
+            dirty = cvar
+                .wait_timeout(dirty, std::time::Duration::from_secs(sleep_secs))
+                .map(|(guard, _)| guard)
+                .unwrap_or_else(|e| e.into_inner().0);
+        }
+        *dirty = false;
+    });
+}
+
+/// 最小化的 cron 表达式解析
+pub mod cron {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct CronSchedule {
+        minutes: u64,
+        hours: u64,
+        days_of_month: u64,
+        months: u64,
+        days_of_week: u64,
+        dom_restricted: bool,
+        dow_restricted: bool,
+    }
+
+    fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+        let mut mask = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((r, s)) => (
+                    r,
+                    s.parse::<u32>()
+                        .ok()
+                        .filter(|s| *s > 0)
+                        .ok_or_else(|| format!("Invalid step '{}' in cron {} field", s, name))?,
+                ),
+                None => (part, 1),
+            };
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((a, b)) = range.split_once('-') {
+                (parse_value(a, name)?, parse_value(b, name)?)
+            } else {
+                let v = parse_value(range, name)?;
+                // "5/10" 表示从 5 开始每 10 个
+                (v, if step > 1 { max } else { v })
+            };
+            if start < min || end > max || start > end {
+                return Err(format!(
+                    "Cron {} field out of range ({}-{}): {}",
+                    name, min, max, part
+                ));
+            }
+            let mut v = start;
+            while v <= end {
+                mask |= 1 << v;
+                v += step;
+            }
+        }
+        Ok(mask)
+    }
+
+    fn parse_value(value: &str, name: &str) -> Result<u32, String> {
+        value
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid value '{}' in cron {} field", value, name))
+    }
+
+    impl CronSchedule {
+        pub fn parse(expr: &str) -> Result<Self, String> {
+            let expr = match expr.trim() {
+                "@hourly" => "0 * * * *",
+                "@daily" | "@midnight" => "0 0 * * *",
+                "@weekly" => "0 0 * * 0",
+                "@monthly" => "0 0 1 * *",
+                "@yearly" | "@annually" => "0 0 1 1 *",
+                other => other,
+            };
+            let fields: Vec<&str> = expr.split_whitespace().collect();
+            if fields.len() != 5 {
+                return Err(format!(
+                    "Cron expression must have 5 fields (minute hour day month weekday): {}",
+                    expr
+                ));
+            }
+            let mut days_of_week = parse_field(fields[4], 0, 7, "weekday")?;
+            // 7 与 0 都表示周日
+            if days_of_week & (1 << 7) != 0 {
+                days_of_week = (days_of_week | 1) & !(1 << 7);
+            }
+            Ok(CronSchedule {
+                minutes: parse_field(fields[0], 0, 59, "minute")?,
+                hours: parse_field(fields[1], 0, 23, "hour")?,
+                days_of_month: parse_field(fields[2], 1, 31, "day")?,
+                months: parse_field(fields[3], 1, 12, "month")?,
+                days_of_week,
+                dom_restricted: fields[2] != "*",
+                dow_restricted: fields[4] != "*",
+            })
+        }
+
+        fn matches_day(&self, date: NaiveDate) -> bool {
+            let dom = self.days_of_month & (1 << date.day()) != 0;
+            let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+            // 与标准 cron 一致：日和周都有限制时满足其一即可
+            match (self.dom_restricted, self.dow_restricted) {
+                (true, true) => dom || dow,
+                (true, false) => dom,
+                (false, true) => dow,
+                (false, false) => true,
+            }
+        }
+
+        /// 严格晚于 `after` 的下一次匹配时间，五年内找不到时返回 None
+        pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+            let tz = after.timezone();
+            let start = after.naive_local().with_second(0)?.with_nanosecond(0)?
+                + Duration::minutes(1);
+            let limit = start + Duration::days(366 * 5);
+            let mut t = start;
+
+            while t < limit {
+                if self.months & (1 << t.month()) == 0 {
+                    let (y, m) = if t.month() == 12 {
+                        (t.year() + 1, 1)
+                    } else {
+                        (t.year(), t.month() + 1)
+                    };
+                    t = NaiveDate::from_ymd_opt(y, m, 1)?.and_hms_opt(0, 0, 0)?;
+                    continue;
+                }
+                if !self.matches_day(t.date()) {
+                    t = (t.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+                    continue;
+                }
+                if self.hours & (1 << t.hour()) == 0 {
+                    t = t.with_minute(0)? + Duration::hours(1);
+                    continue;
+                }
+                if self.minutes & (1 << t.minute()) == 0 {
+                    t += Duration::minutes(1);
+                    continue;
+                }
+                match tz.from_local_datetime(&t).earliest() {
+                    Some(dt) if dt > *after => return Some(dt),
+                    // 夏令时跳过的时间点，继续向后找
+                    _ => t += Duration::minutes(1),
+                }
+            }
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cron::CronSchedule;
+    use super::*;
+    use crate::test_support::temp_dir;
+    use chrono::Utc;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_daily_skips_missed_occurrences() {
+        let rule = Recurrence::Daily { interval: 2 };
+        let anchor = at(2024, 1, 1, 9, 0);
+        assert_eq!(rule.next_after(&anchor, &anchor), Some(at(2024, 1, 3, 9, 0)));
+        // 离线十天后只返回下一次
+        let after = at(2024, 1, 11, 10, 0);
+        assert_eq!(rule.next_after(&anchor, &after), Some(at(2024, 1, 13, 9, 0)));
+    }
+
+    #[test]
+    fn test_weekly_with_weekdays_and_interval() {
+        // 2024-01-01 是周一
+        let anchor = at(2024, 1, 1, 8, 30);
+        let rule = Recurrence::Weekly { interval: 1, weekdays: vec![0, 2] };
+        assert_eq!(rule.next_after(&anchor, &anchor), Some(at(2024, 1, 3, 8, 30)));
+
+        let biweekly = Recurrence::Weekly { interval: 2, weekdays: vec![] };
+        assert_eq!(biweekly.next_after(&anchor, &anchor), Some(at(2024, 1, 15, 8, 30)));
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let schedule = CronSchedule::parse("30 9 * * 1-5").unwrap();
+        // 2024-01-05 是周五
+        assert_eq!(
+            schedule.next_after(&at(2024, 1, 5, 10, 0)),
+            Some(at(2024, 1, 8, 9, 30))
+        );
+
+        let every_15 = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(every_15.next_after(&at(2024, 1, 1, 0, 15)), Some(at(2024, 1, 1, 0, 30)));
+
+        let leap = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(leap.next_after(&at(2024, 3, 1, 0, 0)), Some(at(2028, 2, 29, 0, 0)));
+    }
+
+    #[test]
+    fn test_cron_rejects_invalid_expressions() {
+        assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("@daily").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_recurrence_is_not_fired_as_one_shot() {
+        let dir = temp_dir("memo-reminders-invalid");
+        let conn = db::get_connection(&dir).unwrap();
+        conn.execute(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES ('m', 't', '', 0, 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO memo_reminders (memo_id, remind_at, recurrence, next_fire_at)
+             VALUES ('m', 100, '{\"kind\":\"hourly\"}', 100)",
+            [],
+        )
+        .unwrap();
+
+        assert!(take_due_reminders(200, &dir).unwrap().is_empty());
+        let reminder = get_memo_reminder("m".into(), &dir).unwrap().unwrap();
+        assert!(reminder.recurrence.is_none());
+        assert!(reminder.recurrence_error.is_some());
+        assert_eq!(reminder.next_fire_at, None);
+        // 原始规则保留，写回状态的操作报错
+        let raw: String = conn
+            .query_row("SELECT recurrence FROM memo_reminders WHERE memo_id = 'm'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(raw, r#"{"kind":"hourly"}"#);
+        assert!(snooze_memo_reminder("m".into(), 5, &dir).is_err());
+        assert!(complete_memo_reminder("m".into(), &dir).is_err());
+
+        // 重新设置后恢复正常
+        let reminder = set_memo_reminder("m".into(), 300, None, None, &dir).unwrap();
+        assert!(reminder.recurrence_error.is_none());
+        assert_eq!(take_due_reminders(300, &dir).unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,585 @@
+//! 备忘录修订历史
+//!
+//! 每次新增/更新备忘录时追加一条快照，删除备忘录后快照仍然保留，
+//! 因此误删或误改的内容都可以从历史中恢复。快照同时记下标签、置顶、笔记本等组织属性，
+//! 恢复已删除的备忘录时一并还原。
+
+use crate::db;
+use crate::settings::MemoRevisionRetention;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 超过该规模（行数乘积）时不再计算 LCS，直接视为整体替换
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoRevision {
+    pub id: i64,
+    pub memo_id: String,
+    pub title: String,
+    pub content: String,
+    pub created_at: u64,
+    /// 相对上一版本增加和删除的行数之和
+    pub diff_size: u32,
+    /// 记录快照时备忘录的组织属性；旧的修订没有
+    #[serde(default)]
+    pub meta: Option<MemoRevisionMeta>,
+}
+
+/// 快照中的组织属性
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoRevisionMeta {
+    pub tags: Vec<String>,
+    pub pinned: bool,
+    pub archived: bool,
+    pub notebook_id: Option<String>,
+    pub color: Option<String>,
+}
+
+/// 不含正文的修订摘要，用于列表展示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoRevisionSummary {
+    pub id: i64,
+    pub memo_id: String,
+    pub title: String,
+    pub created_at: u64,
+    pub diff_size: u32,
+    pub content_length: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+    /// 在旧版本中的行号（从 1 开始）
+    pub old_line: Option<usize>,
+    /// 在新版本中的行号（从 1 开始）
+    pub new_line: Option<usize>,
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn row_to_revision(row: &rusqlite::Row) -> rusqlite::Result<MemoRevision> {
+    Ok(MemoRevision {
+        id: row.get(0)?,
+        memo_id: row.get(1)?,
+        title: row.get(2)?,
+        content: row.get(3)?,
+        created_at: row.get::<_, i64>(4)? as u64,
+        diff_size: row.get::<_, i64>(5)? as u32,
+        meta: row
+            .get::<_, Option<String>>(6)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+    })
+}
+
+fn row_to_summary(row: &rusqlite::Row) -> rusqlite::Result<MemoRevisionSummary> {
+    Ok(MemoRevisionSummary {
+        id: row.get(0)?,
+        memo_id: row.get(1)?,
+        title: row.get(2)?,
+        created_at: row.get::<_, i64>(3)? as u64,
+        diff_size: row.get::<_, i64>(4)? as u32,
+        content_length: row.get::<_, i64>(5)? as u32,
+    })
+}
+
+fn latest_revision(
+    conn: &rusqlite::Connection,
+    memo_id: &str,
+) -> Result<Option<MemoRevision>, String> {
+    conn.query_row(
+        "SELECT id, memo_id, title, content, created_at, diff_size, meta
+         FROM memo_revisions WHERE memo_id = ?1
+         ORDER BY id DESC LIMIT 1",
+        params![memo_id],
+        row_to_revision,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load latest memo revision: {}", e))
+}
+
+/// 备忘录当前的组织属性；备忘录不存在时返回 None
+fn current_meta(
+    conn: &rusqlite::Connection,
+    memo_id: &str,
+) -> Result<Option<MemoRevisionMeta>, String> {
+    let meta = conn
+        .query_row(
+            "SELECT pinned, archived, notebook_id, color FROM memos WHERE id = ?1",
+            params![memo_id],
+            |row| {
+                Ok(MemoRevisionMeta {
+                    tags: Vec::new(),
+                    pinned: row.get::<_, i64>(0)? != 0,
+                    archived: row.get::<_, i64>(1)? != 0,
+                    notebook_id: row.get(2)?,
+                    color: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load memo: {}", e))?;
+    let Some(mut meta) = meta else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.name FROM memo_tag_links l JOIN memo_tags t ON t.id = l.tag_id
+             WHERE l.memo_id = ?1 ORDER BY t.name COLLATE NOCASE ASC",
+        )
+        .map_err(|e| format!("Failed to prepare memo tags query: {}", e))?;
+    let rows = stmt
+        .query_map(params![memo_id], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to iterate memo tags: {}", e))?;
+    for row in rows {
+        meta.tags
+            .push(row.map_err(|e| format!("Failed to read memo tag row: {}", e))?);
+    }
+    Ok(Some(meta))
+}
+
+fn meta_json(meta: &Option<MemoRevisionMeta>) -> Option<String> {
+    meta.as_ref().and_then(|m| serde_json::to_string(m).ok())
+}
+
+/// 追加一条修订记录；内容与上一版本完全相同时不追加，只刷新其中的组织属性
+pub fn record_revision(
+    conn: &rusqlite::Connection,
+    memo_id: &str,
+    title: &str,
+    content: &str,
+    retention: &MemoRevisionRetention,
+) -> Result<(), String> {
+    let meta = current_meta(conn, memo_id)?;
+    let previous = latest_revision(conn, memo_id)?;
+    if let Some(ref prev) = previous {
+        if prev.title == title && prev.content == content {
+            if meta.is_some() && prev.meta != meta {
+                conn.execute(
+                    "UPDATE memo_revisions SET meta = ?1 WHERE id = ?2",
+                    params![meta_json(&meta), prev.id],
+                )
+                .map_err(|e| format!("Failed to update memo revision: {}", e))?;
+            }
+            return Ok(());
+        }
+    }
+
+    let diff_size = match previous {
+        Some(prev) => count_changed_lines(&diff_lines(&prev.content, content)),
+        None => content.lines().count(),
+    };
+
+    conn.execute(
+        "INSERT INTO memo_revisions (memo_id, title, content, created_at, diff_size, meta)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            memo_id,
+            title,
+            content,
+            now_ts() as i64,
+            diff_size as i64,
+            meta_json(&meta)
+        ],
+    )
+    .map_err(|e| format!("Failed to insert memo revision: {}", e))?;
+
+    prune_revisions(conn, memo_id, retention)
+}
+
+/// 按保留策略清理旧的修订，始终保留最新的一条；
+/// 已删除备忘录的修订同样按条数清理，超过保存期限后全部删除
+pub fn prune_revisions(
+    conn: &rusqlite::Connection,
+    memo_id: &str,
+    retention: &MemoRevisionRetention,
+) -> Result<(), String> {
+    if retention.max_revisions_per_memo > 0 {
+        conn.execute(
+            "DELETE FROM memo_revisions
+             WHERE memo_id = ?1 AND id NOT IN (
+                SELECT id FROM memo_revisions WHERE memo_id = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            params![memo_id, retention.max_revisions_per_memo as i64],
+        )
+        .map_err(|e| format!("Failed to prune memo revisions: {}", e))?;
+    }
+
+    if retention.max_age_days > 0 {
+        let cutoff = now_ts().saturating_sub(retention.max_age_days as u64 * 86400);
+        conn.execute(
+            "DELETE FROM memo_revisions
+             WHERE memo_id = ?1 AND created_at < ?2
+               AND id != (SELECT MAX(id) FROM memo_revisions WHERE memo_id = ?1)",
+            params![memo_id, cutoff as i64],
+        )
+        .map_err(|e| format!("Failed to prune memo revisions: {}", e))?;
+    }
+
+    prune_deleted_revisions(conn, retention)
+}
+
+/// 清理已删除备忘录的修订，它们不会再有新的修订来触发按备忘录清理
+fn prune_deleted_revisions(
+    conn: &rusqlite::Connection,
+    retention: &MemoRevisionRetention,
+) -> Result<(), String> {
+    if retention.max_revisions_per_memo > 0 {
+        conn.execute(
+            "DELETE FROM memo_revisions
+             WHERE memo_id NOT IN (SELECT id FROM memos)
+               AND (SELECT COUNT(*) FROM memo_revisions newer
+                    WHERE newer.memo_id = memo_revisions.memo_id
+                      AND newer.id > memo_revisions.id) >= ?1",
+            params![retention.max_revisions_per_memo as i64],
+        )
+        .map_err(|e| format!("Failed to prune deleted memo revisions: {}", e))?;
+    }
+
+    if retention.max_age_days > 0 {
+        let cutoff = now_ts().saturating_sub(retention.max_age_days as u64 * 86400);
+        conn.execute(
+            "DELETE FROM memo_revisions
+             WHERE memo_id NOT IN (SELECT id FROM memos) AND created_at < ?1",
+            params![cutoff as i64],
+        )
+        .map_err(|e| format!("Failed to prune deleted memo revisions: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// 获取备忘录的修订列表（新的在前）
+pub fn get_memo_revisions(
+    memo_id: &str,
+    app_data_dir: &Path,
+) -> Result<Vec<MemoRevisionSummary>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, memo_id, title, created_at, diff_size, length(content)
+             FROM memo_revisions WHERE memo_id = ?1
+             ORDER BY id DESC",
+        )
+        .map_err(|e| format!("Failed to prepare memo revisions query: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![memo_id], row_to_summary)
+        .map_err(|e| format!("Failed to iterate memo revisions: {}", e))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read memo revision row: {}", e))?);
+    }
+    Ok(items)
+}
+
+pub fn get_memo_revision(id: i64, app_data_dir: &Path) -> Result<MemoRevision, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    load_revision(&conn, id)
+}
+
+pub fn load_revision(conn: &rusqlite::Connection, id: i64) -> Result<MemoRevision, String> {
+    conn.query_row(
+        "SELECT id, memo_id, title, content, created_at, diff_size, meta
+         FROM memo_revisions WHERE id = ?1",
+        params![id],
+        row_to_revision,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load memo revision: {}", e))?
+    .ok_or_else(|| format!("Memo revision {} not found", id))
+}
+
+/// 比较两个修订；`to_id` 为空时与备忘录当前内容比较
+pub fn diff_memo_revisions(
+    from_id: i64,
+    to_id: Option<i64>,
+    app_data_dir: &Path,
+) -> Result<Vec<DiffLine>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let from = load_revision(&conn, from_id)?;
+
+    let to_content = match to_id {
+        Some(to_id) => load_revision(&conn, to_id)?.content,
+        None => conn
+            .query_row(
+                "SELECT content FROM memos WHERE id = ?1",
+                params![from.memo_id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to load memo: {}", e))?
+            .unwrap_or_default(),
+    };
+
+    Ok(diff_lines(&from.content, &to_content))
+}
+
+/// 列出已被删除但仍有修订记录的备忘录（每个备忘录取最新一条）
+pub fn list_deleted_memos(app_data_dir: &Path) -> Result<Vec<MemoRevisionSummary>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.id, r.memo_id, r.title, r.created_at, r.diff_size, length(r.content)
+             FROM memo_revisions r
+             WHERE r.id IN (SELECT MAX(id) FROM memo_revisions GROUP BY memo_id)
+               AND r.memo_id NOT IN (SELECT id FROM memos)
+             ORDER BY r.created_at DESC",
+        )
+        .map_err(|e| format!("Failed to prepare deleted memos query: {}", e))?;
+
+    let rows = stmt
+        .query_map([], row_to_summary)
+        .map_err(|e| format!("Failed to iterate deleted memos: {}", e))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read memo revision row: {}", e))?);
+    }
+    Ok(items)
+}
+
+fn count_changed_lines(diff: &[DiffLine]) -> usize {
+    diff.iter().filter(|l| l.kind != DiffKind::Equal).count()
+}
+
+/// 行级差异（基于最长公共子序列）
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // 先去掉公共前后缀，缩小 LCS 的计算规模
+    let prefix = old_lines
+        .iter()
+        .zip(new_lines.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut result = Vec::with_capacity(old_lines.len().max(new_lines.len()));
+    for (i, line) in old_lines[..prefix].iter().enumerate() {
+        result.push(DiffLine {
+            kind: DiffKind::Equal,
+            text: line.to_string(),
+            old_line: Some(i + 1),
+            new_line: Some(i + 1),
+        });
+    }
+
+    let push_delete = |result: &mut Vec<DiffLine>, i: usize| {
+        result.push(DiffLine {
+            kind: DiffKind::Delete,
+            text: old_mid[i].to_string(),
+            old_line: Some(prefix + i + 1),
+            new_line: None,
+        });
+    };
+    let push_insert = |result: &mut Vec<DiffLine>, j: usize| {
+        result.push(DiffLine {
+            kind: DiffKind::Insert,
+            text: new_mid[j].to_string(),
+            old_line: None,
+            new_line: Some(prefix + j + 1),
+        });
+    };
+
+    let (n, m) = (old_mid.len(), new_mid.len());
+    if n.saturating_mul(m) > MAX_DIFF_CELLS {
+        (0..n).for_each(|i| push_delete(&mut result, i));
+        (0..m).for_each(|j| push_insert(&mut result, j));
+    } else {
+        // lcs[i][j] = old_mid[i..] 与 new_mid[j..] 的 LCS 长度
+        let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+        let idx = |i: usize, j: usize| i * (m + 1) + j;
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[idx(i, j)] = if old_mid[i] == new_mid[j] {
+                    lcs[idx(i + 1, j + 1)] + 1
+                } else {
+                    lcs[idx(i + 1, j)].max(lcs[idx(i, j + 1)])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                result.push(DiffLine {
+                    kind: DiffKind::Equal,
+                    text: old_mid[i].to_string(),
+                    old_line: Some(prefix + i + 1),
+                    new_line: Some(prefix + j + 1),
+                });
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lcs[idx(i, j + 1)] >= lcs[idx(i + 1, j)]) {
+                push_insert(&mut result, j);
+                j += 1;
+            } else {
+                push_delete(&mut result, i);
+                i += 1;
+            }
+        }
+    }
+
+    let old_offset = old_lines.len() - suffix;
+    let new_offset = new_lines.len() - suffix;
+    for k in 0..suffix {
+        result.push(DiffLine {
+            kind: DiffKind::Equal,
+            text: old_lines[old_offset + k].to_string(),
+            old_line: Some(old_offset + k + 1),
+            new_line: Some(new_offset + k + 1),
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn kinds(diff: &[DiffLine]) -> Vec<(DiffKind, &str)> {
+        diff.iter().map(|l| (l.kind, l.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_diff_lines_identical() {
+        let diff = diff_lines("a\nb", "a\nb");
+        assert_eq!(count_changed_lines(&diff), 0);
+        assert_eq!(diff.len(), 2);
+    }
+
+    #[test]
+    fn test_diff_lines_insert_and_delete() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nx\nc\nd\ne");
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                (DiffKind::Equal, "a"),
+                (DiffKind::Insert, "x"),
+                (DiffKind::Delete, "b"),
+                (DiffKind::Equal, "c"),
+                (DiffKind::Equal, "d"),
+                (DiffKind::Insert, "e"),
+            ]
+        );
+        assert_eq!(count_changed_lines(&diff), 3);
+        assert_eq!(diff[5].new_line, Some(5));
+        assert_eq!(diff[2].old_line, Some(2));
+    }
+
+    #[test]
+    fn test_diff_lines_from_empty() {
+        let diff = diff_lines("", "one\ntwo");
+        assert_eq!(
+            kinds(&diff),
+            vec![(DiffKind::Insert, "one"), (DiffKind::Insert, "two")]
+        );
+    }
+
+    fn revision_count(conn: &rusqlite::Connection, memo_id: &str) -> i64 {
+        conn.query_row(
+            "SELECT COUNT(*) FROM memo_revisions WHERE memo_id = ?1",
+            params![memo_id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_record_revision_refreshes_meta() {
+        let dir = temp_dir("memo-revisions-meta");
+        let conn = db::get_connection(&dir).unwrap();
+        let retention = MemoRevisionRetention::default();
+        conn.execute(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES ('m', 't', 'c', 0, 0)",
+            [],
+        )
+        .unwrap();
+        record_revision(&conn, "m", "t", "c", &retention).unwrap();
+        assert_eq!(latest_revision(&conn, "m").unwrap().unwrap().meta, Some(MemoRevisionMeta::default()));
+
+        // 只改组织属性时不新增修订，但快照中的属性要跟上
+        conn.execute("UPDATE memos SET pinned = 1, color = 'red' WHERE id = 'm'", []).unwrap();
+        crate::memos::write_memo_tags(&conn, "m", &["work".to_string()]).unwrap();
+        record_revision(&conn, "m", "t", "c", &retention).unwrap();
+        assert_eq!(revision_count(&conn, "m"), 1);
+        let meta = latest_revision(&conn, "m").unwrap().unwrap().meta.unwrap();
+        assert!(meta.pinned);
+        assert_eq!(meta.color.as_deref(), Some("red"));
+        assert_eq!(meta.tags, vec!["work"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_prune_includes_deleted_memos() {
+        let dir = temp_dir("memo-revisions-prune");
+        let conn = db::get_connection(&dir).unwrap();
+        let now = now_ts() as i64;
+        for i in 0..4 {
+            conn.execute(
+                "INSERT INTO memo_revisions (memo_id, title, content, created_at) VALUES ('gone', 't', ?1, ?2)",
+                params![i.to_string(), now],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO memo_revisions (memo_id, title, content, created_at) VALUES ('expired', 't', 'c', 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES ('live', 't', 'c', 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        let retention = MemoRevisionRetention { max_revisions_per_memo: 2, max_age_days: 30 };
+        record_revision(&conn, "live", "t", "c", &retention).unwrap();
+
+        // 已删除的备忘录按条数保留最新的修订，超过期限的全部清除
+        let kept: Vec<String> = conn
+            .prepare("SELECT content FROM memo_revisions WHERE memo_id = 'gone' ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(kept, vec!["2", "3"]);
+        assert_eq!(revision_count(&conn, "expired"), 0);
+        assert_eq!(revision_count(&conn, "live"), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
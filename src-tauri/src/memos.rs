@@ -1,6 +1,11 @@
 use crate::db;
-use rusqlite::{params, OptionalExtension};
+use crate::memo_reminders;
+use crate::memo_revisions;
+use crate::settings;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -12,8 +17,70 @@ pub struct MemoItem {
     pub content: String,
     pub created_at: u64,
     pub updated_at: u64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub notebook_id: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>, // 颜色标签，如 "red"、"#ffcc00"
+    #[serde(default)]
+    pub is_todo: bool,
+    #[serde(default)]
+    pub completed_at: Option<u64>,
+    #[serde(default)]
+    pub reminder: Option<memo_reminders::MemoReminder>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoNotebook {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub color: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub memo_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoTag {
+    pub name: String,
+    pub color: Option<String>,
+    pub memo_count: u32,
+}
+
+/// 备忘录列表过滤条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoFilter {
+    /// 关键字，匹配标题和内容
+    pub query: Option<String>,
+    /// 必须同时包含的标签
+    pub tags: Vec<String>,
+    /// 笔记本 id 或名称
+    pub notebook: Option<String>,
+    pub pinned_only: bool,
+    pub include_archived: bool,
+    pub archived_only: bool,
+    /// 置顶的备忘录排在最前
+    pub pinned_first: bool,
+    /// 按更新时间过滤（Unix 秒，闭区间）
+    pub updated_from: Option<u64>,
+    pub updated_to: Option<u64>,
+    pub color: Option<String>,
+    /// 只列出待办
+    pub todo_only: bool,
+    /// 隐藏已完成的待办
+    pub hide_completed: bool,
+}
+
+const MEMO_COLUMNS: &str =
+    "id, title, content, created_at, updated_at, pinned, archived, notebook_id, color, is_todo, completed_at";
+
 fn now_ts() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -25,33 +92,142 @@ fn memos_file_path(app_data_dir: &PathBuf) -> PathBuf {
     app_data_dir.join("memos.json")
 }
 
-pub fn load_memos(app_data_dir: &PathBuf) -> Result<(), String> {
-    // No-op for compatibility; data now lives in SQLite.
-    let _ = app_data_dir;
+fn row_to_memo(row: &rusqlite::Row) -> rusqlite::Result<MemoItem> {
+    Ok(MemoItem {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        content: row.get(2)?,
+        created_at: row.get::<_, i64>(3)? as u64,
+        updated_at: row.get::<_, i64>(4)? as u64,
+        tags: Vec::new(),
+        pinned: row.get::<_, i64>(5)? != 0,
+        archived: row.get::<_, i64>(6)? != 0,
+        notebook_id: row.get(7)?,
+        color: row.get(8)?,
+        is_todo: row.get::<_, i64>(9)? != 0,
+        completed_at: row.get::<_, Option<i64>>(10)?.map(|v| v as u64),
+        reminder: None,
+    })
+}
+
+/// 为备忘录填充标签
+fn attach_tags(conn: &rusqlite::Connection, items: &mut [MemoItem]) -> Result<(), String> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT l.memo_id, t.name
+             FROM memo_tag_links l JOIN memo_tags t ON t.id = l.tag_id
+             ORDER BY t.name COLLATE NOCASE ASC",
+        )
+        .map_err(|e| format!("Failed to prepare memo tags query: {}", e))?;
+
+    let mut tags_by_memo: HashMap<String, Vec<String>> = HashMap::new();
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| format!("Failed to iterate memo tags: {}", e))?;
+    for row in rows {
+        let (memo_id, tag) = row.map_err(|e| format!("Failed to read memo tag row: {}", e))?;
+        tags_by_memo.entry(memo_id).or_default().push(tag);
+    }
+
+    for item in items.iter_mut() {
+        item.tags = tags_by_memo.remove(&item.id).unwrap_or_default();
+    }
     Ok(())
 }
 
-fn save_memos(app_data_dir: &PathBuf, items: &[MemoItem]) -> Result<(), String> {
-    let mut conn = db::get_connection(app_data_dir)?;
-    maybe_migrate_from_json(&mut conn, app_data_dir)?;
+/// 为备忘录填充提醒
+fn attach_reminders(conn: &rusqlite::Connection, items: &mut [MemoItem]) -> Result<(), String> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    let mut reminders = memo_reminders::load_reminders(conn)?;
+    for item in items.iter_mut() {
+        item.reminder = reminders.remove(&item.id);
+    }
+    Ok(())
+}
 
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("Failed to start memos transaction: {}", e))?;
-    tx.execute("DELETE FROM memos", [])
-        .map_err(|e| format!("Failed to clear memos table: {}", e))?;
+pub(crate) fn load_memo(conn: &rusqlite::Connection, id: &str) -> Result<Option<MemoItem>, String> {
+    let memo = conn
+        .query_row(
+            &format!("SELECT {} FROM memos WHERE id = ?1", MEMO_COLUMNS),
+            params![id],
+            row_to_memo,
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load memo: {}", e))?;
 
-    for m in items {
-        tx.execute(
-            "INSERT INTO memos (id, title, content, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![m.id, m.title, m.content, m.created_at as i64, m.updated_at as i64],
+    match memo {
+        Some(memo) => {
+            let mut items = [memo];
+            attach_tags(conn, &mut items)?;
+            attach_reminders(conn, &mut items)?;
+            let [memo] = items;
+            Ok(Some(memo))
+        }
+        None => Ok(None),
+    }
+}
+
+/// 规范化标签：去除首尾空白和 `#` 前缀，忽略空标签并去重（不区分大小写）
+pub(crate) fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').trim().to_string();
+        if tag.is_empty() {
+            continue;
+        }
+        if !result.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            result.push(tag);
+        }
+    }
+    result
+}
+
+pub(crate) fn write_memo_tags(
+    conn: &rusqlite::Connection,
+    memo_id: &str,
+    tags: &[String],
+) -> Result<(), String> {
+    conn.execute("DELETE FROM memo_tag_links WHERE memo_id = ?1", params![memo_id])
+        .map_err(|e| format!("Failed to clear memo tags: {}", e))?;
+
+    for tag in tags {
+        conn.execute(
+            "INSERT INTO memo_tags (name) VALUES (?1) ON CONFLICT(name) DO NOTHING",
+            params![tag],
+        )
+        .map_err(|e| format!("Failed to insert memo tag {}: {}", tag, e))?;
+        conn.execute(
+            "INSERT OR IGNORE INTO memo_tag_links (memo_id, tag_id)
+             SELECT ?1, id FROM memo_tags WHERE name = ?2",
+            params![memo_id, tag],
         )
-        .map_err(|e| format!("Failed to insert memo {}: {}", m.id, e))?;
+        .map_err(|e| format!("Failed to link memo tag {}: {}", tag, e))?;
     }
 
-    tx.commit()
-        .map_err(|e| format!("Failed to commit memos: {}", e))?;
+    // 清理不再被引用的标签
+    conn.execute(
+        "DELETE FROM memo_tags WHERE id NOT IN (SELECT DISTINCT tag_id FROM memo_tag_links)",
+        [],
+    )
+    .map_err(|e| format!("Failed to clean up memo tags: {}", e))?;
+    Ok(())
+}
+
+pub(crate) fn revision_retention(app_data_dir: &PathBuf) -> settings::MemoRevisionRetention {
+    settings::load_settings(app_data_dir)
+        .map(|s| s.memo_revision_retention)
+        .unwrap_or_default()
+}
+
+pub fn load_memos(app_data_dir: &PathBuf) -> Result<(), String> {
+    // No-op for compatibility; data now lives in SQLite.
+    let _ = app_data_dir;
     Ok(())
 }
 
@@ -60,25 +236,22 @@ pub fn get_all_memos(app_data_dir: &PathBuf) -> Result<Vec<MemoItem>, String> {
     maybe_migrate_from_json(&mut conn, app_data_dir)?;
 
     let mut stmt = conn
-        .prepare("SELECT id, title, content, created_at, updated_at FROM memos ORDER BY updated_at DESC")
+        .prepare(&format!(
+            "SELECT {} FROM memos ORDER BY updated_at DESC",
+            MEMO_COLUMNS
+        ))
         .map_err(|e| format!("Failed to prepare memos query: {}", e))?;
 
     let rows = stmt
-        .query_map([], |row| {
-            Ok(MemoItem {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                content: row.get(2)?,
-                created_at: row.get::<_, i64>(3)? as u64,
-                updated_at: row.get::<_, i64>(4)? as u64,
-            })
-        })
+        .query_map([], row_to_memo)
         .map_err(|e| format!("Failed to iterate memos: {}", e))?;
 
     let mut items = Vec::new();
     for row in rows {
         items.push(row.map_err(|e| format!("Failed to read memo row: {}", e))?);
     }
+    attach_tags(&conn, &mut items)?;
+    attach_reminders(&conn, &mut items)?;
     Ok(items)
 }
 
@@ -96,6 +269,14 @@ pub fn add_memo(
         content,
         created_at: now,
         updated_at: now,
+        tags: Vec::new(),
+        pinned: false,
+        archived: false,
+        notebook_id: None,
+        color: None,
+        is_todo: false,
+        completed_at: None,
+        reminder: None,
     };
 
     let mut conn = db::get_connection(app_data_dir)?;
@@ -106,6 +287,13 @@ pub fn add_memo(
         params![item.id, item.title, item.content, item.created_at as i64, item.updated_at as i64],
     )
     .map_err(|e| format!("Failed to insert memo: {}", e))?;
+    memo_revisions::record_revision(
+        &conn,
+        &item.id,
+        &item.title,
+        &item.content,
+        &revision_retention(app_data_dir),
+    )?;
 
     Ok(item)
 }
@@ -119,24 +307,16 @@ pub fn update_memo(
     let mut conn = db::get_connection(app_data_dir)?;
     maybe_migrate_from_json(&mut conn, app_data_dir)?;
 
-    let existing: Option<MemoItem> = conn
-        .query_row(
-            "SELECT id, title, content, created_at, updated_at FROM memos WHERE id = ?1",
-            params![id],
-            |row| {
-                Ok(MemoItem {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    content: row.get(2)?,
-                    created_at: row.get::<_, i64>(3)? as u64,
-                    updated_at: row.get::<_, i64>(4)? as u64,
-                })
-            },
-        )
-        .optional()
-        .map_err(|e| format!("Failed to load memo: {}", e))?;
+    let existing = load_memo(&conn, &id)?;
+    let retention = revision_retention(app_data_dir);
 
     let mut memo = existing.ok_or_else(|| format!("Memo {} not found", id))?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start memo transaction: {}", e))?;
+    // 旧数据可能还没有任何修订，先保存修改前的快照（已是最新快照时会被跳过）
+    memo_revisions::record_revision(&tx, &memo.id, &memo.title, &memo.content, &retention)?;
+
     if let Some(t) = title {
         memo.title = t;
     }
@@ -145,11 +325,200 @@ pub fn update_memo(
     }
     memo.updated_at = now_ts();
 
-    conn.execute(
+    tx.execute(
         "UPDATE memos SET title = ?1, content = ?2, updated_at = ?3 WHERE id = ?4",
         params![memo.title, memo.content, memo.updated_at as i64, memo.id],
     )
     .map_err(|e| format!("Failed to update memo: {}", e))?;
+    memo_revisions::record_revision(&tx, &memo.id, &memo.title, &memo.content, &retention)?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit memo update: {}", e))?;
+
+    Ok(memo)
+}
+
+/// 将备忘录恢复到指定修订；备忘录已被删除时会重新创建
+///
+/// 修订中记录了标签、置顶、归档、笔记本和颜色时一并还原；
+/// 笔记本已不存在的，恢复到未分类
+pub fn restore_memo_revision(revision_id: i64, app_data_dir: &PathBuf) -> Result<MemoItem, String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    maybe_migrate_from_json(&mut conn, app_data_dir)?;
+    let retention = revision_retention(app_data_dir);
+
+    let revision = memo_revisions::load_revision(&conn, revision_id)?;
+    let now = now_ts();
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start memo transaction: {}", e))?;
+    let created_at: i64 = tx
+        .query_row(
+            "SELECT MIN(created_at) FROM memo_revisions WHERE memo_id = ?1",
+            params![revision.memo_id],
+            |row| row.get::<_, Option<i64>>(0),
+        )
+        .map_err(|e| format!("Failed to load memo revisions: {}", e))?
+        .unwrap_or(now as i64);
+    tx.execute(
+        "INSERT INTO memos (id, title, content, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(id) DO UPDATE SET
+            title = excluded.title,
+            content = excluded.content,
+            updated_at = excluded.updated_at",
+        params![revision.memo_id, revision.title, revision.content, created_at, now as i64],
+    )
+    .map_err(|e| format!("Failed to restore memo: {}", e))?;
+    if let Some(ref meta) = revision.meta {
+        let notebook_id = match meta.notebook_id {
+            Some(ref notebook_id) => tx
+                .query_row(
+                    "SELECT id FROM memo_notebooks WHERE id = ?1",
+                    params![notebook_id],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to check notebook: {}", e))?,
+            None => None,
+        };
+        tx.execute(
+            "UPDATE memos SET pinned = ?1, archived = ?2, notebook_id = ?3, color = ?4 WHERE id = ?5",
+            params![
+                if meta.pinned { 1 } else { 0 },
+                if meta.archived { 1 } else { 0 },
+                notebook_id,
+                meta.color,
+                revision.memo_id
+            ],
+        )
+        .map_err(|e| format!("Failed to restore memo: {}", e))?;
+        write_memo_tags(&tx, &revision.memo_id, &normalize_tags(meta.tags.clone()))?;
+    }
+    memo_revisions::record_revision(
+        &tx,
+        &revision.memo_id,
+        &revision.title,
+        &revision.content,
+        &retention,
+    )?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit memo restore: {}", e))?;
+
+    load_memo(&conn, &revision.memo_id)?
+        .ok_or_else(|| format!("Memo {} not found", revision.memo_id))
+}
+
+/// 更新备忘录的组织属性（置顶、归档、笔记本、颜色、标签）
+///
+/// `notebook_id` / `color` 传入空字符串表示清除；同时刷新 `updated_at`
+pub fn update_memo_meta(
+    id: String,
+    pinned: Option<bool>,
+    archived: Option<bool>,
+    notebook_id: Option<String>,
+    color: Option<String>,
+    tags: Option<Vec<String>>,
+    app_data_dir: &PathBuf,
+) -> Result<MemoItem, String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    maybe_migrate_from_json(&mut conn, app_data_dir)?;
+    let retention = revision_retention(app_data_dir);
+
+    let mut memo = load_memo(&conn, &id)?.ok_or_else(|| format!("Memo {} not found", id))?;
+    if let Some(p) = pinned {
+        memo.pinned = p;
+    }
+    if let Some(a) = archived {
+        memo.archived = a;
+    }
+    if let Some(nb) = notebook_id {
+        memo.notebook_id = if nb.trim().is_empty() { None } else { Some(nb) };
+    }
+    if let Some(c) = color {
+        memo.color = if c.trim().is_empty() { None } else { Some(c) };
+    }
+
+    if let Some(ref notebook_id) = memo.notebook_id {
+        let exists: bool = conn
+            .query_row(
+                "SELECT 1 FROM memo_notebooks WHERE id = ?1",
+                params![notebook_id],
+                |_| Ok(true),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to check notebook: {}", e))?
+            .unwrap_or(false);
+        if !exists {
+            return Err(format!("Notebook {} not found", notebook_id));
+        }
+    }
+
+    memo.updated_at = now_ts();
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start memo transaction: {}", e))?;
+    tx.execute(
+        "UPDATE memos SET pinned = ?1, archived = ?2, notebook_id = ?3, color = ?4, updated_at = ?5 WHERE id = ?6",
+        params![
+            if memo.pinned { 1 } else { 0 },
+            if memo.archived { 1 } else { 0 },
+            memo.notebook_id,
+            memo.color,
+            memo.updated_at as i64,
+            memo.id
+        ],
+    )
+    .map_err(|e| format!("Failed to update memo: {}", e))?;
+    if let Some(tags) = tags {
+        memo.tags = normalize_tags(tags);
+        write_memo_tags(&tx, &memo.id, &memo.tags)?;
+    }
+    // 内容未变，只刷新最新修订中记录的组织属性
+    memo_revisions::record_revision(&tx, &memo.id, &memo.title, &memo.content, &retention)?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit memo update: {}", e))?;
+
+    Ok(memo)
+}
+
+/// 设置待办状态：`is_todo` 切换待办模式，`completed` 标记完成或重新打开
+pub fn set_memo_todo(
+    id: String,
+    is_todo: Option<bool>,
+    completed: Option<bool>,
+    app_data_dir: &PathBuf,
+) -> Result<MemoItem, String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    maybe_migrate_from_json(&mut conn, app_data_dir)?;
+
+    let mut memo = load_memo(&conn, &id)?.ok_or_else(|| format!("Memo {} not found", id))?;
+    if let Some(todo) = is_todo {
+        memo.is_todo = todo;
+        if !todo {
+            memo.completed_at = None;
+        }
+    }
+    if let Some(done) = completed {
+        if !memo.is_todo {
+            return Err("Memo is not a todo".to_string());
+        }
+        memo.completed_at = match (done, memo.completed_at) {
+            (true, Some(at)) => Some(at),
+            (true, None) => Some(now_ts()),
+            (false, _) => None,
+        };
+    }
+
+    conn.execute(
+        "UPDATE memos SET is_todo = ?1, completed_at = ?2 WHERE id = ?3",
+        params![
+            if memo.is_todo { 1 } else { 0 },
+            memo.completed_at.map(|v| v as i64),
+            memo.id
+        ],
+    )
+    .map_err(|e| format!("Failed to update memo: {}", e))?;
 
     Ok(memo)
 }
@@ -157,6 +526,15 @@ pub fn update_memo(
 pub fn delete_memo(id: String, app_data_dir: &PathBuf) -> Result<(), String> {
     let mut conn = db::get_connection(app_data_dir)?;
     maybe_migrate_from_json(&mut conn, app_data_dir)?;
+    let memo = load_memo(&conn, &id)?.ok_or_else(|| "Memo not found".to_string())?;
+    // 删除前保存最后一份快照，便于撤销
+    memo_revisions::record_revision(
+        &conn,
+        &memo.id,
+        &memo.title,
+        &memo.content,
+        &revision_retention(app_data_dir),
+    )?;
     let affected = conn
         .execute("DELETE FROM memos WHERE id = ?1", params![id])
         .map_err(|e| format!("Failed to delete memo: {}", e))?;
@@ -166,39 +544,389 @@ pub fn delete_memo(id: String, app_data_dir: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-pub fn search_memos(query: &str, app_data_dir: &PathBuf) -> Result<Vec<MemoItem>, String> {
+/// 按条件列出备忘录
+pub fn list_memos(filter: &MemoFilter, app_data_dir: &PathBuf) -> Result<Vec<MemoItem>, String> {
     let mut conn = db::get_connection(app_data_dir)?;
     maybe_migrate_from_json(&mut conn, app_data_dir)?;
 
-    let like = format!("%{}%", query.to_lowercase());
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(query) = filter.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        values.push(Value::Text(format!("%{}%", query.to_lowercase())));
+        conditions.push(format!(
+            "(lower(title) LIKE ?{n} OR lower(content) LIKE ?{n})",
+            n = values.len()
+        ));
+    }
+    for tag in normalize_tags(filter.tags.clone()) {
+        values.push(Value::Text(tag));
+        conditions.push(format!(
+            "id IN (SELECT l.memo_id FROM memo_tag_links l JOIN memo_tags t ON t.id = l.tag_id
+                    WHERE t.name = ?{} COLLATE NOCASE)",
+            values.len()
+        ));
+    }
+    if let Some(notebook) = filter.notebook.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        values.push(Value::Text(notebook.to_string()));
+        conditions.push(format!(
+            "notebook_id IN (SELECT id FROM memo_notebooks WHERE id = ?{n} OR lower(name) = lower(?{n}))",
+            n = values.len()
+        ));
+    }
+    if filter.pinned_only {
+        conditions.push("pinned = 1".to_string());
+    }
+    if filter.archived_only {
+        conditions.push("archived = 1".to_string());
+    } else if !filter.include_archived {
+        conditions.push("archived = 0".to_string());
+    }
+    if let Some(from) = filter.updated_from {
+        values.push(Value::Integer(from as i64));
+        conditions.push(format!("updated_at >= ?{}", values.len()));
+    }
+    if let Some(to) = filter.updated_to {
+        values.push(Value::Integer(to as i64));
+        conditions.push(format!("updated_at <= ?{}", values.len()));
+    }
+    if let Some(color) = filter.color.as_deref().filter(|c| !c.is_empty()) {
+        values.push(Value::Text(color.to_string()));
+        conditions.push(format!("color = ?{}", values.len()));
+    }
+    if filter.todo_only {
+        conditions.push("is_todo = 1".to_string());
+    }
+    if filter.hide_completed {
+        conditions.push("completed_at IS NULL".to_string());
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+    let order_clause = if filter.pinned_first {
+        "ORDER BY pinned DESC, updated_at DESC"
+    } else {
+        "ORDER BY updated_at DESC"
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM memos {} {}",
+            MEMO_COLUMNS, where_clause, order_clause
+        ))
+        .map_err(|e| format!("Failed to prepare memo list query: {}", e))?;
+
+    let rows = stmt
+        .query_map(params_from_iter(values), row_to_memo)
+        .map_err(|e| format!("Failed to iterate memo list: {}", e))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read memo row: {}", e))?);
+    }
+    attach_tags(&conn, &mut items)?;
+    attach_reminders(&conn, &mut items)?;
+    Ok(items)
+}
+
+/// 解析搜索语句中的 `tag:xxx`、`notebook:xxx` 过滤器，其余部分作为关键字
+pub fn parse_search_query(query: &str) -> MemoFilter {
+    let mut filter = MemoFilter {
+        include_archived: true,
+        ..Default::default()
+    };
+    let mut words = Vec::new();
+
+    for token in query.split_whitespace() {
+        if let Some(tag) = token.strip_prefix("tag:") {
+            if !tag.is_empty() {
+                filter.tags.push(tag.to_string());
+                continue;
+            }
+        }
+        if let Some(notebook) = token.strip_prefix("notebook:") {
+            if !notebook.is_empty() {
+                filter.notebook = Some(notebook.to_string());
+                continue;
+            }
+        }
+        words.push(token);
+    }
+
+    if !words.is_empty() {
+        filter.query = Some(words.join(" "));
+    }
+    filter
+}
+
+pub fn search_memos(query: &str, app_data_dir: &PathBuf) -> Result<Vec<MemoItem>, String> {
+    list_memos(&parse_search_query(query), app_data_dir)
+}
+
+// ===== Notebooks =====
+
+pub fn get_memo_notebooks(app_data_dir: &PathBuf) -> Result<Vec<MemoNotebook>, String> {
+    let conn = db::get_connection(app_data_dir)?;
     let mut stmt = conn
         .prepare(
-            "SELECT id, title, content, created_at, updated_at
-             FROM memos
-             WHERE lower(title) LIKE ?1 OR lower(content) LIKE ?1
-             ORDER BY updated_at DESC",
+            "SELECT n.id, n.name, n.parent_id, n.color, n.created_at, n.updated_at,
+                    (SELECT COUNT(*) FROM memos m WHERE m.notebook_id = n.id AND m.archived = 0)
+             FROM memo_notebooks n
+             ORDER BY n.name COLLATE NOCASE ASC",
         )
-        .map_err(|e| format!("Failed to prepare memo search: {}", e))?;
+        .map_err(|e| format!("Failed to prepare notebooks query: {}", e))?;
 
     let rows = stmt
-        .query_map(params![like], |row| {
-            Ok(MemoItem {
+        .query_map([], |row| {
+            Ok(MemoNotebook {
                 id: row.get(0)?,
-                title: row.get(1)?,
-                content: row.get(2)?,
-                created_at: row.get::<_, i64>(3)? as u64,
-                updated_at: row.get::<_, i64>(4)? as u64,
+                name: row.get(1)?,
+                parent_id: row.get(2)?,
+                color: row.get(3)?,
+                created_at: row.get::<_, i64>(4)? as u64,
+                updated_at: row.get::<_, i64>(5)? as u64,
+                memo_count: row.get::<_, i64>(6)? as u32,
             })
         })
-        .map_err(|e| format!("Failed to iterate memo search: {}", e))?;
+        .map_err(|e| format!("Failed to iterate notebooks: {}", e))?;
 
     let mut items = Vec::new();
     for row in rows {
-        items.push(row.map_err(|e| format!("Failed to read memo row: {}", e))?);
+        items.push(row.map_err(|e| format!("Failed to read notebook row: {}", e))?);
+    }
+    Ok(items)
+}
+
+pub fn add_memo_notebook(
+    name: String,
+    parent_id: Option<String>,
+    color: Option<String>,
+    app_data_dir: &PathBuf,
+) -> Result<MemoNotebook, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Notebook name cannot be empty".to_string());
+    }
+
+    let now = now_ts();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let notebook = MemoNotebook {
+        id: format!("notebook-{}", nanos),
+        name,
+        parent_id,
+        color,
+        created_at: now,
+        updated_at: now,
+        memo_count: 0,
+    };
+
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute(
+        "INSERT INTO memo_notebooks (id, name, parent_id, color, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            notebook.id,
+            notebook.name,
+            notebook.parent_id,
+            notebook.color,
+            notebook.created_at as i64,
+            notebook.updated_at as i64
+        ],
+    )
+    .map_err(|e| format!("Failed to insert notebook: {}", e))?;
+
+    Ok(notebook)
+}
+
+/// 更新笔记本，`parent_id` / `color` 传入空字符串表示清除
+pub fn update_memo_notebook(
+    id: String,
+    name: Option<String>,
+    parent_id: Option<String>,
+    color: Option<String>,
+    app_data_dir: &PathBuf,
+) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    if let Some(ref parent) = parent_id {
+        // 防止把笔记本移动到自身或其子笔记本下
+        let mut current = Some(parent.clone()).filter(|p| !p.is_empty());
+        while let Some(ancestor) = current {
+            if ancestor == id {
+                return Err("Cannot move a notebook into itself".to_string());
+            }
+            current = conn
+                .query_row(
+                    "SELECT parent_id FROM memo_notebooks WHERE id = ?1",
+                    params![ancestor],
+                    |row| row.get::<_, Option<String>>(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to load notebook: {}", e))?
+                .flatten();
+        }
+    }
+
+    let affected = conn
+        .execute(
+            "UPDATE memo_notebooks SET
+                name = COALESCE(?1, name),
+                parent_id = CASE WHEN ?2 IS NULL THEN parent_id WHEN ?2 = '' THEN NULL ELSE ?2 END,
+                color = CASE WHEN ?3 IS NULL THEN color WHEN ?3 = '' THEN NULL ELSE ?3 END,
+                updated_at = ?4
+             WHERE id = ?5",
+            params![
+                name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+                parent_id,
+                color,
+                now_ts() as i64,
+                id
+            ],
+        )
+        .map_err(|e| format!("Failed to update notebook: {}", e))?;
+    if affected == 0 {
+        return Err("Notebook not found".to_string());
+    }
+    Ok(())
+}
+
+/// 删除笔记本，其中的备忘录和子笔记本移动到上一级
+pub fn delete_memo_notebook(id: String, app_data_dir: &PathBuf) -> Result<(), String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start notebook transaction: {}", e))?;
+
+    let parent_id: Option<String> = tx
+        .query_row(
+            "SELECT parent_id FROM memo_notebooks WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load notebook: {}", e))?
+        .ok_or_else(|| "Notebook not found".to_string())?;
+
+    tx.execute(
+        "UPDATE memos SET notebook_id = ?1 WHERE notebook_id = ?2",
+        params![parent_id, id],
+    )
+    .map_err(|e| format!("Failed to move memos out of notebook: {}", e))?;
+    tx.execute(
+        "UPDATE memo_notebooks SET parent_id = ?1 WHERE parent_id = ?2",
+        params![parent_id, id],
+    )
+    .map_err(|e| format!("Failed to move child notebooks: {}", e))?;
+    tx.execute("DELETE FROM memo_notebooks WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete notebook: {}", e))?;
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit notebook deletion: {}", e))?;
+    Ok(())
+}
+
+// ===== Tags =====
+
+pub fn get_memo_tags(app_data_dir: &PathBuf) -> Result<Vec<MemoTag>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.name, t.color, COUNT(l.memo_id)
+             FROM memo_tags t LEFT JOIN memo_tag_links l ON l.tag_id = t.id
+             GROUP BY t.id
+             ORDER BY COUNT(l.memo_id) DESC, t.name COLLATE NOCASE ASC",
+        )
+        .map_err(|e| format!("Failed to prepare memo tags query: {}", e))?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(MemoTag {
+                name: row.get(0)?,
+                color: row.get(1)?,
+                memo_count: row.get::<_, i64>(2)? as u32,
+            })
+        })
+        .map_err(|e| format!("Failed to iterate memo tags: {}", e))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read memo tag row: {}", e))?);
     }
     Ok(items)
 }
 
+/// 重命名或设置标签颜色；重命名为已存在的标签时会合并
+pub fn update_memo_tag(
+    name: String,
+    new_name: Option<String>,
+    color: Option<String>,
+    app_data_dir: &PathBuf,
+) -> Result<(), String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start tag transaction: {}", e))?;
+
+    let tag_id: i64 = tx
+        .query_row(
+            "SELECT id FROM memo_tags WHERE name = ?1 COLLATE NOCASE",
+            params![name],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load tag: {}", e))?
+        .ok_or_else(|| format!("Tag {} not found", name))?;
+
+    if let Some(color) = color {
+        tx.execute(
+            "UPDATE memo_tags SET color = ?1 WHERE id = ?2",
+            params![if color.is_empty() { None } else { Some(color) }, tag_id],
+        )
+        .map_err(|e| format!("Failed to update tag color: {}", e))?;
+    }
+
+    if let Some(new_name) = new_name.and_then(|n| normalize_tags(vec![n]).into_iter().next()) {
+        let target_id: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM memo_tags WHERE name = ?1 COLLATE NOCASE AND id != ?2",
+                params![new_name, tag_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to check tag: {}", e))?;
+
+        match target_id {
+            Some(target_id) => {
+                tx.execute(
+                    "INSERT OR IGNORE INTO memo_tag_links (memo_id, tag_id)
+                     SELECT memo_id, ?1 FROM memo_tag_links WHERE tag_id = ?2",
+                    params![target_id, tag_id],
+                )
+                .map_err(|e| format!("Failed to merge tags: {}", e))?;
+                tx.execute("DELETE FROM memo_tags WHERE id = ?1", params![tag_id])
+                    .map_err(|e| format!("Failed to delete merged tag: {}", e))?;
+            }
+            None => {
+                tx.execute(
+                    "UPDATE memo_tags SET name = ?1 WHERE id = ?2",
+                    params![new_name, tag_id],
+                )
+                .map_err(|e| format!("Failed to rename tag: {}", e))?;
+            }
+        }
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit tag update: {}", e))?;
+    Ok(())
+}
+
 fn maybe_migrate_from_json(
     conn: &mut rusqlite::Connection,
     app_data_dir: &PathBuf,
@@ -237,4 +965,202 @@ fn maybe_migrate_from_json(
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    /// add_memo 的 id 精确到秒，测试中直接写入以便在同一秒内创建多条
+    fn insert_memo(dir: &PathBuf, id: &str, title: &str, updated_at: i64) {
+        let conn = db::get_connection(dir).unwrap();
+        conn.execute(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES (?1, ?2, '', 0, ?3)",
+            params![id, title, updated_at],
+        )
+        .unwrap();
+    }
+
+    fn ids(items: &[MemoItem]) -> Vec<&str> {
+        items.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = vec![
+            " #Work ".to_string(),
+            "work".to_string(),
+            "".to_string(),
+            "#".to_string(),
+            "# 待办".to_string(),
+        ];
+        assert_eq!(normalize_tags(tags), vec!["Work", "待办"]);
+    }
+
+    #[test]
+    fn test_parse_search_query() {
+        let filter = parse_search_query("tag:work  weekly notebook:日记 report tag:");
+        assert_eq!(filter.tags, vec!["work"]);
+        assert_eq!(filter.notebook.as_deref(), Some("日记"));
+        assert_eq!(filter.query.as_deref(), Some("weekly report tag:"));
+        assert!(filter.include_archived);
+        assert!(parse_search_query("  ").query.is_none());
+    }
+
+    #[test]
+    fn test_update_memo_meta() {
+        let dir = temp_dir("memos-meta");
+        insert_memo(&dir, "m", "title", 0);
+        let notebook = add_memo_notebook("工作".into(), None, None, &dir).unwrap();
+
+        let memo = update_memo_meta(
+            "m".into(),
+            Some(true),
+            Some(true),
+            Some(notebook.id.clone()),
+            Some("red".into()),
+            Some(vec!["#b".into(), " a ".into(), "B".into()]),
+            &dir,
+        )
+        .unwrap();
+        assert!(memo.pinned && memo.archived);
+        assert_eq!(memo.tags, vec!["b", "a"]);
+        assert!(memo.updated_at > 0);
+
+        let stored = get_all_memos(&dir).unwrap().remove(0);
+        assert!(stored.pinned && stored.archived);
+        assert_eq!(stored.notebook_id, Some(notebook.id.clone()));
+        assert_eq!(stored.color.as_deref(), Some("red"));
+        assert_eq!(stored.tags, vec!["a", "b"]);
+
+        // 未传入的字段保持不变，空字符串表示清除
+        let memo = update_memo_meta("m".into(), Some(false), None, Some("".into()), Some(" ".into()), None, &dir)
+            .unwrap();
+        assert!(!memo.pinned && memo.archived);
+        assert_eq!(memo.notebook_id, None);
+        assert_eq!(memo.color, None);
+        assert_eq!(memo.tags, vec!["a", "b"]);
+
+        // 不再被引用的标签会被清理
+        update_memo_meta("m".into(), None, None, None, None, Some(vec!["a".into()]), &dir).unwrap();
+        let tags: Vec<String> = get_memo_tags(&dir).unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(tags, vec!["a"]);
+
+        assert!(update_memo_meta("m".into(), None, None, Some("missing".into()), None, None, &dir).is_err());
+        assert!(update_memo_meta("missing".into(), Some(true), None, None, None, None, &dir).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_list_memos_filters() {
+        let dir = temp_dir("memos-filters");
+        insert_memo(&dir, "old-pinned", "Weekly report", 100);
+        insert_memo(&dir, "new", "notes", 300);
+        insert_memo(&dir, "archived", "weekly plan", 200);
+        let notebook = add_memo_notebook("Work".into(), None, None, &dir).unwrap();
+        update_memo_meta(
+            "old-pinned".into(),
+            Some(true),
+            None,
+            Some(notebook.id.clone()),
+            Some("red".into()),
+            Some(vec!["Work".into()]),
+            &dir,
+        )
+        .unwrap();
+        update_memo_meta("archived".into(), None, Some(true), None, None, Some(vec!["work".into()]), &dir)
+            .unwrap();
+        // update_memo_meta 会刷新 updated_at，恢复测试需要的顺序
+        let conn = db::get_connection(&dir).unwrap();
+        for (id, updated_at) in [("old-pinned", 100), ("new", 300), ("archived", 200)] {
+            conn.execute("UPDATE memos SET updated_at = ?1 WHERE id = ?2", params![updated_at, id])
+                .unwrap();
+        }
+
+        let all = list_memos(&MemoFilter { include_archived: true, ..Default::default() }, &dir).unwrap();
+        assert_eq!(ids(&all), vec!["new", "archived", "old-pinned"]);
+        let pinned_first = list_memos(&MemoFilter { pinned_first: true, ..Default::default() }, &dir).unwrap();
+        assert_eq!(ids(&pinned_first), vec!["old-pinned", "new"]);
+
+        let pinned = list_memos(&MemoFilter { pinned_only: true, ..Default::default() }, &dir).unwrap();
+        assert_eq!(ids(&pinned), vec!["old-pinned"]);
+        let archived = list_memos(&MemoFilter { archived_only: true, ..Default::default() }, &dir).unwrap();
+        assert_eq!(ids(&archived), vec!["archived"]);
+
+        // 标签和笔记本名称不区分大小写
+        let tagged = list_memos(
+            &MemoFilter { tags: vec!["#WORK".into()], include_archived: true, ..Default::default() },
+            &dir,
+        )
+        .unwrap();
+        assert_eq!(ids(&tagged), vec!["archived", "old-pinned"]);
+        assert_eq!(ids(&search_memos("notebook:work weekly", &dir).unwrap()), vec!["old-pinned"]);
+        assert_eq!(ids(&search_memos("WEEKLY", &dir).unwrap()), vec!["archived", "old-pinned"]);
+        let red = list_memos(&MemoFilter { color: Some("red".into()), ..Default::default() }, &dir).unwrap();
+        assert_eq!(ids(&red), vec!["old-pinned"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_update_memo_tag_rename_and_merge() {
+        let dir = temp_dir("memos-tags");
+        insert_memo(&dir, "a", "a", 0);
+        insert_memo(&dir, "b", "b", 0);
+        update_memo_meta("a".into(), None, None, None, None, Some(vec!["draft".into()]), &dir).unwrap();
+        update_memo_meta("b".into(), None, None, None, None, Some(vec!["work".into()]), &dir).unwrap();
+
+        update_memo_tag("DRAFT".into(), None, Some("blue".into()), &dir).unwrap();
+        update_memo_tag("draft".into(), Some("#Work".into()), None, &dir).unwrap();
+        let tags = get_memo_tags(&dir).unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "work");
+        assert_eq!(tags[0].memo_count, 2);
+
+        update_memo_tag("work".into(), Some("job".into()), None, &dir).unwrap();
+        let conn = db::get_connection(&dir).unwrap();
+        assert_eq!(load_memo(&conn, "a").unwrap().unwrap().tags, vec!["job"]);
+        assert!(update_memo_tag("missing".into(), None, None, &dir).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_restore_deleted_memo_with_meta() {
+        let dir = temp_dir("memos-restore");
+        let memo = add_memo("title".into(), "content".into(), &dir).unwrap();
+        let kept = add_memo_notebook("kept".into(), None, None, &dir).unwrap();
+        update_memo_meta(
+            memo.id.clone(),
+            Some(true),
+            None,
+            Some(kept.id.clone()),
+            Some("red".into()),
+            Some(vec!["work".into(), "todo".into()]),
+            &dir,
+        )
+        .unwrap();
+        delete_memo(memo.id.clone(), &dir).unwrap();
+
+        let deleted = memo_revisions::list_deleted_memos(&dir).unwrap();
+        assert_eq!(deleted.len(), 1);
+        let restored = restore_memo_revision(deleted[0].id, &dir).unwrap();
+        assert_eq!(restored.content, "content");
+        assert!(restored.pinned);
+        assert_eq!(restored.notebook_id, Some(kept.id.clone()));
+        assert_eq!(restored.color.as_deref(), Some("red"));
+        assert_eq!(restored.tags, vec!["todo", "work"]);
+
+        // 笔记本在恢复前被删除时，恢复到未分类
+        delete_memo(memo.id.clone(), &dir).unwrap();
+        delete_memo_notebook(kept.id, &dir).unwrap();
+        let deleted = memo_revisions::list_deleted_memos(&dir).unwrap();
+        let restored = restore_memo_revision(deleted[0].id, &dir).unwrap();
+        assert_eq!(restored.notebook_id, None);
+        assert!(restored.pinned);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,663 @@
+//! Ollama 客户端
+//!
+//! 封装 Ollama HTTP API（`/api/tags`、`/api/version`、`/api/chat`、`/api/generate`、`/api/embeddings`），
+//! 流式响应按 NDJSON 逐行解析并通过回调交给调用方。供前端命令以及
+//! 单词解释等 Rust 子系统复用。
+//!
+//! 流式请求不设置整体超时（生成可能很长），而是限制两次数据之间的最长间隔。
+
+use crate::settings::OllamaSettings;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// 非流式请求（模型列表、健康检查）的超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// 计算向量的超时（首次调用需要加载模型）
+const EMBED_TIMEOUT: Duration = Duration::from_secs(60);
+/// 流式响应两次数据之间的最长等待（首个 token 可能需要加载模型）
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatMessage {
+    /// system / user / assistant
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self { role: "system".to_string(), content: content.into() }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: "user".to_string(), content: content.into() }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self { role: "assistant".to_string(), content: content.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaModel {
+    pub name: String,
+    pub size: u64,
+    pub modified_at: Option<String>,
+    pub digest: Option<String>,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaHealth {
+    pub available: bool,
+    pub base_url: String,
+    pub version: Option<String>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaCompletion {
+    pub model: String,
+    pub content: String,
+    /// 被取消时为 true，`content` 为取消前已收到的内容
+    pub cancelled: bool,
+    /// 生成的 token 数
+    pub eval_count: Option<u64>,
+    /// 总耗时（毫秒）
+    pub total_duration_ms: Option<u64>,
+}
+
+/// 可选的生成参数，原样传给 Ollama 的 `options`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerateOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+}
+
+/// 取消令牌：可在任意线程调用 `cancel`，流式读取会立即停止
+#[derive(Debug, Default)]
+pub struct CancelToken {
+    flag: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+        // notify_one 会保留许可，等待方稍后进入也能立即返回
+        self.notify.notify_one();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+    }
+
+    async fn cancelled(&self) {
+        if self.is_cancelled() {
+            return;
+        }
+        self.notify.notified().await;
+    }
+}
+
+/// 正在进行的流式请求，按前端传入的 request_id 索引
+static ACTIVE_REQUESTS: LazyLock<Mutex<HashMap<String, Arc<CancelToken>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 注册一个可取消的请求；同 id 的旧请求会被取消
+pub fn register_request(request_id: &str) -> Arc<CancelToken> {
+    let token = CancelToken::new();
+    let mut active = ACTIVE_REQUESTS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(old) = active.insert(request_id.to_string(), token.clone()) {
+        old.cancel();
+    }
+    token
+}
+
+pub fn finish_request(request_id: &str, token: &Arc<CancelToken>) {
+    let mut active = ACTIVE_REQUESTS.lock().unwrap_or_else(|e| e.into_inner());
+    if active.get(request_id).is_some_and(|t| Arc::ptr_eq(t, token)) {
+        active.remove(request_id);
+    }
+}
+
+/// 取消请求，返回请求是否存在
+pub fn cancel_request(request_id: &str) -> bool {
+    let active = ACTIVE_REQUESTS.lock().unwrap_or_else(|e| e.into_inner());
+    match active.get(request_id) {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagModel>,
+}
+
+#[derive(Deserialize)]
+struct TagModel {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    modified_at: Option<String>,
+    digest: Option<String>,
+    details: Option<TagModelDetails>,
+}
+
+#[derive(Deserialize)]
+struct TagModelDetails {
+    family: Option<String>,
+    parameter_size: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    #[serde(default)]
+    embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: Option<String>,
+}
+
+/// 流式响应中的一行，chat 与 generate 共用
+#[derive(Deserialize, Default)]
+struct StreamLine {
+    model: Option<String>,
+    message: Option<ChatMessage>,
+    response: Option<String>,
+    #[serde(default)]
+    done: bool,
+    eval_count: Option<u64>,
+    /// 纳秒
+    total_duration: Option<u64>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OllamaClient {
+    base_url: String,
+    model: String,
+    http: reqwest::Client,
+}
+
+impl OllamaClient {
+    pub fn new(base_url: &str, model: &str) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+        let base_url = base_url.trim().trim_end_matches('/');
+        Ok(Self {
+            base_url: if base_url.is_empty() {
+                OllamaSettings::default().base_url
+            } else {
+                base_url.to_string()
+            },
+            model: model.trim().to_string(),
+            http,
+        })
+    }
+
+    pub fn from_settings(settings: &OllamaSettings) -> Result<Self, String> {
+        Self::new(&settings.base_url, &settings.model)
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// 把 reqwest 错误转换为用户可读的信息
+    fn describe_error(&self, e: &reqwest::Error) -> String {
+        if e.is_connect() {
+            format!("无法连接到 Ollama（{}），请确认 Ollama 已启动", self.base_url)
+        } else if e.is_timeout() {
+            format!("请求 Ollama 超时（{}）", self.base_url)
+        } else {
+            format!("请求 Ollama 失败: {}", e)
+        }
+    }
+
+    async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        // Ollama 的错误响应形如 {"error": "model 'x' not found"}
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<StreamLine>(&body)
+            .ok()
+            .and_then(|l| l.error)
+            .unwrap_or(body);
+        Err(format!("Ollama 返回错误 {}: {}", status.as_u16(), message.trim()))
+    }
+
+    /// 列出本地已安装的模型
+    pub async fn list_models(&self) -> Result<Vec<OllamaModel>, String> {
+        let response = self
+            .http
+            .get(self.url("/api/tags"))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| self.describe_error(&e))?;
+        let tags: TagsResponse = Self::check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("解析 Ollama 模型列表失败: {}", e))?;
+        Ok(tags
+            .models
+            .into_iter()
+            .map(|m| OllamaModel {
+                name: m.name,
+                size: m.size,
+                modified_at: m.modified_at,
+                digest: m.digest,
+                family: m.details.as_ref().and_then(|d| d.family.clone()),
+                parameter_size: m.details.and_then(|d| d.parameter_size),
+            })
+            .collect())
+    }
+
+    /// 健康检查，不返回错误，失败信息放在 `error` 中
+    pub async fn health(&self) -> OllamaHealth {
+        let started = Instant::now();
+        let result: Result<VersionResponse, String> = async {
+            let response = self
+                .http
+                .get(self.url("/api/version"))
+                .timeout(REQUEST_TIMEOUT)
+                .send()
+                .await
+                .map_err(|e| self.describe_error(&e))?;
+            Self::check_status(response)
+                .await?
+                .json::<VersionResponse>()
+                .await
+                .map_err(|e| format!("解析 Ollama 版本信息失败: {}", e))
+        }
+        .await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(v) => OllamaHealth {
+                available: true,
+                base_url: self.base_url.clone(),
+                version: v.version,
+                latency_ms,
+                error: None,
+            },
+            Err(e) => OllamaHealth {
+                available: false,
+                base_url: self.base_url.clone(),
+                version: None,
+                latency_ms,
+                error: Some(e),
+            },
+        }
+    }
+
+    /// 计算文本向量（`/api/embeddings`）
+    pub async fn embed(&self, text: &str, model: &str) -> Result<Vec<f32>, String> {
+        if model.trim().is_empty() {
+            return Err("未配置向量模型".to_string());
+        }
+        let response = self
+            .http
+            .post(self.url("/api/embeddings"))
+            .json(&serde_json::json!({ "model": model, "prompt": text }))
+            .timeout(EMBED_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| self.describe_error(&e))?;
+        let body: EmbeddingResponse = Self::check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("解析 Ollama 向量失败: {}", e))?;
+        if body.embedding.is_empty() {
+            return Err(format!("模型 {} 没有返回向量，请确认它是向量模型", model));
+        }
+        Ok(body.embedding)
+    }
+
+    fn resolve_model(&self, model: Option<&str>) -> Result<String, String> {
+        let model = model.map(str::trim).filter(|m| !m.is_empty()).unwrap_or(&self.model);
+        if model.is_empty() {
+            return Err("未配置 Ollama 模型".to_string());
+        }
+        Ok(model.to_string())
+    }
+
+    /// 流式对话，每收到一段内容调用一次 `on_chunk`
+    pub async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        model: Option<&str>,
+        options: Option<&GenerateOptions>,
+        cancel: &CancelToken,
+        on_chunk: impl FnMut(&str),
+    ) -> Result<OllamaCompletion, String> {
+        let model = self.resolve_model(model)?;
+        let mut body = serde_json::json!({
+            "model": model,
+            "messages": messages,
+            "stream": true,
+        });
+        if let Some(options) = options {
+            body["options"] = serde_json::json!(options);
+        }
+        self.stream("/api/chat", body, model, cancel, on_chunk).await
+    }
+
+    /// 流式补全（`/api/generate`）
+    pub async fn generate_stream(
+        &self,
+        prompt: &str,
+        system: Option<&str>,
+        model: Option<&str>,
+        options: Option<&GenerateOptions>,
+        cancel: &CancelToken,
+        on_chunk: impl FnMut(&str),
+    ) -> Result<OllamaCompletion, String> {
+        let model = self.resolve_model(model)?;
+        let mut body = serde_json::json!({
+            "model": model,
+            "prompt": prompt,
+            "stream": true,
+        });
+        if let Some(system) = system {
+            body["system"] = serde_json::json!(system);
+        }
+        if let Some(options) = options {
+            body["options"] = serde_json::json!(options);
+        }
+        self.stream("/api/generate", body, model, cancel, on_chunk).await
+    }
+
+    /// 不关心中间结果的对话，返回完整回复
+    pub async fn chat(
+        &self,
+        messages: &[ChatMessage],
+        options: Option<&GenerateOptions>,
+        cancel: &CancelToken,
+    ) -> Result<String, String> {
+        let completion = self.chat_stream(messages, None, options, cancel, |_| {}).await?;
+        if completion.cancelled {
+            return Err("请求已取消".to_string());
+        }
+        Ok(completion.content)
+    }
+
+    /// 要求模型以 JSON 格式回复（Ollama 的 `format: "json"`），返回原始文本
+    pub async fn chat_json(
+        &self,
+        messages: &[ChatMessage],
+        options: Option<&GenerateOptions>,
+        cancel: &CancelToken,
+    ) -> Result<String, String> {
+        let model = self.resolve_model(None)?;
+        let mut body = serde_json::json!({
+            "model": model,
+            "messages": messages,
+            "stream": true,
+            "format": "json",
+        });
+        if let Some(options) = options {
+            body["options"] = serde_json::json!(options);
+        }
+        let completion = self.stream("/api/chat", body, model, cancel, |_| {}).await?;
+        if completion.cancelled {
+            return Err("请求已取消".to_string());
+        }
+        Ok(completion.content)
+    }
+
+    async fn stream(
+        &self,
+        path: &str,
+        body: serde_json::Value,
+        model: String,
+        cancel: &CancelToken,
+        mut on_chunk: impl FnMut(&str),
+    ) -> Result<OllamaCompletion, String> {
+        let mut completion = OllamaCompletion { model, ..Default::default() };
+
+        let request = self.http.post(self.url(path)).json(&body).send();
+        let response = tokio::select! {
+            _ = cancel.cancelled() => {
+                completion.cancelled = true;
+                return Ok(completion);
+            }
+            result = tokio::time::timeout(STREAM_IDLE_TIMEOUT, request) => result
+                .map_err(|_| format!("等待 Ollama 响应超时（{}）", self.base_url))?
+                .map_err(|e| self.describe_error(&e))?,
+        };
+        let mut stream = Self::check_status(response).await?.bytes_stream();
+
+        let mut pending: Vec<u8> = Vec::new();
+        loop {
+            let next = tokio::select! {
+                _ = cancel.cancelled() => {
+                    completion.cancelled = true;
+                    return Ok(completion);
+                }
+                next = tokio::time::timeout(STREAM_IDLE_TIMEOUT, stream.next()) => next
+                    .map_err(|_| "Ollama 响应中断：长时间未收到数据".to_string())?,
+            };
+            let Some(chunk) = next else { break };
+            let chunk = chunk.map_err(|e| self.describe_error(&e))?;
+            pending.extend_from_slice(&chunk);
+
+            while let Some(pos) = pending.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=pos).collect();
+                if apply_stream_line(&line, &mut completion, &mut on_chunk)? {
+                    return Ok(completion);
+                }
+            }
+        }
+        // 最后一行可能没有换行符
+        apply_stream_line(&pending, &mut completion, &mut on_chunk)?;
+        Ok(completion)
+    }
+}
+
+/// 处理一行 NDJSON，返回是否已结束
+fn apply_stream_line(
+    line: &[u8],
+    completion: &mut OllamaCompletion,
+    on_chunk: &mut impl FnMut(&str),
+) -> Result<bool, String> {
+    let text = String::from_utf8_lossy(line);
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(false);
+    }
+    let line: StreamLine = serde_json::from_str(text)
+        .map_err(|e| format!("解析 Ollama 响应失败: {}", e))?;
+    if let Some(error) = line.error {
+        return Err(format!("Ollama 返回错误: {}", error));
+    }
+    let piece = line.message.map(|m| m.content).or(line.response).unwrap_or_default();
+    if !piece.is_empty() {
+        completion.content.push_str(&piece);
+        on_chunk(&piece);
+    }
+    if let Some(model) = line.model {
+        completion.model = model;
+    }
+    if line.done {
+        completion.eval_count = line.eval_count;
+        completion.total_duration_ms = line.total_duration.map(|ns| ns / 1_000_000);
+    }
+    Ok(line.done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::read_http_request;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    /// 启动一个只响应一次的 HTTP 服务，`chunks` 之间间隔 `delay` 发送
+    fn mock_server(status: &str, chunks: Vec<String>, delay: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let status = status.to_string();
+        std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            read_http_request(&mut socket);
+            let head = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+                status
+            );
+            if socket.write_all(head.as_bytes()).is_err() {
+                return;
+            }
+            for chunk in chunks {
+                let framed = format!("{:x}\r\n{}\r\n", chunk.len(), chunk);
+                if socket.write_all(framed.as_bytes()).is_err() || socket.flush().is_err() {
+                    return;
+                }
+                std::thread::sleep(delay);
+            }
+            let _ = socket.write_all(b"0\r\n\r\n");
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_list_models() {
+        let url = mock_server(
+            "200 OK",
+            vec![r#"{"models":[{"name":"qwen2:7b","size":4400000000,"details":{"family":"qwen2","parameter_size":"7.6B"}}]}"#.to_string()],
+            Duration::ZERO,
+        );
+        let client = OllamaClient::new(&url, "qwen2:7b").unwrap();
+        let models = client.list_models().await.unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "qwen2:7b");
+        assert_eq!(models[0].parameter_size.as_deref(), Some("7.6B"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_joins_split_lines() {
+        // 一行 JSON 被拆到两个数据块中
+        let url = mock_server(
+            "200 OK",
+            vec![
+                "{\"model\":\"m\",\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n{\"message\":{\"role\":\"assis".to_string(),
+                "tant\",\"content\":\"lo\"},\"done\":false}\n".to_string(),
+                "{\"done\":true,\"eval_count\":2,\"total_duration\":5000000}\n".to_string(),
+            ],
+            Duration::ZERO,
+        );
+        let client = OllamaClient::new(&url, "m").unwrap();
+        let mut pieces = Vec::new();
+        let completion = client
+            .chat_stream(&[ChatMessage::user("hi")], None, None, &CancelToken::default(), |p| {
+                pieces.push(p.to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(pieces, vec!["Hel", "lo"]);
+        assert_eq!(completion.content, "Hello");
+        assert_eq!(completion.eval_count, Some(2));
+        assert_eq!(completion.total_duration_ms, Some(5));
+        assert!(!completion.cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_stream() {
+        let chunks = (0..50)
+            .map(|i| format!("{{\"response\":\"{} \",\"done\":false}}\n", i))
+            .collect();
+        let url = mock_server("200 OK", chunks, Duration::from_millis(50));
+        let client = OllamaClient::new(&url, "m").unwrap();
+        let token = register_request("test-cancel");
+        let canceller = token.clone();
+        let mut received = 0;
+        let completion = client
+            .generate_stream("count", None, None, None, &token, |_| {
+                received += 1;
+                if received == 2 {
+                    canceller.cancel();
+                }
+            })
+            .await
+            .unwrap();
+        finish_request("test-cancel", &token);
+        assert!(completion.cancelled);
+        assert!(completion.content.starts_with("0 1 "));
+        assert!(!cancel_request("test-cancel"));
+    }
+
+    #[tokio::test]
+    async fn test_embed() {
+        let url = mock_server("200 OK", vec![r#"{"embedding":[0.5,-0.25,1.0]}"#.to_string()], Duration::ZERO);
+        let client = OllamaClient::new(&url, "m").unwrap();
+        assert_eq!(client.embed("hello", "nomic-embed-text").await.unwrap(), vec![0.5, -0.25, 1.0]);
+
+        let url = mock_server("200 OK", vec![r#"{"embedding":[]}"#.to_string()], Duration::ZERO);
+        let client = OllamaClient::new(&url, "m").unwrap();
+        assert!(client.embed("hello", "llama2").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_error_responses() {
+        let url = mock_server(
+            "404 Not Found",
+            vec![r#"{"error":"model 'nope' not found"}"#.to_string()],
+            Duration::ZERO,
+        );
+        let client = OllamaClient::new(&url, "nope").unwrap();
+        let err = client
+            .chat_stream(&[ChatMessage::user("hi")], None, None, &CancelToken::default(), |_| {})
+            .await
+            .unwrap_err();
+        assert!(err.contains("404") && err.contains("not found"), "{}", err);
+
+        // 端口未监听
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let client = OllamaClient::new(&format!("http://127.0.0.1:{}", port), "m").unwrap();
+        let health = client.health().await;
+        assert!(!health.available);
+        assert!(health.error.unwrap().contains("无法连接"));
+    }
+}
@@ -1,6 +1,27 @@
+//! 键鼠录制的数据模型和文件格式
+//!
+//! v2 文件结构：
+//! ```json
+//! {
+//!   "header": { "formatVersion": 2, "os": "windows", "screen": {...}, "dpi": 96, ... },
+//!   "events": [ { "timeOffsetMs": 0, "type": "mouseMove", "position": { "x": 0.5, "y": 0.5 } }, ... ]
+//! }
+//! ```
+//! 鼠标坐标按录制时的虚拟屏幕归一化到 0..1，回放时再换算到当前屏幕；
+//! 按键同时保存逻辑键名、虚拟键码和扫描码。
+//! v1 文件（`events` / `duration_ms` / `created_at`，原始坐标和虚拟键码）在加载时自动升级。
+
+use crate::keymap;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
 use std::time::Instant;
 
+pub const RECORDING_FORMAT_VERSION: u32 = 2;
+
+/// Windows 的默认 DPI（100% 缩放）
+pub const DEFAULT_DPI: u32 = 96;
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MouseButton {
     Left,
@@ -8,28 +29,339 @@ pub enum MouseButton {
     Middle,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum EventType {
-    MouseMove,
-    MouseDown { button: MouseButton },
-    MouseUp { button: MouseButton },
+/// 虚拟屏幕范围（多显示器时可能从负坐标开始）
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenGeometry {
+    /// 当前系统的虚拟屏幕范围
+    pub fn current() -> Self {
+        #[cfg(target_os = "windows")]
+        {
+            use windows_sys::Win32::UI::WindowsAndMessaging::{
+                GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN,
+                SM_YVIRTUALSCREEN,
+            };
+            let (x, y, width, height) = unsafe {
+                (
+                    GetSystemMetrics(SM_XVIRTUALSCREEN),
+                    GetSystemMetrics(SM_YVIRTUALSCREEN),
+                    GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                    GetSystemMetrics(SM_CYVIRTUALSCREEN),
+                )
+            };
+            if width > 0 && height > 0 {
+                return Self { x, y, width: width as u32, height: height as u32 };
+            }
+        }
+        Self::default()
+    }
+
+    /// 绝对坐标 -> 归一化坐标
+    pub fn normalize(&self, x: i32, y: i32) -> Position {
+        let width = self.width.max(2) as f64 - 1.0;
+        let height = self.height.max(2) as f64 - 1.0;
+        Position {
+            x: ((x - self.x) as f64 / width).clamp(0.0, 1.0),
+            y: ((y - self.y) as f64 / height).clamp(0.0, 1.0),
+        }
+    }
+
+    /// 归一化坐标 -> 绝对坐标
+    pub fn denormalize(&self, position: Position) -> (i32, i32) {
+        let width = self.width.max(2) as f64 - 1.0;
+        let height = self.height.max(2) as f64 - 1.0;
+        (
+            self.x + (position.x.clamp(0.0, 1.0) * width).round() as i32,
+            self.y + (position.y.clamp(0.0, 1.0) * height).round() as i32,
+        )
+    }
+}
+
+impl Default for ScreenGeometry {
+    fn default() -> Self {
+        Self { x: 0, y: 0, width: 1920, height: 1080 }
+    }
+}
+
+/// 当前系统 DPI
+pub fn current_dpi() -> u32 {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::Graphics::Gdi::{GetDC, GetDeviceCaps, ReleaseDC, LOGPIXELSX};
+        unsafe {
+            let hdc = GetDC(0);
+            if hdc != 0 {
+                let dpi = GetDeviceCaps(hdc, LOGPIXELSX as i32);
+                ReleaseDC(0, hdc);
+                if dpi > 0 {
+                    return dpi as u32;
+                }
+            }
+        }
+    }
+    DEFAULT_DPI
+}
+
+/// 归一化到虚拟屏幕的坐标，取值 0..1
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// 按键信息：逻辑键名用于跨平台回放和编辑，键码用于在原平台上精确还原
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyCode {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_code: Option<u32>,
+    /// 硬件扫描码，扩展键带 0xE000 前缀
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan_code: Option<u32>,
+}
+
+impl KeyCode {
+    pub fn from_vk(vk_code: u32, scan_code: Option<u32>) -> Self {
+        Self {
+            key: keymap::key_name_from_vk(vk_code),
+            vk_code: Some(vk_code),
+            scan_code,
+        }
+    }
+
+    /// 回放用的虚拟键码：优先使用录制的键码，否则按键名换算
+    pub fn resolve_vk(&self) -> Option<u32> {
+        self.vk_code.or_else(|| keymap::vk_from_key_name(&self.key))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InputEvent {
+    MouseMove { position: Position },
+    #[serde(rename_all = "camelCase")]
+    MouseDown {
+        button: MouseButton,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position: Option<Position>,
+    },
+    #[serde(rename_all = "camelCase")]
+    MouseUp {
+        button: MouseButton,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position: Option<Position>,
+    },
+    /// 滚轮，`delta` 为 120 的倍数，正数向上
     MouseWheel { delta: i32 },
-    KeyDown { vk_code: u32 },
-    KeyUp { vk_code: u32 },
+    KeyDown { key: KeyCode },
+    KeyUp { key: KeyCode },
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct RecordedEvent {
-    pub event_type: EventType,
-    pub x: Option<i32>,
-    pub y: Option<i32>,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimedEvent {
     pub time_offset_ms: u64,
+    #[serde(flatten)]
+    pub event: InputEvent,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingHeader {
+    pub format_version: u32,
+    /// 录制时的系统，取值同 `std::env::consts::OS`
+    pub os: String,
+    pub screen: ScreenGeometry,
+    pub dpi: u32,
+    pub created_at: String,
+    pub duration_ms: u64,
+    /// 由旧格式升级而来时记录原版本号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgraded_from: Option<u32>,
+    /// 显示名称，为空时使用文件名
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 编辑次数，每次保存编辑结果加一
+    #[serde(default = "default_revision")]
+    pub revision: u32,
+}
+
+fn default_revision() -> u32 {
+    1
+}
+
+impl RecordingHeader {
+    /// 按当前系统环境生成文件头
+    pub fn for_current_system(duration_ms: u64) -> Self {
+        Self {
+            format_version: RECORDING_FORMAT_VERSION,
+            os: std::env::consts::OS.to_string(),
+            screen: ScreenGeometry::current(),
+            dpi: current_dpi(),
+            created_at: chrono::Local::now().to_rfc3339(),
+            duration_ms,
+            upgraded_from: None,
+            name: format!("录制 {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")),
+            description: String::new(),
+            tags: Vec::new(),
+            revision: default_revision(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub header: RecordingHeader,
+    pub events: Vec<TimedEvent>,
+}
+
+/// v1 格式，只用于升级
+mod v1 {
+    use super::MouseButton;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    pub enum EventType {
+        MouseMove,
+        MouseDown { button: MouseButton },
+        MouseUp { button: MouseButton },
+        MouseWheel { delta: i32 },
+        KeyDown { vk_code: u32 },
+        KeyUp { vk_code: u32 },
+    }
+
+    #[derive(Deserialize)]
+    pub struct RecordedEvent {
+        pub event_type: EventType,
+        pub x: Option<i32>,
+        pub y: Option<i32>,
+        pub time_offset_ms: u64,
+    }
+
+    #[derive(Deserialize)]
+    pub struct RecordingFile {
+        pub events: Vec<RecordedEvent>,
+        #[serde(default)]
+        pub duration_ms: Option<u64>,
+        #[serde(default)]
+        pub created_at: Option<String>,
+    }
+}
+
+/// 把 v1 录制升级为当前格式。v1 没有记录屏幕信息，使用传入的屏幕范围换算坐标
+fn upgrade_v1(file: v1::RecordingFile, screen: ScreenGeometry) -> Recording {
+    let position = |x: Option<i32>, y: Option<i32>| match (x, y) {
+        (Some(x), Some(y)) => Some(screen.normalize(x, y)),
+        _ => None,
+    };
+
+    let events: Vec<TimedEvent> = file
+        .events
+        .into_iter()
+        .filter_map(|e| {
+            let event = match e.event_type {
+                v1::EventType::MouseMove => InputEvent::MouseMove { position: position(e.x, e.y)? },
+                v1::EventType::MouseDown { button } => {
+                    InputEvent::MouseDown { button, position: position(e.x, e.y) }
+                }
+                v1::EventType::MouseUp { button } => {
+                    InputEvent::MouseUp { button, position: position(e.x, e.y) }
+                }
+                v1::EventType::MouseWheel { delta } => InputEvent::MouseWheel { delta },
+                v1::EventType::KeyDown { vk_code } => {
+                    InputEvent::KeyDown { key: KeyCode::from_vk(vk_code, None) }
+                }
+                v1::EventType::KeyUp { vk_code } => {
+                    InputEvent::KeyUp { key: KeyCode::from_vk(vk_code, None) }
+                }
+            };
+            Some(TimedEvent { time_offset_ms: e.time_offset_ms, event })
+        })
+        .collect();
+
+    let duration_ms = file
+        .duration_ms
+        .unwrap_or_else(|| events.last().map(|e| e.time_offset_ms).unwrap_or(0));
+
+    Recording {
+        header: RecordingHeader {
+            format_version: RECORDING_FORMAT_VERSION,
+            // v1 只能在 Windows 上录制
+            os: "windows".to_string(),
+            screen,
+            dpi: DEFAULT_DPI,
+            created_at: file.created_at.unwrap_or_default(),
+            duration_ms,
+            upgraded_from: Some(1),
+            name: String::new(),
+            description: String::new(),
+            tags: Vec::new(),
+            revision: default_revision(),
+        },
+        events,
+    }
+}
+
+/// 解析录制文件内容，旧版本自动升级；`fallback_screen` 用于缺少屏幕信息的旧文件
+pub fn parse_recording(content: &str, fallback_screen: ScreenGeometry) -> Result<Recording, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    let version = match json.get("header") {
+        Some(header) => header
+            .get("formatVersion")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| "Missing or invalid formatVersion in recording header".to_string())?,
+        None if json.get("events").is_some() => 1,
+        None => return Err("Unrecognized recording file format".to_string()),
+    };
+
+    match version {
+        1 => {
+            let file: v1::RecordingFile = serde_json::from_value(json)
+                .map_err(|e| format!("Failed to parse v1 recording: {}", e))?;
+            Ok(upgrade_v1(file, fallback_screen))
+        }
+        2 => serde_json::from_value(json).map_err(|e| format!("Failed to parse recording: {}", e)),
+        v => Err(format!(
+            "Recording format version {} is newer than supported version {}",
+            v, RECORDING_FORMAT_VERSION
+        )),
+    }
+}
+
+/// 读取录制文件
+pub fn load_recording(path: &Path) -> Result<Recording, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
+    parse_recording(&content, ScreenGeometry::current())
+}
+
+/// 以当前格式写入录制文件
+pub fn save_recording(path: &Path, recording: &Recording) -> Result<(), String> {
+    let json_string = serde_json::to_string_pretty(recording)
+        .map_err(|e| format!("Failed to serialize recording data: {}", e))?;
+    fs::write(path, json_string).map_err(|e| format!("Failed to write recording file: {}", e))
 }
 
 pub struct RecordingState {
     pub start_instant: Option<Instant>,
-    pub events: Vec<RecordedEvent>,
+    pub events: Vec<TimedEvent>,
     pub is_recording: bool,
+    /// 录制开始时的屏幕范围，用于归一化鼠标坐标
+    pub screen: ScreenGeometry,
 }
 
 impl RecordingState {
@@ -38,12 +370,14 @@ impl RecordingState {
             start_instant: None,
             events: Vec::new(),
             is_recording: false,
+            screen: ScreenGeometry::default(),
         }
     }
 
     pub fn start(&mut self) {
         self.start_instant = Some(Instant::now());
         self.events.clear();
+        self.screen = ScreenGeometry::current();
         self.is_recording = true;
     }
 
@@ -52,7 +386,7 @@ impl RecordingState {
         self.start_instant = None;
     }
 
-    pub fn add_event(&mut self, event: RecordedEvent) {
+    pub fn add_event(&mut self, event: TimedEvent) {
         if self.is_recording {
             self.events.push(event);
         }
@@ -74,7 +408,187 @@ impl Default for RecordingState {
 pub struct RecordingMeta {
     pub file_path: String,
     pub file_name: String,
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
     pub duration_ms: u64,
     pub event_count: usize,
     pub created_at: String,
+    pub revision: u32,
+}
+
+/// 启动器里按名字查找录制：名字或文件名完全相同的排最前，其次是前缀匹配，
+/// 再其次是名字、描述或标签中包含关键字的；不区分大小写
+pub fn match_recordings<'a>(recordings: &'a [RecordingMeta], query: &str) -> Vec<&'a RecordingMeta> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return recordings.iter().collect();
+    }
+
+    let mut matches: Vec<(u8, &RecordingMeta)> = recordings
+        .iter()
+        .filter_map(|meta| {
+            let name = meta.name.to_lowercase();
+            let stem = meta.file_name.trim_end_matches(".json").to_lowercase();
+            let rank = if name == query || stem == query {
+                0
+            } else if name.starts_with(&query) {
+                1
+            } else if name.contains(&query)
+                || meta.description.to_lowercase().contains(&query)
+                || meta.tags.iter().any(|t| t.to_lowercase() == query)
+            {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, meta))
+        })
+        .collect();
+    // sort_by_key is stable, so the caller's order (newest first) is kept within a rank
+    matches.sort_by_key(|(rank, _)| *rank);
+    matches.into_iter().map(|(_, meta)| meta).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_recording() -> Recording {
+        Recording {
+            header: RecordingHeader {
+                format_version: RECORDING_FORMAT_VERSION,
+                os: "windows".to_string(),
+                screen: ScreenGeometry { x: -1920, y: 0, width: 3840, height: 1080 },
+                dpi: 144,
+                created_at: "2024-05-01T10:00:00+08:00".to_string(),
+                duration_ms: 1500,
+                upgraded_from: None,
+                name: "登录流程".to_string(),
+                description: "打开浏览器并登录".to_string(),
+                tags: vec!["web".to_string()],
+                revision: 3,
+            },
+            events: vec![
+                TimedEvent {
+                    time_offset_ms: 0,
+                    event: InputEvent::MouseMove { position: Position { x: 0.25, y: 0.5 } },
+                },
+                TimedEvent {
+                    time_offset_ms: 100,
+                    event: InputEvent::MouseDown {
+                        button: MouseButton::Left,
+                        position: Some(Position { x: 0.25, y: 0.5 }),
+                    },
+                },
+                TimedEvent {
+                    time_offset_ms: 200,
+                    event: InputEvent::MouseWheel { delta: -120 },
+                },
+                TimedEvent {
+                    time_offset_ms: 300,
+                    event: InputEvent::KeyDown { key: KeyCode::from_vk(0xA2, Some(0x1D)) },
+                },
+                TimedEvent {
+                    time_offset_ms: 400,
+                    event: InputEvent::KeyUp { key: KeyCode::from_vk(0x0D, None) },
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_v2_serde_round_trip() {
+        let recording = sample_recording();
+        let json = serde_json::to_string_pretty(&recording).unwrap();
+        assert!(json.contains("\"formatVersion\": 2"));
+        assert!(json.contains("\"type\": \"keyDown\""));
+        assert!(json.contains("\"key\": \"CtrlLeft\""));
+
+        let parsed = parse_recording(&json, ScreenGeometry::default()).unwrap();
+        assert_eq!(parsed, recording);
+    }
+
+    #[test]
+    fn test_upgrade_v1_recording() {
+        let v1 = r#"{
+            "events": [
+                {"event_type": "MouseMove", "x": 960, "y": 540, "time_offset_ms": 10},
+                {"event_type": {"MouseDown": {"button": "Right"}}, "x": 0, "y": 0, "time_offset_ms": 20},
+                {"event_type": {"KeyDown": {"vk_code": 65}}, "x": null, "y": null, "time_offset_ms": 30},
+                {"event_type": {"MouseWheel": {"delta": 120}}, "x": 5, "y": 5, "time_offset_ms": 40}
+            ],
+            "duration_ms": 50,
+            "created_at": "2024-01-01T00:00:00+00:00"
+        }"#;
+        let screen = ScreenGeometry { x: 0, y: 0, width: 1921, height: 1081 };
+        let recording = parse_recording(v1, screen).unwrap();
+
+        assert_eq!(recording.header.format_version, RECORDING_FORMAT_VERSION);
+        assert_eq!(recording.header.upgraded_from, Some(1));
+        assert_eq!(recording.header.duration_ms, 50);
+        assert_eq!(recording.header.screen, screen);
+        assert_eq!(recording.events.len(), 4);
+        assert_eq!(
+            recording.events[0].event,
+            InputEvent::MouseMove { position: Position { x: 0.5, y: 0.5 } }
+        );
+        assert_eq!(
+            recording.events[2].event,
+            InputEvent::KeyDown {
+                key: KeyCode { key: "A".to_string(), vk_code: Some(0x41), scan_code: None }
+            }
+        );
+
+        // 升级后的内容按 v2 保存再读取不变
+        let json = serde_json::to_string(&recording).unwrap();
+        assert_eq!(parse_recording(&json, ScreenGeometry::default()).unwrap(), recording);
+    }
+
+    #[test]
+    fn test_rejects_unknown_versions() {
+        let future = r#"{"header": {"formatVersion": 99}, "events": []}"#;
+        assert!(parse_recording(future, ScreenGeometry::default()).is_err());
+        assert!(parse_recording(r#"{"foo": 1}"#, ScreenGeometry::default()).is_err());
+    }
+
+    #[test]
+    fn test_screen_normalization() {
+        let screen = ScreenGeometry { x: -1920, y: 0, width: 3840, height: 1080 };
+        for (x, y) in [(-1920, 0), (0, 540), (1919, 1079)] {
+            assert_eq!(screen.denormalize(screen.normalize(x, y)), (x, y));
+        }
+        let other = ScreenGeometry { x: 0, y: 0, width: 1280, height: 720 };
+        assert_eq!(other.denormalize(screen.normalize(1919, 1079)), (1279, 719));
+    }
+
+    #[test]
+    fn test_match_recordings_by_name() {
+        let meta = |file_name: &str, name: &str, tags: &[&str]| RecordingMeta {
+            file_path: format!("recordings/{}", file_name),
+            file_name: file_name.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            duration_ms: 0,
+            event_count: 0,
+            created_at: String::new(),
+            revision: 1,
+        };
+        let recordings = vec![
+            meta("a.json", "Daily report", &[]),
+            meta("b.json", "Report", &["work"]),
+            meta("c.json", "Login", &["report"]),
+            meta("d.json", "Backup", &[]),
+        ];
+
+        let names: Vec<&str> = match_recordings(&recordings, " REPORT ")
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Report", "Daily report", "Login"]);
+        assert_eq!(match_recordings(&recordings, "d")[0].file_name, "d.json");
+        assert_eq!(match_recordings(&recordings, "").len(), 4);
+        assert!(match_recordings(&recordings, "nothing").is_empty());
+    }
 }
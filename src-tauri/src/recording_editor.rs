@@ -0,0 +1,489 @@
+//! 录制编辑
+//!
+//! 编辑操作都作用在内存中的 `Recording` 上，保存时把旧内容放进
+//! `recordings/.history/<文件名>/<revision>.json`，撤销时再取回来。
+//!
+//! 时间以事件的 `time_offset_ms` 为准；"延迟" 指某个事件与前一个事件的间隔，
+//! 第一个事件的延迟就是它的偏移量。
+
+use crate::recording::{self, InputEvent, Recording, ScreenGeometry, TimedEvent};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 每个录制最多保留的历史版本数
+const MAX_HISTORY: usize = 20;
+const HISTORY_DIR: &str = ".history";
+/// 缩放延迟允许的倍数范围
+const MIN_SCALE_FACTOR: f64 = 0.01;
+const MAX_SCALE_FACTOR: f64 = 100.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum EditOperation {
+    /// 把超过 `max_gap_ms` 的空闲间隔（包括开头和结尾）缩短到 `max_gap_ms`
+    #[serde(rename_all = "camelCase")]
+    TrimIdle { max_gap_ms: u64 },
+    /// 剪掉 `[start, end)` 区间的事件，后面的事件前移补上空缺
+    Cut { start: usize, end: usize },
+    /// 删除指定事件，其余事件的时间不变
+    Delete { indices: Vec<usize> },
+    /// 在 `index` 前插入事件；插入事件的 `timeOffsetMs` 相对于插入点，后面的事件顺延
+    Insert { index: usize, events: Vec<TimedEvent> },
+    /// 用 Douglas-Peucker 算法简化连续的鼠标移动，容差单位为录制屏幕上的像素
+    #[serde(rename_all = "camelCase")]
+    SimplifyMoves { tolerance_px: f64 },
+    /// 修改某个事件之前的延迟，后面的事件随之平移
+    #[serde(rename_all = "camelCase")]
+    SetDelay { index: usize, delay_ms: u64 },
+    /// 按倍数缩放所有延迟，倍数范围 0.01 ~ 100
+    ScaleDelays { factor: f64 },
+}
+
+fn last_offset(recording: &Recording) -> u64 {
+    recording.events.last().map(|e| e.time_offset_ms).unwrap_or(0)
+}
+
+/// 最后一个事件之后到录制结束的时间
+fn trailing_ms(recording: &Recording) -> u64 {
+    recording.header.duration_ms.saturating_sub(last_offset(recording))
+}
+
+fn time_out_of_range() -> String {
+    "Event time out of range".to_string()
+}
+
+/// 毫秒数转成可正可负的平移量
+fn signed_ms(ms: u64) -> Result<i64, String> {
+    i64::try_from(ms).map_err(|_| time_out_of_range())
+}
+
+/// 平移事件时间，早于 0 的截断为 0；有事件超出范围时报错且不做任何修改
+fn shift_from(events: &mut [TimedEvent], delta: i64) -> Result<(), String> {
+    if delta > 0 && events.iter().any(|e| e.time_offset_ms.checked_add_signed(delta).is_none()) {
+        return Err(time_out_of_range());
+    }
+    for event in events {
+        event.time_offset_ms = event.time_offset_ms.checked_add_signed(delta).unwrap_or(0);
+    }
+    Ok(())
+}
+
+fn check_index(index: usize, len: usize) -> Result<(), String> {
+    if index >= len {
+        return Err(format!("Event index {} out of range (0..{})", index, len));
+    }
+    Ok(())
+}
+
+/// 对连续鼠标移动做路径简化，返回保留的下标
+fn simplify_path(points: &[(f64, f64)], tolerance: f64) -> Vec<usize> {
+    fn distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len == 0.0 {
+            return ((p.0 - a.0).powi(2) + (p.1 - a.1).powi(2)).sqrt();
+        }
+        ((p.0 - a.0) * dy - (p.1 - a.1) * dx).abs() / len
+    }
+
+    fn recurse(points: &[(f64, f64)], start: usize, end: usize, tolerance: f64, keep: &mut Vec<usize>) {
+        if end <= start + 1 {
+            return;
+        }
+        let (mut max_distance, mut max_index) = (0.0, start);
+        for i in start + 1..end {
+            let d = distance(points[i], points[start], points[end]);
+            if d > max_distance {
+                max_distance = d;
+                max_index = i;
+            }
+        }
+        if max_distance > tolerance {
+            recurse(points, start, max_index, tolerance, keep);
+            keep.push(max_index);
+            recurse(points, max_index, end, tolerance, keep);
+        }
+    }
+
+    if points.len() <= 2 {
+        return (0..points.len()).collect();
+    }
+    let mut keep = vec![0];
+    recurse(points, 0, points.len() - 1, tolerance, &mut keep);
+    keep.push(points.len() - 1);
+    keep
+}
+
+fn simplify_moves(events: Vec<TimedEvent>, screen: &ScreenGeometry, tolerance_px: f64) -> Vec<TimedEvent> {
+    let mut result = Vec::with_capacity(events.len());
+    let mut run: Vec<TimedEvent> = Vec::new();
+
+    let flush = |run: &mut Vec<TimedEvent>, result: &mut Vec<TimedEvent>| {
+        let points: Vec<(f64, f64)> = run
+            .iter()
+            .map(|e| match &e.event {
+                InputEvent::MouseMove { position } => {
+                    let (x, y) = screen.denormalize(*position);
+                    (x as f64, y as f64)
+                }
+                _ => (0.0, 0.0),
+            })
+            .collect();
+        let keep = simplify_path(&points, tolerance_px);
+        let mut keep = keep.into_iter().peekable();
+        for (i, event) in run.drain(..).enumerate() {
+            if keep.peek() == Some(&i) {
+                keep.next();
+                result.push(event);
+            }
+        }
+    };
+
+    for event in events {
+        if matches!(event.event, InputEvent::MouseMove { .. }) {
+            run.push(event);
+        } else {
+            flush(&mut run, &mut result);
+            result.push(event);
+        }
+    }
+    flush(&mut run, &mut result);
+    result
+}
+
+/// 应用一个编辑操作
+pub fn apply_edit(recording: &mut Recording, operation: &EditOperation) -> Result<(), String> {
+    let mut trailing = trailing_ms(recording);
+    let len = recording.events.len();
+    let events = &mut recording.events;
+
+    match operation {
+        EditOperation::TrimIdle { max_gap_ms } => {
+            let mut removed = 0u64;
+            let mut previous = 0u64;
+            for event in events.iter_mut() {
+                let gap = event.time_offset_ms.saturating_sub(previous);
+                previous = event.time_offset_ms;
+                removed += gap.saturating_sub(*max_gap_ms);
+                event.time_offset_ms = event.time_offset_ms.saturating_sub(removed);
+            }
+            trailing = trailing.min(*max_gap_ms);
+        }
+        EditOperation::Cut { start, end } => {
+            if start >= end || *end > len {
+                return Err(format!("Invalid cut range {}..{} (0..{})", start, end, len));
+            }
+            if *end < len {
+                let span = events[*end].time_offset_ms.saturating_sub(events[*start].time_offset_ms);
+                shift_from(&mut events[*end..], -signed_ms(span)?)?;
+            } else {
+                trailing = 0;
+            }
+            events.drain(*start..*end);
+        }
+        EditOperation::Delete { indices } => {
+            for index in indices {
+                check_index(*index, len)?;
+            }
+            let mut index = 0;
+            events.retain(|_| {
+                index += 1;
+                !indices.contains(&(index - 1))
+            });
+        }
+        EditOperation::Insert { index, events: inserted } => {
+            if *index > len {
+                return Err(format!("Insert index {} out of range (0..={})", index, len));
+            }
+            let base = if *index == 0 { 0 } else { events[*index - 1].time_offset_ms };
+            let mut offset = 0;
+            let mut block = Vec::with_capacity(inserted.len());
+            for event in inserted {
+                // 插入事件之间保持顺序
+                offset = offset.max(event.time_offset_ms);
+                let time_offset_ms = base.checked_add(offset).ok_or_else(time_out_of_range)?;
+                block.push(TimedEvent { time_offset_ms, event: event.event.clone() });
+            }
+            shift_from(&mut events[*index..], signed_ms(offset)?)?;
+            events.splice(*index..*index, block);
+        }
+        EditOperation::SimplifyMoves { tolerance_px } => {
+            if *tolerance_px < 0.0 {
+                return Err("Tolerance must not be negative".to_string());
+            }
+            let screen = recording.header.screen;
+            recording.events = simplify_moves(std::mem::take(&mut recording.events), &screen, *tolerance_px);
+        }
+        EditOperation::SetDelay { index, delay_ms } => {
+            check_index(*index, len)?;
+            let previous = if *index == 0 { 0 } else { events[*index - 1].time_offset_ms };
+            let current = events[*index].time_offset_ms.saturating_sub(previous);
+            shift_from(&mut events[*index..], signed_ms(*delay_ms)? - signed_ms(current)?)?;
+        }
+        EditOperation::ScaleDelays { factor } => {
+            if !(MIN_SCALE_FACTOR..=MAX_SCALE_FACTOR).contains(factor) {
+                return Err(format!(
+                    "Scale factor must be between {} and {}",
+                    MIN_SCALE_FACTOR, MAX_SCALE_FACTOR
+                ));
+            }
+            let mut previous_original = 0u64;
+            let mut previous_scaled = 0u64;
+            for event in events.iter_mut() {
+                let gap = event.time_offset_ms.saturating_sub(previous_original);
+                previous_original = event.time_offset_ms;
+                // f64 转 u64 超出范围时取最大值，累加同样饱和
+                previous_scaled = previous_scaled.saturating_add((gap as f64 * factor).round() as u64);
+                event.time_offset_ms = previous_scaled;
+            }
+            trailing = (trailing as f64 * factor).round() as u64;
+        }
+    }
+
+    recording.header.duration_ms = last_offset(recording).saturating_add(trailing);
+    Ok(())
+}
+
+/// 把 `second` 接在 `first` 后面，中间间隔 `gap_ms`
+pub fn merge_recordings(first: &Recording, second: &Recording, gap_ms: u64) -> Result<Recording, String> {
+    let base = first.header.duration_ms.checked_add(gap_ms).ok_or_else(time_out_of_range)?;
+    let duration_ms = base.checked_add(second.header.duration_ms).ok_or_else(time_out_of_range)?;
+    // 录制文件里的时长不一定覆盖所有事件，逐个检查
+    if second.events.iter().any(|e| base.checked_add(e.time_offset_ms).is_none()) {
+        return Err(time_out_of_range());
+    }
+    let mut merged = first.clone();
+    let same_screen = first.header.screen == second.header.screen;
+    merged.events.extend(second.events.iter().map(|e| {
+        let mut event = e.clone();
+        event.time_offset_ms += base;
+        // 两个录制的屏幕不同时，把第二个的坐标换算到第一个的屏幕上
+        if !same_screen {
+            let convert = |p: &mut recording::Position| {
+                let (x, y) = second.header.screen.denormalize(*p);
+                *p = first.header.screen.normalize(x, y);
+            };
+            match &mut event.event {
+                InputEvent::MouseMove { position } => convert(position),
+                InputEvent::MouseDown { position: Some(position), .. }
+                | InputEvent::MouseUp { position: Some(position), .. } => convert(position),
+                _ => {}
+            }
+        }
+        event
+    }));
+    merged.header.duration_ms = duration_ms;
+    merged.header.revision = 1;
+    for tag in &second.header.tags {
+        if !merged.header.tags.contains(tag) {
+            merged.header.tags.push(tag.clone());
+        }
+    }
+    Ok(merged)
+}
+
+fn history_dir(path: &Path) -> Result<PathBuf, String> {
+    let parent = path.parent().ok_or_else(|| format!("Invalid recording path: {}", path.display()))?;
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| format!("Invalid recording path: {}", path.display()))?;
+    Ok(parent.join(HISTORY_DIR).join(stem))
+}
+
+/// 历史版本按 revision 升序排列
+fn history_entries(path: &Path) -> Result<Vec<(u32, PathBuf)>, String> {
+    let dir = history_dir(path)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries: Vec<(u32, PathBuf)> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read recording history: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let revision = path.file_stem()?.to_str()?.parse::<u32>().ok()?;
+            Some((revision, path))
+        })
+        .collect();
+    entries.sort_by_key(|(revision, _)| *revision);
+    Ok(entries)
+}
+
+pub fn can_undo(path: &Path) -> Result<bool, String> {
+    Ok(!history_entries(path)?.is_empty())
+}
+
+/// 保存编辑结果为新版本，旧内容放入历史
+pub fn save_new_revision(path: &Path, recording: &mut Recording) -> Result<(), String> {
+    let current = recording::load_recording(path)?;
+    let dir = history_dir(path)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create recording history: {}", e))?;
+    recording::save_recording(&dir.join(format!("{}.json", current.header.revision)), &current)?;
+
+    let entries = history_entries(path)?;
+    if entries.len() > MAX_HISTORY {
+        for (_, old) in &entries[..entries.len() - MAX_HISTORY] {
+            let _ = fs::remove_file(old);
+        }
+    }
+
+    recording.header.revision = current.header.revision + 1;
+    recording::save_recording(path, recording)
+}
+
+/// 撤销最近一次编辑，返回恢复后的内容
+pub fn undo(path: &Path) -> Result<Recording, String> {
+    let (_, latest) = history_entries(path)?
+        .pop()
+        .ok_or_else(|| "No edits to undo".to_string())?;
+    let previous = recording::load_recording(&latest)?;
+    recording::save_recording(path, &previous)?;
+    fs::remove_file(&latest).map_err(|e| format!("Failed to remove history entry: {}", e))?;
+    Ok(previous)
+}
+
+/// 删除录制的所有历史版本
+pub fn remove_history(path: &Path) -> Result<(), String> {
+    let dir = history_dir(path)?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove recording history: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::{KeyCode, MouseButton, Position, RecordingHeader};
+    use crate::test_support::temp_dir;
+
+    fn move_to(t: u64, x: f64, y: f64) -> TimedEvent {
+        TimedEvent { time_offset_ms: t, event: InputEvent::MouseMove { position: Position { x, y } } }
+    }
+
+    fn key(t: u64, vk: u32) -> TimedEvent {
+        TimedEvent { time_offset_ms: t, event: InputEvent::KeyDown { key: KeyCode::from_vk(vk, None) } }
+    }
+
+    fn recording(events: Vec<TimedEvent>, duration_ms: u64) -> Recording {
+        Recording {
+            header: RecordingHeader {
+                format_version: recording::RECORDING_FORMAT_VERSION,
+                os: "windows".to_string(),
+                screen: ScreenGeometry { x: 0, y: 0, width: 1001, height: 1001 },
+                dpi: 96,
+                created_at: String::new(),
+                duration_ms,
+                upgraded_from: None,
+                name: "test".to_string(),
+                description: String::new(),
+                tags: vec!["a".to_string()],
+                revision: 1,
+            },
+            events,
+        }
+    }
+
+    fn offsets(recording: &Recording) -> Vec<u64> {
+        recording.events.iter().map(|e| e.time_offset_ms).collect()
+    }
+
+    #[test]
+    fn test_timing_edits() {
+        let mut rec = recording(vec![key(3000, 0x41), key(3100, 0x42), key(9100, 0x43)], 15000);
+        apply_edit(&mut rec, &EditOperation::TrimIdle { max_gap_ms: 1000 }).unwrap();
+        assert_eq!(offsets(&rec), vec![1000, 1100, 2100]);
+        assert_eq!(rec.header.duration_ms, 3100);
+
+        apply_edit(&mut rec, &EditOperation::SetDelay { index: 1, delay_ms: 500 }).unwrap();
+        assert_eq!(offsets(&rec), vec![1000, 1500, 2500]);
+
+        apply_edit(&mut rec, &EditOperation::ScaleDelays { factor: 0.5 }).unwrap();
+        assert_eq!(offsets(&rec), vec![500, 750, 1250]);
+        assert_eq!(rec.header.duration_ms, 1750);
+        for factor in [0.0, 0.001, 1000.0, f64::NAN, f64::INFINITY] {
+            assert!(apply_edit(&mut rec, &EditOperation::ScaleDelays { factor }).is_err());
+        }
+        assert_eq!(offsets(&rec), vec![500, 750, 1250]);
+
+        apply_edit(&mut rec, &EditOperation::Cut { start: 0, end: 1 }).unwrap();
+        assert_eq!(offsets(&rec), vec![500, 1000]);
+
+        let inserted = vec![key(100, 0x44), key(300, 0x45)];
+        apply_edit(&mut rec, &EditOperation::Insert { index: 1, events: inserted }).unwrap();
+        assert_eq!(offsets(&rec), vec![500, 600, 800, 1300]);
+
+        apply_edit(&mut rec, &EditOperation::Delete { indices: vec![0, 2] }).unwrap();
+        assert_eq!(offsets(&rec), vec![600, 1300]);
+        assert!(apply_edit(&mut rec, &EditOperation::Delete { indices: vec![5] }).is_err());
+        assert!(apply_edit(&mut rec, &EditOperation::Cut { start: 1, end: 1 }).is_err());
+
+        // 超出范围的时间被拒绝，录制保持不变
+        let huge = vec![key(u64::MAX, 0x46)];
+        assert!(apply_edit(&mut rec, &EditOperation::Insert { index: 1, events: huge }).is_err());
+        assert!(apply_edit(&mut rec, &EditOperation::SetDelay { index: 0, delay_ms: u64::MAX }).is_err());
+        let mut late = recording(vec![key(0, 0x41), key(u64::MAX - 10, 0x42)], u64::MAX);
+        assert!(apply_edit(&mut late, &EditOperation::SetDelay { index: 0, delay_ms: 100 }).is_err());
+        assert_eq!(offsets(&late), vec![0, u64::MAX - 10]);
+        assert_eq!(offsets(&rec), vec![600, 1300]);
+    }
+
+    #[test]
+    fn test_simplify_moves_keeps_corners_and_other_events() {
+        let mut events: Vec<TimedEvent> = (0..=10).map(|i| move_to(i * 10, i as f64 / 20.0, 0.0)).collect();
+        events.extend((1..=10).map(|i| move_to(100 + i * 10, 0.5, i as f64 / 20.0)));
+        events.push(TimedEvent {
+            time_offset_ms: 300,
+            event: InputEvent::MouseDown { button: MouseButton::Left, position: None },
+        });
+        events.push(move_to(310, 0.6, 0.6));
+        let mut rec = recording(events, 400);
+
+        apply_edit(&mut rec, &EditOperation::SimplifyMoves { tolerance_px: 2.0 }).unwrap();
+        assert_eq!(offsets(&rec), vec![0, 100, 200, 300, 310]);
+        assert_eq!(rec.header.duration_ms, 400);
+    }
+
+    #[test]
+    fn test_merge_converts_screen() {
+        let first = recording(vec![key(0, 0x41)], 1000);
+        let mut second = recording(vec![move_to(200, 1.0, 1.0)], 500);
+        second.header.screen = ScreenGeometry { x: 0, y: 0, width: 501, height: 501 };
+        second.header.tags = vec!["a".to_string(), "b".to_string()];
+
+        assert!(merge_recordings(&first, &second, u64::MAX).is_err());
+        let merged = merge_recordings(&first, &second, 100).unwrap();
+        assert_eq!(offsets(&merged), vec![0, 1300]);
+        assert_eq!(merged.header.duration_ms, 1600);
+        assert_eq!(merged.header.tags, vec!["a", "b"]);
+        assert_eq!(merged.events[1].event, InputEvent::MouseMove { position: Position { x: 0.5, y: 0.5 } });
+    }
+
+    #[test]
+    fn test_revisions_and_undo() {
+        let dir = temp_dir("rec-editor");
+        let path = dir.join("recording_1.json");
+
+        let original = recording(vec![key(0, 0x41), key(100, 0x42)], 200);
+        recording::save_recording(&path, &original).unwrap();
+        assert!(!can_undo(&path).unwrap());
+
+        let mut edited = original.clone();
+        apply_edit(&mut edited, &EditOperation::Delete { indices: vec![0] }).unwrap();
+        save_new_revision(&path, &mut edited).unwrap();
+        assert_eq!(edited.header.revision, 2);
+        assert_eq!(recording::load_recording(&path).unwrap().events.len(), 1);
+        assert!(can_undo(&path).unwrap());
+
+        let restored = undo(&path).unwrap();
+        assert_eq!(restored, original);
+        assert_eq!(recording::load_recording(&path).unwrap(), original);
+        assert!(!can_undo(&path).unwrap());
+        assert!(undo(&path).is_err());
+
+        remove_history(&path).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,13 +1,33 @@
-use crate::recording::{EventType, MouseButton, RecordedEvent};
-use serde_json;
-use std::fs;
+use crate::input_backend::InputBackend;
+use crate::recording::{self, InputEvent, KeyCode, MouseButton, ScreenGeometry, TimedEvent};
 use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 单次回放最多执行的事件数
+pub const MAX_REPLAY_EVENTS: usize = 100000;
+/// 录制中相邻鼠标移动的最小间隔，更密集的移动会被跳过以免系统过载
+const MIN_MOUSE_MOVE_INTERVAL_MS: u64 = 5;
+/// 第一个事件前的等待，让系统稳定下来
+const INITIAL_DELAY_MS: u64 = 50;
+const MAX_DELAY_MS: u64 = 60000;
+/// 回放速度倍数的范围
+pub const MIN_SPEED: f32 = 0.1;
+pub const MAX_SPEED: f32 = 10.0;
+/// 等待时检查停止的间隔
+pub(crate) const CANCEL_CHECK_MS: u64 = 50;
 
 pub struct ReplayState {
     pub is_playing: bool,
-    pub current_events: Vec<RecordedEvent>,
+    pub current_events: Vec<TimedEvent>,
     pub current_index: usize,
     pub speed_multiplier: f32,
+    /// 回放时的屏幕范围，归一化坐标按它换算
+    pub screen: ScreenGeometry,
+    /// 总共播放几遍，至少 1
+    pub repeat_count: u32,
+    /// 当前是第几遍（从 0 开始）
+    pub current_repeat: u32,
 }
 
 impl ReplayState {
@@ -17,26 +37,17 @@ impl ReplayState {
             current_events: Vec::new(),
             current_index: 0,
             speed_multiplier: 1.0,
+            screen: ScreenGeometry::default(),
+            repeat_count: 1,
+            current_repeat: 0,
         }
     }
 
     pub fn load_recording<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
-        let content =
-            fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
-
-        // Parse JSON - the file contains {events: [...], duration_ms: ..., created_at: ...}
-        let json: serde_json::Value =
-            serde_json::from_str(&content).map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
-        // Extract events array
-        self.current_events = json["events"]
-            .as_array()
-            .ok_or_else(|| "Missing or invalid 'events' field in recording file".to_string())?
-            .iter()
-            .map(|v| serde_json::from_value(v.clone()))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to parse events: {}", e))?;
-
+        // Older formats are upgraded by the loader
+        let recording = recording::load_recording(path.as_ref())?;
+        self.current_events = recording.events;
+        self.screen = ScreenGeometry::current();
         self.current_index = 0;
         Ok(())
     }
@@ -44,6 +55,7 @@ impl ReplayState {
     pub fn start(&mut self, speed: f32) {
         self.is_playing = true;
         self.current_index = 0;
+        self.current_repeat = 0;
         self.speed_multiplier = speed;
     }
 
@@ -56,10 +68,22 @@ impl ReplayState {
         if self.current_events.is_empty() {
             return 0.0;
         }
-        (self.current_index as f32 / self.current_events.len() as f32) * 100.0
+        let total = self.current_events.len() * self.repeat_count.max(1) as usize;
+        let done = self.current_repeat as usize * self.current_events.len() + self.current_index;
+        (done as f32 / total as f32) * 100.0
+    }
+
+    /// 一遍播放完后开始下一遍，已经是最后一遍时返回 false
+    pub fn next_repeat(&mut self) -> bool {
+        if self.current_repeat + 1 >= self.repeat_count {
+            return false;
+        }
+        self.current_repeat += 1;
+        self.current_index = 0;
+        true
     }
 
-    pub fn get_next_event(&mut self) -> Option<RecordedEvent> {
+    pub fn get_next_event(&mut self) -> Option<TimedEvent> {
         if self.current_index < self.current_events.len() {
             let event = self.current_events[self.current_index].clone();
             self.current_index += 1;
@@ -68,169 +92,339 @@ impl ReplayState {
             None
         }
     }
+}
 
-    pub fn execute_event(event: &RecordedEvent) -> Result<(), String> {
-        #[cfg(target_os = "windows")]
+impl Default for ReplayState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 已按下还没松开的键和鼠标键，停止时据此松开，避免它们一直按着
+#[derive(Debug, Default)]
+pub(crate) struct HeldInput {
+    keys: Vec<KeyCode>,
+    buttons: Vec<MouseButton>,
+}
+
+impl HeldInput {
+    /// 记录一个已执行的事件
+    pub(crate) fn track(&mut self, event: &InputEvent) {
+        match event {
+            InputEvent::KeyDown { key } => {
+                self.keys.retain(|k| k != key);
+                self.keys.push(key.clone());
+            }
+            InputEvent::KeyUp { key } => self.keys.retain(|k| k != key),
+            InputEvent::MouseDown { button, .. } => {
+                self.buttons.retain(|b| b != button);
+                self.buttons.push(*button);
+            }
+            InputEvent::MouseUp { button, .. } => self.buttons.retain(|b| b != button),
+            _ => {}
+        }
+    }
+
+    /// 取出松开所有按着的键所需的事件：先松开鼠标键，再按与按下相反的顺序松开键
+    pub(crate) fn take_releases(&mut self) -> Vec<InputEvent> {
+        let buttons = std::mem::take(&mut self.buttons)
+            .into_iter()
+            .rev()
+            .map(|button| InputEvent::MouseUp { button, position: None });
+        let keys = std::mem::take(&mut self.keys)
+            .into_iter()
+            .rev()
+            .map(|key| InputEvent::KeyUp { key });
+        buttons.chain(keys).collect()
+    }
+}
+
+/// 回放结果统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayOutcome {
+    pub executed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    /// 是否被中止（`stop_playback` 或后端报告中止）
+    pub aborted: bool,
+}
+
+/// 通过输入后端执行一个事件，归一化坐标按 `screen` 换算
+pub fn execute_event(
+    backend: &mut dyn InputBackend,
+    event: &InputEvent,
+    screen: &ScreenGeometry,
+) -> Result<(), String> {
+    match event {
+        InputEvent::MouseMove { position } => {
+            let (x, y) = screen.denormalize(*position);
+            backend.move_to(x, y)
+        }
+        InputEvent::MouseDown { button, position } | InputEvent::MouseUp { button, position } => {
+            if let Some(position) = position {
+                let (x, y) = screen.denormalize(*position);
+                backend.move_to(x, y)?;
+            }
+            backend.mouse_button(*button, matches!(event, InputEvent::MouseDown { .. }))
+        }
+        InputEvent::MouseWheel { delta } => backend.wheel(*delta),
+        InputEvent::KeyDown { key } => backend.key(key, true),
+        InputEvent::KeyUp { key } => backend.key(key, false),
+    }
+}
+
+/// 事件之间的等待时间，按速度倍数缩放
+fn replay_delay_ms(previous_offset: Option<u64>, offset: u64, speed: f32) -> u64 {
+    match previous_offset {
+        None => INITIAL_DELAY_MS,
+        Some(previous) => {
+            let diff = offset.saturating_sub(previous);
+            ((diff as f32 / speed) as u64).clamp(1, MAX_DELAY_MS)
+        }
+    }
+}
+
+/// 分段等待，每段之间检查停止和中止；被打断时返回 false
+fn wait_until_stopped(state: &Mutex<ReplayState>, backend: &mut dyn InputBackend, ms: u64) -> bool {
+    let mut remaining = ms;
+    while remaining > 0 {
+        let slice = remaining.min(CANCEL_CHECK_MS);
+        backend.wait(Duration::from_millis(slice));
+        remaining -= slice;
+        if remaining > 0
+            && (backend.abort_requested() || state.lock().map(|s| !s.is_playing).unwrap_or(true))
         {
-            use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
-                SendInput, INPUT, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYEVENTF_KEYUP,
-                MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN,
-                MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP,
-                MOUSEEVENTF_WHEEL, MOUSEINPUT,
+            return false;
+        }
+    }
+    true
+}
+
+/// 按 `state` 中已加载的事件回放，直到播放完、被停止或后端报告中止。
+/// 每个事件前和等待期间都会检查停止状态，结束时松开仍按着的键和鼠标键。
+/// 进度可通过 `ReplayState::get_progress` 查询。
+pub fn run_replay(state: &Mutex<ReplayState>, backend: &mut dyn InputBackend) -> ReplayOutcome {
+    let mut outcome = ReplayOutcome::default();
+    let mut held = HeldInput::default();
+    let mut last_time: Option<u64> = None;
+    let mut last_mouse_move_time: Option<u64> = None;
+
+    let (speed, screen) = match state.lock() {
+        Ok(state) => (state.speed_multiplier.clamp(MIN_SPEED, MAX_SPEED), state.screen),
+        Err(_) => return outcome,
+    };
+
+    let stop = |state: &Mutex<ReplayState>| {
+        if let Ok(mut state) = state.lock() {
+            state.stop();
+        }
+    };
+
+    loop {
+        if backend.abort_requested() {
+            eprintln!("Playback aborted by user");
+            stop(state);
+            outcome.aborted = true;
+            break;
+        }
+
+        // Safety check: prevent runaway playback
+        if outcome.executed + outcome.skipped + outcome.failed >= MAX_REPLAY_EVENTS {
+            eprintln!("Reached maximum event limit, stopping playback");
+            stop(state);
+            break;
+        }
+
+        // Get event while holding lock briefly
+        let event = {
+            let mut state = match state.lock() {
+                Ok(s) => s,
+                Err(_) => break,
             };
-            use windows_sys::Win32::UI::WindowsAndMessaging::SetCursorPos;
-
-            unsafe {
-                match &event.event_type {
-                    EventType::MouseMove => {
-                        if let (Some(x), Some(y)) = (event.x, event.y) {
-                            // Validate coordinates are within screen bounds
-                            if x < -32768 || x > 32767 || y < -32768 || y > 32767 {
-                                return Err(format!("Invalid mouse coordinates: ({}, {})", x, y));
-                            }
-                            if SetCursorPos(x, y) == 0 {
-                                return Err("Failed to move cursor".to_string());
-                            }
-                        }
-                    }
-                    EventType::MouseDown { button } => {
-                        let flags = match button {
-                            MouseButton::Left => MOUSEEVENTF_LEFTDOWN,
-                            MouseButton::Right => MOUSEEVENTF_RIGHTDOWN,
-                            MouseButton::Middle => MOUSEEVENTF_MIDDLEDOWN,
-                        };
-
-                        let mut input = INPUT {
-                            r#type: INPUT_MOUSE,
-                            Anonymous: windows_sys::Win32::UI::Input::KeyboardAndMouse::INPUT_0 {
-                                mi: MOUSEINPUT {
-                                    dx: 0,
-                                    dy: 0,
-                                    mouseData: 0,
-                                    dwFlags: flags,
-                                    time: 0,
-                                    dwExtraInfo: 0,
-                                },
-                            },
-                        };
-
-                        if SendInput(1, &mut input, std::mem::size_of::<INPUT>() as i32) == 0 {
-                            return Err("Failed to send mouse down event".to_string());
-                        }
-                    }
-                    EventType::MouseUp { button } => {
-                        let flags = match button {
-                            MouseButton::Left => MOUSEEVENTF_LEFTUP,
-                            MouseButton::Right => MOUSEEVENTF_RIGHTUP,
-                            MouseButton::Middle => MOUSEEVENTF_MIDDLEUP,
-                        };
-
-                        let mut input = INPUT {
-                            r#type: INPUT_MOUSE,
-                            Anonymous: windows_sys::Win32::UI::Input::KeyboardAndMouse::INPUT_0 {
-                                mi: MOUSEINPUT {
-                                    dx: 0,
-                                    dy: 0,
-                                    mouseData: 0,
-                                    dwFlags: flags,
-                                    time: 0,
-                                    dwExtraInfo: 0,
-                                },
-                            },
-                        };
-
-                        if SendInput(1, &mut input, std::mem::size_of::<INPUT>() as i32) == 0 {
-                            return Err("Failed to send mouse up event".to_string());
-                        }
-                    }
-                    EventType::MouseWheel { delta } => {
-                        let mut input = INPUT {
-                            r#type: INPUT_MOUSE,
-                            Anonymous: windows_sys::Win32::UI::Input::KeyboardAndMouse::INPUT_0 {
-                                mi: MOUSEINPUT {
-                                    dx: 0,
-                                    dy: 0,
-                                    mouseData: (*delta as u32) << 16,
-                                    dwFlags: MOUSEEVENTF_WHEEL,
-                                    time: 0,
-                                    dwExtraInfo: 0,
-                                },
-                            },
-                        };
-
-                        if SendInput(1, &mut input, std::mem::size_of::<INPUT>() as i32) == 0 {
-                            return Err("Failed to send mouse wheel event".to_string());
-                        }
-                    }
-                    EventType::KeyDown { vk_code } => {
-                        // Validate virtual key code
-                        if *vk_code > 255 {
-                            return Err(format!("Invalid virtual key code: {}", vk_code));
-                        }
-
-                        let mut input = INPUT {
-                            r#type: INPUT_KEYBOARD,
-                            Anonymous: windows_sys::Win32::UI::Input::KeyboardAndMouse::INPUT_0 {
-                                ki: KEYBDINPUT {
-                                    wVk: *vk_code as u16,
-                                    wScan: 0,
-                                    dwFlags: 0,
-                                    time: 0,
-                                    dwExtraInfo: 0,
-                                },
-                            },
-                        };
-
-                        let result = SendInput(1, &mut input, std::mem::size_of::<INPUT>() as i32);
-                        if result == 0 {
-                            return Err(format!(
-                                "Failed to send key down event for VK code: {}",
-                                vk_code
-                            ));
-                        }
-                    }
-                    EventType::KeyUp { vk_code } => {
-                        // Validate virtual key code
-                        if *vk_code > 255 {
-                            return Err(format!("Invalid virtual key code: {}", vk_code));
-                        }
-
-                        let mut input = INPUT {
-                            r#type: INPUT_KEYBOARD,
-                            Anonymous: windows_sys::Win32::UI::Input::KeyboardAndMouse::INPUT_0 {
-                                ki: KEYBDINPUT {
-                                    wVk: *vk_code as u16,
-                                    wScan: 0,
-                                    dwFlags: KEYEVENTF_KEYUP,
-                                    time: 0,
-                                    dwExtraInfo: 0,
-                                },
-                            },
-                        };
-
-                        let result = SendInput(1, &mut input, std::mem::size_of::<INPUT>() as i32);
-                        if result == 0 {
-                            return Err(format!(
-                                "Failed to send key up event for VK code: {}",
-                                vk_code
-                            ));
-                        }
-                    }
+            if !state.is_playing {
+                outcome.aborted = true;
+                break;
+            }
+            state.get_next_event()
+        };
+
+        let Some(event) = event else {
+            // End of this pass: start the next repeat or stop playback
+            if state.lock().map(|mut s| s.next_repeat()).unwrap_or(false) {
+                last_time = None;
+                last_mouse_move_time = None;
+                continue;
+            }
+            stop(state);
+            break;
+        };
+
+        // Skip mouse moves recorded too close together (based on recorded times, not system time)
+        if matches!(event.event, InputEvent::MouseMove { .. }) {
+            if let Some(previous) = last_mouse_move_time {
+                let time_diff = event.time_offset_ms.saturating_sub(previous);
+                if time_diff > 0 && time_diff < MIN_MOUSE_MOVE_INTERVAL_MS {
+                    last_time = Some(event.time_offset_ms);
+                    outcome.skipped += 1;
+                    continue;
                 }
             }
+            last_mouse_move_time = Some(event.time_offset_ms);
         }
 
-        #[cfg(not(target_os = "windows"))]
+        let delay = replay_delay_ms(last_time, event.time_offset_ms, speed);
+        // Stop may have been requested while waiting
+        if !wait_until_stopped(state, backend, delay)
+            || state.lock().map(|s| !s.is_playing).unwrap_or(true)
         {
-            return Err("Replay is only supported on Windows".to_string());
+            stop(state);
+            outcome.aborted = true;
+            break;
         }
 
-        Ok(())
+        match execute_event(backend, &event.event, &screen) {
+            Ok(_) => {
+                held.track(&event.event);
+                outcome.executed += 1;
+            }
+            Err(e) => {
+                // Continue with next event instead of crashing
+                eprintln!("Failed to execute event: {}", e);
+                outcome.failed += 1;
+            }
+        }
+
+        last_time = Some(event.time_offset_ms);
     }
+
+    // 中止或停止时可能还有键按着，不松开的话会一直按下去
+    for event in held.take_releases() {
+        if let Err(e) = execute_event(backend, &event, &screen) {
+            eprintln!("Failed to release held input: {}", e);
+        }
+    }
+
+    outcome
 }
 
-impl Default for ReplayState {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_backend::mock::{MockAction, MockBackend};
+    use crate::recording::{KeyCode, MouseButton, Position};
+
+    fn timed(time_offset_ms: u64, event: InputEvent) -> TimedEvent {
+        TimedEvent { time_offset_ms, event }
+    }
+
+    fn playing_state(events: Vec<TimedEvent>, speed: f32) -> Mutex<ReplayState> {
+        let mut state = ReplayState::new();
+        state.current_events = events;
+        state.screen = ScreenGeometry { x: 0, y: 0, width: 1001, height: 501 };
+        state.start(speed);
+        Mutex::new(state)
+    }
+
+    fn sample_events() -> Vec<TimedEvent> {
+        vec![
+            timed(0, InputEvent::MouseMove { position: Position { x: 0.5, y: 0.5 } }),
+            timed(
+                100,
+                InputEvent::MouseDown { button: MouseButton::Left, position: None },
+            ),
+            timed(300, InputEvent::KeyDown { key: KeyCode::from_vk(0x41, None) }),
+            timed(700, InputEvent::KeyUp { key: KeyCode::from_vk(0x41, None) }),
+        ]
+    }
+
+    #[test]
+    fn test_replay_timing_and_denormalization() {
+        let state = playing_state(sample_events(), 1.0);
+        let mut backend = MockBackend::default();
+        let outcome = run_replay(&state, &mut backend);
+
+        assert_eq!(outcome, ReplayOutcome { executed: 4, ..Default::default() });
+        assert_eq!(
+            backend.actions,
+            vec![
+                (50, MockAction::MoveTo(500, 250)),
+                (150, MockAction::Button(MouseButton::Left, true)),
+                (350, MockAction::Key("A".to_string(), true)),
+                (750, MockAction::Key("A".to_string(), false)),
+                // 录制结束时鼠标左键仍按着，回放结束后松开
+                (750, MockAction::Button(MouseButton::Left, false)),
+            ]
+        );
+        assert!(!state.lock().unwrap().is_playing);
+    }
+
+    #[test]
+    fn test_replay_speed_scaling_and_move_throttling() {
+        let mut events = sample_events();
+        // 2ms 后的移动会被跳过
+        events.insert(1, timed(2, InputEvent::MouseMove { position: Position { x: 0.0, y: 0.0 } }));
+        let state = playing_state(events, 2.0);
+        let mut backend = MockBackend::default();
+        let outcome = run_replay(&state, &mut backend);
+
+        assert_eq!(outcome.executed, 4);
+        assert_eq!(outcome.skipped, 1);
+        let times: Vec<u64> = backend.actions.iter().map(|(t, _)| *t).collect();
+        assert_eq!(times, vec![50, 99, 199, 399, 399]);
+    }
+
+    #[test]
+    fn test_replay_cancellation() {
+        // 后端报告中止（Esc）
+        let state = playing_state(sample_events(), 1.0);
+        let mut backend = MockBackend { abort_on_check: Some(4), ..Default::default() };
+        let outcome = run_replay(&state, &mut backend);
+        assert!(outcome.aborted);
+        assert_eq!(backend.actions.len(), 3);
+        // 中止前按下的鼠标键被松开
+        assert_eq!(backend.actions[2], (150, MockAction::Button(MouseButton::Left, false)));
+        assert!(!state.lock().unwrap().is_playing);
+
+        // 长时间等待中也能及时中止，并松开按着的键
+        let state = playing_state(
+            vec![
+                timed(0, InputEvent::KeyDown { key: KeyCode::from_vk(0x10, None) }),
+                timed(60000, InputEvent::KeyUp { key: KeyCode::from_vk(0x10, None) }),
+            ],
+            1.0,
+        );
+        let mut backend = MockBackend { abort_on_check: Some(5), ..Default::default() };
+        let outcome = run_replay(&state, &mut backend);
+        assert!(outcome.aborted);
+        assert_eq!(
+            backend.actions,
+            vec![
+                (50, MockAction::Key("Shift".to_string(), true)),
+                (200, MockAction::Key("Shift".to_string(), false)),
+            ]
+        );
+
+        // stop_playback 在回放前已停止
+        let state = playing_state(sample_events(), 1.0);
+        state.lock().unwrap().stop();
+        let mut backend = MockBackend::default();
+        let outcome = run_replay(&state, &mut backend);
+        assert!(outcome.aborted);
+        assert!(backend.actions.is_empty());
+    }
+
+    #[test]
+    fn test_replay_repeats() {
+        let state = playing_state(sample_events(), 1.0);
+        state.lock().unwrap().repeat_count = 3;
+        assert_eq!(state.lock().unwrap().get_progress(), 0.0);
+        let mut backend = MockBackend::default();
+        let outcome = run_replay(&state, &mut backend);
+
+        assert_eq!(outcome.executed, 12);
+        // 每一遍都重新从初始延迟开始计时
+        let times: Vec<u64> = backend.actions.iter().map(|(t, _)| *t).collect();
+        assert_eq!(&times[3..6], &[750, 800, 900]);
+        assert!(!state.lock().unwrap().is_playing);
     }
 }
@@ -0,0 +1,776 @@
+//! 语义搜索
+//!
+//! 为备忘录、剪切板文本和单词本建立两套索引：
+//! - `embeddings`：按 (source, item_id) 存储向量和内容哈希，内容或模型变化时重新计算
+//! - `search_fts`：FTS5 全文索引（trigram 分词）
+//!
+//! 查询时把向量余弦相似度与 FTS 的 bm25 分数归一化后加权混合排序；
+//! 向量服务不可用时退化为纯关键字搜索。后台线程定期增量同步，
+//! 数据变化时可调用 `notify_changed` 立即唤醒。
+
+use crate::db;
+use crate::ollama::OllamaClient;
+use crate::settings;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const SOURCE_MEMO: &str = "memo";
+pub const SOURCE_CLIPBOARD: &str = "clipboard";
+pub const SOURCE_WORD: &str = "word";
+
+/// 参与向量计算的最大字符数，过长的内容只取开头
+const MAX_EMBED_CHARS: usize = 2000;
+/// 搜索结果摘要长度
+const SNIPPET_CHARS: usize = 120;
+/// trigram 分词要求关键字至少 3 个字符
+const MIN_FTS_TOKEN_CHARS: usize = 3;
+const DEFAULT_VECTOR_WEIGHT: f32 = 0.6;
+
+/// 启动后首次同步前的等待，避免拖慢启动
+const INDEXER_START_DELAY: Duration = Duration::from_secs(15);
+const INDEXER_INTERVAL: Duration = Duration::from_secs(120);
+const INDEXER_DEBOUNCE: Duration = Duration::from_secs(5);
+/// 向量服务不可用时的重试间隔
+const INDEXER_RETRY_INTERVAL: Duration = Duration::from_secs(600);
+
+static INDEXER_WAKE: LazyLock<(Mutex<bool>, Condvar)> =
+    LazyLock::new(|| (Mutex::new(false), Condvar::new()));
+
+/// 计算文本向量
+pub trait Embedder {
+    fn model(&self) -> &str;
+    fn embed(&self, text: &str) -> Result<Vec<f32>, String>;
+}
+
+/// 在后台线程中同步调用 Ollama
+pub struct OllamaEmbedder {
+    client: OllamaClient,
+    model: String,
+}
+
+impl OllamaEmbedder {
+    pub fn from_settings(app_data_dir: &Path) -> Result<Self, String> {
+        let settings = settings::load_settings(app_data_dir)?;
+        Ok(Self {
+            client: OllamaClient::from_settings(&settings.ollama)?,
+            model: settings.ollama.embedding_model,
+        })
+    }
+}
+
+impl Embedder for OllamaEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed(&self, text: &str) -> Result<Vec<f32>, String> {
+        tauri::async_runtime::block_on(self.client.embed(text, &self.model))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticSearchOptions {
+    /// 为空时搜索全部来源
+    #[serde(default)]
+    pub sources: Vec<String>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    /// 向量分数的权重（0–1），其余为关键字分数
+    #[serde(default = "default_vector_weight")]
+    pub vector_weight: f32,
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+fn default_vector_weight() -> f32 {
+    DEFAULT_VECTOR_WEIGHT
+}
+
+impl Default for SemanticSearchOptions {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            limit: default_limit(),
+            vector_weight: default_vector_weight(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticHit {
+    pub source: String,
+    pub item_id: String,
+    pub snippet: String,
+    pub score: f32,
+    pub vector_score: Option<f32>,
+    pub keyword_score: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSyncSummary {
+    pub embedded: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+    /// 因向量服务出错而跳过的条目，下次同步时重试
+    pub pending: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStatus {
+    pub model: Option<String>,
+    /// 各来源已计算向量的条目数
+    pub embedded: HashMap<String, usize>,
+    pub indexed: usize,
+}
+
+struct IndexDocument {
+    source: &'static str,
+    item_id: String,
+    content: String,
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 数据变化时唤醒后台索引线程
+pub fn notify_changed() {
+    let (lock, cvar) = &*INDEXER_WAKE;
+    if let Ok(mut dirty) = lock.lock() {
+        *dirty = true;
+        cvar.notify_all();
+    }
+}
+
+pub fn content_hash(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest.iter().take(16).map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn vector_to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub fn blob_to_vector(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+/// 余弦相似度，维度不一致或零向量时为 0
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// 混合分数：负的余弦相似度按 0 计
+pub fn hybrid_score(vector: Option<f32>, keyword: Option<f32>, vector_weight: f32) -> f32 {
+    let w = vector_weight.clamp(0.0, 1.0);
+    w * vector.unwrap_or(0.0).max(0.0) + (1.0 - w) * keyword.unwrap_or(0.0)
+}
+
+/// 把查询拆成 FTS5 短语；太短的词无法用 trigram 匹配，返回 None
+pub fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|t| t.chars().count() >= MIN_FTS_TOKEN_CHARS)
+        .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+fn collect_documents(conn: &rusqlite::Connection) -> Result<Vec<IndexDocument>, String> {
+    let queries: [(&'static str, &str); 3] = [
+        (SOURCE_MEMO, "SELECT id, title || char(10) || content FROM memos"),
+        (
+            SOURCE_CLIPBOARD,
+            "SELECT id, content FROM clipboard_history WHERE content_type = 'text'",
+        ),
+        (
+            SOURCE_WORD,
+            "SELECT id, word || char(10) || translation || char(10) || coalesce(context, '')
+                    || char(10) || coalesce(ai_explanation, '')
+             FROM word_records",
+        ),
+    ];
+    let mut docs = Vec::new();
+    for (source, sql) in queries {
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| format!("Failed to prepare {} index query: {}", source, e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| format!("Failed to query {} for index: {}", source, e))?;
+        for row in rows {
+            let (item_id, content) =
+                row.map_err(|e| format!("Failed to read {} row: {}", source, e))?;
+            let content = content.trim().to_string();
+            if !content.is_empty() {
+                docs.push(IndexDocument { source, item_id, content });
+            }
+        }
+    }
+    Ok(docs)
+}
+
+fn upsert_fts(conn: &rusqlite::Connection, doc: &IndexDocument, hash: &str) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM search_fts WHERE source = ?1 AND item_id = ?2",
+        params![doc.source, doc.item_id],
+    )
+    .and_then(|_| {
+        conn.execute(
+            "INSERT INTO search_fts (source, item_id, content) VALUES (?1, ?2, ?3)",
+            params![doc.source, doc.item_id, doc.content],
+        )
+    })
+    .and_then(|_| {
+        conn.execute(
+            "INSERT OR REPLACE INTO search_fts_state (source, item_id, content_hash) VALUES (?1, ?2, ?3)",
+            params![doc.source, doc.item_id, hash],
+        )
+    })
+    .map(|_| ())
+    .map_err(|e| format!("Failed to update search index: {}", e))
+}
+
+/// 读取 (source, item_id) -> 若干列
+fn load_index_keys<T>(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    value: impl Fn(&rusqlite::Row) -> rusqlite::Result<T>,
+) -> Result<HashMap<(String, String), T>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Failed to prepare index query: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(((row.get::<_, String>(0)?, row.get::<_, String>(1)?), value(row)?))
+        })
+        .map_err(|e| format!("Failed to query index: {}", e))?;
+    let mut map = HashMap::new();
+    for row in rows {
+        let (key, value) = row.map_err(|e| format!("Failed to read index row: {}", e))?;
+        map.insert(key, value);
+    }
+    Ok(map)
+}
+
+/// 增量同步索引：删除已不存在的条目，更新变化的关键字索引，为新增或变化的条目计算向量。
+///
+/// 关键字索引与向量分别记录内容哈希；向量服务出错时停止计算（关键字索引仍会更新），下次同步时重试。
+/// 向量在事务外计算，避免慢速的向量服务长时间占用写锁；所有写入在同一个事务中提交。
+pub fn sync_index(embedder: &dyn Embedder, app_data_dir: &Path) -> Result<IndexSyncSummary, String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    let docs = collect_documents(&conn)?;
+    let model = embedder.model().to_string();
+
+    let embedded: HashMap<(String, String), (String, String)> = load_index_keys(
+        &conn,
+        "SELECT source, item_id, content_hash, model FROM embeddings",
+        |row| Ok((row.get(2)?, row.get(3)?)),
+    )?;
+    let fts_hashes: HashMap<(String, String), String> = load_index_keys(
+        &conn,
+        "SELECT source, item_id, content_hash FROM search_fts_state",
+        |row| row.get(2),
+    )?;
+    // 没有哈希记录的旧索引行也要参与清理
+    let fts_keys: HashMap<(String, String), ()> =
+        load_index_keys(&conn, "SELECT source, item_id FROM search_fts", |_| Ok(()))?;
+
+    let mut summary = IndexSyncSummary::default();
+    let mut fts_updates: Vec<(&IndexDocument, String)> = Vec::new();
+    let mut vectors: Vec<(&IndexDocument, String, Vec<f32>)> = Vec::new();
+    for doc in &docs {
+        let hash = content_hash(&doc.content);
+        let key = (doc.source.to_string(), doc.item_id.clone());
+        if fts_hashes.get(&key) != Some(&hash) {
+            fts_updates.push((doc, hash.clone()));
+        }
+        if matches!(embedded.get(&key), Some((h, m)) if *h == hash && *m == model) {
+            summary.unchanged += 1;
+            continue;
+        }
+
+        if summary.error.is_some() {
+            summary.pending += 1;
+            continue;
+        }
+        match embedder.embed(truncate_chars(&doc.content, MAX_EMBED_CHARS)) {
+            Ok(vector) => vectors.push((doc, hash, vector)),
+            Err(e) => {
+                summary.error = Some(e);
+                summary.pending += 1;
+            }
+        }
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start index transaction: {}", e))?;
+    let live: HashSet<(&str, &str)> = docs.iter().map(|d| (d.source, d.item_id.as_str())).collect();
+    let indexed: HashSet<&(String, String)> = embedded
+        .keys()
+        .chain(fts_hashes.keys())
+        .chain(fts_keys.keys())
+        .collect();
+    for (source, item_id) in indexed {
+        if live.contains(&(source.as_str(), item_id.as_str())) {
+            continue;
+        }
+        for table in ["embeddings", "search_fts", "search_fts_state"] {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE source = ?1 AND item_id = ?2", table),
+                params![source, item_id],
+            )
+            .map_err(|e| format!("Failed to remove stale index entry: {}", e))?;
+        }
+        summary.removed += 1;
+    }
+    for (doc, hash) in fts_updates {
+        upsert_fts(&tx, doc, &hash)?;
+    }
+    for (doc, hash, vector) in vectors {
+        tx.execute(
+            "INSERT OR REPLACE INTO embeddings
+                (source, item_id, content_hash, model, dim, vector, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                doc.source,
+                doc.item_id,
+                hash,
+                model,
+                vector.len() as i64,
+                vector_to_blob(&vector),
+                now_ts() as i64
+            ],
+        )
+        .map_err(|e| format!("Failed to save embedding: {}", e))?;
+        summary.embedded += 1;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit index update: {}", e))?;
+    Ok(summary)
+}
+
+fn source_filter(sources: &[String]) -> impl Fn(&str) -> bool + '_ {
+    move |source| sources.is_empty() || sources.iter().any(|s| s == source)
+}
+
+/// 混合搜索。`query_vector` 为 None 时只使用关键字分数。
+pub fn search(
+    query: &str,
+    query_vector: Option<&[f32]>,
+    model: &str,
+    options: &SemanticSearchOptions,
+    app_data_dir: &Path,
+) -> Result<Vec<SemanticHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let wanted = source_filter(&options.sources);
+    let mut hits: HashMap<(String, String), (Option<f32>, Option<f32>)> = HashMap::new();
+
+    if let Some(query_vector) = query_vector {
+        let mut stmt = conn
+            .prepare("SELECT source, item_id, vector FROM embeddings WHERE model = ?1 AND dim = ?2")
+            .map_err(|e| format!("Failed to prepare vector search: {}", e))?;
+        let rows = stmt
+            .query_map(params![model, query_vector.len() as i64], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Vec<u8>>(2)?))
+            })
+            .map_err(|e| format!("Failed to run vector search: {}", e))?;
+        let mut scored = Vec::new();
+        for row in rows {
+            let (source, item_id, blob) =
+                row.map_err(|e| format!("Failed to read embedding row: {}", e))?;
+            if wanted(&source) {
+                let score = cosine_similarity(query_vector, &blob_to_vector(&blob));
+                scored.push(((source, item_id), score));
+            }
+        }
+        // 只保留向量最相近的候选，避免全部条目都出现在结果中
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        for (key, score) in scored.into_iter().take(options.limit.max(1) * 3) {
+            hits.entry(key).or_default().0 = Some(score);
+        }
+    }
+
+    // 关键字分数：bm25 越小越相关，归一化到 0–1
+    let mut keyword: Vec<((String, String), f64)> = Vec::new();
+    if let Some(fts) = fts_query(query) {
+        let mut stmt = conn
+            .prepare(
+                "SELECT source, item_id, bm25(search_fts) FROM search_fts
+                 WHERE search_fts MATCH ?1 ORDER BY bm25(search_fts) LIMIT 200",
+            )
+            .map_err(|e| format!("Failed to prepare keyword search: {}", e))?;
+        let rows = stmt
+            .query_map(params![fts], |row| {
+                Ok(((row.get::<_, String>(0)?, row.get::<_, String>(1)?), row.get::<_, f64>(2)?))
+            })
+            .map_err(|e| format!("Failed to run keyword search: {}", e))?;
+        for row in rows {
+            keyword.push(row.map_err(|e| format!("Failed to read keyword row: {}", e))?);
+        }
+    } else {
+        // 查询太短，退化为子串匹配
+        let mut stmt = conn
+            .prepare("SELECT source, item_id FROM search_fts WHERE instr(lower(content), lower(?1)) > 0 LIMIT 200")
+            .map_err(|e| format!("Failed to prepare keyword search: {}", e))?;
+        let rows = stmt
+            .query_map(params![query], |row| Ok(((row.get(0)?, row.get(1)?), -1.0)))
+            .map_err(|e| format!("Failed to run keyword search: {}", e))?;
+        for row in rows {
+            keyword.push(row.map_err(|e| format!("Failed to read keyword row: {}", e))?);
+        }
+    }
+    keyword.retain(|((source, _), _)| wanted(source));
+    let best = keyword.iter().map(|(_, s)| *s).fold(f64::INFINITY, f64::min);
+    let worst = keyword.iter().map(|(_, s)| *s).fold(f64::NEG_INFINITY, f64::max);
+    for (key, rank) in keyword {
+        let score = if worst > best { (worst - rank) / (worst - best) } else { 1.0 };
+        // 命中关键字的条目至少得 0.5，排在只有弱向量相似度的条目前面
+        hits.entry(key).or_default().1 = Some(0.5 + 0.5 * score as f32);
+    }
+
+    let mut results: Vec<SemanticHit> = hits
+        .into_iter()
+        .map(|((source, item_id), (vector_score, keyword_score))| SemanticHit {
+            score: hybrid_score(vector_score, keyword_score, options.vector_weight),
+            source,
+            item_id,
+            snippet: String::new(),
+            vector_score,
+            keyword_score,
+        })
+        .collect();
+    results.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.item_id.cmp(&b.item_id)));
+    results.truncate(options.limit);
+
+    for hit in &mut results {
+        let content: Option<String> = conn
+            .query_row(
+                "SELECT content FROM search_fts WHERE source = ?1 AND item_id = ?2",
+                params![hit.source, hit.item_id],
+                |row| row.get(0),
+            )
+            .ok();
+        if let Some(content) = content {
+            hit.snippet = truncate_chars(&content.replace('\n', " "), SNIPPET_CHARS).to_string();
+        }
+    }
+    Ok(results)
+}
+
+pub fn get_index_status(app_data_dir: &Path) -> Result<IndexStatus, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let mut status = IndexStatus::default();
+    let mut stmt = conn
+        .prepare("SELECT source, model, COUNT(*) FROM embeddings GROUP BY source, model")
+        .map_err(|e| format!("Failed to prepare index status query: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })
+        .map_err(|e| format!("Failed to query index status: {}", e))?;
+    for row in rows {
+        let (source, model, count) = row.map_err(|e| format!("Failed to read index status: {}", e))?;
+        *status.embedded.entry(source).or_default() += count.max(0) as usize;
+        status.model = Some(model);
+    }
+    status.indexed = conn
+        .query_row("SELECT COUNT(*) FROM search_fts", [], |row| row.get::<_, i64>(0))
+        .map(|v| v.max(0) as usize)
+        .map_err(|e| format!("Failed to count index entries: {}", e))?;
+    Ok(status)
+}
+
+/// 启动后台索引线程
+pub fn start_indexer(app_data_dir: PathBuf) {
+    std::thread::spawn(move || {
+        std::thread::sleep(INDEXER_START_DELAY);
+        loop {
+            let wait = match OllamaEmbedder::from_settings(&app_data_dir)
+                .and_then(|embedder| sync_index(&embedder, &app_data_dir))
+            {
+                Ok(summary) => {
+                    if let Some(ref e) = summary.error {
+                        eprintln!("[SemanticSearch] Embedding unavailable, {} items pending: {}", summary.pending, e);
+                        INDEXER_RETRY_INTERVAL
+                    } else {
+                        INDEXER_INTERVAL
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[SemanticSearch] Failed to sync index: {}", e);
+                    INDEXER_RETRY_INTERVAL
+                }
+            };
+
+            let woken = {
+                let (lock, cvar) = &*INDEXER_WAKE;
+                let mut dirty = lock.lock().unwrap_or_else(|e| e.into_inner());
+                if !*dirty {
+                    dirty = cvar
+                        .wait_timeout(dirty, wait)
+                        .map(|(guard, _)| guard)
+                        .unwrap_or_else(|e| e.into_inner().0);
+                }
+                std::mem::replace(&mut *dirty, false)
+            };
+            // 连续的变化（如频繁复制）合并为一次同步
+            if woken {
+                std::thread::sleep(INDEXER_DEBOUNCE);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{read_http_request, temp_dir};
+    use std::cell::Cell;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::sync::Arc;
+
+    /// 按字母出现次数生成向量，拼写相近的文本向量也相近
+    struct StubEmbedder {
+        calls: Cell<usize>,
+        fail: bool,
+    }
+
+    impl Embedder for StubEmbedder {
+        fn model(&self) -> &str {
+            "stub"
+        }
+
+        fn embed(&self, text: &str) -> Result<Vec<f32>, String> {
+            if self.fail {
+                return Err("connection refused".to_string());
+            }
+            self.calls.set(self.calls.get() + 1);
+            let mut v = vec![0.0f32; 26];
+            for c in text.to_lowercase().chars().filter(|c| c.is_ascii_lowercase()) {
+                v[(c as u8 - b'a') as usize] += 1.0;
+            }
+            Ok(v)
+        }
+    }
+
+    #[test]
+    fn test_vector_helpers() {
+        let v = vec![0.5f32, -1.25, 3.0];
+        assert_eq!(blob_to_vector(&vector_to_blob(&v)), v);
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+        assert!((hybrid_score(Some(0.8), Some(1.0), 0.5) - 0.9).abs() < 1e-6);
+        assert_eq!(hybrid_score(Some(-0.5), None, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("rust  a \"db\""), Some("\"rust\" OR \"\"\"db\"\"\"".to_string()));
+        assert_eq!(fts_query("ab 中文"), None);
+        assert_eq!(fts_query("数据库"), Some("\"数据库\"".to_string()));
+    }
+
+    #[test]
+    fn test_sync_and_hybrid_search() {
+        let dir = temp_dir("semantic-sync");
+        let conn = db::get_connection(&dir).unwrap();
+        conn.execute_batch(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES
+                ('m1', 'Groceries', 'buy apples and bananas', 0, 0),
+                ('m2', 'Meeting', 'quarterly planning with the team', 0, 0);
+             INSERT INTO clipboard_history (id, content, content_type, created_at) VALUES
+                ('c1', 'banana bread recipe', 'text', 0),
+                ('c2', 'C:/images/shot.png', 'image', 0);",
+        )
+        .unwrap();
+
+        let embedder = StubEmbedder { calls: Cell::new(0), fail: false };
+        let summary = sync_index(&embedder, &dir).unwrap();
+        assert_eq!((summary.embedded, summary.removed), (3, 0));
+
+        // 未变化的条目不会重新计算
+        let summary = sync_index(&embedder, &dir).unwrap();
+        assert_eq!((summary.embedded, summary.unchanged), (0, 3));
+        assert_eq!(embedder.calls.get(), 3);
+
+        let query = embedder.embed("bananas").unwrap();
+        let hits = search("bananas", Some(&query), "stub", &SemanticSearchOptions::default(), &dir).unwrap();
+        assert_eq!(hits[0].item_id, "m1");
+        assert!(hits[0].keyword_score.is_some() && hits[0].vector_score.is_some());
+        assert!(hits.iter().any(|h| h.item_id == "c1"));
+
+        let only_clipboard = SemanticSearchOptions { sources: vec![SOURCE_CLIPBOARD.to_string()], ..Default::default() };
+        let hits = search("banana", None, "stub", &only_clipboard, &dir).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].item_id, "c1");
+
+        // 删除和修改
+        conn.execute_batch(
+            "DELETE FROM memos WHERE id = 'm2';
+             UPDATE clipboard_history SET content = 'sourdough notes' WHERE id = 'c1';",
+        )
+        .unwrap();
+        let summary = sync_index(&embedder, &dir).unwrap();
+        assert_eq!((summary.embedded, summary.removed, summary.unchanged), (1, 1, 1));
+        assert_eq!(get_index_status(&dir).unwrap().indexed, 2);
+    }
+
+    #[test]
+    fn test_sync_keeps_keyword_index_when_embedding_fails() {
+        let dir = temp_dir("semantic-offline");
+        let conn = db::get_connection(&dir).unwrap();
+        conn.execute(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES ('m1', 'Todo', 'renew passport', 0, 0)",
+            [],
+        )
+        .unwrap();
+        let summary = sync_index(&StubEmbedder { calls: Cell::new(0), fail: true }, &dir).unwrap();
+        assert_eq!((summary.embedded, summary.pending), (0, 1));
+        assert!(summary.error.is_some());
+
+        let hits = search("passport", None, "stub", &SemanticSearchOptions::default(), &dir).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].vector_score, None);
+    }
+
+    /// 模拟 Ollama 的 `/api/embeddings`，逐个应答请求并记录收到的请求
+    fn ollama_stub(status: &'static str, requests: Arc<Mutex<Vec<String>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for socket in listener.incoming() {
+                let Ok(mut socket) = socket else { return };
+                requests.lock().unwrap().push(read_http_request(&mut socket));
+                let body = if status.starts_with("200") {
+                    r#"{"embedding":[0.6,0.8]}"#
+                } else {
+                    r#"{"error":"model not found"}"#
+                };
+                let head = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let _ = socket.write_all(head.as_bytes());
+                let _ = socket.write_all(body.as_bytes());
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_sync_with_ollama_embedder() {
+        let dir = temp_dir("semantic-ollama");
+        let conn = db::get_connection(&dir).unwrap();
+        conn.execute(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES ('m1', 'Todo', 'renew passport', 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let embedder = OllamaEmbedder {
+            client: OllamaClient::new(&ollama_stub("200 OK", requests.clone()), "llama3").unwrap(),
+            model: "nomic-embed-text".to_string(),
+        };
+        let summary = sync_index(&embedder, &dir).unwrap();
+        assert_eq!((summary.embedded, summary.pending), (1, 0));
+        assert!(summary.error.is_none());
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("POST /api/embeddings "));
+        assert!(request.contains(r#""model":"nomic-embed-text""#));
+        assert!(request.contains("renew passport"));
+
+        let hits = search("passport", Some(&[0.6, 0.8]), "nomic-embed-text", &SemanticSearchOptions::default(), &dir)
+            .unwrap();
+        assert_eq!(hits[0].item_id, "m1");
+        assert!((hits[0].vector_score.unwrap() - 1.0).abs() < 1e-6);
+
+        // 服务出错时记下错误，新条目留待下次重试，关键字索引照常更新
+        conn.execute(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES ('m2', 'Trip', 'passport photos', 0, 0)",
+            [],
+        )
+        .unwrap();
+        let failing = OllamaEmbedder {
+            client: OllamaClient::new(&ollama_stub("404 Not Found", Arc::default()), "llama3").unwrap(),
+            model: "nomic-embed-text".to_string(),
+        };
+        let summary = sync_index(&failing, &dir).unwrap();
+        assert_eq!((summary.embedded, summary.unchanged, summary.pending), (0, 1, 1));
+        assert!(summary.error.is_some());
+        assert_eq!(get_index_status(&dir).unwrap().indexed, 2);
+    }
+
+    #[test]
+    fn test_sync_removes_deleted_items_without_embeddings() {
+        let dir = temp_dir("semantic-offline-delete");
+        let conn = db::get_connection(&dir).unwrap();
+        conn.execute_batch(
+            "INSERT INTO memos (id, title, content, created_at, updated_at) VALUES
+                ('m1', 'Todo', 'renew passport', 0, 0),
+                ('m2', 'Trip', 'passport photos', 0, 0);",
+        )
+        .unwrap();
+        let embedder = StubEmbedder { calls: Cell::new(0), fail: true };
+        sync_index(&embedder, &dir).unwrap();
+        let fts_rowid = |id: &str| -> i64 {
+            conn.query_row("SELECT rowid FROM search_fts WHERE item_id = ?1", params![id], |row| row.get(0))
+                .unwrap()
+        };
+        let before = fts_rowid("m1");
+
+        conn.execute("DELETE FROM memos WHERE id = 'm2'", []).unwrap();
+        let summary = sync_index(&embedder, &dir).unwrap();
+        assert_eq!(summary.removed, 1);
+
+        let hits = search("passport", None, "stub", &SemanticSearchOptions::default(), &dir).unwrap();
+        assert_eq!(hits.iter().map(|h| h.item_id.as_str()).collect::<Vec<_>>(), vec!["m1"]);
+        // 未变化的条目没有重新写入关键字索引
+        assert_eq!(fts_rowid("m1"), before);
+    }
+}
@@ -1,12 +1,24 @@
 use crate::db;
-use rusqlite::{params, OptionalExtension};
+use crate::settings_service::{self, ChangeSource};
+use rusqlite::{params, OptionalExtension, TransactionBehavior};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// 设置结构的当前版本，旧数据需要迁移时加一并在 `MIGRATIONS` 末尾追加迁移函数
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// 剪切板历史数量上限（0 表示不限制）
+pub const MAX_CLIPBOARD_ITEMS: u32 = 10_000;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Settings {
+    /// 数据库中没有版本号的旧数据视为版本 0；前端提交时省略则为当前版本
+    #[serde(default = "default_settings_version")]
+    pub version: u32,
+    #[serde(default)]
     pub ollama: OllamaSettings,
     #[serde(default)]
     pub startup_enabled: bool,
@@ -20,8 +32,8 @@ pub struct Settings {
     pub app_hotkeys: HashMap<String, HotkeyConfig>,
     #[serde(default = "default_close_on_blur")]
     pub close_on_blur: bool,
-    #[serde(default = "default_result_style")]
-    pub result_style: String,
+    #[serde(default)]
+    pub result_style: ResultStyle,
     #[serde(default = "default_auto_check_update")]
     pub auto_check_update: bool,
     #[serde(default)]
@@ -34,14 +46,37 @@ pub struct Settings {
     pub translation_tab_order: Vec<String>,
     #[serde(default = "default_search_engines")]
     pub search_engines: Vec<SearchEngineConfig>,
+    #[serde(default)]
+    pub memo_revision_retention: MemoRevisionRetention,
+    /// AI 单词解释的提示词模板，支持 {word}、{translation}、{context} 占位符
+    #[serde(default = "default_word_explanation_prompt")]
+    pub word_explanation_prompt: String,
+    /// 启动器中触发 AI 问答的前缀
+    #[serde(default = "default_ai_prefix")]
+    pub ai_prefix: String,
+    /// 录制的快捷键和回放参数，按录制文件名索引
+    #[serde(default)]
+    pub recording_bindings: HashMap<String, RecordingBinding>,
+    /// 停止任何正在进行的回放
+    #[serde(default)]
+    pub playback_panic_hotkey: Option<HotkeyConfig>,
+    /// 组合序列、双击修饰键和按住触发的时间阈值
+    #[serde(default)]
+    pub hotkey_timing: HotkeyTiming,
+    /// 没有匹配的前缀或 bang 时使用的搜索引擎（按名称引用 `search_engines`）
+    #[serde(default)]
+    pub fallback_search_engine: Option<String>,
+    /// 启动器中是否获取搜索建议（会把输入内容发送给搜索引擎）
+    #[serde(default)]
+    pub search_suggestions_enabled: bool,
 }
 
 fn default_clipboard_max_items() -> u32 {
     100
 }
 
-fn default_result_style() -> String {
-    "skeuomorphic".to_string()
+fn default_settings_version() -> u32 {
+    SETTINGS_VERSION
 }
 
 fn default_close_on_blur() -> bool {
@@ -52,6 +87,24 @@ fn default_auto_check_update() -> bool {
     true
 }
 
+fn default_ai_prefix() -> String {
+    "ai ".to_string()
+}
+
+pub fn default_word_explanation_prompt() -> String {
+    r#"请为英语学习者解释单词 "{word}"（中文翻译：{translation}）。
+单词出现的原句：{context}
+
+请只返回一个 JSON 对象，不要包含其他文字，字段如下：
+{
+  "meaning_in_context": "结合原句说明该单词在此处的含义和用法（中文）",
+  "collocations": ["常见搭配或短语，附中文释义"],
+  "synonyms": ["近义词，附简短区别说明"],
+  "example": { "sentence": "一个新的英文例句", "translation": "例句的中文翻译" }
+}"#
+    .to_string()
+}
+
 fn default_translation_tab_order() -> Vec<String> {
     vec!["translation".to_string(), "wordbook".to_string()]
 }
@@ -59,6 +112,7 @@ fn default_translation_tab_order() -> Vec<String> {
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            version: SETTINGS_VERSION,
             ollama: OllamaSettings::default(),
             startup_enabled: false,
             hotkey: None,
@@ -66,43 +120,252 @@ impl Default for Settings {
             plugin_hotkeys: HashMap::new(),
             app_hotkeys: HashMap::new(),
             close_on_blur: default_close_on_blur(),
-            result_style: default_result_style(),
+            result_style: ResultStyle::default(),
             auto_check_update: default_auto_check_update(),
             last_update_check_time: None,
             ignored_update_version: None,
             clipboard_max_items: default_clipboard_max_items(),
             translation_tab_order: default_translation_tab_order(),
             search_engines: default_search_engines(),
+            memo_revision_retention: MemoRevisionRetention::default(),
+            word_explanation_prompt: default_word_explanation_prompt(),
+            ai_prefix: default_ai_prefix(),
+            recording_bindings: HashMap::new(),
+            playback_panic_hotkey: None,
+            hotkey_timing: HotkeyTiming::default(),
+            fallback_search_engine: None,
+            search_suggestions_enabled: false,
+        }
+    }
+}
+
+impl Settings {
+    /// 校验用户提交的设置，错误信息直接显示给用户
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_http_url(&self.ollama.base_url) {
+            return Err(format!(
+                "Ollama 地址必须是 http:// 或 https:// 开头的 URL: {}",
+                self.ollama.base_url
+            ));
         }
+        if self.clipboard_max_items > MAX_CLIPBOARD_ITEMS {
+            return Err(format!(
+                "剪切板历史数量不能超过 {}（0 表示不限制）",
+                MAX_CLIPBOARD_ITEMS
+            ));
+        }
+        for engine in &self.search_engines {
+            if engine.prefix.trim().is_empty() {
+                return Err(format!("搜索引擎 {} 的触发前缀不能为空", engine.name));
+            }
+            if !is_http_url(&engine.url)
+                || !SEARCH_PLACEHOLDERS.iter().any(|p| engine.url.contains(p))
+            {
+                return Err(format!(
+                    "搜索引擎 {} 的 URL 必须是 http(s) 地址并包含 {{query}}、{{raw}} 或 {{clipboard}} 占位符",
+                    engine.name
+                ));
+            }
+            if let Some(ref charset) = engine.charset {
+                if encoding_rs::Encoding::for_label(charset.trim().as_bytes()).is_none() {
+                    return Err(format!("搜索引擎 {} 的字符集 {} 不受支持", engine.name, charset));
+                }
+            }
+            if let Some(ref url) = engine.suggest_url {
+                if !is_http_url(url) {
+                    return Err(format!("搜索引擎 {} 的搜索建议地址必须是 http(s) URL", engine.name));
+                }
+            }
+        }
+        if let Some(ref fallback) = self.fallback_search_engine {
+            if !self.search_engines.iter().any(|e| &e.name == fallback) {
+                return Err(format!("后备搜索引擎 {} 不存在", fallback));
+            }
+        }
+        Ok(())
     }
 }
 
+/// 只检查协议和主机名是否存在，不做完整的 URL 解析
+fn is_http_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    let Some(rest) = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    !host.is_empty() && !url.chars().any(char::is_whitespace)
+}
+
+/// 启动器结果列表的样式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultStyle {
+    Compact,
+    Soft,
+    #[default]
+    Skeuomorphic,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HotkeyConfig {
     pub modifiers: Vec<String>,
     pub key: String,
+    /// 组合序列或按住触发的完整写法（如 "Ctrl+K, Ctrl+S"、"Hold Ctrl+Space"），
+    /// 此时 `modifiers` / `key` 只保存第一步
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<String>,
+}
+
+/// 快捷键时间阈值（毫秒）
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct HotkeyTiming {
+    /// 组合序列两步之间的最长间隔
+    #[serde(default = "default_chord_timeout_ms")]
+    pub chord_timeout_ms: u64,
+    /// 双击修饰键两次抬起之间的最长间隔
+    #[serde(default = "default_double_tap_ms")]
+    pub double_tap_ms: u64,
+    /// 按住触发需要按住的时长
+    #[serde(default = "default_hold_ms")]
+    pub hold_ms: u64,
+}
+
+fn default_chord_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_double_tap_ms() -> u64 {
+    500
+}
+
+fn default_hold_ms() -> u64 {
+    800
+}
+
+impl Default for HotkeyTiming {
+    fn default() -> Self {
+        Self {
+            chord_timeout_ms: default_chord_timeout_ms(),
+            double_tap_ms: default_double_tap_ms(),
+            hold_ms: default_hold_ms(),
+        }
+    }
+}
+
+/// 录制回放绑定，快捷键和启动器触发时都按这里的速度和次数播放
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordingBinding {
+    #[serde(default)]
+    pub hotkey: Option<HotkeyConfig>,
+    #[serde(default = "default_playback_speed")]
+    pub speed: f32,
+    #[serde(default = "default_playback_repeat")]
+    pub repeat: u32,
+}
+
+fn default_playback_speed() -> f32 {
+    1.0
+}
+
+fn default_playback_repeat() -> u32 {
+    1
+}
+
+impl Default for RecordingBinding {
+    fn default() -> Self {
+        Self {
+            hotkey: None,
+            speed: default_playback_speed(),
+            repeat: default_playback_repeat(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OllamaSettings {
+    #[serde(default = "default_ollama_model")]
     pub model: String,
+    #[serde(default = "default_ollama_base_url")]
     pub base_url: String,
+    /// 语义搜索使用的向量模型
+    #[serde(default = "default_embedding_model")]
+    pub embedding_model: String,
+}
+
+fn default_ollama_model() -> String {
+    "llama2".to_string()
+}
+
+fn default_ollama_base_url() -> String {
+    "http://localhost:11434".to_string()
+}
+
+fn default_embedding_model() -> String {
+    "nomic-embed-text".to_string()
 }
 
 impl Default for OllamaSettings {
     fn default() -> Self {
         Self {
-            model: "llama2".to_string(),
-            base_url: "http://localhost:11434".to_string(),
+            model: default_ollama_model(),
+            base_url: default_ollama_base_url(),
+            embedding_model: default_embedding_model(),
+        }
+    }
+}
+
+/// 备忘录修订历史的保留策略，0 表示不限制
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MemoRevisionRetention {
+    #[serde(default = "default_max_revisions_per_memo")]
+    pub max_revisions_per_memo: u32,
+    #[serde(default)]
+    pub max_age_days: u32,
+}
+
+fn default_max_revisions_per_memo() -> u32 {
+    50
+}
+
+impl Default for MemoRevisionRetention {
+    fn default() -> Self {
+        Self {
+            max_revisions_per_memo: default_max_revisions_per_memo(),
+            max_age_days: 0,
         }
     }
 }
 
+/// 搜索 URL 模板支持的占位符：编码后的关键词、原样的关键词、编码后的剪切板文本
+pub const SEARCH_PLACEHOLDERS: [&str; 3] = ["{query}", "{raw}", "{clipboard}"];
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SearchEngineConfig {
     pub prefix: String,  // 触发前缀，如 "s ", "g "
-    pub url: String,     // URL 模板，使用 {query} 作为占位符
+    pub url: String,     // URL 模板，占位符见 SEARCH_PLACEHOLDERS
     pub name: String,    // 显示名称，如 "Google", "百度"
+    #[serde(default)]
+    pub encoding: QueryEncoding,
+    /// 编码前把关键词转换成的字符集（如部分中文网站使用 "gbk"），为空时使用 UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charset: Option<String>,
+    /// 搜索建议接口，返回 OpenSearch 格式的 JSON，占位符与 `url` 相同
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggest_url: Option<String>,
+}
+
+/// 关键词中空格的编码方式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryEncoding {
+    /// 百分号编码，空格为 %20
+    #[default]
+    Percent,
+    /// 表单编码，空格为 +
+    Plus,
 }
 
 fn default_search_engines() -> Vec<SearchEngineConfig> {
@@ -111,16 +374,25 @@ fn default_search_engines() -> Vec<SearchEngineConfig> {
             prefix: "s ".to_string(),
             url: "https://www.google.com/search?q={query}".to_string(),
             name: "Google".to_string(),
+            encoding: QueryEncoding::Percent,
+            charset: None,
+            suggest_url: None,
         },
         SearchEngineConfig {
             prefix: "bd ".to_string(),
             url: "https://www.baidu.com/s?wd={query}".to_string(),
             name: "百度".to_string(),
+            encoding: QueryEncoding::Percent,
+            charset: None,
+            suggest_url: None,
         },
         SearchEngineConfig {
             prefix: "b ".to_string(),
             url: "https://www.bing.com/search?q={query}".to_string(),
             name: "必应".to_string(),
+            encoding: QueryEncoding::Percent,
+            charset: None,
+            suggest_url: None,
         },
     ]
 }
@@ -129,30 +401,194 @@ pub fn get_settings_file_path(app_data_dir: &Path) -> PathBuf {
     app_data_dir.join("settings.json")
 }
 
+/// 读取当前设置；设置服务启动后直接返回内存中的副本
 pub fn load_settings(app_data_dir: &Path) -> Result<Settings, String> {
+    if let Some(settings) = settings_service::cached(app_data_dir) {
+        return Ok(settings);
+    }
+    load_settings_from_db(app_data_dir)
+}
+
+/// 绕过内存副本直接从数据库读取
+pub(crate) fn load_settings_from_db(app_data_dir: &Path) -> Result<Settings, String> {
     let conn = db::get_connection(app_data_dir)?;
     maybe_migrate_from_json(&conn, app_data_dir)?;
+    load_settings_with_conn(&conn)
+}
+
+/// 数据库中保存的设置原文，不解析也不迁移
+pub(crate) fn read_settings_json(conn: &rusqlite::Connection) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'settings' LIMIT 1",
+        [],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load settings from database: {}", e))
+}
+
+pub(crate) fn load_settings_with_conn(conn: &rusqlite::Connection) -> Result<Settings, String> {
+    let Some(json) = read_settings_json(conn)? else {
+        return Ok(Settings::default());
+    };
+    let value: Value = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse settings from database: {}", e))?;
+    let (value, migrated) = migrate_settings(value);
+    let settings: Settings = serde_json::from_value(value)
+        .map_err(|e| format!("Failed to parse settings from database: {}", e))?;
+    if migrated {
+        // Persist the upgraded blob so migrations only run once; loading still works if this fails.
+        let _ = save_settings_with_conn(conn, &settings);
+    }
+    Ok(settings)
+}
+
+/// 用 JSON Merge Patch（RFC 7396）更新设置：只修改补丁中出现的字段，`null` 恢复默认值。
+/// 读取、合并和保存在同一个写事务中完成，多个窗口同时修改不同字段时不会互相覆盖。
+pub fn update_settings(app_data_dir: &Path, patch: &Value) -> Result<Settings, String> {
+    if !patch.is_object() {
+        return Err("Settings patch must be a JSON object".to_string());
+    }
+    modify_settings(app_data_dir, |settings| {
+        *settings = apply_settings_patch(settings, patch)?;
+        Ok(settings.clone())
+    })
+}
+
+/// 在同一个写事务中读取、修改并保存设置，返回 `modify` 的结果。
+///
+/// 后端所有"读取-修改-写回"都走这里，与其它窗口的 `update_settings` 互不覆盖；
+/// 设置没有变化时不写入。`save_settings` 只用于整体替换。
+pub fn modify_settings<T>(
+    app_data_dir: &Path,
+    modify: impl FnOnce(&mut Settings) -> Result<T, String>,
+) -> Result<T, String> {
+    modify_settings_in_transaction(app_data_dir, |_, settings| modify(settings))
+}
+
+/// 同 [`modify_settings`]，`modify` 还可以在同一个事务中写入其它表；
+/// 任何一步出错时设置和这些写入一起回滚。
+pub(crate) fn modify_settings_in_transaction<T>(
+    app_data_dir: &Path,
+    modify: impl FnOnce(&rusqlite::Transaction, &mut Settings) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    maybe_migrate_from_json(&conn, app_data_dir)?;
+    let guard = settings_service::lock_writes();
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| format!("Failed to start settings transaction: {}", e))?;
 
-    let value: Option<String> = conn
-        .query_row(
-            "SELECT value FROM settings WHERE key = 'settings' LIMIT 1",
-            [],
-            |row| row.get(0),
-        )
-        .optional()
-        .map_err(|e| format!("Failed to load settings from database: {}", e))?;
-
-    if let Some(json) = value {
-        serde_json::from_str(&json)
-            .map_err(|e| format!("Failed to parse settings from database: {}", e))
+    let current = load_settings_with_conn(&tx)?;
+    let mut settings = current.clone();
+    let result = modify(&tx, &mut settings)?;
+    let changed = !settings_service::diff(&current, &settings).is_empty();
+    if changed {
+        settings.validate()?;
+        save_settings_with_conn(&tx, &settings)?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit settings: {}", e))?;
+    let change = if changed {
+        settings_service::record(app_data_dir, &settings, ChangeSource::App)
     } else {
-        Ok(Settings::default())
+        None
+    };
+    drop(guard);
+    if let Some(change) = change {
+        settings_service::notify(change);
+    }
+    Ok(result)
+}
+
+fn apply_settings_patch(current: &Settings, patch: &Value) -> Result<Settings, String> {
+    let mut value = serde_json::to_value(current)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    merge_patch(&mut value, patch);
+    let mut settings: Settings =
+        serde_json::from_value(value).map_err(|e| format!("设置格式不正确: {}", e))?;
+    settings.version = SETTINGS_VERSION;
+    Ok(settings)
+}
+
+/// RFC 7396 JSON Merge Patch
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.as_str()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// 第 i 项把版本 i 的设置升级到版本 i + 1
+const MIGRATIONS: [fn(&mut Map<String, Value>); SETTINGS_VERSION as usize] = [migrate_v0_to_v1];
+
+/// 把数据库中的设置升级到当前版本，返回升级后的数据和是否做过迁移。
+/// 比当前版本新的数据（来自新版程序）原样返回，未知字段在反序列化时忽略。
+pub(crate) fn migrate_settings(mut value: Value) -> (Value, bool) {
+    let Value::Object(map) = &mut value else {
+        return (value, false);
+    };
+    let version = map
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v.min(u32::MAX as u64) as u32);
+    if version >= SETTINGS_VERSION {
+        return (value, false);
+    }
+
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(map);
+    }
+    map.insert("version".to_string(), SETTINGS_VERSION.into());
+    (value, true)
+}
+
+/// v1：`result_style` 改为枚举，数值字段加上范围限制；无效的旧值恢复默认
+fn migrate_v0_to_v1(map: &mut Map<String, Value>) {
+    let valid_style = map
+        .get("result_style")
+        .is_some_and(|style| serde_json::from_value::<ResultStyle>(style.clone()).is_ok());
+    if !valid_style {
+        map.remove("result_style");
+    }
+
+    match map.get("clipboard_max_items").and_then(Value::as_u64) {
+        Some(n) if n > MAX_CLIPBOARD_ITEMS as u64 => {
+            map.insert(
+                "clipboard_max_items".to_string(),
+                MAX_CLIPBOARD_ITEMS.into(),
+            );
+        }
+        Some(_) => {}
+        None => {
+            map.remove("clipboard_max_items");
+        }
     }
 }
 
 pub fn save_settings(app_data_dir: &Path, settings: &Settings) -> Result<(), String> {
     let conn = db::get_connection(app_data_dir)?;
-    save_settings_with_conn(&conn, settings)
+    let guard = settings_service::lock_writes();
+    save_settings_with_conn(&conn, settings)?;
+    let change = settings_service::record(app_data_dir, settings, ChangeSource::App);
+    drop(guard);
+    if let Some(change) = change {
+        settings_service::notify(change);
+    }
+    Ok(())
 }
 
 fn save_settings_with_conn(conn: &rusqlite::Connection, settings: &Settings) -> Result<(), String> {
@@ -182,7 +618,11 @@ fn maybe_migrate_from_json(
         let settings_file = get_settings_file_path(app_data_dir);
         if settings_file.exists() {
             if let Ok(content) = fs::read_to_string(&settings_file) {
-                if let Ok(settings) = serde_json::from_str::<Settings>(&content) {
+                let settings = serde_json::from_str::<Value>(&content)
+                    .ok()
+                    .map(|value| migrate_settings(value).0)
+                    .and_then(|value| serde_json::from_value::<Settings>(value).ok());
+                if let Some(settings) = settings {
                     // Best effort import; ignore errors to avoid blocking startup.
                     let _ = save_settings_with_conn(conn, &settings);
                 }
@@ -193,3 +633,158 @@ fn maybe_migrate_from_json(
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_legacy_settings() {
+        let legacy = json!({
+            "ollama": { "model": "llama2", "base_url": "http://localhost:11434" },
+            "result_style": "fancy",
+            "clipboard_max_items": 999999,
+            "plugin_hotkeys": { "calc": { "modifiers": ["Ctrl"], "key": "K" } }
+        });
+        let (value, migrated) = migrate_settings(legacy);
+        assert!(migrated);
+        let settings: Settings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.result_style, ResultStyle::Skeuomorphic);
+        assert_eq!(settings.clipboard_max_items, MAX_CLIPBOARD_ITEMS);
+        assert!(settings.plugin_hotkeys.contains_key("calc"));
+
+        let (value, migrated) = migrate_settings(serde_json::to_value(&settings).unwrap());
+        assert!(!migrated);
+        assert_eq!(value["result_style"], "skeuomorphic");
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut target = json!({ "a": 1, "b": { "c": 2, "d": 3 }, "e": [1, 2] });
+        merge_patch(
+            &mut target,
+            &json!({ "a": null, "b": { "c": 5 }, "e": [3], "f": "x" }),
+        );
+        assert_eq!(
+            target,
+            json!({ "b": { "c": 5, "d": 3 }, "e": [3], "f": "x" })
+        );
+    }
+
+    #[test]
+    fn test_patch_keeps_other_fields() {
+        let mut current = Settings {
+            close_on_blur: false,
+            ..Default::default()
+        };
+        current.plugin_hotkeys.insert(
+            "calc".to_string(),
+            HotkeyConfig {
+                modifiers: vec!["Ctrl".to_string()],
+                key: "K".to_string(),
+                sequence: None,
+            },
+        );
+
+        let patched =
+            apply_settings_patch(&current, &json!({ "clipboard_max_items": 20 })).unwrap();
+        assert_eq!(patched.clipboard_max_items, 20);
+        assert!(!patched.close_on_blur);
+        assert!(patched.plugin_hotkeys.contains_key("calc"));
+
+        // null 恢复默认值，无效的枚举值被拒绝
+        let patched = apply_settings_patch(&current, &json!({ "close_on_blur": null })).unwrap();
+        assert!(patched.close_on_blur);
+        assert!(apply_settings_patch(&current, &json!({ "result_style": "fancy" })).is_err());
+
+        // 嵌套对象同样可以用 null 恢复默认值
+        let mut current = current;
+        current.ollama.model = "qwen2".to_string();
+        current.ollama.base_url = "http://127.0.0.1:8080".to_string();
+        let patched = apply_settings_patch(&current, &json!({ "ollama": { "model": null } })).unwrap();
+        assert_eq!(patched.ollama.model, "llama2");
+        assert_eq!(patched.ollama.base_url, "http://127.0.0.1:8080");
+        let patched = apply_settings_patch(&current, &json!({ "ollama": null })).unwrap();
+        assert_eq!(patched.ollama.model, "llama2");
+        assert_eq!(patched.ollama.base_url, "http://localhost:11434");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Settings::default().validate().is_ok());
+
+        let mut settings = Settings::default();
+        settings.ollama.base_url = "localhost:11434".to_string();
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            clipboard_max_items: MAX_CLIPBOARD_ITEMS + 1,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.search_engines[0].url = "https://www.google.com/search".to_string();
+        assert!(settings.validate().is_err());
+        settings.search_engines[0].url = "https://www.google.com/search?q={query}".to_string();
+        settings.search_engines[0].prefix = " ".to_string();
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.search_engines[1].url = "https://www.baidu.com/s?wd={raw}".to_string();
+        settings.search_engines[1].charset = Some("gbk".to_string());
+        settings.fallback_search_engine = Some("百度".to_string());
+        assert!(settings.validate().is_ok());
+        settings.search_engines[1].charset = Some("klingon".to_string());
+        assert!(settings.validate().is_err());
+        settings.search_engines[1].charset = None;
+        settings.fallback_search_engine = Some("Yahoo".to_string());
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_modify_settings_concurrent() {
+        let dir = temp_dir("settings");
+
+        // 并发修改不同字段，每个修改都应保留下来
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let dir = dir.clone();
+                std::thread::spawn(move || {
+                    modify_settings(&dir, |s| {
+                        s.plugin_hotkeys.insert(
+                            format!("plugin-{}", i),
+                            HotkeyConfig {
+                                modifiers: vec!["Ctrl".to_string()],
+                                key: format!("F{}", i + 1),
+                                sequence: None,
+                            },
+                        );
+                        Ok(())
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        update_settings(&dir, &json!({ "clipboard_max_items": 20 })).unwrap();
+
+        let settings = load_settings_from_db(&dir).unwrap();
+        assert_eq!(settings.plugin_hotkeys.len(), 8);
+        assert_eq!(settings.clipboard_max_items, 20);
+
+        // 修改出错时不写入
+        let result: Result<(), String> = modify_settings(&dir, |s| {
+            s.clipboard_max_items = 30;
+            Err("cancelled".to_string())
+        });
+        assert!(result.is_err());
+        assert_eq!(load_settings_from_db(&dir).unwrap().clipboard_max_items, 20);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,262 @@
+//! 设置服务
+//!
+//! 在内存中保存当前设置。任何途径保存设置后（命令、其它窗口、外部修改数据库），
+//! 与上一份设置逐字段比较，向所有窗口发送 `settings-changed` 事件并通知 Rust 端订阅者，
+//! 各窗口和子系统无需重启或重新读取就能用上新设置。
+//!
+//! 写入设置的一方需持有 [`lock_writes`] 返回的写锁，在锁内提交数据库并调用 [`record`]，
+//! 释放锁后再调用 [`notify`]，保证内存中的设置与数据库中最后一次提交一致。
+
+use crate::db;
+use crate::settings::{self, Settings};
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 检查数据库是否被其它连接修改的间隔
+const EXTERNAL_CHANGE_POLL_MS: u64 = 500;
+
+/// 设置从哪里被修改
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeSource {
+    /// 本程序保存（命令或其它窗口）
+    App,
+    /// 其它进程直接修改了数据库
+    External,
+}
+
+/// `settings-changed` 事件负载
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsChange {
+    /// 发生变化的顶层字段名（与序列化后的字段名一致），按字母排序
+    pub changed: Vec<String>,
+    pub settings: Settings,
+    pub source: ChangeSource,
+}
+
+impl SettingsChange {
+    pub fn touches(&self, field: &str) -> bool {
+        self.changed.iter().any(|f| f == field)
+    }
+
+    pub fn touches_any(&self, fields: &[&str]) -> bool {
+        fields.iter().any(|f| self.touches(f))
+    }
+}
+
+type Subscriber = Arc<dyn Fn(&SettingsChange) + Send + Sync>;
+
+/// 当前设置及其所属的数据目录
+static CURRENT: LazyLock<Mutex<Option<(PathBuf, Settings)>>> = LazyLock::new(|| Mutex::new(None));
+static SUBSCRIBERS: LazyLock<Mutex<Vec<Subscriber>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+/// 串行化“提交到数据库 + 更新内存”，避免先提交的写入后更新内存
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 加载设置到内存，开始向窗口广播变化并轮询数据库的外部修改
+pub fn start(app: AppHandle, app_data_dir: PathBuf) -> Result<(), String> {
+    {
+        let _guard = lock_writes();
+        let settings = settings::load_settings_from_db(&app_data_dir)?;
+        *lock_current() = Some((app_data_dir.clone(), settings));
+    }
+    let _ = APP_HANDLE.set(app);
+
+    // 只读连接不执行迁移，轮询开销只有一次 PRAGMA 查询
+    let conn = db::get_readonly_connection(&app_data_dir)?;
+    let mut last_version = data_version(&conn)?;
+    let mut last_json = settings::read_settings_json(&conn)?;
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_millis(EXTERNAL_CHANGE_POLL_MS));
+        let version = match data_version(&conn) {
+            Ok(version) => version,
+            Err(e) => {
+                eprintln!("[Settings] {}", e);
+                continue;
+            }
+        };
+        if version == last_version {
+            continue;
+        }
+        last_version = version;
+
+        // 其它表的写入也会改变 data_version，只有设置行变化时才重新加载
+        let guard = lock_writes();
+        let change = match settings::read_settings_json(&conn) {
+            Ok(json) if json == last_json => None,
+            Ok(json) => {
+                last_json = json;
+                match settings::load_settings_with_conn(&conn) {
+                    Ok(settings) => record(&app_data_dir, &settings, ChangeSource::External),
+                    Err(e) => {
+                        eprintln!("[Settings] Failed to reload settings: {}", e);
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("[Settings] Failed to reload settings: {}", e);
+                None
+            }
+        };
+        drop(guard);
+        if let Some(change) = change {
+            notify(change);
+        }
+    });
+
+    Ok(())
+}
+
+/// 其它连接每提交一次写入，本连接读到的值就会变化
+fn data_version(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("PRAGMA data_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read database data_version: {}", e))
+}
+
+/// 写入设置前获取；在锁内提交数据库并调用 [`record`]
+pub(crate) fn lock_writes() -> MutexGuard<'static, ()> {
+    WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 内存中的设置；服务尚未启动或数据目录不同时返回 None
+pub fn cached(app_data_dir: &Path) -> Option<Settings> {
+    lock_current()
+        .as_ref()
+        .filter(|(dir, _)| dir == app_data_dir)
+        .map(|(_, settings)| settings.clone())
+}
+
+/// 注册设置变化的回调，在保存设置的线程上同步调用
+pub fn subscribe(callback: impl Fn(&SettingsChange) + Send + Sync + 'static) {
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(callback));
+}
+
+/// 设置已提交到数据库、写锁尚未释放时调用：更新内存中的设置，返回需要广播的变化
+pub(crate) fn record(
+    app_data_dir: &Path,
+    settings: &Settings,
+    source: ChangeSource,
+) -> Option<SettingsChange> {
+    let mut current = lock_current();
+    let changed = match current.as_ref() {
+        Some((dir, previous)) if dir == app_data_dir => diff(previous, settings),
+        // 服务未启动（如单独调用存储层）时不广播
+        _ => return None,
+    };
+    if changed.is_empty() {
+        return None;
+    }
+    *current = Some((app_data_dir.to_path_buf(), settings.clone()));
+    Some(SettingsChange {
+        changed,
+        settings: settings.clone(),
+        source,
+    })
+}
+
+/// 释放写锁后广播 [`record`] 返回的变化，回调里可以再次读写设置
+pub(crate) fn notify(mut change: SettingsChange) {
+    // 多次写入的广播可能交错，总是带上内存中最新的设置
+    if let Some((_, latest)) = lock_current().as_ref() {
+        change.settings = latest.clone();
+    }
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = app.emit("settings-changed", &change) {
+            eprintln!("[Settings] Failed to emit settings-changed event: {}", e);
+        }
+    }
+    // 先复制出订阅者再调用，回调里可以再次读写设置
+    let subscribers = SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    for subscriber in subscribers {
+        subscriber(&change);
+    }
+}
+
+/// 序列化后值不同的顶层字段
+pub fn diff(previous: &Settings, next: &Settings) -> Vec<String> {
+    let (Ok(Value::Object(previous)), Ok(Value::Object(next))) =
+        (serde_json::to_value(previous), serde_json::to_value(next))
+    else {
+        return Vec::new();
+    };
+    let mut changed: Vec<String> = next
+        .iter()
+        .filter(|(key, value)| previous.get(key.as_str()) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .chain(previous.keys().filter(|key| !next.contains_key(*key)).cloned())
+        .collect();
+    changed.sort();
+    changed
+}
+
+fn lock_current() -> std::sync::MutexGuard<'static, Option<(PathBuf, Settings)>> {
+    CURRENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let previous = Settings::default();
+        assert!(diff(&previous, &previous.clone()).is_empty());
+
+        let mut next = previous.clone();
+        next.close_on_blur = false;
+        next.clipboard_max_items = 20;
+        next.ollama.model = "qwen2".to_string();
+        assert_eq!(
+            diff(&previous, &next),
+            vec!["clipboard_max_items", "close_on_blur", "ollama"]
+        );
+    }
+
+    #[test]
+    fn test_record_and_notify() {
+        let dir = PathBuf::from("refast-settings-service-test");
+        *lock_current() = Some((dir.clone(), Settings::default()));
+
+        let mut first = Settings::default();
+        first.close_on_blur = false;
+        let mut second = first.clone();
+        second.clipboard_max_items = 20;
+
+        let change_first = record(&dir, &first, ChangeSource::App).unwrap();
+        let change_second = record(&dir, &second, ChangeSource::App).unwrap();
+        assert_eq!(change_first.changed, vec!["close_on_blur"]);
+        assert_eq!(change_second.changed, vec!["clipboard_max_items"]);
+        assert!(record(&dir, &second, ChangeSource::External).is_none());
+        assert_eq!(cached(&dir).unwrap().clipboard_max_items, 20);
+
+        // 先提交的写入晚广播时也不会让内存退回旧值
+        notify(change_second);
+        notify(change_first);
+        assert_eq!(cached(&dir).unwrap().clipboard_max_items, 20);
+        assert!(cached(Path::new("other")).is_none());
+    }
+
+    #[test]
+    fn test_change_touches() {
+        let change = SettingsChange {
+            changed: vec!["hotkey".to_string()],
+            settings: Settings::default(),
+            source: ChangeSource::App,
+        };
+        assert!(change.touches("hotkey"));
+        assert!(!change.touches("plugin_hotkeys"));
+        assert!(change.touches_any(&["plugin_hotkeys", "hotkey"]));
+    }
+}
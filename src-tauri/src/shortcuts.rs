@@ -125,6 +125,14 @@ pub fn delete_shortcut(id: String, app_data_dir: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// 用给定列表替换内存中的全部快捷方式（切换或导入配置档案时使用），
+/// 数据库由调用方在自己的事务中通过 [`write_shortcuts`] 写入
+pub fn replace_cached_shortcuts(items: Vec<ShortcutItem>) -> Result<(), String> {
+    let mut state = SHORTCUTS.lock().map_err(|e| e.to_string())?;
+    *state = items.into_iter().map(|item| (item.id.clone(), item)).collect();
+    Ok(())
+}
+
 fn load_shortcuts_into(
     state: &mut HashMap<String, ShortcutItem>,
     app_data_dir: &Path,
@@ -169,11 +177,24 @@ fn save_shortcuts_internal(
         .transaction()
         .map_err(|e| format!("Failed to start shortcuts transaction: {}", e))?;
 
-    tx.execute("DELETE FROM shortcuts", [])
+    write_shortcuts(&tx, state.values())?;
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit shortcuts: {}", e))?;
+
+    Ok(())
+}
+
+/// 用给定列表替换 shortcuts 表的全部内容，调用方负责事务
+pub(crate) fn write_shortcuts<'a>(
+    conn: &rusqlite::Connection,
+    items: impl IntoIterator<Item = &'a ShortcutItem>,
+) -> Result<(), String> {
+    conn.execute("DELETE FROM shortcuts", [])
         .map_err(|e| format!("Failed to clear shortcuts table: {}", e))?;
 
-    for item in state.values() {
-        tx.execute(
+    for item in items {
+        conn.execute(
             "INSERT INTO shortcuts (id, name, path, icon, created_at, updated_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
@@ -188,9 +209,6 @@ fn save_shortcuts_internal(
         .map_err(|e| format!("Failed to insert shortcut {}: {}", item.id, e))?;
     }
 
-    tx.commit()
-        .map_err(|e| format!("Failed to commit shortcuts: {}", e))?;
-
     Ok(())
 }
 
@@ -0,0 +1,687 @@
+use crate::db;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnippetItem {
+    pub id: String,
+    pub name: String,
+    pub keyword: Option<String>, // 触发关键字，如 ";sig"
+    pub folder: Option<String>,  // 分组/文件夹，使用 "/" 分隔层级
+    pub content: String,         // 模板内容，支持 {date:...}、{clipboard} 等占位符
+    pub use_count: u32,
+    pub last_used: Option<u64>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// 渲染后的片段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedSnippet {
+    pub text: String,
+    /// `{cursor}` 所在位置（按字符计），粘贴后光标应停留在此处
+    pub cursor_offset: Option<usize>,
+}
+
+const SNIPPET_COLUMNS: &str =
+    "id, name, keyword, folder, content, use_count, last_used, created_at, updated_at";
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn new_snippet_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("snippet-{}", nanos)
+}
+
+fn row_to_snippet(row: &rusqlite::Row) -> rusqlite::Result<SnippetItem> {
+    Ok(SnippetItem {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        keyword: row.get(2)?,
+        folder: row.get(3)?,
+        content: row.get(4)?,
+        use_count: row.get::<_, i64>(5)? as u32,
+        last_used: row.get::<_, Option<i64>>(6)?.map(|v| v as u64),
+        created_at: row.get::<_, i64>(7)? as u64,
+        updated_at: row.get::<_, i64>(8)? as u64,
+    })
+}
+
+/// 空字符串视为未设置
+fn normalize_optional(value: Option<String>) -> Option<String> {
+    value.and_then(|v| {
+        let trimmed = v.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
+        }
+    })
+}
+
+fn ensure_keyword_unique(
+    conn: &rusqlite::Connection,
+    keyword: &Option<String>,
+    exclude_id: Option<&str>,
+) -> Result<(), String> {
+    if let Some(keyword) = keyword {
+        let existing: Option<String> = conn
+            .query_row(
+                "SELECT id FROM snippets WHERE keyword = ?1",
+                params![keyword],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to check snippet keyword: {}", e))?;
+        if let Some(existing_id) = existing {
+            if Some(existing_id.as_str()) != exclude_id {
+                return Err(format!("关键字 \"{}\" 已被其他片段使用", keyword));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 获取所有片段
+pub fn get_all_snippets(app_data_dir: &Path) -> Result<Vec<SnippetItem>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM snippets ORDER BY folder ASC, name ASC",
+            SNIPPET_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare snippets query: {}", e))?;
+
+    let rows = stmt
+        .query_map([], row_to_snippet)
+        .map_err(|e| format!("Failed to iterate snippets: {}", e))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read snippet row: {}", e))?);
+    }
+    Ok(items)
+}
+
+pub fn get_snippet(id: &str, app_data_dir: &Path) -> Result<SnippetItem, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.query_row(
+        &format!("SELECT {} FROM snippets WHERE id = ?1", SNIPPET_COLUMNS),
+        params![id],
+        row_to_snippet,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to load snippet: {}", e))?
+    .ok_or_else(|| format!("Snippet {} not found", id))
+}
+
+/// 添加片段
+pub fn add_snippet(
+    name: String,
+    keyword: Option<String>,
+    folder: Option<String>,
+    content: String,
+    app_data_dir: &Path,
+) -> Result<SnippetItem, String> {
+    let now = now_ts();
+    let item = SnippetItem {
+        id: new_snippet_id(),
+        name,
+        keyword: normalize_optional(keyword),
+        folder: normalize_optional(folder),
+        content,
+        use_count: 0,
+        last_used: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let conn = db::get_connection(app_data_dir)?;
+    ensure_keyword_unique(&conn, &item.keyword, None)?;
+
+    conn.execute(
+        "INSERT INTO snippets (id, name, keyword, folder, content, use_count, last_used, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 0, NULL, ?6, ?7)",
+        params![
+            item.id,
+            item.name,
+            item.keyword,
+            item.folder,
+            item.content,
+            item.created_at as i64,
+            item.updated_at as i64
+        ],
+    )
+    .map_err(|e| format!("Failed to insert snippet: {}", e))?;
+
+    Ok(item)
+}
+
+/// 更新片段
+///
+/// `keyword` / `folder` 传入空字符串表示清除
+pub fn update_snippet(
+    id: String,
+    name: Option<String>,
+    keyword: Option<String>,
+    folder: Option<String>,
+    content: Option<String>,
+    app_data_dir: &Path,
+) -> Result<SnippetItem, String> {
+    let mut snippet = get_snippet(&id, app_data_dir)?;
+    let conn = db::get_connection(app_data_dir)?;
+
+    if let Some(n) = name {
+        snippet.name = n;
+    }
+    if keyword.is_some() {
+        snippet.keyword = normalize_optional(keyword);
+        ensure_keyword_unique(&conn, &snippet.keyword, Some(&snippet.id))?;
+    }
+    if folder.is_some() {
+        snippet.folder = normalize_optional(folder);
+    }
+    if let Some(c) = content {
+        snippet.content = c;
+    }
+    snippet.updated_at = now_ts();
+
+    conn.execute(
+        "UPDATE snippets SET name = ?1, keyword = ?2, folder = ?3, content = ?4, updated_at = ?5 WHERE id = ?6",
+        params![
+            snippet.name,
+            snippet.keyword,
+            snippet.folder,
+            snippet.content,
+            snippet.updated_at as i64,
+            snippet.id
+        ],
+    )
+    .map_err(|e| format!("Failed to update snippet: {}", e))?;
+
+    Ok(snippet)
+}
+
+/// 删除片段
+pub fn delete_snippet(id: String, app_data_dir: &Path) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let affected = conn
+        .execute("DELETE FROM snippets WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete snippet: {}", e))?;
+    if affected == 0 {
+        return Err("Snippet not found".to_string());
+    }
+    Ok(())
+}
+
+/// 获取所有文件夹（去重）
+pub fn get_snippet_folders(app_data_dir: &Path) -> Result<Vec<String>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT folder FROM snippets WHERE folder IS NOT NULL ORDER BY folder ASC")
+        .map_err(|e| format!("Failed to prepare snippet folders query: {}", e))?;
+    let folders = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| format!("Failed to query snippet folders: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(folders)
+}
+
+/// 搜索片段（启动器使用）
+///
+/// 关键字完全匹配的片段排在最前，其次按使用次数排序
+pub fn search_snippets(query: &str, app_data_dir: &Path) -> Result<Vec<SnippetItem>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let query = query.trim().to_lowercase();
+    let like = format!(
+        "%{}%",
+        query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}
+             FROM snippets
+             WHERE lower(name) LIKE ?1 ESCAPE '\\' OR lower(keyword) LIKE ?1 ESCAPE '\\'
+                OR lower(folder) LIKE ?1 ESCAPE '\\' OR lower(content) LIKE ?1 ESCAPE '\\'
+             ORDER BY (lower(keyword) = ?2) DESC, use_count DESC, updated_at DESC",
+            SNIPPET_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare snippet search: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![like, query], row_to_snippet)
+        .map_err(|e| format!("Failed to iterate snippet search: {}", e))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read snippet row: {}", e))?);
+    }
+    Ok(items)
+}
+
+/// 记录一次使用
+pub fn mark_snippet_used(id: &str, app_data_dir: &Path) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute(
+        "UPDATE snippets SET use_count = use_count + 1, last_used = ?1 WHERE id = ?2",
+        params![now_ts() as i64, id],
+    )
+    .map_err(|e| format!("Failed to update snippet usage: {}", e))?;
+    Ok(())
+}
+
+/// 将剪切板收藏中的文本项导入为片段，已存在相同内容的片段会被跳过
+///
+/// 返回新导入的数量
+pub fn import_clipboard_favorites(
+    folder: Option<String>,
+    app_data_dir: &Path,
+) -> Result<usize, String> {
+    let mut conn = db::get_connection(app_data_dir)?;
+    let folder = normalize_optional(folder);
+
+    let favorites: Vec<String> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT content FROM clipboard_history
+                 WHERE is_favorite = 1 AND content_type = 'text'
+                 ORDER BY created_at ASC",
+            )
+            .map_err(|e| format!("Failed to prepare favorites query: {}", e))?;
+        let favorites = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| format!("Failed to query favorites: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        favorites
+    };
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start snippet import: {}", e))?;
+    let now = now_ts() as i64;
+    let mut imported = 0;
+    for content in favorites {
+        let exists: bool = tx
+            .query_row(
+                "SELECT 1 FROM snippets WHERE content = ?1 LIMIT 1",
+                params![content],
+                |_| Ok(true),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to check existing snippet: {}", e))?
+            .unwrap_or(false);
+        if exists {
+            continue;
+        }
+
+        let name: String = content
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("")
+            .trim()
+            .chars()
+            .take(40)
+            .collect();
+        tx.execute(
+            "INSERT INTO snippets (id, name, keyword, folder, content, use_count, last_used, created_at, updated_at)
+             VALUES (?1, ?2, NULL, ?3, ?4, 0, NULL, ?5, ?5)",
+            params![new_snippet_id(), name, folder, content, now],
+        )
+        .map_err(|e| format!("Failed to import favorite as snippet: {}", e))?;
+        imported += 1;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit snippet import: {}", e))?;
+
+    Ok(imported)
+}
+
+/// 渲染片段，嵌套的 `{snippet:keyword}` 会从数据库解析
+pub fn render_snippet(
+    id: &str,
+    inputs: &HashMap<String, String>,
+    clipboard: Option<String>,
+    app_data_dir: &Path,
+) -> Result<RenderedSnippet, String> {
+    let snippet = get_snippet(id, app_data_dir)?;
+    let all = get_all_snippets(app_data_dir)?;
+    let by_keyword: HashMap<String, String> = all
+        .into_iter()
+        .filter_map(|s| s.keyword.map(|k| (k, s.content)))
+        .collect();
+
+    let resolve = |keyword: &str| by_keyword.get(keyword).cloned();
+    let ctx = template::RenderContext {
+        now: chrono::Local::now(),
+        clipboard,
+        inputs,
+        resolve_snippet: &resolve,
+    };
+    template::render(&snippet.content, snippet.keyword.as_deref(), &ctx)
+}
+
+/// 列出片段（包括嵌套片段）中需要用户输入的字段名
+pub fn get_snippet_inputs(id: &str, app_data_dir: &Path) -> Result<Vec<String>, String> {
+    let snippet = get_snippet(id, app_data_dir)?;
+    let all = get_all_snippets(app_data_dir)?;
+    let by_keyword: HashMap<String, String> = all
+        .into_iter()
+        .filter_map(|s| s.keyword.map(|k| (k, s.content)))
+        .collect();
+    Ok(template::collect_inputs(&snippet.content, &|keyword: &str| {
+        by_keyword.get(keyword).cloned()
+    }))
+}
+
+/// 片段模板引擎
+///
+/// 支持的占位符：
+/// - `{date}` / `{date:%Y-%m-%d %H:%M}`：当前时间，格式同 chrono strftime
+/// - `{clipboard}`：当前剪切板文本
+/// - `{cursor}`：粘贴后光标位置
+/// - `{input:名称}`：粘贴前提示用户输入
+/// - `{snippet:关键字}`：嵌套其他片段
+///
+/// `{{` 与 `}}` 分别转义为 `{` 与 `}`，无法识别的占位符按原样输出。
+pub mod template {
+    use super::RenderedSnippet;
+    use std::collections::HashMap;
+
+    const MAX_NESTING_DEPTH: usize = 8;
+    const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+    pub struct RenderContext<'a> {
+        pub now: chrono::DateTime<chrono::Local>,
+        pub clipboard: Option<String>,
+        pub inputs: &'a HashMap<String, String>,
+        pub resolve_snippet: &'a dyn Fn(&str) -> Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Token {
+        Text(String),
+        Date(String),
+        Clipboard,
+        Cursor,
+        Input(String),
+        Snippet(String),
+    }
+
+    fn parse_placeholder(inner: &str) -> Option<Token> {
+        let (name, arg) = match inner.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg)),
+            None => (inner.trim(), None),
+        };
+        match (name, arg) {
+            ("date", None) => Some(Token::Date(DEFAULT_DATE_FORMAT.to_string())),
+            ("date", Some(fmt)) if !fmt.is_empty() => Some(Token::Date(fmt.to_string())),
+            ("clipboard", None) => Some(Token::Clipboard),
+            ("cursor", None) => Some(Token::Cursor),
+            ("input", Some(label)) if !label.trim().is_empty() => {
+                Some(Token::Input(label.trim().to_string()))
+            }
+            ("snippet", Some(keyword)) if !keyword.trim().is_empty() => {
+                Some(Token::Snippet(keyword.trim().to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    fn tokenize(source: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        let mut rest = source;
+
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                text.push(c);
+                rest = &rest[2..];
+                continue;
+            }
+            if c == '{' {
+                if let Some(end) = rest.find('}') {
+                    let inner = &rest[1..end];
+                    if !inner.contains('{') {
+                        if let Some(token) = parse_placeholder(inner) {
+                            if !text.is_empty() {
+                                tokens.push(Token::Text(std::mem::take(&mut text)));
+                            }
+                            tokens.push(token);
+                            rest = &rest[end + 1..];
+                            continue;
+                        }
+                    }
+                }
+            }
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+        tokens
+    }
+
+    fn render_into(
+        source: &str,
+        ctx: &RenderContext,
+        stack: &mut Vec<String>,
+        out: &mut String,
+        cursor: &mut Option<usize>,
+    ) -> Result<(), String> {
+        for token in tokenize(source) {
+            match token {
+                Token::Text(t) => out.push_str(&t),
+                Token::Date(fmt) => {
+                    use std::fmt::Write;
+                    // chrono 在格式串非法时会在 Display 阶段返回错误
+                    write!(out, "{}", ctx.now.format(&fmt))
+                        .map_err(|_| format!("无效的日期格式: {}", fmt))?;
+                }
+                Token::Clipboard => {
+                    if let Some(ref text) = ctx.clipboard {
+                        out.push_str(text);
+                    }
+                }
+                Token::Cursor => {
+                    // 只记录第一个光标位置
+                    if cursor.is_none() {
+                        *cursor = Some(out.chars().count());
+                    }
+                }
+                Token::Input(label) => {
+                    if let Some(value) = ctx.inputs.get(&label) {
+                        out.push_str(value);
+                    }
+                }
+                Token::Snippet(keyword) => {
+                    if stack.iter().any(|k| k == &keyword) {
+                        return Err(format!("片段 \"{}\" 存在循环引用", keyword));
+                    }
+                    if stack.len() >= MAX_NESTING_DEPTH {
+                        return Err("片段嵌套层级过深".to_string());
+                    }
+                    let nested = (ctx.resolve_snippet)(&keyword)
+                        .ok_or_else(|| format!("未找到关键字为 \"{}\" 的片段", keyword))?;
+                    stack.push(keyword);
+                    render_into(&nested, ctx, stack, out, cursor)?;
+                    stack.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 渲染模板，`root_keyword` 为模板自身的关键字，用于检测自引用
+    pub fn render(
+        source: &str,
+        root_keyword: Option<&str>,
+        ctx: &RenderContext,
+    ) -> Result<RenderedSnippet, String> {
+        let mut out = String::new();
+        let mut cursor = None;
+        let mut stack: Vec<String> = root_keyword.map(|k| k.to_string()).into_iter().collect();
+        render_into(source, ctx, &mut stack, &mut out, &mut cursor)?;
+        Ok(RenderedSnippet {
+            text: out,
+            cursor_offset: cursor,
+        })
+    }
+
+    /// 收集模板（含嵌套）中的 `{input:...}` 字段，按出现顺序去重
+    pub fn collect_inputs(source: &str, resolve: &dyn Fn(&str) -> Option<String>) -> Vec<String> {
+        fn walk(
+            source: &str,
+            resolve: &dyn Fn(&str) -> Option<String>,
+            stack: &mut Vec<String>,
+            out: &mut Vec<String>,
+        ) {
+            for token in tokenize(source) {
+                match token {
+                    Token::Input(label) => {
+                        if !out.contains(&label) {
+                            out.push(label);
+                        }
+                    }
+                    Token::Snippet(keyword) => {
+                        if stack.contains(&keyword) || stack.len() >= MAX_NESTING_DEPTH {
+                            continue;
+                        }
+                        if let Some(nested) = resolve(&keyword) {
+                            stack.push(keyword);
+                            walk(&nested, resolve, stack, out);
+                            stack.pop();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let mut out = Vec::new();
+        walk(source, resolve, &mut Vec::new(), &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::template::*;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn render_with(
+        source: &str,
+        inputs: &HashMap<String, String>,
+        snippets: &HashMap<String, String>,
+    ) -> Result<super::RenderedSnippet, String> {
+        let resolve = |k: &str| snippets.get(k).cloned();
+        let ctx = RenderContext {
+            now: chrono::Local.with_ymd_and_hms(2024, 3, 5, 9, 7, 0).unwrap(),
+            clipboard: Some("CLIP".to_string()),
+            inputs,
+            resolve_snippet: &resolve,
+        };
+        render(source, None, &ctx)
+    }
+
+    #[test]
+    fn test_render_builtin_placeholders() {
+        let mut inputs = HashMap::new();
+        inputs.insert("Name".to_string(), "Alice".to_string());
+        let result = render_with(
+            "Hi {input:Name}, {date} {date:%H:%M} [{clipboard}]",
+            &inputs,
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(result.text, "Hi Alice, 2024-03-05 09:07 [CLIP]");
+        assert_eq!(result.cursor_offset, None);
+    }
+
+    #[test]
+    fn test_render_cursor_and_escapes() {
+        let result = render_with("fn {{ {cursor} }}", &HashMap::new(), &HashMap::new()).unwrap();
+        assert_eq!(result.text, "fn {  }");
+        assert_eq!(result.cursor_offset, Some(5));
+    }
+
+    #[test]
+    fn test_render_unknown_placeholder_is_literal() {
+        let result = render_with("{foo} {input:}", &HashMap::new(), &HashMap::new()).unwrap();
+        assert_eq!(result.text, "{foo} {input:}");
+    }
+
+    #[test]
+    fn test_render_nested_snippets() {
+        let mut snippets = HashMap::new();
+        snippets.insert("sig".to_string(), "-- {input:Name}".to_string());
+        let mut inputs = HashMap::new();
+        inputs.insert("Name".to_string(), "Bob".to_string());
+        let result = render_with("Thanks\n{snippet:sig}", &inputs, &snippets).unwrap();
+        assert_eq!(result.text, "Thanks\n-- Bob");
+
+        let resolve = |k: &str| snippets.get(k).cloned();
+        assert_eq!(
+            collect_inputs("{input:Title} {snippet:sig} {input:Title}", &resolve),
+            vec!["Title".to_string(), "Name".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_render_detects_cycles() {
+        let mut snippets = HashMap::new();
+        snippets.insert("a".to_string(), "A{snippet:b}".to_string());
+        snippets.insert("b".to_string(), "B{snippet:a}".to_string());
+        assert!(render_with("{snippet:a}", &HashMap::new(), &snippets).is_err());
+        assert!(render_with("{snippet:missing}", &HashMap::new(), &snippets).is_err());
+
+        let resolve = |k: &str| snippets.get(k).cloned();
+        let inputs = HashMap::new();
+        let ctx = RenderContext {
+            now: chrono::Local::now(),
+            clipboard: None,
+            inputs: &inputs,
+            resolve_snippet: &resolve,
+        };
+        assert!(render("self {snippet:me}", Some("me"), &ctx).is_err());
+    }
+
+    #[test]
+    fn test_search_snippets_escapes_like_wildcards() {
+        let dir = crate::test_support::temp_dir("snippets-search");
+        let add = |name: &str, keyword: Option<&str>, content: &str| {
+            super::add_snippet(name.into(), keyword.map(Into::into), None, content.into(), &dir).unwrap()
+        };
+        add("Discount", None, "save 100% today");
+        add("Signature", Some("sig"), "Best regards");
+        add("Snake", Some("my_sig"), "snake_case");
+
+        let names = |query: &str| -> Vec<String> {
+            super::search_snippets(query, &dir).unwrap().into_iter().map(|s| s.name).collect()
+        };
+        assert_eq!(names("100%"), vec!["Discount"]);
+        assert_eq!(names("%"), vec!["Discount"]);
+        assert_eq!(names("_"), vec!["Snake"]);
+        // 关键字完全匹配的排在最前
+        assert_eq!(names("SIG"), vec!["Signature", "Snake"]);
+    }
+}
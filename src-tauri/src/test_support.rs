@@ -0,0 +1,40 @@
+//! 单元测试共用的辅助函数
+
+use std::io::Read;
+use std::net::TcpStream;
+use std::path::PathBuf;
+
+/// 为测试创建一个空的临时数据目录，同名目录已存在时先清空
+///
+/// 目录名带上进程号，`cargo test` 的多个进程互不干扰；
+/// 同一进程内的测试需要传入不同的 `name`
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("refast-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// 读完一个 HTTP 请求的请求头和请求体并原样返回，避免未读数据导致连接被重置
+pub(crate) fn read_http_request(socket: &mut TcpStream) -> String {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let Ok(n) = socket.read(&mut buf) else { break };
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+        let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") else { continue };
+        let head = String::from_utf8_lossy(&data[..end]).to_lowercase();
+        let length: usize = head
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length:"))
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(0);
+        if data.len() >= end + 4 + length {
+            break;
+        }
+    }
+    String::from_utf8_lossy(&data).into_owned()
+}
@@ -0,0 +1,617 @@
+//! 网页搜索
+//!
+//! - 搜索引擎前缀（如 "g rust"）和 DuckDuckGo 风格的 bang（"!gh rust" 或 "rust !gh"）。
+//!   bang 先匹配设置中的搜索引擎（前缀去掉空格即为 bang），再查导入的 bang 列表
+//! - URL 模板占位符：`{query}` 按引擎的编码方式编码，`{raw}` 原样插入，`{clipboard}` 为编码后的剪切板文本；
+//!   引擎可指定字符集（如部分中文网站使用 GBK），编码前先转换
+//! - 没有匹配的前缀或 bang 时使用设置中的后备引擎
+//! - 搜索建议通过引擎配置的 OpenSearch 建议接口获取，结果在内存中缓存一段时间
+
+use crate::db;
+use crate::settings::{QueryEncoding, SearchEngineConfig, Settings};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+const SUGGEST_TIMEOUT: Duration = Duration::from_secs(3);
+const SUGGEST_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+const SUGGEST_CACHE_MAX_ENTRIES: usize = 256;
+const MAX_SUGGESTIONS: usize = 8;
+
+/// 建议接口 URL -> (获取时间, 建议)
+static SUGGEST_CACHE: LazyLock<Mutex<HashMap<String, (Instant, Vec<String>)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 搜索是如何匹配到引擎的
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMatch {
+    Prefix,
+    Bang,
+    Fallback,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchTarget {
+    pub engine: String,
+    pub keyword: String,
+    pub url: String,
+    pub matched_by: SearchMatch,
+}
+
+/// 导入的 bang
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchBang {
+    pub trigger: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub encoding: QueryEncoding,
+}
+
+impl SearchBang {
+    fn to_engine(&self) -> SearchEngineConfig {
+        SearchEngineConfig {
+            prefix: format!("!{}", self.trigger),
+            url: self.url.clone(),
+            name: self.name.clone(),
+            encoding: self.encoding,
+            charset: None,
+            suggest_url: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BangImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+// ===== URL 构建 =====
+
+/// 按引擎的字符集和编码方式编码文本
+fn encode_component(text: &str, engine: &SearchEngineConfig) -> String {
+    let bytes = match engine
+        .charset
+        .as_deref()
+        .and_then(|label| encoding_rs::Encoding::for_label(label.trim().as_bytes()))
+    {
+        // 无法表示的字符会被转换成 HTML 数字实体，与浏览器提交表单的行为一致
+        Some(encoding) => encoding.encode(text).0.into_owned(),
+        None => text.as_bytes().to_vec(),
+    };
+
+    let mut encoded = String::with_capacity(bytes.len() * 3);
+    for byte in bytes {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b' ' if engine.encoding == QueryEncoding::Plus => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// 替换 URL 模板中的占位符
+pub fn build_search_url(
+    template: &str,
+    engine: &SearchEngineConfig,
+    keyword: &str,
+    clipboard: Option<&str>,
+) -> String {
+    let mut url = template
+        .replace("{query}", &encode_component(keyword, engine))
+        .replace("{raw}", keyword);
+    if url.contains("{clipboard}") {
+        let clipboard = clipboard.map(str::trim).unwrap_or_default();
+        url = url.replace("{clipboard}", &encode_component(clipboard, engine));
+    }
+    url
+}
+
+// ===== 匹配 =====
+
+/// 按前缀匹配设置中的搜索引擎，前缀重叠时优先更长的前缀（与启动器前端一致）
+fn match_prefix<'a>(
+    query: &'a str,
+    engines: &'a [SearchEngineConfig],
+) -> Option<(&'a SearchEngineConfig, &'a str)> {
+    let mut sorted: Vec<&SearchEngineConfig> =
+        engines.iter().filter(|e| !e.prefix.is_empty()).collect();
+    sorted.sort_by_key(|e| std::cmp::Reverse(e.prefix.len()));
+    sorted.into_iter().find_map(|engine| {
+        let rest = query.strip_prefix(engine.prefix.as_str())?;
+        // 前缀本身不以空格结尾时，要求后面跟一个空格，避免 "gh" 误匹配 "ghost"
+        let keyword = if engine.prefix.ends_with(' ') {
+            rest
+        } else {
+            rest.strip_prefix(' ')?
+        };
+        Some((engine, keyword.trim()))
+    })
+}
+
+/// 拆出开头或结尾的 `!bang`，返回小写的 bang 和剩余关键词
+pub fn split_bang(query: &str) -> Option<(String, String)> {
+    let query = query.trim();
+    let is_bang = |token: &str| token.len() > 1 && token.starts_with('!');
+
+    let (first, rest) = query.split_once(char::is_whitespace).unwrap_or((query, ""));
+    if is_bang(first) {
+        return Some((first[1..].to_lowercase(), rest.trim().to_string()));
+    }
+    let (rest, last) = query.rsplit_once(char::is_whitespace)?;
+    if is_bang(last) {
+        return Some((last[1..].to_lowercase(), rest.trim().to_string()));
+    }
+    None
+}
+
+/// 解析启动器输入：先匹配前缀，再匹配 bang，`use_fallback` 时都不匹配（或 bang 未知）则使用后备引擎
+pub fn resolve(
+    query: &str,
+    settings: &Settings,
+    find_bang: impl Fn(&str) -> Option<SearchBang>,
+    use_fallback: bool,
+    clipboard: Option<&str>,
+) -> Option<SearchTarget> {
+    let target = |engine: &SearchEngineConfig, keyword: &str, matched_by| SearchTarget {
+        engine: engine.name.clone(),
+        keyword: keyword.to_string(),
+        url: build_search_url(&engine.url, engine, keyword, clipboard),
+        matched_by,
+    };
+
+    if let Some((engine, keyword)) = match_prefix(query, &settings.search_engines) {
+        return Some(target(engine, keyword, SearchMatch::Prefix));
+    }
+
+    let mut keyword = query.trim().to_string();
+    if let Some((bang, rest)) = split_bang(query) {
+        let configured = settings
+            .search_engines
+            .iter()
+            .find(|e| e.prefix.trim().eq_ignore_ascii_case(&bang));
+        if let Some(engine) = configured {
+            return Some(target(engine, &rest, SearchMatch::Bang));
+        }
+        if let Some(bang) = find_bang(bang.as_str()) {
+            return Some(target(&bang.to_engine(), &rest, SearchMatch::Bang));
+        }
+        // 未知的 bang 按普通文字处理，去掉后交给后备引擎
+        keyword = rest;
+    }
+
+    if !use_fallback || keyword.is_empty() {
+        return None;
+    }
+    let fallback = settings.fallback_search_engine.as_ref()?;
+    let engine = settings
+        .search_engines
+        .iter()
+        .find(|e| e.name == *fallback)?;
+    Some(target(engine, &keyword, SearchMatch::Fallback))
+}
+
+// ===== bang 列表 =====
+
+/// 从 JSON 导入 bang 列表，支持 DuckDuckGo 的 bang.js 格式（`t` / `s` / `u`，占位符 `{{{s}}}`）
+/// 和本程序的格式（`trigger` / `name` / `url`）；同名 bang 覆盖旧的
+pub fn import_bangs(source: &Path, app_data_dir: &Path) -> Result<BangImportSummary, String> {
+    let content =
+        fs::read_to_string(source).map_err(|e| format!("Failed to read bang list: {}", e))?;
+    let entries: Vec<Value> =
+        serde_json::from_str(&content).map_err(|e| format!("bang 列表格式不正确: {}", e))?;
+
+    let mut summary = BangImportSummary::default();
+    let bangs: Vec<SearchBang> = entries
+        .iter()
+        .filter_map(|entry| {
+            let bang = parse_bang_entry(entry);
+            if bang.is_none() {
+                summary.skipped += 1;
+            }
+            bang
+        })
+        .collect();
+
+    let mut conn = db::get_connection(app_data_dir)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start bang import transaction: {}", e))?;
+    for bang in &bangs {
+        let encoding = serde_json::to_value(bang.encoding)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        tx.execute(
+            "INSERT INTO search_bangs (trigger, name, url, encoding) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(trigger) DO UPDATE SET name = excluded.name, url = excluded.url, encoding = excluded.encoding",
+            params![bang.trigger, bang.name, bang.url, encoding],
+        )
+        .map_err(|e| format!("Failed to save bang {}: {}", bang.trigger, e))?;
+        summary.imported += 1;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit bang import: {}", e))?;
+    Ok(summary)
+}
+
+fn parse_bang_entry(entry: &Value) -> Option<SearchBang> {
+    let field = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| entry.get(*name).and_then(Value::as_str))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    let trigger = field(&["trigger", "t"])?
+        .trim_start_matches('!')
+        .to_lowercase();
+    let name = field(&["name", "s"]).unwrap_or(&trigger).to_string();
+    let url = field(&["url", "u"])?.replace("{{{s}}}", "{query}");
+    if trigger.is_empty() || trigger.contains(char::is_whitespace) || !url.contains("{query}") {
+        return None;
+    }
+
+    // bang.js 用 fmt 标记空格转 +，本程序格式直接给出 encoding
+    let plus = entry
+        .get("fmt")
+        .and_then(Value::as_array)
+        .is_some_and(|fmt| fmt.iter().any(|f| f == "url_encode_space_to_plus"));
+    let encoding = entry
+        .get("encoding")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or(if plus {
+            QueryEncoding::Plus
+        } else {
+            QueryEncoding::Percent
+        });
+
+    Some(SearchBang {
+        trigger,
+        name,
+        url,
+        encoding,
+    })
+}
+
+fn bang_from_row(row: &rusqlite::Row) -> rusqlite::Result<SearchBang> {
+    let encoding: String = row.get(3)?;
+    Ok(SearchBang {
+        trigger: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        encoding: serde_json::from_value(Value::String(encoding)).unwrap_or_default(),
+    })
+}
+
+pub fn find_bang(trigger: &str, app_data_dir: &Path) -> Result<Option<SearchBang>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.query_row(
+        "SELECT trigger, name, url, encoding FROM search_bangs WHERE trigger = ?1",
+        params![trigger.to_lowercase()],
+        bang_from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to look up bang: {}", e))
+}
+
+/// 按前缀列出 bang，用于输入 "!" 时的补全
+pub fn list_bangs(
+    prefix: &str,
+    limit: usize,
+    app_data_dir: &Path,
+) -> Result<Vec<SearchBang>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let pattern = format!(
+        "{}%",
+        prefix
+            .trim_start_matches('!')
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let mut stmt = conn
+        .prepare(
+            "SELECT trigger, name, url, encoding FROM search_bangs
+             WHERE trigger LIKE ?1 ESCAPE '\\' ORDER BY length(trigger), trigger LIMIT ?2",
+        )
+        .map_err(|e| format!("Failed to prepare bang query: {}", e))?;
+    let rows = stmt
+        .query_map(params![pattern, limit as i64], bang_from_row)
+        .map_err(|e| format!("Failed to query bangs: {}", e))?;
+    let bangs = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read bang row: {}", e))?;
+    Ok(bangs)
+}
+
+pub fn clear_bangs(app_data_dir: &Path) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute("DELETE FROM search_bangs", [])
+        .map_err(|e| format!("Failed to clear bangs: {}", e))?;
+    Ok(())
+}
+
+// ===== 搜索建议 =====
+
+/// 解析 OpenSearch 建议响应 `["query", ["建议1", "建议2"], ...]`，也接受纯字符串数组
+fn parse_suggestions(body: &str) -> Result<Vec<String>, String> {
+    let value: Value =
+        serde_json::from_str(body).map_err(|e| format!("Invalid suggestion response: {}", e))?;
+    let list = match &value {
+        Value::Array(items) => match items.get(1) {
+            Some(Value::Array(list)) if items.first().is_some_and(Value::is_string) => list,
+            _ => items,
+        },
+        _ => return Err("Invalid suggestion response: expected a JSON array".to_string()),
+    };
+    Ok(list
+        .iter()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .take(MAX_SUGGESTIONS)
+        .collect())
+}
+
+fn cached_suggestions(url: &str) -> Option<Vec<String>> {
+    let cache = SUGGEST_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .get(url)
+        .filter(|(fetched_at, _)| fetched_at.elapsed() < SUGGEST_CACHE_TTL)
+        .map(|(_, suggestions)| suggestions.clone())
+}
+
+fn cache_suggestions(url: String, suggestions: Vec<String>) {
+    let mut cache = SUGGEST_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.len() >= SUGGEST_CACHE_MAX_ENTRIES {
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < SUGGEST_CACHE_TTL);
+    }
+    if cache.len() >= SUGGEST_CACHE_MAX_ENTRIES {
+        if let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, (fetched_at, _))| *fetched_at)
+            .map(|(key, _)| key.clone())
+        {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(url, (Instant::now(), suggestions));
+}
+
+/// 从引擎的建议接口获取建议；引擎没有配置接口或关键词为空时返回空列表
+pub async fn fetch_suggestions(
+    engine: &SearchEngineConfig,
+    keyword: &str,
+) -> Result<Vec<String>, String> {
+    let Some(ref template) = engine.suggest_url else {
+        return Ok(Vec::new());
+    };
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let url = build_search_url(template, engine, keyword, None);
+    if let Some(suggestions) = cached_suggestions(&url) {
+        return Ok(suggestions);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(SUGGEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch suggestions: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Suggestion endpoint returned {}",
+            response.status()
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read suggestions: {}", e))?;
+
+    // 使用 GBK 等字符集的站点，建议接口通常也返回同样的字符集
+    let encoding = engine
+        .charset
+        .as_deref()
+        .and_then(|label| encoding_rs::Encoding::for_label(label.trim().as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    let suggestions = parse_suggestions(&encoding.decode(&bytes).0)?;
+    cache_suggestions(url, suggestions.clone());
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    fn engine(prefix: &str, url: &str, name: &str) -> SearchEngineConfig {
+        SearchEngineConfig {
+            prefix: prefix.to_string(),
+            url: url.to_string(),
+            name: name.to_string(),
+            encoding: QueryEncoding::Percent,
+            charset: None,
+            suggest_url: None,
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            search_engines: vec![
+                engine("g ", "https://google.com/search?q={query}", "Google"),
+                engine("gh", "https://github.com/search?q={query}", "GitHub"),
+                engine("ddg ", "https://duckduckgo.com/?q={query}", "DuckDuckGo"),
+            ],
+            fallback_search_engine: Some("DuckDuckGo".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_search_url() {
+        let mut e = engine(
+            "s ",
+            "https://x.com/?q={query}&raw={raw}&c={clipboard}",
+            "X",
+        );
+        assert_eq!(
+            build_search_url(&e.url, &e, "a b&c", Some(" 剪 ")),
+            "https://x.com/?q=a%20b%26c&raw=a b&c&c=%E5%89%AA"
+        );
+
+        e.encoding = QueryEncoding::Plus;
+        assert_eq!(
+            build_search_url("https://x.com/?q={query}", &e, "a b", None),
+            "https://x.com/?q=a+b"
+        );
+
+        e.charset = Some("GBK".to_string());
+        assert_eq!(
+            build_search_url("https://x.com/?q={query}", &e, "中文", None),
+            "https://x.com/?q=%D6%D0%CE%C4"
+        );
+    }
+
+    #[test]
+    fn test_split_bang() {
+        assert_eq!(
+            split_bang("!GH tauri"),
+            Some(("gh".to_string(), "tauri".to_string()))
+        );
+        assert_eq!(
+            split_bang("tauri  window !w"),
+            Some(("w".to_string(), "tauri  window".to_string()))
+        );
+        assert_eq!(split_bang("!w"), Some(("w".to_string(), String::new())));
+        assert_eq!(split_bang("hello ! world"), None);
+        assert_eq!(split_bang("wow!"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let settings = settings();
+        let bangs = |trigger: &str| {
+            (trigger == "w").then(|| SearchBang {
+                trigger: "w".to_string(),
+                name: "Wikipedia".to_string(),
+                url: "https://en.wikipedia.org/wiki/Special:Search?search={query}".to_string(),
+                encoding: QueryEncoding::Plus,
+            })
+        };
+
+        let target = resolve("gh tauri", &settings, bangs, false, None).unwrap();
+        assert_eq!(
+            (target.engine.as_str(), target.matched_by),
+            ("GitHub", SearchMatch::Prefix)
+        );
+
+        let target = resolve("rust lang !g", &settings, bangs, false, None).unwrap();
+        assert_eq!(target.engine, "Google");
+        assert_eq!(target.url, "https://google.com/search?q=rust%20lang");
+
+        let target = resolve("!w rust lang", &settings, bangs, false, None).unwrap();
+        assert_eq!(target.engine, "Wikipedia");
+        assert!(target.url.ends_with("search=rust+lang"));
+
+        assert!(resolve("rust lang", &settings, bangs, false, None).is_none());
+        let target = resolve("!nope rust", &settings, bangs, true, None).unwrap();
+        assert_eq!(
+            (target.engine.as_str(), target.matched_by),
+            ("DuckDuckGo", SearchMatch::Fallback)
+        );
+        assert_eq!(target.keyword, "rust");
+    }
+
+    #[test]
+    fn test_parse_bang_entry() {
+        let entry = serde_json::json!({
+            "t": "gh", "s": "GitHub", "u": "https://github.com/search?q={{{s}}}",
+            "fmt": ["open_base_path", "url_encode_placeholder", "url_encode_space_to_plus"]
+        });
+        let bang = parse_bang_entry(&entry).unwrap();
+        assert_eq!(bang.url, "https://github.com/search?q={query}");
+        assert_eq!(bang.encoding, QueryEncoding::Plus);
+
+        let entry = serde_json::json!({ "trigger": "!W", "url": "https://w.org/?s={query}" });
+        let bang = parse_bang_entry(&entry).unwrap();
+        assert_eq!((bang.trigger.as_str(), bang.name.as_str()), ("w", "w"));
+        assert!(
+            parse_bang_entry(&serde_json::json!({ "t": "x", "u": "https://x.org/" })).is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_suggestions() {
+        assert_eq!(
+            parse_suggestions(r#"["rust", ["rust lang", "rust book"], [], []]"#).unwrap(),
+            vec!["rust lang", "rust book"]
+        );
+        assert_eq!(parse_suggestions(r#"["a", "b"]"#).unwrap(), vec!["a", "b"]);
+        assert!(parse_suggestions(r#"{"q": 1}"#).is_err());
+    }
+
+    /// 启动一个只响应一次的 HTTP 服务，返回服务地址
+    fn stub_server(body: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf);
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = socket.write_all(head.as_bytes());
+            let _ = socket.write_all(&body);
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_suggestions_cached() {
+        let base = stub_server(br#"["rust", ["rust lang", "rustup"]]"#.to_vec());
+        let mut e = engine("s ", "https://x.com/?q={query}", "X");
+        e.suggest_url = Some(format!("{}/complete?q={{query}}", base));
+
+        let suggestions = fetch_suggestions(&e, "rust").await.unwrap();
+        assert_eq!(suggestions, vec!["rust lang", "rustup"]);
+        // 服务只响应一次，第二次必须来自缓存
+        assert_eq!(fetch_suggestions(&e, "rust").await.unwrap(), suggestions);
+        assert!(fetch_suggestions(&e, " ").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_suggestions_gbk() {
+        let (body, _, _) = encoding_rs::GBK.encode(r#"["中", ["中文", "中国"]]"#);
+        let base = stub_server(body.into_owned());
+        let mut e = engine("bd ", "https://www.baidu.com/s?wd={query}", "百度");
+        e.charset = Some("gbk".to_string());
+        e.suggest_url = Some(format!("{}/su?wd={{query}}", base));
+
+        assert_eq!(
+            fetch_suggestions(&e, "中").await.unwrap(),
+            vec!["中文", "中国"]
+        );
+    }
+}
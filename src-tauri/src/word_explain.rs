@@ -0,0 +1,217 @@
+//! AI 单词解释
+//!
+//! 使用设置中的提示词模板，让 Ollama 以 JSON 返回结构化解释（语境含义、搭配、
+//! 近义词、例句），渲染为 Markdown 后写入 `word_records.ai_explanation`，
+//! 与前端已有的解释展示保持兼容。模型没有按 JSON 回复时原样保存文本。
+
+use crate::ollama::{CancelToken, ChatMessage, GenerateOptions, OllamaClient};
+use crate::word_records::{self, row_to_word_record, WordRecord, WORD_RECORD_COLUMNS};
+use crate::db;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExampleSentence {
+    pub sentence: String,
+    pub translation: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WordExplanation {
+    pub meaning_in_context: String,
+    pub collocations: Vec<String>,
+    pub synonyms: Vec<String>,
+    pub example: ExampleSentence,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplanationBatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub cancelled: bool,
+}
+
+/// 用单词信息填充提示词模板
+pub fn render_prompt(template: &str, record: &WordRecord) -> String {
+    let context = record
+        .context
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .unwrap_or("（无）");
+    template
+        .replace("{word}", &record.word)
+        .replace("{translation}", &record.translation)
+        .replace("{context}", context)
+}
+
+/// 从模型回复中解析结构化解释，兼容 ```json 代码块和前后多余文字
+pub fn parse_explanation(raw: &str) -> Option<WordExplanation> {
+    let start = raw.find('{')?;
+    let end = raw.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    let explanation: WordExplanation = serde_json::from_str(&raw[start..=end]).ok()?;
+    let is_empty = explanation.meaning_in_context.trim().is_empty()
+        && explanation.collocations.is_empty()
+        && explanation.synonyms.is_empty()
+        && explanation.example.sentence.trim().is_empty();
+    (!is_empty).then_some(explanation)
+}
+
+pub fn explanation_to_markdown(explanation: &WordExplanation) -> String {
+    let mut out = String::new();
+    let meaning = explanation.meaning_in_context.trim();
+    if !meaning.is_empty() {
+        out.push_str(&format!("### 语境含义\n\n{}\n\n", meaning));
+    }
+    let list = |title: &str, items: &[String], out: &mut String| {
+        let items: Vec<&str> = items.iter().map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
+        if items.is_empty() {
+            return;
+        }
+        out.push_str(&format!("### {}\n\n", title));
+        for item in items {
+            out.push_str(&format!("- {}\n", item));
+        }
+        out.push('\n');
+    };
+    list("常见搭配", &explanation.collocations, &mut out);
+    list("近义词", &explanation.synonyms, &mut out);
+    let sentence = explanation.example.sentence.trim();
+    if !sentence.is_empty() {
+        out.push_str(&format!("### 例句\n\n> {}\n", sentence));
+        let translation = explanation.example.translation.trim();
+        if !translation.is_empty() {
+            out.push_str(&format!(">\n> {}\n", translation));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// 生成单词解释（Markdown 文本），不写入数据库
+pub async fn generate_explanation(
+    client: &OllamaClient,
+    template: &str,
+    record: &WordRecord,
+    cancel: &CancelToken,
+) -> Result<String, String> {
+    let messages = [ChatMessage::user(render_prompt(template, record))];
+    let options = GenerateOptions { temperature: Some(0.3), ..Default::default() };
+    let raw = client.chat_json(&messages, Some(&options), cancel).await?;
+    match parse_explanation(&raw) {
+        Some(explanation) => Ok(explanation_to_markdown(&explanation)),
+        None if !raw.trim().is_empty() => Ok(raw.trim().to_string()),
+        None => Err(format!("模型没有返回单词 {} 的解释", record.word)),
+    }
+}
+
+pub fn load_word_record(id: &str, app_data_dir: &Path) -> Result<WordRecord, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    conn.query_row(
+        &format!("SELECT {} FROM word_records WHERE id = ?1", WORD_RECORD_COLUMNS),
+        params![id],
+        row_to_word_record,
+    )
+    .map_err(|e| format!("Failed to load word_record {}: {}", id, e))
+}
+
+/// 还没有 AI 解释的单词，按添加时间先后
+pub fn get_words_missing_explanation(
+    limit: Option<u32>,
+    app_data_dir: &Path,
+) -> Result<Vec<WordRecord>, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM word_records
+             WHERE ai_explanation IS NULL OR trim(ai_explanation) = ''
+             ORDER BY created_at ASC
+             LIMIT ?1",
+            WORD_RECORD_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare word_records query: {}", e))?;
+    let rows = stmt
+        .query_map(params![limit.map(|l| l as i64).unwrap_or(-1)], row_to_word_record)
+        .map_err(|e| format!("Failed to query word_records: {}", e))?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read word_record row: {}", e))?);
+    }
+    Ok(items)
+}
+
+pub fn save_explanation(id: &str, explanation: String, app_data_dir: &Path) -> Result<WordRecord, String> {
+    word_records::update_word_record(
+        id.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(explanation),
+        None,
+        None,
+        None,
+        app_data_dir,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(context: Option<&str>) -> WordRecord {
+        serde_json::from_value(serde_json::json!({
+            "id": "word-1",
+            "word": "bank",
+            "translation": "河岸",
+            "context": context,
+            "tags": [],
+            "masteryLevel": 0,
+            "reviewCount": 0,
+            "createdAt": 0,
+            "updatedAt": 0,
+            "isFavorite": false,
+            "isMastered": false
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let prompt = render_prompt("{word}|{translation}|{context}", &record(Some(" the river bank ")));
+        assert_eq!(prompt, "bank|河岸|the river bank");
+        assert_eq!(render_prompt("{context}", &record(None)), "（无）");
+    }
+
+    #[test]
+    fn test_parse_explanation_from_fenced_reply() {
+        let raw = "好的：\n```json\n{\"meaning_in_context\":\"河岸\",\"collocations\":[\"river bank\"],\"synonyms\":[],\"example\":{\"sentence\":\"We sat on the bank.\",\"translation\":\"我们坐在河岸上。\"}}\n```";
+        let explanation = parse_explanation(raw).unwrap();
+        assert_eq!(explanation.collocations, vec!["river bank"]);
+        assert!(parse_explanation("no json here").is_none());
+        assert!(parse_explanation("{}").is_none());
+    }
+
+    #[test]
+    fn test_markdown_skips_empty_sections() {
+        let markdown = explanation_to_markdown(&WordExplanation {
+            meaning_in_context: "河岸".to_string(),
+            collocations: vec!["river bank".to_string(), " ".to_string()],
+            synonyms: Vec::new(),
+            example: ExampleSentence { sentence: "We sat on the bank.".to_string(), translation: String::new() },
+        });
+        assert_eq!(
+            markdown,
+            "### 语境含义\n\n河岸\n\n### 常见搭配\n\n- river bank\n\n### 例句\n\n> We sat on the bank."
+        );
+    }
+}
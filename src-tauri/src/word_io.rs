@@ -0,0 +1,952 @@
+//! 单词本导入/导出
+//!
+//! 导入支持 CSV/TSV（可配置列映射）、Anki 纯文本导出（"Notes in Plain Text"）、
+//! Anki 牌组包（`.apkg` / `.colpkg`）以及每行一个单词的简单列表；按 `word`（不区分大小写）去重并合并标签。
+//! 导入前可通过 [`preview_word_import`] 查看冲突。
+//!
+//! 导出支持 CSV、Anki 可导入的 TSV 和 Markdown 表格。
+
+use crate::db;
+use crate::word_records::{row_to_word_record, WordRecord, WORD_RECORD_COLUMNS};
+use crate::word_review::DEFAULT_EASE_FACTOR;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordImportFormat {
+    Csv,
+    Tsv,
+    /// Anki "Notes in Plain Text" 导出（制表符分隔，可带 `#separator:` 等头部）
+    AnkiText,
+    /// Anki 牌组包：ZIP 中的 `collection.anki2` / `collection.anki21`（SQLite），读取 `notes` 表
+    AnkiPackage,
+    /// 每行一个单词，可用制表符附带释义
+    WordList,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordExportFormat {
+    Csv,
+    /// 正面、背面、标签三列，可直接导入 Anki
+    AnkiTsv,
+    Markdown,
+}
+
+/// 已存在同名单词时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordConflictStrategy {
+    /// 保留原记录
+    Skip,
+    /// 合并标签，只填充原记录中为空的字段
+    Merge,
+    /// 使用导入内容覆盖，标签仍然合并
+    Overwrite,
+}
+
+/// CSV/TSV 列映射（从 0 开始的列序号）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ColumnMapping {
+    pub word: usize,
+    pub translation: Option<usize>,
+    pub phonetic: Option<usize>,
+    pub context: Option<usize>,
+    pub example_sentence: Option<usize>,
+    /// 标签列，多个标签以空格或逗号分隔
+    pub tags: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WordImportOptions {
+    /// 为空时按扩展名推断
+    pub format: Option<WordImportFormat>,
+    /// 为空时根据表头推断，没有表头时依次为 单词、释义、音标、标签
+    pub mapping: Option<ColumnMapping>,
+    /// 第一行是否为表头；为空时自动判断
+    pub has_header: Option<bool>,
+    /// 给所有导入的单词追加的标签
+    pub extra_tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedWord {
+    pub word: String,
+    pub translation: String,
+    pub phonetic: Option<String>,
+    pub context: Option<String>,
+    pub example_sentence: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordConflict {
+    pub existing: WordRecord,
+    pub incoming: ImportedWord,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordImportPreview {
+    pub total: usize,
+    pub new_words: Vec<ImportedWord>,
+    pub conflicts: Vec<WordConflict>,
+    /// 文件内重复、已合并的行数
+    pub duplicates_in_file: usize,
+    /// 无法解析或缺少单词的行
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn word_key(word: &str) -> String {
+    word.trim().to_lowercase()
+}
+
+fn non_empty(value: Option<&String>) -> Option<String> {
+    value.map(|v| v.trim()).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+fn split_tags(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| c == ',' || c == '，' || c.is_whitespace())
+        .map(|t| t.trim().trim_start_matches('#'))
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 合并标签，保持原有顺序并去重（不区分大小写）
+fn merge_tags(existing: &[String], incoming: &[String]) -> Vec<String> {
+    let mut merged: Vec<String> = Vec::new();
+    for tag in existing.iter().chain(incoming) {
+        if !merged.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            merged.push(tag.clone());
+        }
+    }
+    merged
+}
+
+/// 解析带引号的分隔文本（RFC 4180），引号内允许换行和转义引号 `""`
+pub fn parse_delimited(source: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut field_started = false;
+    let mut chars = source.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+        match c {
+            '"' if !field_started => {
+                in_quotes = true;
+                field_started = true;
+            }
+            c if c == delimiter => {
+                row.push(std::mem::take(&mut field));
+                field_started = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(std::mem::take(&mut field));
+                field_started = false;
+                if row.iter().any(|f| !f.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            c => {
+                field.push(c);
+                field_started = true;
+            }
+        }
+    }
+    if field_started || !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        if row.iter().any(|f| !f.is_empty()) {
+            rows.push(row);
+        }
+    }
+    rows
+}
+
+/// 根据表头推断列映射，无法识别单词列时返回 None
+fn mapping_from_header(header: &[String]) -> Option<ColumnMapping> {
+    let find = |names: &[&str]| {
+        header.iter().position(|h| {
+            let h = h.trim().to_lowercase();
+            names.iter().any(|n| h == *n)
+        })
+    };
+    Some(ColumnMapping {
+        word: find(&["word", "单词", "front", "term", "词汇"])?,
+        translation: find(&["translation", "释义", "翻译", "back", "meaning", "definition"]),
+        phonetic: find(&["phonetic", "音标", "pronunciation"]),
+        context: find(&["context", "语境", "来源"]),
+        example_sentence: find(&["example", "example_sentence", "examplesentence", "例句"]),
+        tags: find(&["tags", "标签", "tag"]),
+    })
+}
+
+fn default_mapping() -> ColumnMapping {
+    ColumnMapping {
+        word: 0,
+        translation: Some(1),
+        phonetic: Some(2),
+        tags: Some(3),
+        ..Default::default()
+    }
+}
+
+fn row_to_word(row: &[String], mapping: &ColumnMapping, strip_html: bool) -> Option<ImportedWord> {
+    let get = |index: Option<usize>| {
+        index
+            .and_then(|i| row.get(i))
+            .map(|v| if strip_html { html_to_text(v) } else { v.clone() })
+    };
+    let word = get(Some(mapping.word))?.trim().to_string();
+    if word.is_empty() {
+        return None;
+    }
+    Some(ImportedWord {
+        word,
+        translation: get(mapping.translation).unwrap_or_default().trim().to_string(),
+        phonetic: non_empty(get(mapping.phonetic).as_ref()),
+        context: non_empty(get(mapping.context).as_ref()),
+        example_sentence: non_empty(get(mapping.example_sentence).as_ref()),
+        tags: get(mapping.tags).map(|t| split_tags(&t)).unwrap_or_default(),
+    })
+}
+
+/// 去掉 HTML 标签并还原常见实体
+pub(crate) fn html_to_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut in_tag = false;
+    let mut tag = String::new();
+    for c in value.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag.trim_start_matches('/').to_lowercase();
+                if name.starts_with("br") || name.starts_with("div") || name.starts_with("p") {
+                    out.push('\n');
+                }
+            }
+            c if in_tag => tag.push(c),
+            c => out.push(c),
+        }
+    }
+    out.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+fn detect_format(path: &Path) -> Result<WordImportFormat, String> {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "csv" => Ok(WordImportFormat::Csv),
+        "tsv" | "tab" => Ok(WordImportFormat::Tsv),
+        "txt" => Ok(WordImportFormat::WordList),
+        "apkg" | "colpkg" => Ok(WordImportFormat::AnkiPackage),
+        other => Err(format!("Unsupported word import file type: .{}", other)),
+    }
+}
+
+/// 解析导入文件内容
+pub fn parse_import(
+    source: &str,
+    format: WordImportFormat,
+    options: &WordImportOptions,
+) -> (Vec<ImportedWord>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut words = Vec::new();
+
+    // `.txt` 可能是 Anki 导出，根据头部自动识别
+    let format = if format == WordImportFormat::WordList
+        && source.trim_start_matches('\u{feff}').starts_with("#separator:")
+    {
+        WordImportFormat::AnkiText
+    } else {
+        format
+    };
+
+    match format {
+        WordImportFormat::WordList => {
+            for line in source.trim_start_matches('\u{feff}').lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let (word, translation) = match line.split_once('\t') {
+                    Some((w, t)) => (w.trim(), t.trim()),
+                    None => (line, ""),
+                };
+                words.push(ImportedWord {
+                    word: word.to_string(),
+                    translation: translation.to_string(),
+                    phonetic: None,
+                    context: None,
+                    example_sentence: None,
+                    tags: Vec::new(),
+                });
+            }
+        }
+        WordImportFormat::AnkiText => {
+            let mut delimiter = '\t';
+            let mut html = false;
+            let mut tags_column: Option<usize> = None;
+            let mut body = String::new();
+            for line in source.trim_start_matches('\u{feff}').lines() {
+                if let Some(header) = line.strip_prefix('#') {
+                    if let Some((key, value)) = header.split_once(':') {
+                        match key.trim() {
+                            "separator" => {
+                                delimiter = match value.trim() {
+                                    "tab" | "Tab" => '\t',
+                                    "comma" | "Comma" => ',',
+                                    "semicolon" | "Semicolon" => ';',
+                                    "space" | "Space" => ' ',
+                                    "pipe" | "Pipe" => '|',
+                                    other => other.chars().next().unwrap_or('\t'),
+                                }
+                            }
+                            "html" => html = value.trim() == "true",
+                            // Anki 的列号从 1 开始
+                            "tags column" => {
+                                tags_column = value
+                                    .trim()
+                                    .parse::<usize>()
+                                    .ok()
+                                    .and_then(|c| c.checked_sub(1))
+                            }
+                            _ => {}
+                        }
+                    }
+                    continue;
+                }
+                body.push_str(line);
+                body.push('\n');
+            }
+            let mapping = options.mapping.clone().unwrap_or(ColumnMapping {
+                word: 0,
+                translation: Some(1),
+                tags: tags_column,
+                ..Default::default()
+            });
+            for (i, row) in parse_delimited(&body, delimiter).iter().enumerate() {
+                match row_to_word(row, &mapping, html) {
+                    Some(word) => words.push(word),
+                    None => errors.push(format!("Line {}: missing word", i + 1)),
+                }
+            }
+        }
+        // 牌组包是二进制文件，由 read_anki_package 处理
+        WordImportFormat::AnkiPackage => {
+            errors.push("Anki packages must be imported from a file".to_string());
+        }
+        WordImportFormat::Csv | WordImportFormat::Tsv => {
+            let delimiter = if format == WordImportFormat::Csv { ',' } else { '\t' };
+            let rows = parse_delimited(source, delimiter);
+            let header_mapping = rows.first().and_then(|h| mapping_from_header(h));
+            let has_header = options.has_header.unwrap_or(header_mapping.is_some());
+            let mapping = options
+                .mapping
+                .clone()
+                .or(if has_header { header_mapping } else { None })
+                .unwrap_or_else(default_mapping);
+            let skip = if has_header { 1 } else { 0 };
+            for (i, row) in rows.iter().enumerate().skip(skip) {
+                match row_to_word(row, &mapping, false) {
+                    Some(word) => words.push(word),
+                    None => errors.push(format!("Row {}: missing word", i + 1)),
+                }
+            }
+        }
+    }
+
+    for word in &mut words {
+        word.tags = merge_tags(&word.tags, &options.extra_tags);
+    }
+    (words, errors)
+}
+
+fn read_import(
+    path: &Path,
+    options: &WordImportOptions,
+) -> Result<(Vec<ImportedWord>, Vec<String>), String> {
+    let format = match options.format {
+        Some(f) => f,
+        None => detect_format(path)?,
+    };
+    if format == WordImportFormat::AnkiPackage {
+        return read_anki_package(path, options);
+    }
+    let source =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read import file: {}", e))?;
+    Ok(parse_import(&source, format, options))
+}
+
+/// 读取 Anki 牌组包。每条笔记的字段以 0x1f 分隔，默认第一个字段为单词、第二个为释义，
+/// 笔记标签追加在字段之后，可通过 `mapping` 指定其它字段
+fn read_anki_package(
+    path: &Path,
+    options: &WordImportOptions,
+) -> Result<(Vec<ImportedWord>, Vec<String>), String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to read import file: {}", e))?;
+    let mut zip =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid Anki package: {}", e))?;
+
+    // 新版 Anki 导出时 collection.anki2 只是提示升级的占位集合，真实数据在 collection.anki21
+    let name = ["collection.anki21", "collection.anki2"]
+        .into_iter()
+        .find(|name| zip.index_for_name(name).is_some());
+    let Some(name) = name else {
+        return Err(if zip.index_for_name("collection.anki21b").is_some() {
+            "该 Anki 牌组包使用了新格式，请在 Anki 导出时勾选“支持旧版本 Anki”后重试".to_string()
+        } else {
+            "Invalid Anki package: collection not found".to_string()
+        });
+    };
+    let mut collection = Vec::new();
+    zip.by_name(name)
+        .and_then(|mut entry| entry.read_to_end(&mut collection).map_err(Into::into))
+        .map_err(|e| format!("Failed to read Anki collection: {}", e))?;
+
+    // SQLite 只能从文件打开，先解压到临时文件
+    let temp = std::env::temp_dir().join(format!(
+        "refast-anki-{}-{}.sqlite",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    ));
+    fs::write(&temp, &collection).map_err(|e| format!("Failed to extract Anki collection: {}", e))?;
+    let notes = read_anki_notes(&temp);
+    let _ = fs::remove_file(&temp);
+    let notes = notes?;
+
+    let mut words = Vec::new();
+    let mut errors = Vec::new();
+    for (i, (fields, tags)) in notes.iter().enumerate() {
+        let mut row: Vec<String> = fields.split('\u{1f}').map(str::to_string).collect();
+        let mapping = options.mapping.clone().unwrap_or(ColumnMapping {
+            word: 0,
+            translation: Some(1),
+            tags: Some(row.len()),
+            ..Default::default()
+        });
+        row.push(tags.trim().to_string());
+        match row_to_word(&row, &mapping, true) {
+            Some(mut word) => {
+                word.tags = merge_tags(&word.tags, &options.extra_tags);
+                words.push(word);
+            }
+            None => errors.push(format!("Note {}: missing word", i + 1)),
+        }
+    }
+    Ok((words, errors))
+}
+
+fn read_anki_notes(collection: &Path) -> Result<Vec<(String, String)>, String> {
+    let conn = rusqlite::Connection::open_with_flags(
+        collection,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )
+    .map_err(|e| format!("Failed to open Anki collection: {}", e))?;
+    let mut stmt = conn
+        .prepare("SELECT flds, tags FROM notes ORDER BY id")
+        .map_err(|e| format!("Invalid Anki collection: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| format!("Failed to read Anki notes: {}", e))?;
+    let notes = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read Anki note: {}", e))?;
+    Ok(notes)
+}
+
+/// 合并文件内的重复单词，返回合并后的列表和重复行数
+fn dedup_incoming(words: Vec<ImportedWord>) -> (Vec<ImportedWord>, usize) {
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut merged: Vec<ImportedWord> = Vec::new();
+    let mut duplicates = 0;
+    for word in words {
+        match index.get(&word_key(&word.word)) {
+            Some(&i) => {
+                duplicates += 1;
+                let target = &mut merged[i];
+                if target.translation.is_empty() {
+                    target.translation = word.translation;
+                }
+                target.phonetic = target.phonetic.take().or(word.phonetic);
+                target.context = target.context.take().or(word.context);
+                target.example_sentence = target.example_sentence.take().or(word.example_sentence);
+                target.tags = merge_tags(&target.tags, &word.tags);
+            }
+            None => {
+                index.insert(word_key(&word.word), merged.len());
+                merged.push(word);
+            }
+        }
+    }
+    (merged, duplicates)
+}
+
+fn load_existing(conn: &rusqlite::Connection) -> Result<HashMap<String, WordRecord>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM word_records", WORD_RECORD_COLUMNS))
+        .map_err(|e| format!("Failed to prepare word_records query: {}", e))?;
+    let rows = stmt
+        .query_map([], row_to_word_record)
+        .map_err(|e| format!("Failed to iterate word_records: {}", e))?;
+    let mut map = HashMap::new();
+    for row in rows {
+        let record = row.map_err(|e| format!("Failed to read word_record row: {}", e))?;
+        map.entry(word_key(&record.word)).or_insert(record);
+    }
+    Ok(map)
+}
+
+/// 与现有记录相同（导入不会带来任何变化）
+fn is_same(existing: &WordRecord, incoming: &ImportedWord) -> bool {
+    existing.translation.trim() == incoming.translation.trim()
+        && (incoming.phonetic.is_none() || existing.phonetic == incoming.phonetic)
+        && (incoming.context.is_none() || existing.context == incoming.context)
+        && (incoming.example_sentence.is_none()
+            || existing.example_sentence == incoming.example_sentence)
+        && merge_tags(&existing.tags, &incoming.tags).len() == existing.tags.len()
+}
+
+/// 预览导入结果，不写入数据库
+pub fn preview_word_import(
+    path: &Path,
+    options: &WordImportOptions,
+    app_data_dir: &Path,
+) -> Result<WordImportPreview, String> {
+    let (words, errors) = read_import(path, options)?;
+    let total = words.len();
+    let (words, duplicates_in_file) = dedup_incoming(words);
+
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let existing = load_existing(&conn)?;
+
+    let mut preview = WordImportPreview {
+        total,
+        duplicates_in_file,
+        errors,
+        ..Default::default()
+    };
+    for word in words {
+        match existing.get(&word_key(&word.word)) {
+            Some(record) if is_same(record, &word) => {}
+            Some(record) => preview.conflicts.push(WordConflict {
+                existing: record.clone(),
+                incoming: word,
+            }),
+            None => preview.new_words.push(word),
+        }
+    }
+    Ok(preview)
+}
+
+/// 导入单词，已存在的单词按 `strategy` 处理
+pub fn import_words(
+    path: &Path,
+    options: &WordImportOptions,
+    strategy: WordConflictStrategy,
+    app_data_dir: &Path,
+) -> Result<WordImportSummary, String> {
+    let (words, errors) = read_import(path, options)?;
+    let (words, _) = dedup_incoming(words);
+
+    let mut conn = db::get_connection(app_data_dir)?;
+    let existing = load_existing(&conn)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start import transaction: {}", e))?;
+
+    let now = now_ts();
+    let mut summary = WordImportSummary {
+        errors,
+        ..Default::default()
+    };
+
+    for (i, word) in words.into_iter().enumerate() {
+        match existing.get(&word_key(&word.word)) {
+            Some(record) if is_same(record, &word) => summary.skipped += 1,
+            Some(_) if strategy == WordConflictStrategy::Skip => summary.skipped += 1,
+            Some(record) => {
+                let mut updated = record.clone();
+                if strategy == WordConflictStrategy::Overwrite {
+                    if !word.translation.is_empty() {
+                        updated.translation = word.translation;
+                    }
+                    updated.phonetic = word.phonetic.or(updated.phonetic);
+                    updated.context = word.context.or(updated.context);
+                    updated.example_sentence = word.example_sentence.or(updated.example_sentence);
+                } else {
+                    if updated.translation.trim().is_empty() {
+                        updated.translation = word.translation;
+                    }
+                    updated.phonetic = updated.phonetic.or(word.phonetic);
+                    updated.context = updated.context.or(word.context);
+                    updated.example_sentence = updated.example_sentence.or(word.example_sentence);
+                }
+                updated.tags = merge_tags(&updated.tags, &word.tags);
+                let tags_json = serde_json::to_string(&updated.tags)
+                    .map_err(|e| format!("Failed to serialize tags: {}", e))?;
+                tx.execute(
+                    "UPDATE word_records
+                     SET translation = ?1, phonetic = ?2, context = ?3, example_sentence = ?4,
+                         tags = ?5, updated_at = ?6
+                     WHERE id = ?7",
+                    params![
+                        updated.translation,
+                        updated.phonetic,
+                        updated.context,
+                        updated.example_sentence,
+                        tags_json,
+                        now as i64,
+                        updated.id
+                    ],
+                )
+                .map_err(|e| format!("Failed to update word_record: {}", e))?;
+                summary.updated += 1;
+            }
+            None => {
+                let tags_json = serde_json::to_string(&word.tags)
+                    .map_err(|e| format!("Failed to serialize tags: {}", e))?;
+                tx.execute(
+                    "INSERT INTO word_records (id, word, translation, context, phonetic,
+                                               example_sentence, tags, mastery_level, review_count,
+                                               created_at, updated_at, is_favorite, is_mastered,
+                                               ease_factor)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0, 0, ?8, ?8, 0, 0, ?9)",
+                    params![
+                        // 批量导入时同一秒内会生成多条，追加序号避免 id 冲突
+                        format!("word-{}-{}", now, i),
+                        word.word,
+                        word.translation,
+                        word.context,
+                        word.phonetic,
+                        word.example_sentence,
+                        tags_json,
+                        now as i64,
+                        DEFAULT_EASE_FACTOR
+                    ],
+                )
+                .map_err(|e| format!("Failed to insert word_record: {}", e))?;
+                summary.created += 1;
+            }
+        }
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit word import: {}", e))?;
+    Ok(summary)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Anki TSV 字段：文件声明了 `#html:true`，先转义 HTML 特殊字符；
+/// 制表符和换行不能出现在字段中，换行转为 `<br>`
+fn anki_field(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\t', " ")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// 将单词记录渲染为指定格式
+pub fn render_words(records: &[WordRecord], format: WordExportFormat) -> String {
+    let mut out = String::new();
+    match format {
+        WordExportFormat::Csv => {
+            out.push_str("word,translation,phonetic,context,example_sentence,tags,mastery_level\n");
+            for r in records {
+                let fields = [
+                    csv_field(&r.word),
+                    csv_field(&r.translation),
+                    csv_field(r.phonetic.as_deref().unwrap_or("")),
+                    csv_field(r.context.as_deref().unwrap_or("")),
+                    csv_field(r.example_sentence.as_deref().unwrap_or("")),
+                    csv_field(&r.tags.join(" ")),
+                    r.mastery_level.to_string(),
+                ];
+                out.push_str(&fields.join(","));
+                out.push('\n');
+            }
+        }
+        WordExportFormat::AnkiTsv => {
+            out.push_str("#separator:tab\n#html:true\n#tags column:3\n");
+            for r in records {
+                let mut back = anki_field(&r.translation);
+                if let Some(ref phonetic) = r.phonetic {
+                    back = format!("{}<br>{}", anki_field(phonetic), back);
+                }
+                if let Some(ref example) = r.example_sentence {
+                    back = format!("{}<br><i>{}</i>", back, anki_field(example));
+                }
+                // Anki 标签以空格分隔，标签内的空格替换为下划线
+                let tags: Vec<String> = r.tags.iter().map(|t| t.replace(' ', "_")).collect();
+                out.push_str(&format!("{}\t{}\t{}\n", anki_field(&r.word), back, tags.join(" ")));
+            }
+        }
+        WordExportFormat::Markdown => {
+            out.push_str("| 单词 | 音标 | 释义 | 例句 | 标签 | 掌握程度 |\n");
+            out.push_str("| --- | --- | --- | --- | --- | --- |\n");
+            for r in records {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {}/5 |\n",
+                    markdown_cell(&r.word),
+                    markdown_cell(r.phonetic.as_deref().unwrap_or("")),
+                    markdown_cell(&r.translation),
+                    markdown_cell(r.example_sentence.as_deref().unwrap_or("")),
+                    markdown_cell(&r.tags.join(", ")),
+                    r.mastery_level
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// 导出单词记录，`ids` 为空时导出全部；返回导出的条数
+pub fn export_words(
+    path: &Path,
+    format: WordExportFormat,
+    ids: Option<Vec<String>>,
+    app_data_dir: &Path,
+) -> Result<usize, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM word_records ORDER BY created_at ASC",
+            WORD_RECORD_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare word_records query: {}", e))?;
+    let rows = stmt
+        .query_map([], row_to_word_record)
+        .map_err(|e| format!("Failed to iterate word_records: {}", e))?;
+
+    let mut records = Vec::new();
+    for row in rows {
+        let record = row.map_err(|e| format!("Failed to read word_record row: {}", e))?;
+        if let Some(ref ids) = ids {
+            if !ids.contains(&record.id) {
+                continue;
+            }
+        }
+        records.push(record);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    fs::write(path, render_words(&records, format))
+        .map_err(|e| format!("Failed to write export file: {}", e))?;
+    Ok(records.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn test_parse_delimited_handles_quotes() {
+        let rows = parse_delimited("word,translation\n\"a, b\",\"say \"\"hi\"\"\nnext\"\n\nc,d", ',');
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], vec!["a, b", "say \"hi\"\nnext"]);
+        assert_eq!(rows[2], vec!["c", "d"]);
+    }
+
+    #[test]
+    fn test_csv_header_mapping() {
+        let source = "释义,单词,标签\n苹果,apple,fruit food\n,,\n香蕉,banana,";
+        let (words, errors) =
+            parse_import(source, WordImportFormat::Csv, &WordImportOptions::default());
+        assert!(errors.is_empty());
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "apple");
+        assert_eq!(words[0].translation, "苹果");
+        assert_eq!(words[0].tags, vec!["fruit", "food"]);
+    }
+
+    #[test]
+    fn test_anki_text_export() {
+        let source = "#separator:tab\n#html:true\n#tags column:3\nhello\t<b>你好</b><br>hi\tgreeting\n";
+        let options = WordImportOptions {
+            extra_tags: vec!["anki".to_string()],
+            ..Default::default()
+        };
+        let (words, _) = parse_import(source, WordImportFormat::WordList, &options);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].translation, "你好\nhi");
+        assert_eq!(words[0].tags, vec!["greeting", "anki"]);
+
+        // 列号 0 无效，忽略而不是下溢
+        let source = "#separator:tab\n#tags column:0\nhello\t你好\n";
+        let (words, _) = parse_import(source, WordImportFormat::WordList, &options);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].tags, vec!["anki"]);
+    }
+
+    #[test]
+    fn test_anki_package() {
+        let dir = temp_dir("apkg");
+
+        let collection = dir.join("collection.anki2");
+        let conn = rusqlite::Connection::open(&collection).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY, flds TEXT NOT NULL, tags TEXT NOT NULL);
+             INSERT INTO notes VALUES (1, 'hello' || char(31) || '<b>你好</b>', ' greeting vocab ');
+             INSERT INTO notes VALUES (2, char(31) || 'empty', '');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let package = dir.join("deck.apkg");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&package).unwrap());
+        zip.start_file("collection.anki2", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut zip, &fs::read(&collection).unwrap()).unwrap();
+        zip.finish().unwrap();
+
+        let options = WordImportOptions {
+            extra_tags: vec!["anki".to_string()],
+            ..Default::default()
+        };
+        let (words, errors) = read_import(&package, &options).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].word, "hello");
+        assert_eq!(words[0].translation, "你好");
+        assert_eq!(words[0].tags, vec!["greeting", "vocab", "anki"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_dedup_merges_tags() {
+        let (words, _) = parse_import(
+            "word\ttranslation\ttags\nApple\t\ta\napple\t苹果\tb",
+            WordImportFormat::Tsv,
+            &WordImportOptions::default(),
+        );
+        let (merged, duplicates) = dedup_incoming(words);
+        assert_eq!(duplicates, 1);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].translation, "苹果");
+        assert_eq!(merged[0].tags, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_export_escaping() {
+        let record = WordRecord {
+            id: "w".to_string(),
+            word: "a|b".to_string(),
+            translation: "x, \"y\"".to_string(),
+            context: None,
+            phonetic: None,
+            example_sentence: None,
+            tags: vec!["t 1".to_string()],
+            ai_explanation: None,
+            mastery_level: 2,
+            review_count: 0,
+            last_reviewed: None,
+            created_at: 0,
+            updated_at: 0,
+            is_favorite: false,
+            is_mastered: false,
+            ease_factor: DEFAULT_EASE_FACTOR,
+            interval_days: 0,
+            repetitions: 0,
+            lapses: 0,
+            due_at: None,
+        };
+        let csv = render_words(std::slice::from_ref(&record), WordExportFormat::Csv);
+        assert!(csv.contains("a|b,\"x, \"\"y\"\"\",,,,t 1,2"));
+        let md = render_words(std::slice::from_ref(&record), WordExportFormat::Markdown);
+        assert!(md.contains("| a\\|b |"));
+        let anki = render_words(std::slice::from_ref(&record), WordExportFormat::AnkiTsv);
+        assert!(anki.ends_with("a|b\tx, \"y\"\tt_1\n"));
+
+        // 导出声明了 HTML，特殊字符需要转义
+        let record = WordRecord {
+            word: "a<b>".to_string(),
+            translation: "R&D\nline".to_string(),
+            ..record
+        };
+        let anki = render_words(&[record], WordExportFormat::AnkiTsv);
+        assert!(anki.ends_with("a&lt;b&gt;\tR&amp;D<br>line\tt_1\n"));
+    }
+}
@@ -22,6 +22,59 @@ pub struct WordRecord {
     pub updated_at: u64,
     pub is_favorite: bool,
     pub is_mastered: bool,
+    /// 间隔重复（SM-2）调度状态
+    #[serde(default = "default_ease_factor")]
+    pub ease_factor: f64,
+    #[serde(default)]
+    pub interval_days: u32,
+    #[serde(default)]
+    pub repetitions: u32,
+    #[serde(default)]
+    pub lapses: u32,
+    /// 下次复习时间，新单词为空
+    #[serde(default)]
+    pub due_at: Option<u64>,
+}
+
+fn default_ease_factor() -> f64 {
+    crate::word_review::DEFAULT_EASE_FACTOR
+}
+
+pub(crate) const WORD_RECORD_COLUMNS: &str =
+    "id, word, translation, context, phonetic, example_sentence, tags, ai_explanation, \
+     mastery_level, review_count, last_reviewed, created_at, updated_at, is_favorite, is_mastered, \
+     ease_factor, interval_days, repetitions, lapses, due_at";
+
+pub(crate) fn row_to_word_record(row: &rusqlite::Row) -> rusqlite::Result<WordRecord> {
+    let tags_json: Option<String> = row.get(6)?;
+    let tags = if let Some(json) = tags_json {
+        serde_json::from_str(&json).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    Ok(WordRecord {
+        id: row.get(0)?,
+        word: row.get(1)?,
+        translation: row.get(2)?,
+        context: row.get(3)?,
+        phonetic: row.get(4)?,
+        example_sentence: row.get(5)?,
+        tags,
+        ai_explanation: row.get(7)?,
+        mastery_level: row.get(8)?,
+        review_count: row.get(9)?,
+        last_reviewed: row.get::<_, Option<i64>>(10)?.map(|v| v as u64),
+        created_at: row.get::<_, i64>(11)? as u64,
+        updated_at: row.get::<_, i64>(12)? as u64,
+        is_favorite: row.get::<_, i32>(13)? != 0,
+        is_mastered: row.get::<_, i32>(14)? != 0,
+        ease_factor: row.get(15)?,
+        interval_days: row.get::<_, i64>(16)?.max(0) as u32,
+        repetitions: row.get::<_, i64>(17)?.max(0) as u32,
+        lapses: row.get::<_, i64>(18)?.max(0) as u32,
+        due_at: row.get::<_, Option<i64>>(19)?.map(|v| v as u64),
+    })
 }
 
 fn now_ts() -> u64 {
@@ -35,41 +88,14 @@ pub fn get_all_word_records(app_data_dir: &Path) -> Result<Vec<WordRecord>, Stri
     let mut conn = db::get_connection(app_data_dir)?;
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, word, translation, context, phonetic, 
-                    example_sentence, tags, ai_explanation, mastery_level, review_count, last_reviewed, 
-                    created_at, updated_at, is_favorite, is_mastered 
-             FROM word_records ORDER BY mastery_level ASC",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM word_records ORDER BY mastery_level ASC",
+            WORD_RECORD_COLUMNS
+        ))
         .map_err(|e| format!("Failed to prepare word_records query: {}", e))?;
 
     let rows = stmt
-        .query_map([], |row| {
-            let tags_json: Option<String> = row.get(6)?;
-            let tags = if let Some(json) = tags_json {
-                serde_json::from_str(&json).unwrap_or_default()
-            } else {
-                Vec::new()
-            };
-
-            Ok(WordRecord {
-                id: row.get(0)?,
-                word: row.get(1)?,
-                translation: row.get(2)?,
-                context: row.get(3)?,
-                phonetic: row.get(4)?,
-                example_sentence: row.get(5)?,
-                tags,
-                ai_explanation: row.get(7)?,
-                mastery_level: row.get(8)?,
-                review_count: row.get(9)?,
-                last_reviewed: row.get::<_, Option<i64>>(10)?.map(|v| v as u64),
-                created_at: row.get::<_, i64>(11)? as u64,
-                updated_at: row.get::<_, i64>(12)? as u64,
-                is_favorite: row.get::<_, i32>(13)? != 0,
-                is_mastered: row.get::<_, i32>(14)? != 0,
-            })
-        })
+        .query_map([], row_to_word_record)
         .map_err(|e| format!("Failed to iterate word_records: {}", e))?;
 
     let mut items = Vec::new();
@@ -79,6 +105,29 @@ pub fn get_all_word_records(app_data_dir: &Path) -> Result<Vec<WordRecord>, Stri
     Ok(items)
 }
 
+fn autofill_from_dictionary(
+    word: &str,
+    translation: String,
+    phonetic: Option<String>,
+    app_data_dir: &Path,
+) -> (String, Option<String>) {
+    let has_phonetic = phonetic.as_deref().is_some_and(|p| !p.trim().is_empty());
+    if (!translation.trim().is_empty() && has_phonetic) || !crate::dictionary::is_installed(app_data_dir) {
+        return (translation, phonetic);
+    }
+    let entry = match crate::dictionary::lookup(word, app_data_dir) {
+        Ok(Some(entry)) => entry,
+        _ => return (translation, phonetic),
+    };
+    let translation = if translation.trim().is_empty() {
+        entry.translation.or(entry.definition).unwrap_or(translation)
+    } else {
+        translation
+    };
+    let phonetic = if has_phonetic { phonetic } else { entry.phonetic.or(phonetic) };
+    (translation, phonetic)
+}
+
 pub fn add_word_record(
     word: String,
     translation: String,
@@ -91,6 +140,9 @@ pub fn add_word_record(
     let now = now_ts();
     let id = format!("word-{}", now);
 
+    // 翻译或音标缺失时从离线词典补全
+    let (translation, phonetic) = autofill_from_dictionary(&word, translation, phonetic, app_data_dir);
+
     let tags_json = serde_json::to_string(&tags)
         .map_err(|e| format!("Failed to serialize tags: {}", e))?;
 
@@ -110,6 +162,11 @@ pub fn add_word_record(
         updated_at: now,
         is_favorite: false,
         is_mastered: false,
+        ease_factor: crate::word_review::DEFAULT_EASE_FACTOR,
+        interval_days: 0,
+        repetitions: 0,
+        lapses: 0,
+        due_at: None,
     };
 
     let mut conn = db::get_connection(app_data_dir)?;
@@ -159,37 +216,9 @@ pub fn update_word_record(
 
     let existing: Option<WordRecord> = conn
         .query_row(
-            "SELECT id, word, translation, context, phonetic, 
-                    example_sentence, tags, ai_explanation, mastery_level, review_count, last_reviewed, 
-                    created_at, updated_at, is_favorite, is_mastered 
-             FROM word_records WHERE id = ?1",
+            &format!("SELECT {} FROM word_records WHERE id = ?1", WORD_RECORD_COLUMNS),
             params![id],
-            |row| {
-                let tags_json: Option<String> = row.get(6)?;
-                let tags = if let Some(json) = tags_json {
-                    serde_json::from_str(&json).unwrap_or_default()
-                } else {
-                    Vec::new()
-                };
-
-                Ok(WordRecord {
-                    id: row.get(0)?,
-                    word: row.get(1)?,
-                    translation: row.get(2)?,
-                    context: row.get(3)?,
-                    phonetic: row.get(4)?,
-                    example_sentence: row.get(5)?,
-                    tags,
-                    ai_explanation: row.get(7)?,
-                    mastery_level: row.get(8)?,
-                    review_count: row.get(9)?,
-                    last_reviewed: row.get::<_, Option<i64>>(10)?.map(|v| v as u64),
-                    created_at: row.get::<_, i64>(11)? as u64,
-                    updated_at: row.get::<_, i64>(12)? as u64,
-                    is_favorite: row.get::<_, i32>(13)? != 0,
-                    is_mastered: row.get::<_, i32>(14)? != 0,
-                })
-            },
+            row_to_word_record,
         )
         .optional()
         .map_err(|e| format!("Failed to load word_record: {}", e))?;
@@ -272,43 +301,16 @@ pub fn search_word_records(query: &str, app_data_dir: &Path) -> Result<Vec<WordR
 
     let like = format!("%{}%", query.to_lowercase());
     let mut stmt = conn
-        .prepare(
-            "SELECT id, word, translation, context, phonetic, 
-                    example_sentence, tags, ai_explanation, mastery_level, review_count, last_reviewed, 
-                    created_at, updated_at, is_favorite, is_mastered 
-             FROM word_records
+        .prepare(&format!(
+            "SELECT {} FROM word_records
              WHERE lower(word) LIKE ?1 OR lower(translation) LIKE ?1
              ORDER BY mastery_level ASC",
-        )
+            WORD_RECORD_COLUMNS
+        ))
         .map_err(|e| format!("Failed to prepare word_record search: {}", e))?;
 
     let rows = stmt
-        .query_map(params![like], |row| {
-            let tags_json: Option<String> = row.get(6)?;
-            let tags = if let Some(json) = tags_json {
-                serde_json::from_str(&json).unwrap_or_default()
-            } else {
-                Vec::new()
-            };
-
-            Ok(WordRecord {
-                id: row.get(0)?,
-                word: row.get(1)?,
-                translation: row.get(2)?,
-                context: row.get(3)?,
-                phonetic: row.get(4)?,
-                example_sentence: row.get(5)?,
-                tags,
-                ai_explanation: row.get(7)?,
-                mastery_level: row.get(8)?,
-                review_count: row.get(9)?,
-                last_reviewed: row.get::<_, Option<i64>>(10)?.map(|v| v as u64),
-                created_at: row.get::<_, i64>(11)? as u64,
-                updated_at: row.get::<_, i64>(12)? as u64,
-                is_favorite: row.get::<_, i32>(13)? != 0,
-                is_mastered: row.get::<_, i32>(14)? != 0,
-            })
-        })
+        .query_map(params![like], row_to_word_record)
         .map_err(|e| format!("Failed to iterate word_record search: {}", e))?;
 
     let mut items = Vec::new();
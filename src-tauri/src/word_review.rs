@@ -0,0 +1,471 @@
+//! 单词本间隔重复复习（SM-2）
+//!
+//! 评分 `grade` 取 0–5：
+//! - 0–2：没记住，重新开始学习
+//! - 3：勉强想起
+//! - 4：想起来了
+//! - 5：非常轻松
+//!
+//! 每次复习都会写入 `word_reviews`，用于每日统计。
+
+use crate::db;
+use crate::word_records::{row_to_word_record, WordRecord, WORD_RECORD_COLUMNS};
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_EASE_FACTOR: f64 = 2.5;
+pub const MIN_EASE_FACTOR: f64 = 1.3;
+pub const MAX_GRADE: u8 = 5;
+/// 达到及格的最低评分
+pub const PASS_GRADE: u8 = 3;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+/// 复习时间按 15 分钟分桶后再换算本地日期：所有时区偏移都是 15 分钟的整数倍，
+/// 同一桶内的时间一定落在同一个本地日期
+pub(crate) const BUCKET_SECONDS: i64 = 900;
+
+/// SM-2 调度状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReviewState {
+    pub ease_factor: f64,
+    pub interval_days: u32,
+    /// 连续答对次数
+    pub repetitions: u32,
+    /// 遗忘次数
+    pub lapses: u32,
+}
+
+impl Default for ReviewState {
+    fn default() -> Self {
+        ReviewState {
+            ease_factor: DEFAULT_EASE_FACTOR,
+            interval_days: 0,
+            repetitions: 0,
+            lapses: 0,
+        }
+    }
+}
+
+impl ReviewState {
+    fn from_record(record: &WordRecord) -> Self {
+        ReviewState {
+            ease_factor: record.ease_factor,
+            interval_days: record.interval_days,
+            repetitions: record.repetitions,
+            lapses: record.lapses,
+        }
+    }
+}
+
+/// 根据评分计算下一次的调度状态
+pub fn schedule(state: &ReviewState, grade: u8) -> ReviewState {
+    let grade = grade.min(MAX_GRADE);
+    let q = (MAX_GRADE - grade) as f64;
+    let ease_factor = (state.ease_factor + (0.1 - q * (0.08 + q * 0.02))).max(MIN_EASE_FACTOR);
+
+    if grade < PASS_GRADE {
+        return ReviewState {
+            ease_factor,
+            interval_days: 1,
+            repetitions: 0,
+            lapses: state.lapses + 1,
+        };
+    }
+
+    // 间隔使用本次调整前的难度系数，与原始 SM-2 一致
+    let interval_days = match state.repetitions {
+        0 => 1,
+        1 => 6,
+        _ => ((state.interval_days.max(1) as f64) * state.ease_factor).round() as u32,
+    };
+    ReviewState {
+        ease_factor,
+        interval_days,
+        repetitions: state.repetitions + 1,
+        lapses: state.lapses,
+    }
+}
+
+/// 将复习间隔映射到 0–5 的掌握程度
+pub fn mastery_for_interval(interval_days: u32, repetitions: u32) -> i32 {
+    if repetitions == 0 {
+        return if interval_days == 0 { 0 } else { 1 };
+    }
+    match interval_days {
+        0..=1 => 1,
+        2..=6 => 2,
+        7..=20 => 3,
+        21..=59 => 4,
+        _ => 5,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyReviewStat {
+    /// 本地日期，YYYY-MM-DD
+    pub date: String,
+    pub reviewed: u32,
+    /// 评分及格的次数
+    pub correct: u32,
+    /// 当天首次学习的单词数
+    pub new_learned: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewStats {
+    /// 已到期待复习的单词数
+    pub due_now: u32,
+    /// 明天结束前将到期的单词数（含已到期）
+    pub due_tomorrow: u32,
+    /// 尚未学习的单词数
+    pub new_words: u32,
+    pub reviewed_today: u32,
+    /// 连续复习天数（截至今天或昨天）
+    pub streak_days: u32,
+    /// 最近若干天的统计，按日期升序
+    pub daily: Vec<DailyReviewStat>,
+}
+
+pub(crate) fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub(crate) fn local_date(ts: u64) -> NaiveDate {
+    Local
+        .timestamp_opt(ts as i64, 0)
+        .single()
+        .map(|dt| dt.date_naive())
+        .unwrap_or_else(|| Local::now().date_naive())
+}
+
+pub(crate) fn local_day_start(date: NaiveDate) -> u64 {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+        .map(|dt| dt.timestamp().max(0) as u64)
+        .unwrap_or(0)
+}
+
+/// 时间桶（`时间戳 / BUCKET_SECONDS`）对应的本地日期
+pub(crate) fn bucket_date(bucket: i64) -> NaiveDate {
+    local_date((bucket.max(0) * BUCKET_SECONDS) as u64)
+}
+
+/// 有复习记录的所有本地日期，用于计算连续天数
+pub(crate) fn review_dates(conn: &rusqlite::Connection) -> Result<HashSet<NaiveDate>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT reviewed_at / ?1 FROM word_reviews")
+        .map_err(|e| format!("Failed to prepare review streak query: {}", e))?;
+    let rows = stmt
+        .query_map(params![BUCKET_SECONDS], |row| row.get::<_, i64>(0))
+        .map_err(|e| format!("Failed to iterate review streak: {}", e))?;
+    let mut dates = HashSet::new();
+    for row in rows {
+        let bucket = row.map_err(|e| format!("Failed to read review streak row: {}", e))?;
+        dates.insert(bucket_date(bucket));
+    }
+    Ok(dates)
+}
+
+/// 计算截至 `today` 的连续天数；今天还没复习时从昨天开始算
+pub fn streak_from_dates(dates: &HashSet<NaiveDate>, today: NaiveDate) -> u32 {
+    let mut day = if dates.contains(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    let mut streak = 0;
+    while dates.contains(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+fn query_records(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<WordRecord>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Failed to prepare due words query: {}", e))?;
+    let rows = stmt
+        .query_map(params, row_to_word_record)
+        .map_err(|e| format!("Failed to iterate due words: {}", e))?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("Failed to read word_record row: {}", e))?);
+    }
+    Ok(items)
+}
+
+/// 获取待复习的单词：先返回已到期的（最早到期的在前），不足 `limit` 时补充新单词
+pub fn get_due_words(limit: u32, app_data_dir: &Path) -> Result<Vec<WordRecord>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let now = now_ts() as i64;
+
+    let mut items = query_records(
+        &conn,
+        &format!(
+            "SELECT {} FROM word_records
+             WHERE is_mastered = 0 AND due_at IS NOT NULL AND due_at <= ?1
+             ORDER BY due_at ASC LIMIT ?2",
+            WORD_RECORD_COLUMNS
+        ),
+        params![now, limit as i64],
+    )?;
+
+    let remaining = limit as usize - items.len().min(limit as usize);
+    if remaining > 0 {
+        items.extend(query_records(
+            &conn,
+            &format!(
+                "SELECT {} FROM word_records
+                 WHERE is_mastered = 0 AND due_at IS NULL
+                 ORDER BY created_at ASC LIMIT ?1",
+                WORD_RECORD_COLUMNS
+            ),
+            params![remaining as i64],
+        )?);
+    }
+    Ok(items)
+}
+
+/// 提交一次复习结果，原子地更新调度状态并记录复习日志
+pub fn submit_review(id: String, grade: u8, app_data_dir: &Path) -> Result<WordRecord, String> {
+    if grade > MAX_GRADE {
+        return Err(format!("Grade must be between 0 and {}", MAX_GRADE));
+    }
+
+    let mut conn = db::get_connection(app_data_dir)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start review transaction: {}", e))?;
+
+    let mut record = tx
+        .query_row(
+            &format!("SELECT {} FROM word_records WHERE id = ?1", WORD_RECORD_COLUMNS),
+            params![id],
+            row_to_word_record,
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load word_record: {}", e))?
+        .ok_or_else(|| format!("WordRecord {} not found", id))?;
+
+    let now = now_ts();
+    let next = schedule(&ReviewState::from_record(&record), grade);
+    record.ease_factor = next.ease_factor;
+    record.interval_days = next.interval_days;
+    record.repetitions = next.repetitions;
+    record.lapses = next.lapses;
+    record.due_at = Some(now + next.interval_days as u64 * SECONDS_PER_DAY);
+    record.review_count += 1;
+    record.last_reviewed = Some(now);
+    record.mastery_level = mastery_for_interval(next.interval_days, next.repetitions);
+    record.updated_at = now;
+
+    tx.execute(
+        "UPDATE word_records
+         SET ease_factor = ?1, interval_days = ?2, repetitions = ?3, lapses = ?4, due_at = ?5,
+             review_count = ?6, last_reviewed = ?7, mastery_level = ?8, updated_at = ?9
+         WHERE id = ?10",
+        params![
+            record.ease_factor,
+            record.interval_days as i64,
+            record.repetitions as i64,
+            record.lapses as i64,
+            record.due_at.map(|v| v as i64),
+            record.review_count,
+            now as i64,
+            record.mastery_level,
+            now as i64,
+            record.id
+        ],
+    )
+    .map_err(|e| format!("Failed to update word_record: {}", e))?;
+    tx.execute(
+        "INSERT INTO word_reviews (word_id, grade, reviewed_at, interval_days, ease_factor)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            record.id,
+            grade as i64,
+            now as i64,
+            record.interval_days as i64,
+            record.ease_factor
+        ],
+    )
+    .map_err(|e| format!("Failed to record word review: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit word review: {}", e))?;
+
+    Ok(record)
+}
+
+/// 复习统计，`days` 为每日统计覆盖的天数（含今天）
+pub fn get_review_stats(days: u32, app_data_dir: &Path) -> Result<ReviewStats, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let now = now_ts();
+    let today = local_date(now);
+    let end_of_tomorrow = local_day_start(today + Duration::days(2));
+
+    let count = |sql: &str, value: i64| -> Result<u32, String> {
+        conn.query_row(sql, params![value], |row| row.get::<_, i64>(0))
+            .map(|v| v.max(0) as u32)
+            .map_err(|e| format!("Failed to count words: {}", e))
+    };
+    let due_now = count(
+        "SELECT COUNT(*) FROM word_records WHERE is_mastered = 0 AND due_at IS NOT NULL AND due_at <= ?1",
+        now as i64,
+    )?;
+    let due_tomorrow = count(
+        "SELECT COUNT(*) FROM word_records WHERE is_mastered = 0 AND due_at IS NOT NULL AND due_at < ?1",
+        end_of_tomorrow as i64,
+    )?;
+    let new_words = conn
+        .query_row(
+            "SELECT COUNT(*) FROM word_records WHERE is_mastered = 0 AND due_at IS NULL",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|v| v.max(0) as u32)
+        .map_err(|e| format!("Failed to count words: {}", e))?;
+
+    let days = days.max(1);
+    let first_day = today - Duration::days(days as i64 - 1);
+    let mut daily: BTreeMap<NaiveDate, DailyReviewStat> = BTreeMap::new();
+    for offset in 0..days {
+        let date = first_day + Duration::days(offset as i64);
+        daily.insert(
+            date,
+            DailyReviewStat {
+                date: date.format("%Y-%m-%d").to_string(),
+                reviewed: 0,
+                correct: 0,
+                new_learned: 0,
+            },
+        );
+    }
+
+    // 每个单词的首次复习时间，用于统计当天新学单词
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.grade, r.reviewed_at, r.reviewed_at = f.first_at
+             FROM word_reviews r
+             JOIN (SELECT word_id, MIN(reviewed_at) AS first_at FROM word_reviews GROUP BY word_id) f
+               ON f.word_id = r.word_id
+             WHERE r.reviewed_at >= ?1",
+        )
+        .map_err(|e| format!("Failed to prepare review stats query: {}", e))?;
+    let rows = stmt
+        .query_map(params![local_day_start(first_day) as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)? != 0,
+            ))
+        })
+        .map_err(|e| format!("Failed to iterate review stats: {}", e))?;
+    for row in rows {
+        let (grade, reviewed_at, is_first) =
+            row.map_err(|e| format!("Failed to read review stats row: {}", e))?;
+        let date = local_date(reviewed_at.max(0) as u64);
+        if let Some(stat) = daily.get_mut(&date) {
+            stat.reviewed += 1;
+            if grade >= PASS_GRADE as i64 {
+                stat.correct += 1;
+            }
+            if is_first {
+                stat.new_learned += 1;
+            }
+        }
+    }
+
+    let review_dates = review_dates(&conn)?;
+
+    let reviewed_today = daily.get(&today).map(|s| s.reviewed).unwrap_or(0);
+    Ok(ReviewStats {
+        due_now,
+        due_tomorrow,
+        new_words,
+        reviewed_today,
+        streak_days: streak_from_dates(&review_dates, today),
+        daily: daily.into_values().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_reviews_follow_sm2_intervals() {
+        let s1 = schedule(&ReviewState::default(), 4);
+        assert_eq!((s1.interval_days, s1.repetitions), (1, 1));
+        let s2 = schedule(&s1, 4);
+        assert_eq!((s2.interval_days, s2.repetitions), (6, 2));
+        let s3 = schedule(&s2, 4);
+        // 6 * 2.5 = 15
+        assert_eq!((s3.interval_days, s3.repetitions), (15, 3));
+        assert!((s3.ease_factor - DEFAULT_EASE_FACTOR).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ease_factor_adjustment() {
+        let state = ReviewState::default();
+        assert!((schedule(&state, 5).ease_factor - 2.6).abs() < 1e-9);
+        assert!((schedule(&state, 3).ease_factor - 2.36).abs() < 1e-9);
+        // 难度系数不会低于下限
+        let mut s = state;
+        for _ in 0..20 {
+            s = schedule(&s, 0);
+        }
+        assert!((s.ease_factor - MIN_EASE_FACTOR).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_failed_review_resets_progress() {
+        let learned = ReviewState {
+            ease_factor: 2.5,
+            interval_days: 30,
+            repetitions: 5,
+            lapses: 0,
+        };
+        let next = schedule(&learned, 1);
+        assert_eq!(next.interval_days, 1);
+        assert_eq!(next.repetitions, 0);
+        assert_eq!(next.lapses, 1);
+        assert_eq!(mastery_for_interval(next.interval_days, next.repetitions), 1);
+    }
+
+    #[test]
+    fn test_mastery_mapping() {
+        assert_eq!(mastery_for_interval(0, 0), 0);
+        assert_eq!(mastery_for_interval(6, 2), 2);
+        assert_eq!(mastery_for_interval(15, 3), 3);
+        assert_eq!(mastery_for_interval(90, 6), 5);
+    }
+
+    #[test]
+    fn test_streak_counts_consecutive_days() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let dates: HashSet<NaiveDate> = [9, 8, 7, 5]
+            .iter()
+            .map(|d| NaiveDate::from_ymd_opt(2024, 3, *d).unwrap())
+            .collect();
+        // 今天还没复习，从昨天算起
+        assert_eq!(streak_from_dates(&dates, today), 3);
+        let mut with_today = dates.clone();
+        with_today.insert(today);
+        assert_eq!(streak_from_dates(&with_today, today), 4);
+        assert_eq!(streak_from_dates(&HashSet::new(), today), 0);
+    }
+}
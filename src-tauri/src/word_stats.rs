@@ -0,0 +1,383 @@
+//! 单词本学习统计
+//!
+//! 聚合在 SQL 中完成，只把按 15 分钟分桶后的计数取回本地，再按本地日期
+//! 归并成按天或按周的时间序列（空缺的周期补 0），可直接用于图表。
+
+use crate::db;
+use crate::word_review::{
+    bucket_date, local_date, local_day_start, now_ts, review_dates, streak_from_dates,
+    BUCKET_SECONDS, PASS_GRADE,
+};
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+const MAX_MASTERY_LEVEL: i32 = 5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StatsGranularity {
+    #[default]
+    Day,
+    /// 周一为一周的开始
+    Week,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WordStatsOptions {
+    /// 统计最近多少天
+    pub days: u32,
+    pub granularity: StatsGranularity,
+    pub most_missed_limit: u32,
+}
+
+impl Default for WordStatsOptions {
+    fn default() -> Self {
+        Self {
+            days: 30,
+            granularity: StatsGranularity::Day,
+            most_missed_limit: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsPoint {
+    /// 周期起始日期，YYYY-MM-DD
+    pub period: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewPoint {
+    pub period: String,
+    pub reviewed: u32,
+    pub correct: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MasteryBucket {
+    pub level: i32,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissedWord {
+    pub id: String,
+    pub word: String,
+    pub translation: String,
+    /// 统计范围内评分不及格的次数
+    pub misses: u32,
+    /// 统计范围内的复习次数
+    pub reviews: u32,
+    pub lapses: u32,
+    pub mastery_level: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordStats {
+    pub total_words: u32,
+    pub mastered_words: u32,
+    pub favorite_words: u32,
+    /// 已开始复习但尚未掌握
+    pub learning_words: u32,
+    /// 尚未复习过
+    pub new_words: u32,
+    pub added_in_range: u32,
+    pub reviews_in_range: u32,
+    /// 统计范围内非首次复习的及格率；没有复习时为 None
+    pub retention_rate: Option<f64>,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub added: Vec<StatsPoint>,
+    pub reviews: Vec<ReviewPoint>,
+    /// 掌握程度 0–5 的分布
+    pub mastery_distribution: Vec<MasteryBucket>,
+    pub most_missed: Vec<MissedWord>,
+}
+
+/// 日期所在周期的起始日期
+pub fn period_start(date: NaiveDate, granularity: StatsGranularity) -> NaiveDate {
+    match granularity {
+        StatsGranularity::Day => date,
+        StatsGranularity::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+    }
+}
+
+/// 统计范围内所有周期的起始日期，按时间升序
+pub fn period_range(today: NaiveDate, days: u32, granularity: StatsGranularity) -> Vec<NaiveDate> {
+    let first = period_start(today - Duration::days(days.max(1) as i64 - 1), granularity);
+    let step = match granularity {
+        StatsGranularity::Day => 1,
+        StatsGranularity::Week => 7,
+    };
+    let mut periods = Vec::new();
+    let mut day = first;
+    while day <= today {
+        periods.push(day);
+        day += Duration::days(step);
+    }
+    periods
+}
+
+/// 最长连续天数
+pub fn longest_streak(dates: &HashSet<NaiveDate>) -> u32 {
+    let mut sorted: Vec<&NaiveDate> = dates.iter().collect();
+    sorted.sort();
+    let (mut longest, mut current) = (0, 0);
+    let mut prev: Option<NaiveDate> = None;
+    for &date in sorted {
+        current = match prev {
+            Some(p) if date - p == Duration::days(1) => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        prev = Some(date);
+    }
+    longest
+}
+
+fn count(conn: &rusqlite::Connection, sql: &str) -> Result<u32, String> {
+    conn.query_row(sql, [], |row| row.get::<_, i64>(0))
+        .map(|v| v.max(0) as u32)
+        .map_err(|e| format!("Failed to count words: {}", e))
+}
+
+/// 执行按时间分桶的聚合查询，返回 (本地日期, 各列计数)
+///
+/// 第一列必须是 `时间 / BUCKET_SECONDS` 得到的时间桶
+fn bucketed<const N: usize>(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    args: impl rusqlite::Params,
+) -> Result<Vec<(NaiveDate, [u32; N])>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Failed to prepare word stats query: {}", e))?;
+    let rows = stmt
+        .query_map(args, |row| {
+            let bucket: i64 = row.get(0)?;
+            let mut values = [0u32; N];
+            for (i, value) in values.iter_mut().enumerate() {
+                *value = row.get::<_, Option<i64>>(i + 1)?.unwrap_or(0).max(0) as u32;
+            }
+            Ok((bucket, values))
+        })
+        .map_err(|e| format!("Failed to iterate word stats: {}", e))?;
+    let mut out = Vec::new();
+    for row in rows {
+        let (bucket, values) = row.map_err(|e| format!("Failed to read word stats row: {}", e))?;
+        out.push((bucket_date(bucket), values));
+    }
+    Ok(out)
+}
+
+pub fn get_word_stats(options: &WordStatsOptions, app_data_dir: &Path) -> Result<WordStats, String> {
+    let conn = db::get_readonly_connection(app_data_dir)?;
+    let today = local_date(now_ts());
+    let granularity = options.granularity;
+    let periods = period_range(today, options.days, granularity);
+    let since = periods.first().map(|d| local_day_start(*d)).unwrap_or(0) as i64;
+
+    let total_words = count(&conn, "SELECT COUNT(*) FROM word_records")?;
+    let mastered_words = count(&conn, "SELECT COUNT(*) FROM word_records WHERE is_mastered = 1")?;
+    let favorite_words = count(&conn, "SELECT COUNT(*) FROM word_records WHERE is_favorite = 1")?;
+    let learning_words = count(
+        &conn,
+        "SELECT COUNT(*) FROM word_records WHERE is_mastered = 0 AND repetitions > 0",
+    )?;
+    let new_words = count(
+        &conn,
+        "SELECT COUNT(*) FROM word_records WHERE is_mastered = 0 AND repetitions = 0 AND due_at IS NULL",
+    )?;
+
+    // 新增单词
+    let mut added: BTreeMap<NaiveDate, u32> = periods.iter().map(|d| (*d, 0)).collect();
+    let rows = bucketed::<1>(
+        &conn,
+        "SELECT created_at / ?2, COUNT(*) FROM word_records
+         WHERE created_at >= ?1 GROUP BY created_at / ?2",
+        params![since, BUCKET_SECONDS],
+    )?;
+    for (date, [n]) in rows {
+        if let Some(slot) = added.get_mut(&period_start(date, granularity)) {
+            *slot += n;
+        }
+    }
+
+    // 复习次数与保持率（排除每个单词的首次复习）
+    let mut reviews: BTreeMap<NaiveDate, (u32, u32)> = periods.iter().map(|d| (*d, (0, 0))).collect();
+    let (mut recall_total, mut recall_passed) = (0u32, 0u32);
+    let rows = bucketed::<4>(
+        &conn,
+        "SELECT r.reviewed_at / ?3,
+                COUNT(*),
+                SUM(r.grade >= ?2),
+                SUM(r.id > f.first_id),
+                SUM(r.id > f.first_id AND r.grade >= ?2)
+         FROM word_reviews r
+         JOIN (SELECT word_id, MIN(id) AS first_id FROM word_reviews GROUP BY word_id) f
+           ON f.word_id = r.word_id
+         WHERE r.reviewed_at >= ?1
+         GROUP BY r.reviewed_at / ?3",
+        params![since, PASS_GRADE as i64, BUCKET_SECONDS],
+    )?;
+    for (date, [reviewed, correct, recalls, recalled]) in rows {
+        if let Some(slot) = reviews.get_mut(&period_start(date, granularity)) {
+            slot.0 += reviewed;
+            slot.1 += correct;
+            recall_total += recalls;
+            recall_passed += recalled;
+        }
+    }
+
+    let review_dates = review_dates(&conn)?;
+
+    // 掌握程度分布
+    let mut mastery = [0u32; MAX_MASTERY_LEVEL as usize + 1];
+    let mut stmt = conn
+        .prepare("SELECT mastery_level, COUNT(*) FROM word_records GROUP BY mastery_level")
+        .map_err(|e| format!("Failed to prepare mastery query: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| format!("Failed to iterate mastery stats: {}", e))?;
+    for row in rows {
+        let (level, n) = row.map_err(|e| format!("Failed to read mastery row: {}", e))?;
+        mastery[level.clamp(0, MAX_MASTERY_LEVEL as i64) as usize] += n.max(0) as u32;
+    }
+
+    // 最常出错的单词
+    let mut stmt = conn
+        .prepare(
+            "SELECT w.id, w.word, w.translation, SUM(r.grade < ?2) AS misses, COUNT(*),
+                    w.lapses, w.mastery_level
+             FROM word_reviews r
+             JOIN word_records w ON w.id = r.word_id
+             WHERE r.reviewed_at >= ?1
+             GROUP BY w.id
+             HAVING misses > 0
+             ORDER BY misses DESC, w.lapses DESC, w.word ASC
+             LIMIT ?3",
+        )
+        .map_err(|e| format!("Failed to prepare most missed query: {}", e))?;
+    let rows = stmt
+        .query_map(
+            params![since, PASS_GRADE as i64, options.most_missed_limit as i64],
+            |row| {
+                Ok(MissedWord {
+                    id: row.get(0)?,
+                    word: row.get(1)?,
+                    translation: row.get(2)?,
+                    misses: row.get::<_, i64>(3)?.max(0) as u32,
+                    reviews: row.get::<_, i64>(4)?.max(0) as u32,
+                    lapses: row.get::<_, Option<i64>>(5)?.unwrap_or(0).max(0) as u32,
+                    mastery_level: row.get(6)?,
+                })
+            },
+        )
+        .map_err(|e| format!("Failed to iterate most missed words: {}", e))?;
+    let mut most_missed = Vec::new();
+    for row in rows {
+        most_missed.push(row.map_err(|e| format!("Failed to read most missed row: {}", e))?);
+    }
+
+    let format = |d: &NaiveDate| d.format("%Y-%m-%d").to_string();
+    Ok(WordStats {
+        total_words,
+        mastered_words,
+        favorite_words,
+        learning_words,
+        new_words,
+        added_in_range: added.values().sum(),
+        reviews_in_range: reviews.values().map(|r| r.0).sum(),
+        retention_rate: (recall_total > 0).then(|| recall_passed as f64 / recall_total as f64),
+        current_streak: streak_from_dates(&review_dates, today),
+        longest_streak: longest_streak(&review_dates),
+        added: added
+            .iter()
+            .map(|(d, count)| StatsPoint { period: format(d), count: *count })
+            .collect(),
+        reviews: reviews
+            .iter()
+            .map(|(d, (reviewed, correct))| ReviewPoint {
+                period: format(d),
+                reviewed: *reviewed,
+                correct: *correct,
+            })
+            .collect(),
+        mastery_distribution: mastery
+            .iter()
+            .enumerate()
+            .map(|(level, count)| MasteryBucket { level: level as i32, count: *count })
+            .collect(),
+        most_missed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_period_range_by_week_starts_on_monday() {
+        // 2024-05-15 是周三
+        let periods = period_range(date("2024-05-15"), 14, StatsGranularity::Week);
+        assert_eq!(periods, vec![date("2024-04-29"), date("2024-05-06"), date("2024-05-13")]);
+        assert_eq!(period_range(date("2024-05-15"), 3, StatsGranularity::Day).len(), 3);
+    }
+
+    #[test]
+    fn test_longest_streak() {
+        let dates: HashSet<NaiveDate> = ["2024-01-01", "2024-01-02", "2024-01-05", "2024-01-06", "2024-01-07"]
+            .iter()
+            .map(|s| date(s))
+            .collect();
+        assert_eq!(longest_streak(&dates), 3);
+        assert_eq!(longest_streak(&HashSet::new()), 0);
+    }
+
+    #[test]
+    fn test_word_stats_aggregation() {
+        let dir = temp_dir("word-stats");
+
+        let record = crate::word_records::add_word_record(
+            "apple".into(),
+            "苹果".into(),
+            None,
+            None,
+            None,
+            Vec::new(),
+            &dir,
+        )
+        .unwrap();
+        crate::word_review::submit_review(record.id.clone(), 4, &dir).unwrap();
+        crate::word_review::submit_review(record.id.clone(), 1, &dir).unwrap();
+        crate::word_review::submit_review(record.id.clone(), 5, &dir).unwrap();
+
+        let stats = get_word_stats(&WordStatsOptions::default(), &dir).unwrap();
+        assert_eq!(stats.total_words, 1);
+        assert_eq!(stats.added.len(), 30);
+        assert_eq!(stats.added_in_range, 1);
+        assert_eq!(stats.reviews_in_range, 3);
+        assert_eq!(stats.reviews.last().unwrap().correct, 2);
+        // 非首次复习 2 次，其中 1 次及格
+        assert_eq!(stats.retention_rate, Some(0.5));
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.most_missed.len(), 1);
+        assert_eq!(stats.most_missed[0].misses, 1);
+        assert_eq!(stats.mastery_distribution.iter().map(|b| b.count).sum::<u32>(), 1);
+    }
+}
@@ -162,7 +162,7 @@ function App() {
   const handleDeleteRecording = async (recording: RecordingMeta) => {
     try {
       await tauriApi.deleteRecording(recording.file_path);
-      setMessage(`已删除录制: ${recording.file_name}`);
+      setMessage(`已删除录制: ${recording.name || recording.file_name}`);
       // Reload recordings list
       await loadRecordings();
       // Clear selection if deleted recording was selected
@@ -3,6 +3,7 @@ import type {
   FileHistoryItem,
   EverythingResult,
   MemoItem,
+  SnippetItem,
   ShortcutItem,
   WordRecord,
 } from "../types";
@@ -95,6 +96,11 @@ export const tauriApi = {
   deleteMemo: async () => {},
   searchMemos: async () => [] as MemoItem[],
 
+  // Snippet APIs
+  searchSnippets: async () => [] as SnippetItem[],
+  getSnippetInputs: async () => [] as string[],
+  pasteSnippet: async () => {},
+
   // Window APIs
   showMainWindow: async () => {},
   showMemoWindow: async () => {},
@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   RecordingMeta,
+  HotkeyTiming,
   AppInfo,
   FileHistoryItem,
   EverythingSearchResponse,
@@ -8,6 +9,7 @@ import type {
   EverythingResult,
   ShortcutItem,
   MemoItem,
+  SnippetItem,
   IndexStatus,
   FilePreview,
   DatabaseBackupList,
@@ -18,6 +20,11 @@ import type {
   OpenHistoryItem,
   WordRecord,
   SearchEngineConfig,
+  SettingsProfile,
+  ProfileExportOptions,
+  ProfileImportPreview,
+  SearchTarget,
+  SearchBang,
 } from "../types";
 
 export const tauriApi = {
@@ -41,8 +48,16 @@ export const tauriApi = {
     return invoke("delete_recording", { path });
   },
 
-  async playRecording(path: string, speed: number): Promise<void> {
-    return invoke("play_recording", { path, speed });
+  async playRecording(path: string, speed: number, repeat?: number): Promise<void> {
+    return invoke("play_recording", { path, speed, repeat });
+  },
+
+  async searchRecordings(query: string): Promise<RecordingMeta[]> {
+    return invoke("search_recordings", { query });
+  },
+
+  async playRecordingByName(name: string): Promise<RecordingMeta> {
+    return invoke("play_recording_by_name", { name });
   },
 
   async stopPlayback(): Promise<void> {
@@ -359,6 +374,21 @@ export const tauriApi = {
     return invoke("search_memos", { query });
   },
 
+  // Snippet APIs
+  async searchSnippets(query: string): Promise<SnippetItem[]> {
+    return invoke("search_snippets", { query });
+  },
+
+  // 片段中 {input:...} 需要用户填写的字段
+  async getSnippetInputs(id: string): Promise<string[]> {
+    return invoke("get_snippet_inputs", { id });
+  },
+
+  // 渲染并粘贴到光标处，调用前先隐藏启动器让焦点回到目标窗口
+  async pasteSnippet(id: string, inputs?: Record<string, string>): Promise<void> {
+    return invoke("paste_snippet", { id, inputs });
+  },
+
   async showMainWindow(): Promise<void> {
     return invoke("show_main_window");
   },
@@ -541,14 +571,69 @@ export const tauriApi = {
 
 
   // Settings APIs
-  async getSettings(): Promise<{ ollama: { model: string; base_url: string }; startup_enabled?: boolean; result_style?: "compact" | "soft" | "skeuomorphic"; close_on_blur?: boolean; auto_check_update?: boolean; clipboard_max_items?: number; translation_tab_order?: string[]; search_engines?: SearchEngineConfig[] }> {
+  async getSettings(): Promise<{ ollama: { model: string; base_url: string }; startup_enabled?: boolean; result_style?: "compact" | "soft" | "skeuomorphic"; close_on_blur?: boolean; auto_check_update?: boolean; clipboard_max_items?: number; translation_tab_order?: string[]; search_engines?: SearchEngineConfig[]; fallback_search_engine?: string | null; search_suggestions_enabled?: boolean }> {
     return invoke("get_settings");
   },
 
-  async saveSettings(settings: { ollama: { model: string; base_url: string }; startup_enabled?: boolean; result_style?: "compact" | "soft" | "skeuomorphic"; close_on_blur?: boolean; clipboard_max_items?: number; translation_tab_order?: string[]; search_engines?: SearchEngineConfig[] }): Promise<void> {
+  async saveSettings(settings: { ollama: { model: string; base_url: string }; startup_enabled?: boolean; result_style?: "compact" | "soft" | "skeuomorphic"; close_on_blur?: boolean; clipboard_max_items?: number; translation_tab_order?: string[]; search_engines?: SearchEngineConfig[]; fallback_search_engine?: string | null; search_suggestions_enabled?: boolean }): Promise<void> {
     return invoke("save_settings", { settings });
   },
 
+  // 只更新提交的字段（JSON Merge Patch），null 表示恢复默认值
+  async updateSettings(patch: Record<string, unknown>): Promise<{ ollama: { model: string; base_url: string }; startup_enabled?: boolean; result_style?: "compact" | "soft" | "skeuomorphic"; close_on_blur?: boolean; auto_check_update?: boolean; clipboard_max_items?: number; translation_tab_order?: string[]; search_engines?: SearchEngineConfig[]; fallback_search_engine?: string | null; search_suggestions_enabled?: boolean }> {
+    return invoke("update_settings", { patch });
+  },
+
+  // 配置档案：设置、Everything 过滤器、快捷方式和快捷键
+  async listSettingsProfiles(): Promise<SettingsProfile[]> {
+    return invoke("list_settings_profiles");
+  },
+
+  async saveSettingsProfile(name: string): Promise<void> {
+    return invoke("save_settings_profile", { name });
+  },
+
+  async switchSettingsProfile(name: string): Promise<void> {
+    return invoke("switch_settings_profile", { name });
+  },
+
+  async deleteSettingsProfile(name: string): Promise<void> {
+    return invoke("delete_settings_profile", { name });
+  },
+
+  async exportSettingsProfile(targetPath: string, name?: string, options?: ProfileExportOptions): Promise<{ path: string; excluded: string[] }> {
+    return invoke("export_settings_profile", { name, targetPath, options });
+  },
+
+  async previewSettingsProfileImport(sourcePath: string): Promise<ProfileImportPreview> {
+    return invoke("preview_settings_profile_import", { sourcePath });
+  },
+
+  async importSettingsProfile(sourcePath: string, name?: string, activate?: boolean): Promise<SettingsProfile> {
+    return invoke("import_settings_profile", { sourcePath, name, activate });
+  },
+
+  // Web Search APIs
+  async resolveWebSearch(query: string, useFallback?: boolean): Promise<SearchTarget | null> {
+    return invoke("resolve_web_search", { query, useFallback });
+  },
+
+  async getSearchSuggestions(query: string): Promise<string[]> {
+    return invoke("get_search_suggestions", { query });
+  },
+
+  async importSearchBangs(sourcePath: string): Promise<{ imported: number; skipped: number }> {
+    return invoke("import_search_bangs", { sourcePath });
+  },
+
+  async searchBangs(query: string, limit?: number): Promise<SearchBang[]> {
+    return invoke("search_bangs", { query, limit });
+  },
+
+  async clearSearchBangs(): Promise<void> {
+    return invoke("clear_search_bangs");
+  },
+
 
   // Startup APIs
   async isStartupEnabled(): Promise<boolean> {
@@ -572,6 +657,14 @@ export const tauriApi = {
     return invoke("show_hotkey_settings");
   },
 
+  async getHotkeyTiming(): Promise<HotkeyTiming> {
+    return invoke("get_hotkey_timing");
+  },
+
+  async saveHotkeyTiming(timing: HotkeyTiming): Promise<void> {
+    return invoke("save_hotkey_timing", { timing });
+  },
+
   async restartApp(): Promise<void> {
     return invoke("restart_app");
   },
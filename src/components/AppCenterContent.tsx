@@ -1,6 +1,6 @@
 import { useState, useMemo, useEffect, useRef, useCallback } from "react";
 import { plugins, executePlugin } from "../plugins";
-import type { PluginContext, IndexStatus, DatabaseBackupInfo, PluginUsage } from "../types";
+import type { PluginContext, IndexStatus, DatabaseBackupInfo, PluginUsage, SettingsChangedEvent } from "../types";
 import { tauriApi } from "../api/tauri";
 import { listen, emit } from "@tauri-apps/api/event";
 import { OllamaSettingsPage, SystemSettingsPage, AboutSettingsPage, LauncherSettingsPage } from "./SettingsPages";
@@ -58,6 +58,8 @@ interface Settings {
     url: string;
     name: string;
   }>;
+  fallback_search_engine?: string | null;
+  search_suggestions_enabled?: boolean;
 }
 
 interface MenuItem {
@@ -172,6 +174,8 @@ export function AppCenterContent({ onPluginClick, onClose: _onClose }: AppCenter
   const hasLoadedSettingsRef = useRef(false);
   // 标记当前是否正在应用后端加载的设置，避免立即触发自动保存
   const isApplyingSettingsRef = useRef(false);
+  // 最近一次与后端一致的设置，自动保存时只提交与它不同的字段
+  const lastSavedSettingsRef = useRef<Settings | null>(null);
   const saveTimerRef = useRef<number | null>(null);
   const [usersCount, setUsersCount] = useState<number | null>(null);
   const [isLoadingUsersCount, setIsLoadingUsersCount] = useState(false);
@@ -665,12 +669,14 @@ export function AppCenterContent({ onPluginClick, onClose: _onClose }: AppCenter
       const data = await tauriApi.getSettings();
       // 同步开机启动状态
       const startupEnabled = await tauriApi.isStartupEnabled();
-      setSettings({
+      const loaded: Settings = {
         ...data,
         startup_enabled: startupEnabled,
         result_style: (data.result_style as Settings["result_style"]) || (localStorage.getItem("result-style") as Settings["result_style"]) || "skeuomorphic",
         close_on_blur: data.close_on_blur ?? true,
-      });
+      };
+      lastSavedSettingsRef.current = loaded;
+      setSettings(loaded);
     } catch (error) {
       console.error("Failed to load settings:", error);
     } finally {
@@ -689,13 +695,24 @@ export function AppCenterContent({ onPluginClick, onClose: _onClose }: AppCenter
   // 保存设置
   const saveSettings = useCallback(async () => {
     try {
+      // 只提交改动过的字段，避免用本窗口的旧值覆盖其它窗口刚保存的设置
+      const previous = lastSavedSettingsRef.current;
+      const patch: Record<string, unknown> = {};
+      for (const key of Object.keys(settings) as (keyof Settings)[]) {
+        if (!previous || JSON.stringify(settings[key]) !== JSON.stringify(previous[key])) {
+          patch[key] = settings[key] ?? null;
+        }
+      }
+      if (Object.keys(patch).length === 0) return;
+
       setIsSaving(true);
       setSaveMessage("正在保存...");
-      await tauriApi.saveSettings(settings);
+      await tauriApi.updateSettings(patch);
       // 保存开机启动设置
-      if (settings.startup_enabled !== undefined) {
+      if ("startup_enabled" in patch && settings.startup_enabled !== undefined) {
         await tauriApi.setStartupEnabled(settings.startup_enabled);
       }
+      lastSavedSettingsRef.current = settings;
       // 本地缓存样式，避免后端旧版本未持久化时丢失
       if (settings.result_style) {
         localStorage.setItem("result-style", settings.result_style);
@@ -739,6 +756,37 @@ export function AppCenterContent({ onPluginClick, onClose: _onClose }: AppCenter
     };
   }, [settings, isLoadingSettings, saveSettings]);
 
+  // 其它窗口或外部修改的设置合并进本地，避免下次自动保存把它们改回去
+  useEffect(() => {
+    const unlisten = listen<SettingsChangedEvent>("settings-changed", (event) => {
+      const { changed, settings: incoming } = event.payload;
+      const previousSaved = lastSavedSettingsRef.current;
+      if (previousSaved) {
+        const saved: Record<string, unknown> = { ...previousSaved };
+        for (const key of changed) {
+          saved[key] = incoming[key];
+        }
+        lastSavedSettingsRef.current = saved as unknown as Settings;
+      }
+      setSettings((prev) => {
+        const next: Record<string, unknown> = { ...prev };
+        const prevRecord = prev as unknown as Record<string, unknown>;
+        const savedRecord = previousSaved as unknown as Record<string, unknown> | null;
+        for (const key of changed) {
+          // 本窗口还没保存的修改优先，下次自动保存时提交
+          if (!savedRecord || JSON.stringify(prevRecord[key]) === JSON.stringify(savedRecord[key])) {
+            next[key] = incoming[key];
+          }
+        }
+        return next as unknown as Settings;
+      });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // 卸载时清理定时器
   useEffect(() => {
     return () => {
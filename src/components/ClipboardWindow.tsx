@@ -89,11 +89,7 @@ export function ClipboardWindow() {
 
   const saveMaxItems = async () => {
     try {
-      const settings = await tauriApi.getSettings();
-      await tauriApi.saveSettings({
-        ...settings,
-        clipboard_max_items: tempMaxItems,
-      });
+      await tauriApi.updateSettings({ clipboard_max_items: tempMaxItems });
       setMaxItems(tempMaxItems);
     } catch (error) {
       console.error("Failed to save settings:", error);
@@ -9,6 +9,8 @@ interface HotkeySettingsProps {
 interface HotkeyConfig {
   modifiers: string[];
   key: string;
+  /** 组合序列或按住触发的完整写法，如 "Ctrl+K, Ctrl+S" */
+  sequence?: string;
 }
 
 export function HotkeySettings({ onClose }: HotkeySettingsProps) {
@@ -1,11 +1,12 @@
 import { useState, useEffect, useRef, useMemo, useCallback, startTransition } from "react";
 import { tauriApi } from "../api/tauri";
-import type { AppInfo, FileHistoryItem, EverythingResult, MemoItem, PluginContext, UpdateCheckResult, SearchEngineConfig } from "../types";
+import type { AppInfo, FileHistoryItem, EverythingResult, MemoItem, SnippetItem, PluginContext, UpdateCheckResult, SearchEngineConfig } from "../types";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { LogicalSize } from "@tauri-apps/api/window";
 import { plugins, executePlugin } from "../plugins";
 import { MemoModal } from "./MemoModal";
 import { RemarkEditModal } from "./RemarkEditModal";
+import { SnippetInputModal } from "./SnippetInputModal";
 import { PluginListModal } from "./PluginListModal";
 import { ContextMenu } from "./ContextMenu";
 import { ErrorDialog } from "./ErrorDialog";
@@ -18,7 +19,7 @@ import { clearAllResults, loadResultsIncrementally } from "../utils/resultUtils"
 import { getMainContainer as getMainContainerUtil } from "../utils/windowUtils";
 import type { SearchResult } from "../utils/resultUtils";
 import { askOllama } from "../utils/ollamaUtils";
-import { handleLaunch as handleLaunchUtil } from "../utils/launchUtils";
+import { handleLaunch as handleLaunchUtil, pasteSnippet } from "../utils/launchUtils";
 import {
   startEverythingSearchSession,
   closeEverythingSession,
@@ -61,6 +62,7 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
   const [systemFolders, setSystemFolders] = useState<Array<{ name: string; path: string; display_name: string; is_folder: boolean; icon?: string; name_pinyin?: string; name_pinyin_initials?: string }>>([]);
   const [memos, setMemos] = useState<MemoItem[]>([]);
   const [filteredMemos, setFilteredMemos] = useState<MemoItem[]>([]);
+  const [filteredSnippets, setFilteredSnippets] = useState<SnippetItem[]>([]);
   const [everythingResults, setEverythingResults] = useState<EverythingResult[]>([]);
   const [everythingTotalCount, setEverythingTotalCount] = useState<number | null>(null);
   const [everythingCurrentCount, setEverythingCurrentCount] = useState<number>(0); // 当前已加载的数量
@@ -110,7 +112,13 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
   const [isRemarkModalOpen, setIsRemarkModalOpen] = useState(false);
   const [editingRemarkUrl, setEditingRemarkUrl] = useState<string | null>(null);
   const [searchEngines, setSearchEngines] = useState<SearchEngineConfig[]>([]);
+  const [fallbackSearchEngine, setFallbackSearchEngine] = useState<string | null>(null);
+  const [searchSuggestionsEnabled, setSearchSuggestionsEnabled] = useState(false);
+  const [searchSuggestions, setSearchSuggestions] = useState<string[]>([]);
   const [remarkText, setRemarkText] = useState<string>("");
+  // 等待填写 {input:...} 的片段
+  const [snippetInput, setSnippetInput] = useState<{ snippet: SnippetItem; inputs: string[] } | null>(null);
+  const [snippetInputValues, setSnippetInputValues] = useState<Record<string, string>>({});
   const [urlRemarks, setUrlRemarks] = useState<Record<string, string>>({});
   const [launchingAppPath, setLaunchingAppPath] = useState<string | null>(null); // 正在启动的应用路径
   const [pastedImagePath, setPastedImagePath] = useState<string | null>(null); // 粘贴的图片路径
@@ -143,6 +151,8 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
   const isPluginListModalOpenRef = useRef(false);
   // 记录备注弹窗是否打开，用于全局 ESC 处理时优先关闭备注弹窗，而不是隐藏整个窗口
   const isRemarkModalOpenRef = useRef(false);
+  // 记录片段输入弹窗是否打开，用于全局 ESC 处理时优先关闭输入弹窗，而不是隐藏整个窗口
+  const isSnippetInputOpenRef = useRef(false);
   // 记录右键菜单是否打开，用于全局 ESC 处理时优先关闭右键菜单，而不是隐藏整个窗口
   const contextMenuRef = useRef<{ x: number; y: number; result: SearchResult } | null>(null);
   const shouldPreserveScrollRef = useRef(false); // 标记是否需要保持滚动位置
@@ -185,6 +195,10 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
     isRemarkModalOpenRef.current = isRemarkModalOpen;
   }, [isRemarkModalOpen]);
 
+  useEffect(() => {
+    isSnippetInputOpenRef.current = snippetInput !== null;
+  }, [snippetInput]);
+
   useEffect(() => {
     contextMenuRef.current = contextMenu;
   }, [contextMenu]);
@@ -410,6 +424,14 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
         setRemarkText("");
         return;
       }
+
+      // 如果片段输入弹窗已打开，优先关闭输入弹窗，不关闭启动器
+      if ((e.key === "Escape" || e.keyCode === 27) && isSnippetInputOpenRef.current) {
+        e.preventDefault();
+        e.stopPropagation();
+        setSnippetInput(null);
+        return;
+      }
       
       if (handleEscapeKey(e, {
         isPluginListModalOpen: () => isPluginListModalOpenRef.current,
@@ -595,8 +617,32 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
   // 同步更新 hasResultsRef，用于优化查询去重检查
   useEffect(() => {
     hasResultsRef.current = filteredApps.length > 0 || filteredFiles.length > 0 || filteredMemos.length > 0 || 
-                             filteredPlugins.length > 0 || everythingResults.length > 0;
-  }, [filteredApps, filteredFiles, filteredMemos, filteredPlugins, everythingResults]);
+                             filteredSnippets.length > 0 || filteredPlugins.length > 0 || everythingResults.length > 0;
+  }, [filteredApps, filteredFiles, filteredMemos, filteredSnippets, filteredPlugins, everythingResults]);
+
+  // 开启搜索建议时，输入停顿后向后端请求建议；过期的请求结果直接丢弃
+  useEffect(() => {
+    if (!searchSuggestionsEnabled || !query.trim()) {
+      setSearchSuggestions([]);
+      return;
+    }
+    let cancelled = false;
+    const timer = window.setTimeout(() => {
+      tauriApi
+        .getSearchSuggestions(query)
+        .then((suggestions) => {
+          if (!cancelled) setSearchSuggestions(suggestions);
+        })
+        .catch((error) => {
+          console.error("Failed to get search suggestions:", error);
+          if (!cancelled) setSearchSuggestions([]);
+        });
+    }, 250);
+    return () => {
+      cancelled = true;
+      window.clearTimeout(timer);
+    };
+  }, [query, searchSuggestionsEnabled]);
 
 
   // 使用自定义 Hook 合并搜索结果
@@ -606,6 +652,7 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
     filteredApps,
     filteredFiles,
     filteredMemos,
+    filteredSnippets,
     systemFolders,
     everythingResults,
     filteredPlugins,
@@ -616,6 +663,8 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
     openHistory,
     urlRemarks,
     searchEngines,
+    fallbackSearchEngine,
+    searchSuggestions,
     apps,
     extractedFileIconsRef,
   });
@@ -1168,6 +1217,8 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
     setResultStyle,
     setCloseOnBlur,
     setSearchEngines,
+    setFallbackSearchEngine,
+    setSearchSuggestionsEnabled,
     setIsEverythingAvailable,
     setEverythingError,
     setEverythingPath,
@@ -1203,6 +1254,7 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
   // 使用自定义 hook 管理搜索相关的 wrapper 函数
   const {
     searchMemosWrapper,
+    searchSnippetsWrapper,
     searchSystemFoldersWrapper,
     searchApplicationsWrapper,
     searchFileHistoryWrapper,
@@ -1223,6 +1275,7 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
     updateSearchResults,
     filterWindowsApps,
     setFilteredMemos,
+    setFilteredSnippets,
     setFilteredFiles,
     setFilteredApps,
     setFilteredPlugins,
@@ -1314,6 +1367,7 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
     setFilteredApps,
     setFilteredFiles,
     setFilteredMemos,
+    setFilteredSnippets,
     setFilteredPlugins,
     setEverythingResults,
     setEverythingTotalCount,
@@ -1339,6 +1393,7 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
     searchFileHistoryWrapper,
     searchApplicationsWrapper,
     searchMemosWrapper,
+    searchSnippetsWrapper,
     handleSearchPlugins,
     handleDirectPathLookup,
     startSearchSession,
@@ -1369,6 +1424,23 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
     });
   }, [setIsDownloadingEverything, setEverythingDownloadProgress, tauriApi]);
 
+  const openSnippetInputs = useCallback((snippet: SnippetItem, inputs: string[]) => {
+    setSnippetInputValues({});
+    setSnippetInput({ snippet, inputs });
+  }, []);
+
+  const handleSubmitSnippetInputs = useCallback(async () => {
+    if (!snippetInput) return;
+    const { snippet } = snippetInput;
+    setSnippetInput(null);
+    try {
+      await pasteSnippet(snippet.id, snippetInputValues, { hideLauncherAndResetState, tauriApi });
+    } catch (error: any) {
+      console.error("Failed to paste snippet:", error);
+      setErrorMessage(error?.message || error?.toString() || "粘贴片段失败");
+    }
+  }, [snippetInput, snippetInputValues, hideLauncherAndResetState, tauriApi]);
+
   const handleLaunch = useCallback(
     async (result: SearchResult) => {
       await handleLaunchUtil({
@@ -1391,6 +1463,7 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
         setSelectedIndex,
         setContextMenu,
           setIsPluginListModalOpen,
+        openSnippetInputs,
         allFileHistoryCacheRef,
         allFileHistoryCacheLoadedRef,
         pendingJsonContentRef,
@@ -1412,6 +1485,7 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
       setQuery,
       setSelectedIndex,
       setContextMenu,
+      openSnippetInputs,
       allFileHistoryCacheRef,
       allFileHistoryCacheLoadedRef,
       pendingJsonContentRef,
@@ -1872,6 +1946,16 @@ export function LauncherWindow({ updateInfo }: LauncherWindowProps) {
         onSave={handleSaveRemark}
       />
 
+      {/* Snippet Input Modal */}
+      <SnippetInputModal
+        snippet={snippetInput?.snippet ?? null}
+        inputs={snippetInput?.inputs ?? []}
+        values={snippetInputValues}
+        setValue={(name, value) => setSnippetInputValues((prev) => ({ ...prev, [name]: value }))}
+        onClose={() => setSnippetInput(null)}
+        onSubmit={handleSubmitSnippetInputs}
+      />
+
       {/* Memo Detail Modal */}
       <MemoModal
         isOpen={isMemoModalOpen}
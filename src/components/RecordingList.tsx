@@ -40,7 +40,7 @@ export const RecordingList: React.FC<RecordingListProps> = ({
               className="flex-1 cursor-pointer"
               onClick={() => onSelect?.(rec)}
             >
-              <div className="font-medium">{rec.file_name}</div>
+              <div className="font-medium">{rec.name || rec.file_name}</div>
               <div className="text-sm text-gray-500 mt-1">
                 时长: {formatDuration(rec.duration_ms)} | 事件数: {rec.event_count}
               </div>
@@ -52,7 +52,7 @@ export const RecordingList: React.FC<RecordingListProps> = ({
               <button
                 onClick={(e) => {
                   e.stopPropagation();
-                  if (confirm(`确定要删除录制文件 "${rec.file_name}" 吗？`)) {
+                  if (confirm(`确定要删除录制文件 "${rec.name || rec.file_name}" 吗？`)) {
                     onDelete(rec);
                   }
                 }}
@@ -344,6 +344,20 @@ export function ResultIcon({
     );
   }
 
+  // 处理片段图标
+  if (result.type === "snippet") {
+    return (
+      <svg className={`w-5 h-5 ${theme.iconColor(isSelected, "text-purple-500")}`} fill="none" stroke="currentColor" viewBox="0 0 24 24">
+        <path
+          strokeLinecap="round"
+          strokeLinejoin="round"
+          strokeWidth={2}
+          d="M10 20l4-16m4 4l4 4-4 4M6 16l-4-4 4-4"
+        />
+      </svg>
+    );
+  }
+
   // 处理历史记录图标
   if (result.type === "history") {
     return (
@@ -245,7 +245,7 @@ const VerticalResultItem = React.memo<{
               {result.aiAnswer}
             </div>
           )}
-          {result.path && result.type !== "memo" && result.type !== "history" && result.type !== "ai" && (
+          {result.path && result.type !== "memo" && result.type !== "snippet" && result.type !== "history" && result.type !== "ai" && (
             <div
               className={`text-xs truncate mt-0.5 ${theme.pathText(isSelected)}`}
               dangerouslySetInnerHTML={{ __html: highlightText(result.path, query) }}
@@ -386,6 +386,30 @@ const VerticalResultItem = React.memo<{
               )}
             </div>
           )}
+          {result.type === "snippet" && result.snippet && (
+            <div className="flex items-center gap-2 mt-1.5">
+              <span
+                className={`text-xs px-2.5 py-1 rounded-md font-medium transition-all ${theme.tag("memo", isSelected)}`}
+                title="片段，回车粘贴到当前光标处"
+              >
+                {result.snippet.keyword || "片段"}
+              </span>
+              {result.snippet.folder && (
+                <span className={`text-xs ${theme.metaText(isSelected)}`}>
+                  {result.snippet.folder}
+                </span>
+              )}
+              <span
+                className={`text-xs truncate ${theme.metaText(isSelected)}`}
+                dangerouslySetInnerHTML={{
+                  __html: highlightText(
+                    result.snippet.content.slice(0, 50) + (result.snippet.content.length > 50 ? "..." : ""),
+                    query
+                  )
+                }}
+              />
+            </div>
+          )}
           {result.type === "everything" && (
             <div className="flex items-center gap-2 mt-1.5">
               <span
@@ -1,5 +1,6 @@
 import { tauriApi } from "../api/tauri";
 import { useEffect, useState } from "react";
+import { open } from "@tauri-apps/plugin-dialog";
 import { UpdateSection } from "./UpdateSection";
 import { ErrorDialog } from "./ErrorDialog";
 import type { SearchEngineConfig } from "../types";
@@ -350,6 +351,8 @@ export function SystemSettingsPage({
 interface LauncherSettingsProps {
   settings: {
     search_engines?: SearchEngineConfig[];
+    fallback_search_engine?: string | null;
+    search_suggestions_enabled?: boolean;
   };
   onSettingsChange: (settings: any) => void;
 }
@@ -409,6 +412,47 @@ export function LauncherSettingsPage({
     setSearchEngines([...searchEngines, preset]);
   };
 
+  const handleImportBangs = async () => {
+    try {
+      const selected = await open({
+        filters: [{ name: "Bang 列表", extensions: ["json", "js"] }],
+        multiple: false,
+        title: "选择要导入的 bang 列表",
+      });
+      if (!selected || typeof selected !== "string") return;
+      const summary = await tauriApi.importSearchBangs(selected);
+      setErrorDialog({
+        isOpen: true,
+        type: "success",
+        message: `已导入 ${summary.imported} 个 bang，跳过 ${summary.skipped} 个`,
+      });
+    } catch (error) {
+      console.error("Failed to import bangs:", error);
+      setErrorDialog({
+        isOpen: true,
+        type: "error",
+        message: `导入 bang 列表失败：${error}`,
+      });
+    }
+  };
+
+  const handleClearBangs = async () => {
+    try {
+      await tauriApi.clearSearchBangs();
+      setSaveSuccessMessage("已清空导入的 bang 列表");
+      setTimeout(() => {
+        setSaveSuccessMessage(null);
+      }, 2000);
+    } catch (error) {
+      console.error("Failed to clear bangs:", error);
+      setErrorDialog({
+        isOpen: true,
+        type: "error",
+        message: `清空 bang 列表失败：${error}`,
+      });
+    }
+  };
+
   const handleSave = () => {
     // 验证配置
     for (const engine of searchEngines) {
@@ -420,11 +464,11 @@ export function LauncherSettingsPage({
         });
         return;
       }
-      if (!engine.url.includes("{query}")) {
+      if (!["{query}", "{raw}", "{clipboard}"].some((p) => engine.url.includes(p))) {
         setErrorDialog({
           isOpen: true,
           type: "warning",
-          message: `搜索引擎 "${engine.name}" 的 URL 模板必须包含 {query} 占位符`,
+          message: `搜索引擎 "${engine.name}" 的 URL 模板必须包含 {query}、{raw} 或 {clipboard} 占位符`,
         });
         return;
       }
@@ -441,9 +485,13 @@ export function LauncherSettingsPage({
       return;
     }
     // 保存配置
+    // 后备引擎被删除或改名时一并清除，否则后端校验不通过
+    const fallback = settings.fallback_search_engine;
     onSettingsChange({
       ...settings,
       search_engines: searchEngines,
+      fallback_search_engine:
+        fallback && searchEngines.some((e) => e.name === fallback) ? fallback : null,
     });
     // 显示成功提示（自动消失）
     setSaveSuccessMessage("搜索引擎配置已保存成功");
@@ -519,7 +567,7 @@ export function LauncherSettingsPage({
               </div>
             </div>
             <p className="text-sm text-gray-500 mb-4">
-              配置搜索引擎前缀，输入特定前缀时可在浏览器中快速搜索。URL 模板中使用 <code className="bg-gray-100 px-1 rounded">{`{query}`}</code> 作为搜索关键词的占位符。
+              配置搜索引擎前缀，输入特定前缀时可在浏览器中快速搜索。URL 模板中使用 <code className="bg-gray-100 px-1 rounded">{`{query}`}</code> 作为搜索关键词的占位符，<code className="bg-gray-100 px-1 rounded">{`{raw}`}</code> 插入未编码的关键词，<code className="bg-gray-100 px-1 rounded">{`{clipboard}`}</code> 插入剪切板文本。也可以用 <code className="bg-gray-100 px-1 rounded">!前缀</code> 放在关键词前后搜索（如 "rust !gh"）。
             </p>
 
             {/* 预设搜索引擎 */}
@@ -613,7 +661,24 @@ export function LauncherSettingsPage({
                           className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent text-sm font-mono"
                         />
                         <p className="text-xs text-gray-500 mt-1">
-                          必须包含 <code className="bg-gray-100 px-1 rounded">{`{query}`}</code> 占位符
+                          必须包含 <code className="bg-gray-100 px-1 rounded">{`{query}`}</code>、<code className="bg-gray-100 px-1 rounded">{`{raw}`}</code> 或 <code className="bg-gray-100 px-1 rounded">{`{clipboard}`}</code> 占位符
+                        </p>
+                      </div>
+                      <div>
+                        <label className="block text-sm font-medium text-gray-700 mb-1">
+                          搜索建议接口
+                        </label>
+                        <input
+                          type="text"
+                          value={engine.suggest_url || ""}
+                          onChange={(e) =>
+                            handleUpdateEngine(index, "suggest_url", e.target.value)
+                          }
+                          placeholder='例如: "https://suggestqueries.google.com/complete/search?client=firefox&q={query}"'
+                          className="w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent text-sm font-mono"
+                        />
+                        <p className="text-xs text-gray-500 mt-1">
+                          可选，OpenSearch 格式的建议接口，开启搜索建议后使用
                         </p>
                       </div>
                     </div>
@@ -625,10 +690,94 @@ export function LauncherSettingsPage({
         </div>
       </div>
 
+      <div className="bg-white rounded-lg shadow-sm border border-gray-200 p-6">
+        <div className="space-y-6">
+          <h3 className="text-lg font-medium text-gray-700">网页搜索</h3>
+
+          <div className="flex items-center justify-between">
+            <div className="flex-1">
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                后备搜索引擎
+              </label>
+              <p className="text-xs text-gray-500">
+                输入没有前缀或 bang 时，在结果末尾提供一条使用该引擎的网页搜索
+              </p>
+            </div>
+            <select
+              value={settings.fallback_search_engine || ""}
+              onChange={(e) =>
+                onSettingsChange({
+                  ...settings,
+                  fallback_search_engine: e.target.value || null,
+                })
+              }
+              className="px-3 py-2 border border-gray-300 rounded-md text-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white"
+            >
+              <option value="">不使用</option>
+              {(settings.search_engines || []).map((engine) => (
+                <option key={engine.name} value={engine.name}>
+                  {engine.name}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="flex items-center justify-between">
+            <div className="flex-1">
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                搜索建议
+              </label>
+              <p className="text-xs text-gray-500">
+                在启动器中显示搜索引擎的联想词，需要引擎配置了建议接口（会把输入发送给该引擎）
+              </p>
+            </div>
+            <label className="relative inline-flex items-center cursor-pointer">
+              <input
+                type="checkbox"
+                checked={settings.search_suggestions_enabled ?? false}
+                onChange={(e) =>
+                  onSettingsChange({
+                    ...settings,
+                    search_suggestions_enabled: e.target.checked,
+                  })
+                }
+                className="sr-only peer"
+              />
+              <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-600"></div>
+            </label>
+          </div>
+
+          <div className="flex items-center justify-between">
+            <div className="flex-1">
+              <label className="block text-sm font-medium text-gray-700 mb-1">
+                Bang 列表
+              </label>
+              <p className="text-xs text-gray-500">
+                导入 DuckDuckGo 的 bang.js 或 <code className="bg-gray-100 px-1 rounded">{`[{trigger, name, url}]`}</code> 格式的 JSON，之后可用 !bang 搜索
+              </p>
+            </div>
+            <div className="flex gap-2">
+              <button
+                onClick={handleImportBangs}
+                className="px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700 transition-colors text-sm"
+              >
+                导入
+              </button>
+              <button
+                onClick={handleClearBangs}
+                className="px-4 py-2 bg-gray-100 text-gray-700 rounded-md hover:bg-gray-200 transition-colors text-sm"
+              >
+                清空
+              </button>
+            </div>
+          </div>
+        </div>
+      </div>
+
       <ErrorDialog
         isOpen={errorDialog.isOpen}
         type={errorDialog.type}
-        title={errorDialog.type === "success" ? "保存成功" : errorDialog.type === "warning" ? "配置验证" : "错误"}
+        title={errorDialog.type === "success" ? "导入完成" : errorDialog.type === "warning" ? "配置验证" : "错误"}
         message={errorDialog.message}
         onClose={() => setErrorDialog({ ...errorDialog, isOpen: false })}
       />
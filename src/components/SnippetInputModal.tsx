@@ -0,0 +1,67 @@
+import type { SnippetItem } from "../types";
+
+interface SnippetInputModalProps {
+  snippet: SnippetItem | null;
+  inputs: string[];
+  values: Record<string, string>;
+  setValue: (name: string, value: string) => void;
+  onClose: () => void;
+  onSubmit: () => void;
+}
+
+export function SnippetInputModal({
+  snippet,
+  inputs,
+  values,
+  setValue,
+  onClose,
+  onSubmit,
+}: SnippetInputModalProps) {
+  if (!snippet) return null;
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50" onClick={onClose}>
+      <div className="bg-white rounded-lg shadow-xl p-4 max-w-sm w-full mx-4" onClick={(e) => e.stopPropagation()}>
+        <h2 className="text-base font-semibold mb-3">{snippet.name}</h2>
+        <div className="mb-3 space-y-2">
+          {inputs.map((name, index) => (
+            <div key={name}>
+              <label className="block text-xs font-medium text-gray-700 mb-1">{name}:</label>
+              <input
+                value={values[name] ?? ""}
+                onChange={(e) => setValue(name, e.target.value)}
+                className="w-full px-2 py-1.5 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent text-sm"
+                autoFocus={index === 0}
+                onKeyDown={(e) => {
+                  if (e.key === "Escape") {
+                    e.preventDefault();
+                    e.stopPropagation();
+                    onClose();
+                  } else if (e.key === "Enter") {
+                    e.preventDefault();
+                    e.stopPropagation();
+                    onSubmit();
+                  }
+                }}
+              />
+            </div>
+          ))}
+        </div>
+        <div className="flex justify-end gap-2">
+          <button
+            onClick={onClose}
+            className="px-3 py-1.5 text-xs text-gray-700 bg-gray-100 rounded-md hover:bg-gray-200 transition-colors"
+          >
+            取消
+          </button>
+          <button
+            onClick={onSubmit}
+            className="px-3 py-1.5 text-xs text-white bg-blue-600 rounded-md hover:bg-blue-700 transition-colors"
+          >
+            粘贴
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -4,7 +4,8 @@ import { useEscapeKeyWithPriority } from "../hooks/useEscapeKeyWithPriority";
 import { TranslationPanel } from "./TranslationPanel";
 import { WordbookPanel } from "./WordbookPanel";
 import { useWindowClose } from "../hooks/useWindowClose";
-import type { WordRecord } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type { WordRecord, SettingsChangedEvent } from "../types";
 
 type TabType = "translation" | "wordbook";
 
@@ -99,6 +100,18 @@ export function TranslationWindow() {
       }
     };
     loadSettings();
+
+    // 其它窗口修改了相关设置时重新加载
+    const unlisten = listen<SettingsChangedEvent>("settings-changed", (event) => {
+      const { changed } = event.payload;
+      if (changed.includes("ollama") || changed.includes("translation_tab_order")) {
+        loadSettings();
+      }
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
   }, []);
 
   // 监听标签页切换，保存到 localStorage
@@ -289,11 +302,7 @@ export function TranslationWindow() {
               <button
                 onClick={async () => {
                   try {
-                    const settings = await tauriApi.getSettings();
-                    await tauriApi.saveSettings({
-                      ...settings,
-                      translation_tab_order: tabOrder,
-                    });
+                    await tauriApi.updateSettings({ translation_tab_order: tabOrder });
                     setShowTabOrderSettings(false);
                   } catch (error) {
                     console.error("Failed to save tab order:", error);
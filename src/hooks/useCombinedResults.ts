@@ -7,7 +7,7 @@ import { useState, useEffect, useRef, useDeferredValue, useMemo } from "react";
 import { startTransition } from "react";
 import { computeCombinedResults } from "../utils/combineResultsUtils";
 import type { SearchResult } from "../utils/resultUtils";
-import type { AppInfo, FileHistoryItem, MemoItem, SearchEngineConfig } from "../types";
+import type { AppInfo, FileHistoryItem, MemoItem, SnippetItem, SearchEngineConfig } from "../types";
 import type { EverythingResult } from "../types";
 
 export interface UseCombinedResultsOptions {
@@ -16,6 +16,7 @@ export interface UseCombinedResultsOptions {
   filteredApps: AppInfo[];
   filteredFiles: FileHistoryItem[];
   filteredMemos: MemoItem[];
+  filteredSnippets: SnippetItem[];
   systemFolders: Array<{ name: string; path: string; display_name: string; is_folder: boolean; icon?: string; name_pinyin?: string; name_pinyin_initials?: string }>;
   everythingResults: EverythingResult[];
   filteredPlugins: Array<{ id: string; name: string; description?: string }>;
@@ -26,6 +27,8 @@ export interface UseCombinedResultsOptions {
   openHistory: Record<string, number>;
   urlRemarks: Record<string, string>;
   searchEngines: SearchEngineConfig[];
+  fallbackSearchEngine: string | null;
+  searchSuggestions: string[];
   apps: AppInfo[];
   extractedFileIconsRef: React.MutableRefObject<Map<string, string>>;
 }
@@ -41,6 +44,7 @@ export function useCombinedResults(options: UseCombinedResultsOptions) {
     filteredApps,
     filteredFiles,
     filteredMemos,
+    filteredSnippets,
     systemFolders,
     everythingResults,
     filteredPlugins,
@@ -51,6 +55,8 @@ export function useCombinedResults(options: UseCombinedResultsOptions) {
     openHistory,
     urlRemarks,
     searchEngines,
+    fallbackSearchEngine,
+    searchSuggestions,
     apps,
     extractedFileIconsRef,
   } = options;
@@ -71,6 +77,7 @@ export function useCombinedResults(options: UseCombinedResultsOptions) {
           filteredApps,
           filteredFiles,
           filteredMemos,
+          filteredSnippets,
           systemFolders,
           everythingResults,
           filteredPlugins,
@@ -81,6 +88,8 @@ export function useCombinedResults(options: UseCombinedResultsOptions) {
           openHistory,
           urlRemarks,
           searchEngines,
+          fallbackSearchEngine,
+          searchSuggestions,
           apps,
           extractedFileIconsRef,
         });
@@ -94,7 +103,7 @@ export function useCombinedResults(options: UseCombinedResultsOptions) {
     } else {
       setTimeout(scheduleCompute, 0);
     }
-  }, [filteredApps, filteredFiles, filteredMemos, filteredPlugins, everythingResults, detectedUrls, detectedEmails, detectedJson, openHistory, urlRemarks, query, aiAnswer, searchEngines, systemFolders, directPathResult, apps, extractedFileIconsRef]);
+  }, [filteredApps, filteredFiles, filteredMemos, filteredSnippets, filteredPlugins, everythingResults, detectedUrls, detectedEmails, detectedJson, openHistory, urlRemarks, query, aiAnswer, searchEngines, fallbackSearchEngine, searchSuggestions, systemFolders, directPathResult, apps, extractedFileIconsRef]);
 
   // 使用 useDeferredValue 延迟 combinedResults 的更新，让输入框保持响应
   // 当用户快速输入时，React 会延迟更新 combinedResults，优先处理输入事件
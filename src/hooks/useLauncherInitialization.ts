@@ -7,7 +7,7 @@ import type React from "react";
 import { useEffect, useRef } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { tauriApi } from "../api/tauri";
-import type { AppInfo, FileHistoryItem, MemoItem, SearchEngineConfig, PluginContext } from "../types";
+import type { AppInfo, FileHistoryItem, MemoItem, SearchEngineConfig, PluginContext, SettingsChangedEvent } from "../types";
 import type { ResultStyle } from "../utils/themeConfig";
 
 // 全局标志，确保整个应用只有一个插件快捷键监听器
@@ -30,6 +30,8 @@ export interface LauncherInitializationOptions {
   setResultStyle: (style: ResultStyle) => void;
   setCloseOnBlur: (close: boolean) => void;
   setSearchEngines: (engines: SearchEngineConfig[]) => void;
+  setFallbackSearchEngine: (engine: string | null) => void;
+  setSearchSuggestionsEnabled: (enabled: boolean) => void;
   setIsEverythingAvailable: (available: boolean) => void;
   setEverythingError: (error: string | null) => void;
   setEverythingPath: (path: string | null) => void;
@@ -70,6 +72,8 @@ export function useLauncherInitialization(
     setResultStyle,
     setCloseOnBlur,
     setSearchEngines,
+    setFallbackSearchEngine,
+    setSearchSuggestionsEnabled,
     setIsEverythingAvailable,
     setEverythingError,
     setEverythingPath,
@@ -115,6 +119,8 @@ export function useLauncherInitialization(
         if (settings.search_engines) {
           setSearchEngines(settings.search_engines);
         }
+        setFallbackSearchEngine(settings.fallback_search_engine ?? null);
+        setSearchSuggestionsEnabled(settings.search_suggestions_enabled ?? false);
       } catch (error) {
         console.error("Failed to load settings:", error);
       }
@@ -125,11 +131,16 @@ export function useLauncherInitialization(
     const unlisten = listen("settings:updated", () => {
       loadSettings();
     });
+    // 其它窗口或外部修改设置后由后端广播，立即生效
+    const unlistenChanged = listen<SettingsChangedEvent>("settings-changed", () => {
+      loadSettings();
+    });
 
     return () => {
       unlisten.then((fn) => fn());
+      unlistenChanged.then((fn) => fn());
     };
-  }, [setOllamaSettings, setResultStyle, setCloseOnBlur, setSearchEngines, closeOnBlurRef]);
+  }, [setOllamaSettings, setResultStyle, setCloseOnBlur, setSearchEngines, setFallbackSearchEngine, setSearchSuggestionsEnabled, closeOnBlurRef]);
 
   // 监听 JSON 查看器窗口准备好事件，发送待处理的内容
   useEffect(() => {
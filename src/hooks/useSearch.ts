@@ -11,7 +11,7 @@ import {
   isValidJson,
   isLikelyAbsolutePath,
 } from "../utils/launcherUtils";
-import type { AppInfo, FileHistoryItem, MemoItem, EverythingResult } from "../types";
+import type { AppInfo, FileHistoryItem, MemoItem, SnippetItem, EverythingResult } from "../types";
 
 export interface UseSearchOptions {
   // 查询状态
@@ -22,6 +22,7 @@ export interface UseSearchOptions {
   setFilteredApps: React.Dispatch<React.SetStateAction<AppInfo[]>>;
   setFilteredFiles: React.Dispatch<React.SetStateAction<FileHistoryItem[]>>;
   setFilteredMemos: React.Dispatch<React.SetStateAction<MemoItem[]>>;
+  setFilteredSnippets: React.Dispatch<React.SetStateAction<SnippetItem[]>>;
   setFilteredPlugins: React.Dispatch<React.SetStateAction<Array<{ id: string; name: string; description?: string }>>>;
   setEverythingResults: React.Dispatch<React.SetStateAction<EverythingResult[]>>;
   setEverythingTotalCount: React.Dispatch<React.SetStateAction<number | null>>;
@@ -53,6 +54,7 @@ export interface UseSearchOptions {
   searchFileHistoryWrapper: (query: string) => Promise<void>;
   searchApplicationsWrapper: (query: string) => Promise<void>;
   searchMemosWrapper: (query: string) => Promise<void>;
+  searchSnippetsWrapper: (query: string) => Promise<void>;
   handleSearchPlugins: (query: string) => void;
   handleDirectPathLookup: (path: string) => Promise<void>;
   startSearchSession: (query: string) => Promise<void>;
@@ -70,6 +72,7 @@ export function useSearch(options: UseSearchOptions): void {
     setFilteredApps,
     setFilteredFiles,
     setFilteredMemos,
+    setFilteredSnippets,
     setFilteredPlugins,
     setEverythingResults,
     setEverythingTotalCount,
@@ -95,6 +98,7 @@ export function useSearch(options: UseSearchOptions): void {
     searchFileHistoryWrapper,
     searchApplicationsWrapper,
     searchMemosWrapper,
+    searchSnippetsWrapper,
     handleSearchPlugins,
     handleDirectPathLookup,
     startSearchSession,
@@ -140,6 +144,7 @@ export function useSearch(options: UseSearchOptions): void {
       setFilteredApps([]);
       setFilteredFiles([]);
       setFilteredMemos([]);
+      setFilteredSnippets([]);
       setFilteredPlugins([]);
       setEverythingResults([]);
       setEverythingTotalCount(null);
@@ -190,6 +195,7 @@ export function useSearch(options: UseSearchOptions): void {
           setFilteredApps([]);
           setFilteredFiles([]);
           setFilteredMemos([]);
+          setFilteredSnippets([]);
           setFilteredPlugins([]);
           setEverythingResults([]);
           setEverythingTotalCount(null);
@@ -326,6 +332,11 @@ export function useSearch(options: UseSearchOptions): void {
         // 备忘录和插件搜索是纯前端过滤，立即执行（不会阻塞）
         searchMemosWrapper(trimmedQuery);
         handleSearchPlugins(trimmedQuery);
+
+        // 片段由后端搜索
+        searchSnippetsWrapper(trimmedQuery).catch((error) => {
+          console.error("[搜索错误] searchSnippets 调用失败:", error);
+        });
       }, 0);
     }, debounceTime) as unknown as number;
     
@@ -6,9 +6,9 @@
 import { useCallback, type MutableRefObject } from "react";
 import { startTransition } from "react";
 import { tauriApi } from "../api/tauri";
-import { searchMemos, searchSystemFolders, searchApplications, searchFileHistory } from "../utils/searchUtils";
+import { searchMemos, searchSnippets, searchSystemFolders, searchApplications, searchFileHistory } from "../utils/searchUtils";
 import { searchPlugins } from "../plugins";
-import type { AppInfo, FileHistoryItem, MemoItem } from "../types";
+import type { AppInfo, FileHistoryItem, MemoItem, SnippetItem } from "../types";
 
 /**
  * 搜索 Wrappers Hook 的选项接口
@@ -34,6 +34,7 @@ export interface UseSearchWrappersOptions {
 
   // Setters
   setFilteredMemos: React.Dispatch<React.SetStateAction<MemoItem[]>>;
+  setFilteredSnippets: React.Dispatch<React.SetStateAction<SnippetItem[]>>;
   setFilteredFiles: React.Dispatch<React.SetStateAction<FileHistoryItem[]>>;
   setFilteredApps: React.Dispatch<React.SetStateAction<AppInfo[]>>;
   setFilteredPlugins: React.Dispatch<React.SetStateAction<Array<{ id: string; name: string; description?: string }>>>;
@@ -49,6 +50,7 @@ export function useSearchWrappers(
   options: UseSearchWrappersOptions
 ): {
   searchMemosWrapper: (q: string) => Promise<void>;
+  searchSnippetsWrapper: (q: string) => Promise<void>;
   searchSystemFoldersWrapper: (searchQuery: string) => Promise<void>;
   searchApplicationsWrapper: (searchQuery: string) => Promise<void>;
   searchFileHistoryWrapper: (searchQuery: string) => Promise<void>;
@@ -70,6 +72,7 @@ export function useSearchWrappers(
     updateSearchResults,
     filterWindowsApps,
     setFilteredMemos,
+    setFilteredSnippets,
     setFilteredFiles,
     setFilteredApps,
     setFilteredPlugins,
@@ -90,6 +93,17 @@ export function useSearchWrappers(
     [memos, query, updateSearchResults, setFilteredMemos]
   );
 
+  const searchSnippetsWrapper = useCallback(
+    async (q: string) => {
+      await searchSnippets(q, {
+        currentQuery: query,
+        updateSearchResults,
+        setFilteredSnippets,
+      });
+    },
+    [query, updateSearchResults, setFilteredSnippets]
+  );
+
   const searchSystemFoldersWrapper = useCallback(
     async (searchQuery: string) => {
       await searchSystemFolders(searchQuery, {
@@ -199,6 +213,7 @@ export function useSearchWrappers(
 
   return {
     searchMemosWrapper,
+    searchSnippetsWrapper,
     searchSystemFoldersWrapper,
     searchApplicationsWrapper,
     searchFileHistoryWrapper,
@@ -1,31 +1,89 @@
-export enum EventType {
-  MouseMove = "MouseMove",
-  MouseDown = "MouseDown",
-  MouseUp = "MouseUp",
-  MouseWheel = "MouseWheel",
-  KeyDown = "KeyDown",
-  KeyUp = "KeyUp",
-}
-
 export enum MouseButton {
   Left = "Left",
   Right = "Right",
   Middle = "Middle",
 }
 
-export interface RecordedEvent {
-  event_type: EventType;
-  x?: number;
-  y?: number;
-  time_offset_ms: number;
+/** 归一化到录制时虚拟屏幕的坐标，取值 0..1 */
+export interface Position {
+  x: number;
+  y: number;
+}
+
+export interface KeyCode {
+  key: string;
+  vkCode?: number;
+  scanCode?: number;
+}
+
+export type InputEvent =
+  | { type: "mouseMove"; position: Position }
+  | { type: "mouseDown"; button: MouseButton; position?: Position }
+  | { type: "mouseUp"; button: MouseButton; position?: Position }
+  | { type: "mouseWheel"; delta: number }
+  | { type: "keyDown"; key: KeyCode }
+  | { type: "keyUp"; key: KeyCode };
+
+export type TimedEvent = InputEvent & { timeOffsetMs: number };
+
+export interface RecordingHeader {
+  formatVersion: number;
+  os: string;
+  screen: { x: number; y: number; width: number; height: number };
+  dpi: number;
+  createdAt: string;
+  durationMs: number;
+  upgradedFrom?: number;
+  name: string;
+  description: string;
+  tags: string[];
+  revision: number;
+}
+
+export interface Recording {
+  header: RecordingHeader;
+  events: TimedEvent[];
 }
 
 export interface RecordingMeta {
   file_path: string;
   file_name: string;
+  name: string;
+  description: string;
+  tags: string[];
   duration_ms: number;
   event_count: number;
   created_at: string;
+  revision: number;
+}
+
+export type EditOperation =
+  | { op: "trimIdle"; maxGapMs: number }
+  | { op: "cut"; start: number; end: number }
+  | { op: "delete"; indices: number[] }
+  | { op: "insert"; index: number; events: TimedEvent[] }
+  | { op: "simplifyMoves"; tolerancePx: number }
+  | { op: "setDelay"; index: number; delayMs: number }
+  | { op: "scaleDelays"; factor: number };
+
+/** 组合序列超时、双击修饰键间隔和按住触发时长（毫秒） */
+export interface HotkeyTiming {
+  chord_timeout_ms: number;
+  double_tap_ms: number;
+  hold_ms: number;
+}
+
+/** 录制的全局快捷键与回放参数，快捷键和启动器触发时使用 */
+export interface RecordingBinding {
+  hotkey: { modifiers: string[]; key: string; sequence?: string } | null;
+  speed: number;
+  repeat: number;
+}
+
+export interface RecordingDocument {
+  meta: RecordingMeta;
+  recording: Recording;
+  canUndo: boolean;
 }
 
 export type AppStatus = "idle" | "recording" | "playing";
@@ -155,6 +213,19 @@ export interface MemoItem {
   updated_at: number;
 }
 
+// 文本片段，content 为模板，支持日期、剪切板、光标、输入和嵌套片段占位符
+export interface SnippetItem {
+  id: string;
+  name: string;
+  keyword?: string | null; // 触发关键字，如 ";sig"
+  folder?: string | null; // 分组，使用 "/" 分隔层级
+  content: string;
+  use_count: number;
+  last_used?: number | null;
+  created_at: number;
+  updated_at: number;
+}
+
 export interface WordRecord {
   id: string;
   word: string;
@@ -256,7 +327,57 @@ export interface OpenHistoryItem {
 
 export interface SearchEngineConfig {
   prefix: string;
+  // 可用占位符：{query}（编码后的关键词）、{raw}（原始关键词）、{clipboard}（编码后的剪切板文本）
+  url: string;
+  name: string;
+  // 空格编码方式，默认 "percent"（%20）
+  encoding?: "percent" | "plus";
+  // 关键词字符集，如 "GBK"；为空时使用 UTF-8
+  charset?: string;
+  // OpenSearch 建议接口 URL 模板，同样使用 {query}
+  suggest_url?: string;
+}
+
+export interface SearchTarget {
+  engine: string;
+  keyword: string;
+  url: string;
+  matched_by: "prefix" | "bang" | "fallback";
+}
+
+export interface SearchBang {
+  trigger: string;
+  name: string;
   url: string;
+  encoding: "percent" | "plus";
+}
+
+// `settings-changed` 事件负载：变化的顶层字段和变化后的完整设置
+export interface SettingsChangedEvent {
+  changed: string[];
+  settings: Record<string, unknown>;
+  source: "app" | "external";
+}
+
+export interface SettingsProfile {
+  name: string;
+  is_active: boolean;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface ProfileExportOptions {
+  exclude_secrets?: boolean;
+  exclude_machine_paths?: boolean;
+}
+
+// 导入预览：设置差异按 JSON Pointer 列出，过滤器和快捷方式按名称列出
+export interface ProfileImportPreview {
   name: string;
+  exported_at: number;
+  excluded: string[];
+  settings: { path: string; current?: unknown; incoming?: unknown }[];
+  everything_filters: { added: string[]; removed: string[]; changed: string[] };
+  shortcuts: { added: string[]; removed: string[]; changed: string[] };
 }
 
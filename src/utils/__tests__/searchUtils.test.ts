@@ -1,18 +1,24 @@
 import { describe, it, expect, vi } from "vitest";
 import {
   detectSearchIntent,
+  detectBang,
+  detectBangIntent,
   buildSearchUrl,
   getSearchResultItem,
+  getSuggestionResultItems,
   searchApplicationsFrontend,
   searchFileHistoryFrontend,
+  searchSnippets,
 } from "../searchUtils";
-import type { SearchEngineConfig, AppInfo, FileHistoryItem } from "../../types";
+import { tauriApi } from "../../api/tauri";
+import type { SearchEngineConfig, AppInfo, FileHistoryItem, SnippetItem } from "../../types";
 
 // Mock tauriApi
 vi.mock("../../api/tauri", () => ({
   tauriApi: {
     searchApplications: vi.fn(),
     scanApplications: vi.fn(),
+    searchSnippets: vi.fn(),
   },
 }));
 
@@ -74,6 +80,29 @@ describe("searchUtils", () => {
       const url = buildSearchUrl("https://example.com?q={query}&lang={query}", "test");
       expect(url).toBe("https://example.com?q=test&lang=test");
     });
+
+    it("应该支持空格编码为 + 和 {raw} 占位符", () => {
+      expect(buildSearchUrl("https://example.com?q={query}", "a b", "plus")).toBe("https://example.com?q=a+b");
+      expect(buildSearchUrl("https://example.com/{raw}", "a/b")).toBe("https://example.com/a/b");
+    });
+  });
+
+  describe("detectBang", () => {
+    it("应该检测开头或结尾的 bang", () => {
+      expect(detectBang("!GH tauri")).toEqual({ bang: "gh", keyword: "tauri" });
+      expect(detectBang("tauri  window !w")).toEqual({ bang: "w", keyword: "tauri  window" });
+      expect(detectBang("hello ! world")).toBeNull();
+      expect(detectBang("wow!")).toBeNull();
+    });
+
+    it("应该用 bang 匹配设置中的引擎", () => {
+      const engines: SearchEngineConfig[] = [
+        { name: "GitHub", prefix: "gh ", url: "https://github.com/search?q={query}" },
+      ];
+
+      expect(detectBangIntent("rust !gh", engines)?.engine?.name).toBe("GitHub");
+      expect(detectBangIntent("!w rust", engines)).toEqual({ bang: "w", keyword: "rust", engine: null });
+    });
   });
 
   describe("getSearchResultItem", () => {
@@ -92,6 +121,21 @@ describe("searchUtils", () => {
     });
   });
 
+  describe("getSuggestionResultItems", () => {
+    it("应该为每条建议生成带原始输入的搜索结果", () => {
+      const engine: SearchEngineConfig = {
+        name: "Google",
+        prefix: "g ",
+        url: "https://google.com/search?q={query}",
+      };
+
+      const results = getSuggestionResultItems(engine, ["rust", "rust book"], (s) => `g ${s}`);
+      expect(results).toHaveLength(2);
+      expect(results[1].path).toBe("https://google.com/search?q=rust%20book");
+      expect(results[1].searchQuery).toBe("g rust book");
+    });
+  });
+
   describe("searchApplicationsFrontend", () => {
     const mockApps: AppInfo[] = [
       { name: "微信", path: "C:\\WeChat.exe", name_pinyin: "weixin", name_pinyin_initials: "wx" },
@@ -205,5 +249,43 @@ describe("searchUtils", () => {
       expect(results.length).toBeLessThanOrEqual(100);
     });
   });
+
+  describe("searchSnippets", () => {
+    const snippet = (id: string): SnippetItem => ({
+      id,
+      name: `片段 ${id}`,
+      keyword: `;${id}`,
+      folder: null,
+      content: "Best regards",
+      use_count: 0,
+      created_at: 0,
+      updated_at: 0,
+    });
+    const updateSearchResults = <T,>(setter: (value: T) => void, value: T) => setter(value);
+
+    it("应该返回后端的搜索结果", async () => {
+      vi.mocked(tauriApi.searchSnippets).mockResolvedValue([snippet("sig")]);
+      const setFilteredSnippets = vi.fn();
+      await searchSnippets(" sig ", { currentQuery: "sig", updateSearchResults, setFilteredSnippets });
+      expect(tauriApi.searchSnippets).toHaveBeenCalledWith("sig");
+      expect(setFilteredSnippets).toHaveBeenCalledWith([snippet("sig")]);
+    });
+
+    it("查询已变化时应该丢弃结果", async () => {
+      vi.mocked(tauriApi.searchSnippets).mockResolvedValue([snippet("sig")]);
+      const setFilteredSnippets = vi.fn();
+      await searchSnippets("sig", { currentQuery: "sign", updateSearchResults, setFilteredSnippets });
+      expect(setFilteredSnippets).toHaveBeenCalledWith([]);
+    });
+
+    it("后端出错时应该清空结果", async () => {
+      vi.mocked(tauriApi.searchSnippets).mockRejectedValue("boom");
+      const setFilteredSnippets = vi.fn();
+      const consoleError = vi.spyOn(console, "error").mockImplementation(() => {});
+      await searchSnippets("sig", { currentQuery: "sig", updateSearchResults, setFilteredSnippets });
+      expect(setFilteredSnippets).toHaveBeenCalledWith([]);
+      consoleError.mockRestore();
+    });
+  });
 });
 
@@ -9,6 +9,7 @@ import type {
   FileHistoryItem,
   EverythingResult,
   MemoItem,
+  SnippetItem,
   SearchEngineConfig,
 } from "../types";
 import type { SearchResult } from "./resultUtils";
@@ -20,7 +21,12 @@ import {
   getResultUsageInfo,
   isLnkPath,
 } from "./launcherUtils";
-import { detectSearchIntent, getSearchResultItem } from "./searchUtils";
+import {
+  detectSearchIntent,
+  detectBangIntent,
+  getSearchResultItem,
+  getSuggestionResultItems,
+} from "./searchUtils";
 
 /**
  * 组合搜索结果的选项接口
@@ -31,6 +37,7 @@ export interface CombineResultsOptions {
   filteredApps: AppInfo[];
   filteredFiles: FileHistoryItem[];
   filteredMemos: MemoItem[];
+  filteredSnippets: SnippetItem[];
   systemFolders: Array<{
     name: string;
     path: string;
@@ -49,6 +56,10 @@ export interface CombineResultsOptions {
   openHistory: Record<string, number>;
   urlRemarks: Record<string, string>;
   searchEngines: SearchEngineConfig[];
+  // 后备搜索引擎名称，普通输入时在结果末尾提供一条网页搜索
+  fallbackSearchEngine: string | null;
+  // 当前输入的搜索建议（未开启建议时为空）
+  searchSuggestions: string[];
   apps: AppInfo[];
   extractedFileIconsRef: React.MutableRefObject<Map<string, string>>;
 }
@@ -65,6 +76,7 @@ export function computeCombinedResults(options: CombineResultsOptions): SearchRe
     systemFolders,
     everythingResults,
     filteredMemos,
+    filteredSnippets,
     filteredPlugins,
     detectedUrls,
     detectedEmails,
@@ -73,6 +85,8 @@ export function computeCombinedResults(options: CombineResultsOptions): SearchRe
     openHistory,
     urlRemarks,
     searchEngines,
+    fallbackSearchEngine,
+    searchSuggestions,
     apps,
     extractedFileIconsRef,
   } = options;
@@ -320,12 +334,41 @@ export function computeCombinedResults(options: CombineResultsOptions): SearchRe
 
   // 如果检测到搜索引擎前缀，只返回搜索引擎结果，屏蔽其他所有搜索
   if (searchIntent) {
-    const searchResultItem = getSearchResultItem(searchIntent.engine, searchIntent.keyword);
+    const searchResultItem = getSearchResultItem(searchIntent.engine, searchIntent.keyword, query);
     const searchResult: SearchResult = {
       ...searchResultItem,
       type: "search" as const,
     };
-    return [searchResult];
+    const prefix = searchIntent.engine.prefix.endsWith(" ")
+      ? searchIntent.engine.prefix
+      : searchIntent.engine.prefix + " ";
+    const suggestionResults = getSuggestionResultItems(
+      searchIntent.engine,
+      searchSuggestions,
+      (suggestion) => prefix + suggestion
+    );
+    return [searchResult, ...suggestionResults];
+  }
+
+  // 检测 !bang：设置中的引擎直接生成结果，其它 bang 在打开时由后端从导入的 bang 列表解析
+  const bangIntent = detectBangIntent(query, searchEngines);
+  if (bangIntent) {
+    const searchResult: SearchResult = bangIntent.engine
+      ? { ...getSearchResultItem(bangIntent.engine, bangIntent.keyword, query), type: "search" as const }
+      : {
+          type: "search" as const,
+          displayName: `使用 !${bangIntent.bang} 搜索：${bangIntent.keyword}`,
+          path: `bang://${bangIntent.bang}`,
+          searchQuery: query,
+        };
+    const suggestionResults = bangIntent.engine
+      ? getSuggestionResultItems(
+          bangIntent.engine,
+          searchSuggestions,
+          (suggestion) => `!${bangIntent.bang} ${suggestion}`
+        )
+      : [];
+    return [searchResult, ...suggestionResults];
   }
 
   let otherResults: SearchResult[] = [
@@ -647,6 +690,12 @@ export function computeCombinedResults(options: CombineResultsOptions): SearchRe
       displayName: memo.title || memo.content.slice(0, 50),
       path: memo.id,
     })),
+    ...filteredSnippets.map((snippet) => ({
+      type: "snippet" as const,
+      snippet,
+      displayName: snippet.name,
+      path: `snippet://${snippet.id}`,
+    })),
     // 将文件工具箱插件单独提取，优先显示
     ...filteredPlugins
       .filter((plugin) => plugin.id === "file_toolbox")
@@ -1192,6 +1241,17 @@ export function computeCombinedResults(options: CombineResultsOptions): SearchRe
     return a.displayName.localeCompare(b.displayName);
   });
 
+  // 普通输入时，在末尾追加后备引擎的网页搜索及搜索建议
+  const fallbackEngine = fallbackSearchEngine
+    ? searchEngines.find((e) => e.name === fallbackSearchEngine)
+    : undefined;
+  if (fallbackEngine && query.trim()) {
+    allResultsToSort.push(
+      getSearchResultItem(fallbackEngine, query.trim(), query),
+      ...getSuggestionResultItems(fallbackEngine, searchSuggestions, (suggestion) => suggestion)
+    );
+  }
+
   return allResultsToSort;
 }
 
@@ -4,7 +4,7 @@
  */
 
 import type React from "react";
-import type { AppInfo, FileHistoryItem, PluginContext } from "../types";
+import type { AppInfo, FileHistoryItem, PluginContext, SnippetItem } from "../types";
 import type { SearchResult } from "./resultUtils";
 import { normalizePathForHistory } from "./launcherUtils";
 import { tauriApi } from "../api/tauri";
@@ -36,6 +36,8 @@ export interface LaunchOptions {
   setSelectedIndex: (index: number) => void;
   setContextMenu: (menu: { x: number; y: number; result: SearchResult } | null) => void;
   setIsPluginListModalOpen: (open: boolean) => void;
+  // 片段需要用户输入时打开输入弹窗
+  openSnippetInputs: (snippet: SnippetItem, inputs: string[]) => void;
   
   // Refs
   allFileHistoryCacheRef: React.MutableRefObject<FileHistoryItem[]>;
@@ -76,6 +78,7 @@ export async function handleLaunch(options: LaunchOptions): Promise<void> {
     setSelectedIndex,
     setContextMenu,
     setIsPluginListModalOpen,
+    openSnippetInputs,
     allFileHistoryCacheRef,
     allFileHistoryCacheLoadedRef,
     pendingJsonContentRef,
@@ -105,6 +108,7 @@ export async function handleLaunch(options: LaunchOptions): Promise<void> {
         result.type === "history" ||
         result.type === "settings" ||
         result.type === "memo" ||
+        result.type === "snippet" ||
         result.type === "plugin"
       ) {
         return false;
@@ -238,7 +242,26 @@ export async function handleLaunch(options: LaunchOptions): Promise<void> {
       return;
     } else if (result.type === "search") {
       // 处理搜索类型：打开浏览器进行搜索
-      await tauriApi.openUrl(result.path);
+      // 由后端解析最终 URL（字符集、{clipboard}、导入的 bang、后备引擎），失败时使用前端构建的 URL
+      let url = result.path;
+      if (result.searchQuery) {
+        try {
+          const target = await tauriApi.resolveWebSearch(result.searchQuery, true);
+          if (target) {
+            url = target.url;
+          }
+        } catch (error) {
+          console.error("Failed to resolve web search:", error);
+        }
+      }
+      if (url.startsWith("bang://")) {
+        setSuccessMessage(`未找到 bang：${url.slice("bang://".length)}`);
+        setTimeout(() => {
+          setSuccessMessage(null);
+        }, 3000);
+        return;
+      }
+      await tauriApi.openUrl(url);
       await hideLauncherAndResetState();
       return;
     } else if (result.type === "email" && result.email) {
@@ -562,6 +585,15 @@ export async function handleLaunch(options: LaunchOptions): Promise<void> {
       setIsMemoModalOpen(true);
       // 不关闭启动器，让用户查看/编辑备忘录
       return;
+    } else if (result.type === "snippet" && result.snippet) {
+      // 片段包含 {input:...} 时先在启动器中填写，填写完成后再粘贴
+      const inputs = await tauriApi.getSnippetInputs(result.snippet.id);
+      if (inputs.length > 0) {
+        openSnippetInputs(result.snippet, inputs);
+        return;
+      }
+      await pasteSnippet(result.snippet.id, undefined, { hideLauncherAndResetState, tauriApi });
+      return;
     } else if (result.type === "plugin" && result.plugin) {
       // 使用插件系统执行插件
       const pluginContext: PluginContext = {
@@ -603,3 +635,15 @@ export async function handleLaunch(options: LaunchOptions): Promise<void> {
   }
 }
 
+/**
+ * 粘贴片段
+ * 先隐藏启动器让焦点回到目标窗口，再由后端渲染片段并粘贴到光标处
+ */
+export async function pasteSnippet(
+  snippetId: string,
+  inputs: Record<string, string> | undefined,
+  deps: Pick<LaunchOptions, 'hideLauncherAndResetState' | 'tauriApi'>
+): Promise<void> {
+  await deps.hideLauncherAndResetState();
+  await deps.tauriApi.pasteSnippet(snippetId, inputs);
+}
@@ -4,6 +4,7 @@
  */
 
 import type React from "react";
+import type { SnippetItem } from "../types";
 import {
   normalizePathForHistory,
   isSystemFolder,
@@ -14,16 +15,19 @@ import {
 
 // SearchResult 类型定义（与 LauncherWindow.tsx 中的定义保持一致）
 export type SearchResult = {
-  type: "app" | "file" | "everything" | "url" | "email" | "memo" | "plugin" | "history" | "ai" | "json_formatter" | "settings" | "search";
+  type: "app" | "file" | "everything" | "url" | "email" | "memo" | "snippet" | "plugin" | "history" | "ai" | "json_formatter" | "settings" | "search";
   app?: any;
   file?: any;
   everything?: any;
   url?: string;
   email?: string;
   memo?: any;
+  snippet?: SnippetItem;
   plugin?: { id: string; name: string; description?: string };
   aiAnswer?: string;
   jsonContent?: string;
+  // 搜索结果的原始输入，打开时由后端解析出最终 URL
+  searchQuery?: string;
   displayName: string;
   path: string;
 };
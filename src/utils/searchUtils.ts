@@ -4,7 +4,7 @@
  */
 
 import type React from "react";
-import type { SearchEngineConfig, AppInfo, FileHistoryItem, MemoItem } from "../types";
+import type { SearchEngineConfig, AppInfo, FileHistoryItem, MemoItem, SnippetItem } from "../types";
 import { containsChinese, processBatchAsync, isValidIcon, normalizePathForHistory } from "./launcherUtils";
 import { tauriApi } from "../api/tauri";
 
@@ -15,6 +15,8 @@ export interface SearchResultItem {
   type: "search";
   displayName: string;
   path: string;
+  // 原始输入，打开时交给后端解析（处理字符集、剪切板占位符和导入的 bang）
+  searchQuery?: string;
 }
 
 /**
@@ -53,11 +55,55 @@ export function detectSearchIntent(
 }
 
 /**
- * 构建搜索 URL，将 {query} 替换为编码后的关键词
+ * 检测输入开头或结尾的 !bang（如 "!gh tauri" 或 "tauri !gh"）
+ * bang 统一转为小写，是否存在对应的引擎由调用方判断
  */
-export function buildSearchUrl(urlTemplate: string, keyword: string): string {
-  const encodedKeyword = encodeURIComponent(keyword);
-  return urlTemplate.replace(/{query}/g, encodedKeyword);
+export function detectBang(query: string): { bang: string; keyword: string } | null {
+  const trimmed = query.trim();
+  const isBang = (token: string) => token.length > 1 && token.startsWith("!");
+
+  const first = trimmed.split(/\s+/)[0] ?? "";
+  if (isBang(first)) {
+    return { bang: first.slice(1).toLowerCase(), keyword: trimmed.slice(first.length).trim() };
+  }
+  const lastSpace = trimmed.search(/\s\S*$/);
+  if (lastSpace < 0) return null;
+  const last = trimmed.slice(lastSpace).trim();
+  if (isBang(last)) {
+    return { bang: last.slice(1).toLowerCase(), keyword: trimmed.slice(0, lastSpace).trim() };
+  }
+  return null;
+}
+
+/**
+ * 用 bang 匹配设置中的搜索引擎（前缀去掉空格即为 bang）
+ */
+export function detectBangIntent(
+  query: string,
+  engines: SearchEngineConfig[]
+): { bang: string; keyword: string; engine: SearchEngineConfig | null } | null {
+  const detected = detectBang(query);
+  if (!detected) return null;
+  const engine = engines.find((e) => e.prefix.trim().toLowerCase() === detected.bang) ?? null;
+  return { ...detected, engine };
+}
+
+/**
+ * 构建搜索 URL，将 {query} 替换为编码后的关键词，{raw} 替换为原始关键词
+ * 引擎的 encoding 为 "plus" 时空格编码为 +；字符集转换和 {clipboard} 由后端处理
+ */
+export function buildSearchUrl(
+  urlTemplate: string,
+  keyword: string,
+  encoding: SearchEngineConfig["encoding"] = "percent"
+): string {
+  let encodedKeyword = encodeURIComponent(keyword);
+  if (encoding === "plus") {
+    encodedKeyword = encodedKeyword.replace(/%20/g, "+");
+  }
+  return urlTemplate
+    .replace(/{query}/g, encodedKeyword)
+    .replace(/{raw}/g, keyword);
 }
 
 /**
@@ -65,17 +111,30 @@ export function buildSearchUrl(urlTemplate: string, keyword: string): string {
  */
 export function getSearchResultItem(
   engine: SearchEngineConfig,
-  keyword: string
+  keyword: string,
+  searchQuery?: string
 ): SearchResultItem {
-  const searchUrl = buildSearchUrl(engine.url, keyword);
+  const searchUrl = buildSearchUrl(engine.url, keyword, engine.encoding);
   
   return {
     type: "search",
     displayName: `在 ${engine.name} 搜索：${keyword}`,
     path: searchUrl,
+    searchQuery,
   };
 }
 
+/**
+ * 将搜索建议转为结果项，toQuery 把建议词还原为启动器输入（带上前缀或 bang）
+ */
+export function getSuggestionResultItems(
+  engine: SearchEngineConfig,
+  suggestions: string[],
+  toQuery: (suggestion: string) => string
+): SearchResultItem[] {
+  return suggestions.map((suggestion) => getSearchResultItem(engine, suggestion, toQuery(suggestion)));
+}
+
 /**
  * 前端搜索应用（基于缓存的应用列表）
  * 异步分批处理，避免阻塞UI
@@ -246,6 +305,7 @@ export interface SearchDependencies {
   setFilteredApps: (apps: AppInfo[]) => void;
   setFilteredFiles: (files: FileHistoryItem[]) => void;
   setFilteredMemos: (memos: MemoItem[]) => void;
+  setFilteredSnippets: (snippets: SnippetItem[]) => void;
   setSystemFolders: (folders: SystemFolder[]) => void;
   setApps: (apps: AppInfo[]) => void;
   
@@ -313,6 +373,37 @@ export async function searchMemos(
   }
 }
 
+// 启动器中最多显示的片段数量
+const MAX_SNIPPET_RESULTS = 8;
+
+/**
+ * 搜索片段
+ * 由后端按名称、关键字、文件夹和内容匹配，关键字完全匹配的排在最前
+ */
+export async function searchSnippets(
+  q: string,
+  deps: Pick<SearchDependencies, 'currentQuery' | 'updateSearchResults' | 'setFilteredSnippets'>
+): Promise<void> {
+  try {
+    if (!q || q.trim() === "") {
+      deps.updateSearchResults(deps.setFilteredSnippets, []);
+      return;
+    }
+
+    const snippets = await tauriApi.searchSnippets(q.trim());
+
+    // Only update if query hasn't changed
+    if (deps.currentQuery.trim() === q.trim()) {
+      deps.updateSearchResults(deps.setFilteredSnippets, snippets.slice(0, MAX_SNIPPET_RESULTS));
+    } else {
+      deps.updateSearchResults(deps.setFilteredSnippets, []);
+    }
+  } catch (error) {
+    console.error("Failed to search snippets:", error);
+    deps.updateSearchResults(deps.setFilteredSnippets, []);
+  }
+}
+
 /**
  * 搜索系统文件夹
  */